@@ -0,0 +1,68 @@
+; Built-in NAL inference rules, compiled into the binary by `static_rules::load_rules`.
+; One rule per line: premises !- conclusion truth-function [annotations]
+; (see `static_rules::parse_rules` for the annotation syntax).
+; Syllogisms are `:allow-backward`: a question on the conclusion asks for the missing premise.
+
+; --- IMMEDIATE INFERENCE ---
+(-- :M)                   !- (:M)                    negation
+(:S --> :P)               !- (:P --> :S)             conversion
+(:S ==> :P)               !- (:P ==> :S)             conversion
+(:S ==> :P)               !- ((-- :P) ==> (-- :S))   contraposition
+
+; --- SYLLOGISMS (NAL-1) ---
+(:M --> :P) (:S --> :M)   !- (:S --> :P)             deduction :allow-backward
+(:P --> :M) (:S --> :M)   !- (:S --> :P)             abduction :allow-backward
+(:M --> :P) (:M --> :S)   !- (:S --> :P)             induction :allow-backward
+(:P --> :M) (:M --> :S)   !- (:S --> :P)             exemplification :allow-backward
+
+; --- SYLLOGISMS (NAL-2) ---
+(:S --> :P) (:P --> :S)   !- (:P <-> :S)             intersection
+(:M --> :P) (:S <-> :M)   !- (:S --> :P)             analogy :allow-backward
+(:P --> :M) (:S <-> :M)   !- (:P --> :S)             analogy :allow-backward
+(:M <-> :P) (:S <-> :M)   !- (:P <-> :S)             resemblance :allow-backward
+; Structural bridging: similarity holds both ways as inheritance (the way back is intersection above)
+(:S <-> :P)               !- (:S --> :P)             structural_deduction
+(:S <-> :P)               !- (:P --> :S)             structural_deduction
+
+; --- HIGHER ORDER (NAL-5) ---
+(:M ==> :P) (:S ==> :M)   !- (:S ==> :P)             deduction :allow-backward
+(:P ==> :M) (:S ==> :M)   !- (:S ==> :P)             abduction :allow-backward
+(:M ==> :P) (:M ==> :S)   !- (:S ==> :P)             induction :allow-backward
+(:S ==> :P) (:P ==> :S)   !- (:S <=> :P)             intersection
+(:M ==> :P) (:S <=> :M)   !- (:S ==> :P)             analogy :allow-backward
+(:M <=> :P) (:S <=> :M)   !- (:S <=> :P)             resemblance :allow-backward
+
+; --- VARIABLES (NAL-6) ---
+(:S --> :M) (:P --> :M)   !- ((:P --> $X) ==> (:S --> $X))   abduction
+(:S --> :M) (:P --> :M)   !- ((:S --> $X) ==> (:P --> $X))   induction
+(:M --> :S) (:M --> :P)   !- (($X --> :S) ==> ($X --> :P))   induction
+(:M --> :S) (:M --> :P)   !- (($X --> :P) ==> ($X --> :S))   abduction
+
+; --- SETS & COMPOSITION (NAL-3) ---
+; Intersection (&)
+(:P --> :M) (:S --> :M)   !- ((& :S :P) --> :M)      intersection
+(:M --> :P) (:M --> :S)   !- (:M --> (& :P :S))      intersection
+; Union (+)
+(:P --> :M) (:S --> :M)   !- ((+ :S :P) --> :M)      union
+(:M --> :P) (:M --> :S)   !- (:M --> (+ :P :S))      union
+; Difference (-) and (~)
+(:P --> :M) (:S --> :M)   !- ((~ :P :S) --> :M)      difference
+(:M --> :P) (:M --> :S)   !- (:M --> (- :P :S))      difference
+
+; --- DECOMPOSITION (NAL-3) ---
+; Simplification for sets
+(:S --> :M) ((& :S :P) --> :M)   !- (:P --> :M)      decomposition
+(:M --> :S) (:M --> (& :S :P))   !- (:M --> :P)      decomposition
+; Difference decomposition: what is S but not (S - P) is P; what is neither S nor (P - S) is not P
+(:M --> :S) (:M --> (- :S :P))   !- (:M --> :P)      decompose_pnp
+(:M --> :S) (:M --> (- :P :S))   !- (:M --> :P)      decompose_nnn
+(:S --> :M) ((~ :S :P) --> :M)   !- (:P --> :M)      decompose_pnp
+(:S --> :M) ((~ :P :S) --> :M)   !- (:P --> :M)      decompose_nnn
+; Disjunction decomposition
+(:S --> (| :P :M)) (:S --> :M)   !- (:S --> :P)      reduce_disjunction
+; Structural decomposition (single premise)
+((| :S :P) --> :M)        !- (:S --> :M)             structural_deduction
+(:M --> (& :S :P))        !- (:M --> :S)             structural_deduction
+(:M --> (| :S :P))        !- (:M --> :S)             structural_deduction
+(:M --> (- :S :P))        !- (:M --> :S)             structural_deduction
+((~ :S :P) --> :M)        !- (:S --> :M)             structural_deduction
@@ -0,0 +1,61 @@
+//! Heap allocations per reasoning cycle, counted by a wrapping global allocator:
+//! `cargo bench --bench allocations [-- <cycles> [<background concepts>]]`. Background
+//! concepts are unrelated beliefs put straight into memory, to measure a larger store.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+use hybrid_nars_rust::nars::control::NarsSystem;
+use hybrid_nars_rust::nars::memory::{Concept, ConceptStore};
+use hybrid_nars_rust::nars::parser::{parse_narsese, parse_term};
+use hybrid_nars_rust::nars::sentence::Stamp;
+use hybrid_nars_rust::nars::truth::TruthValue;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    let mut numbers = std::env::args().skip(1).filter_map(|arg| arg.parse().ok());
+    let cycles: u64 = numbers.next().unwrap_or(1000);
+    let background = numbers.next().unwrap_or(0);
+    let mut system = NarsSystem::new(0.1, -1.0);
+    system.memory = ConceptStore::new(10_000 + background as usize);
+    for i in 0..background {
+        let term = parse_term(&format!("<d{} --> e{}>", i, i % 100)).unwrap().1;
+        let vector = system.resolve_vector(&term);
+        system.add_concept(Concept::new(term, vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), true);
+    }
+    system.reasoner.sample_size = 60;
+    system.reasoner.max_partners = 60;
+    system.dedup_window = 0;
+    for i in 0..40 {
+        for text in [format!("<a{} --> b{}>.", i, i % 7), format!("<b{} --> c{}>.", i % 7, i % 5)] {
+            system.input(parse_narsese(&text).unwrap());
+        }
+    }
+    // Integrate the inputs first so only steady-state reasoning is measured
+    system.run_cycles(100);
+
+    let (allocations, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed));
+    system.run_cycles(cycles as usize);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let bytes = BYTES.load(Ordering::Relaxed) - bytes;
+    eprintln!("{} cycles: {:.0} allocations/cycle, {:.1} KiB/cycle ({} concepts, {} derivations)",
+        cycles, allocations as f64 / cycles as f64, bytes as f64 / cycles as f64 / 1024.0,
+        system.memory.len(), system.stats.derivations);
+}
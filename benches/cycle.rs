@@ -0,0 +1,44 @@
+//! Cycle throughput of sequential against pipelined cycles (rule matching and retrieval on
+//! background threads): `cargo bench --bench cycle [-- <cycles>]`.
+use std::time::Instant;
+use hybrid_nars_rust::nars::control::NarsSystem;
+use hybrid_nars_rust::nars::parser::parse_narsese;
+
+/// A memory dense enough in related statements that most cycles reason with many partners.
+fn populated_system(pipelined: bool) -> NarsSystem {
+    let mut system = NarsSystem::new(0.1, -1.0);
+    system.seed(1);
+    system.reasoner.sample_size = 60;
+    system.reasoner.max_partners = 60;
+    system.reasoner.pipelined = pipelined;
+    system.dedup_window = 0;
+    for i in 0..40 {
+        for text in [
+            format!("<a{} --> b{}>.", i, i % 7),
+            format!("<b{} --> c{}>.", i % 7, i % 5),
+            format!("<<$x --> a{}> ==> <$x --> c{}>>.", i, i % 5),
+            format!("<(*, a{}, b{}) --> r{}>.", i, i % 7, i % 3),
+        ] {
+            system.input(parse_narsese(&text).unwrap());
+        }
+    }
+    system
+}
+
+fn main() {
+    let cycles: usize = std::env::args().skip(1).find_map(|arg| arg.parse().ok()).unwrap_or(2000);
+    // Pipelined cycles select the next task earlier, so the runs take different paths:
+    // derivations per second compare the work done as well as cycles per second
+    let throughput = [false, true].map(|pipelined| {
+        let mut system = populated_system(pipelined);
+        let start = Instant::now();
+        system.run_cycles(cycles);
+        let secs = start.elapsed().as_secs_f64();
+        let (rate, derivation_rate) = (cycles as f64 / secs, system.stats.derivations as f64 / secs);
+        eprintln!("{:<10} {} cycles in {:.2}s ({:.0} cycles/s, {:.0} derivations/s, {} concepts)",
+            if pipelined { "pipelined" } else { "sequential" }, cycles, secs, rate, derivation_rate, system.memory.len());
+        (rate, derivation_rate)
+    });
+    eprintln!("pipelined/sequential: {:.2}x cycles/s, {:.2}x derivations/s",
+        throughput[1].0 / throughput[0].0, throughput[1].1 / throughput[0].1);
+}
@@ -0,0 +1,59 @@
+//! Live heap of a concept store and what sharing interned terms saves in it:
+//! `cargo bench --bench memory [-- <concepts>]`. Each concept's term is held by the store
+//! under several handles (map key, concept, attention bag, index entries); without interning
+//! each of them would be a copy of the tree.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use hybrid_nars_rust::nars::memory::{Concept, ConceptStore, Hypervector};
+use hybrid_nars_rust::nars::parser::parse_term;
+use hybrid_nars_rust::nars::sentence::Stamp;
+use hybrid_nars_rust::nars::truth::TruthValue;
+
+struct Counting;
+
+static LIVE: AtomicI64 = AtomicI64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size() as i64, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size() as i64, Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    let concepts: usize = std::env::args().skip(1).find_map(|arg| arg.parse().ok()).unwrap_or(10_000);
+    let terms: Vec<_> = (0..concepts)
+        .map(|i| parse_term(&format!("<(*, a{}, (&, b{}, c{})) --> r{}>", i, i % 97, i % 89, i % 13)).unwrap().1)
+        .collect();
+
+    let live = LIVE.load(Ordering::Relaxed);
+    let mut store = ConceptStore::new(concepts);
+    for (i, term) in terms.iter().enumerate() {
+        let vector = Hypervector::from_term(term);
+        store.put(Concept::new(term.clone(), vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![i as u64])));
+    }
+    let store_bytes = LIVE.load(Ordering::Relaxed) - live;
+
+    // Bytes of the concepts' terms as held once, and as one copy per handle the store keeps
+    let (mut shared, mut copied) = (0, 0);
+    for concept in store.values() {
+        let bytes = BYTES.load(Ordering::Relaxed);
+        drop(std::hint::black_box(concept.term.term().clone()));
+        let tree = BYTES.load(Ordering::Relaxed) - bytes;
+        shared += tree;
+        copied += tree * concept.term.handles() as u64;
+    }
+    eprintln!("{} concepts: {:.1} MiB live heap ({} B/concept)", store.len(), store_bytes as f64 / 1048576.0,
+        store_bytes / store.len() as i64);
+    eprintln!("terms: {:.1} KiB interned, {:.1} KiB as a copy per handle", shared as f64 / 1024.0, copied as f64 / 1024.0);
+}
@@ -0,0 +1,38 @@
+//! Generates one integration test per `.nal` file in `tests/nal<level>/` (see `tests/nal.rs`).
+//! Levels whose inference is still incomplete are ignored unless their `nal<level>-tests`
+//! feature is enabled.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// NAL levels whose tests only run with their `nal<level>-tests` feature.
+const GATED_LEVELS: [u8; 4] = [5, 6, 7, 8];
+
+fn main() {
+    println!("cargo:rerun-if-changed=tests");
+    let mut tests = String::new();
+    let mut dirs: Vec<_> = fs::read_dir("tests").into_iter().flatten().filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    dirs.sort();
+    for dir in dirs {
+        let Some(level) = dir.file_name().and_then(|n| n.to_str()).and_then(|n| n.strip_prefix("nal")).and_then(|l| l.parse::<u8>().ok()) else {
+            continue;
+        };
+        let mut files: Vec<_> = fs::read_dir(&dir).into_iter().flatten().filter_map(|e| e.ok()).map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "nal"))
+            .collect();
+        files.sort();
+        for file in files {
+            let stem = file.file_stem().unwrap().to_string_lossy();
+            let name: String = stem.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+            tests.push_str("#[test]\n");
+            if GATED_LEVELS.contains(&level) {
+                tests.push_str(&format!("#[cfg_attr(not(feature = \"nal{0}-tests\"), ignore = \"NAL-{0} inference is incomplete, enable the nal{0}-tests feature\")]\n", level));
+            }
+            tests.push_str(&format!("fn nal{}_{}() {{\n    run_nal_file(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/{}\"));\n}}\n\n",
+                level, name, file.to_string_lossy().replace('\\', "/")));
+        }
+    }
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("nal_tests.rs");
+    fs::write(out, tests).unwrap();
+}
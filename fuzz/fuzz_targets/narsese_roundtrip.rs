@@ -0,0 +1,19 @@
+#![no_main]
+
+use hybrid_nars_rust::nars::parser::parse_term;
+use libfuzzer_sys::fuzz_target;
+
+// Any term the parser accepts must print back to Narsese that parses to the same term.
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(("", term)) = parse_term(input) else {
+        return;
+    };
+    let printed = term.to_string();
+    match parse_term(&printed) {
+        Ok(("", reparsed)) => assert_eq!(term, reparsed, "round trip mismatch for {}", printed),
+        other => panic!("printed term {} did not re-parse: {:?}", printed, other),
+    }
+});
@@ -0,0 +1,135 @@
+use anyhow::{bail, Result};
+use hybrid_nars_rust::nars::control::NarsSystem;
+use hybrid_nars_rust::nars::ingest::{parse_csv_rows, parse_jsonl_rows, RowTemplate};
+use hybrid_nars_rust::nars::pack::build_pack;
+use hybrid_nars_rust::nars::persist::{SnapshotHeader, SNAPSHOT_VERSION};
+use hybrid_nars_rust::nars::sentence::Source;
+use std::env;
+use std::path::Path;
+
+const USAGE: &str = "Usage:
+  nars snapshot inspect <file>
+  nars build-pack -o <pack> [--cycles <n>] [--manifest <file>] <source>...
+      sources: .nal scripts, RDF N-Triples (.nt), CSV triples (.csv), Prolog facts and rules (.pl)
+  nars import --template <narsese> -o <snapshot> [--cycles <n>] <file>...
+      files: CSV with a header row (.csv), JSON Lines (.jsonl); fields as {name} or {col1}";
+
+const DEFAULT_BUILD_CYCLES: usize = 100;
+const MAX_REPORTED_ROWS: usize = 10; // rows skipped per file that are reported one by one
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["snapshot", "inspect", path] => inspect_snapshot(path),
+        ["build-pack", rest @ ..] => build(rest),
+        ["import", rest @ ..] => import(rest),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints the header of a memory snapshot without decoding its concepts.
+fn inspect_snapshot(path: &str) -> Result<()> {
+    let header = SnapshotHeader::read(path)?;
+    println!("File:     {}", path);
+    if header.version == 0 {
+        println!("Version:  0 (no header; migrated on load, current version is {})", SNAPSHOT_VERSION);
+        println!("Payload:  {} bytes", header.payload_len);
+        return Ok(());
+    }
+    let status = if header.version < SNAPSHOT_VERSION { ", migrated on load" } else { "" };
+    println!("Version:  {} (current {}{})", header.version, SNAPSHOT_VERSION, status);
+    println!("Cycle:    {}", header.cycle);
+    println!("Concepts: {}", header.concepts);
+    println!("Payload:  {} bytes", header.payload_len);
+    Ok(())
+}
+
+/// Builds a knowledge pack from source files and writes its manifest next to it
+/// (`kb.pack` -> `kb.manifest.json`) unless `--manifest` says otherwise.
+fn build(args: &[&str]) -> Result<()> {
+    let (mut output, mut manifest_path, mut cycles, mut sources) = (None, None, DEFAULT_BUILD_CYCLES, Vec::new());
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = args.next().copied(),
+            "--manifest" => manifest_path = args.next().map(|s| s.to_string()),
+            "--cycles" => match args.next().map(|n| n.parse()) {
+                Some(Ok(n)) => cycles = n,
+                _ => bail!("--cycles expects a number\n{}", USAGE),
+            },
+            _ => sources.push(arg),
+        }
+    }
+    let Some(output) = output else { bail!("missing -o <pack>\n{}", USAGE) };
+    if sources.is_empty() {
+        bail!("no sources given\n{}", USAGE);
+    }
+    let manifest_path = manifest_path.unwrap_or_else(|| Path::new(output).with_extension("manifest.json").to_string_lossy().into_owned());
+
+    let mut system = NarsSystem::new(0.1, 0.55);
+    let manifest = build_pack(&mut system, &sources, cycles, output)?;
+    manifest.write(&manifest_path)?;
+    for source in &manifest.sources {
+        println!("{} ({}): {} statements, {} skipped", source.path, source.format, source.statements, source.errors);
+    }
+    println!("{} cycles, {} derivations, {} concepts merged", manifest.cycles, manifest.derivations, manifest.merged);
+    println!("Wrote {} ({} concepts, {} beliefs) and {}", output, manifest.entries, manifest.beliefs, manifest_path);
+    Ok(())
+}
+
+/// Turns every row of CSV or JSON Lines files into a sentence through a Narsese template,
+/// inputs them in one batch per file (each row with evidence of its own), runs `--cycles`
+/// cycles and saves memory as a snapshot for `.load`.
+fn import(args: &[&str]) -> Result<()> {
+    let (mut template, mut output, mut cycles, mut files) = (None, None, 0, Vec::new());
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--template" => template = args.next().copied(),
+            "-o" | "--output" => output = args.next().copied(),
+            "--cycles" => match args.next().map(|n| n.parse()) {
+                Some(Ok(n)) => cycles = n,
+                _ => bail!("--cycles expects a number\n{}", USAGE),
+            },
+            _ => files.push(arg),
+        }
+    }
+    let Some(template) = template else { bail!("missing --template <narsese>\n{}", USAGE) };
+    let Some(output) = output else { bail!("missing -o <snapshot>\n{}", USAGE) };
+    if files.is_empty() {
+        bail!("no files given\n{}", USAGE);
+    }
+    let template = RowTemplate::parse(template)?;
+
+    let mut system = NarsSystem::new(0.1, 0.55);
+    for file in files {
+        let text = std::fs::read_to_string(file)?;
+        let rows = match Path::new(file).extension().and_then(|e| e.to_str()) {
+            Some("csv") => parse_csv_rows(&text)?,
+            Some("jsonl" | "ndjson") => parse_jsonl_rows(&text)?,
+            _ => bail!("{}: unknown format (expected .csv or .jsonl)", file),
+        };
+        let (mut sentences, mut skipped) = (Vec::with_capacity(rows.len()), 0);
+        for (line, row) in &rows {
+            match template.render(row).and_then(|narsese| system.parse(&narsese).map_err(|e| e.to_string())) {
+                Ok(sentence) => sentences.push(sentence),
+                Err(e) => {
+                    if skipped < MAX_REPORTED_ROWS {
+                        eprintln!("{}:{}: {}", file, line, e);
+                    }
+                    skipped += 1;
+                }
+            }
+        }
+        system.input_batch_from(&sentences, true, Source::Import(format!("file:{}", file)));
+        println!("{}: {} rows imported, {} skipped", file, sentences.len(), skipped);
+    }
+    system.run_cycles(cycles);
+    system.save_memory(output)?;
+    println!("Wrote {} ({} concepts)", output, system.memory.len());
+    Ok(())
+}
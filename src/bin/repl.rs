@@ -1,21 +1,35 @@
 use anyhow::Result;
-use hybrid_nars_rust::nars::control::NarsSystem;
-use hybrid_nars_rust::nars::parser::parse_narsese;
-use hybrid_nars_rust::nars::memory::{Concept, Hypervector};
+use hybrid_nars_rust::nars::control::{BeliefFilter, BeliefOrigin, NarsSystem};
+use hybrid_nars_rust::nars::parser::parse_term;
+use hybrid_nars_rust::nars::script::{run_pipe, run_script_file, write_nal};
+use hybrid_nars_rust::nars::error::NarsError;
+use hybrid_nars_rust::nars::memory::Hypervector;
 use hybrid_nars_rust::nars::term::{Term, Operator};
-use hybrid_nars_rust::nars::sentence::{Sentence, Punctuation, Stamp};
-use hybrid_nars_rust::nars::truth::TruthValue;
+use hybrid_nars_rust::nars::sentence::{Sentence, Punctuation, Stamp, Source};
+use hybrid_nars_rust::nars::truth::{TruthValue, TruthRepresentation};
 use std::io::{self, Write};
 
 fn main() -> Result<()> {
+    // Increase similarity threshold to 0.55 to avoid matching random noise
+    let mut system = NarsSystem::new(0.1, 0.55);
+    let glove_path = "assets/glove.txt";
+
+    // OpenNARS shell protocol on stdin/stdout, for existing tools and GUIs
+    if std::env::args().any(|arg| arg == "--pipe") {
+        if std::path::Path::new(glove_path).exists() && let Err(e) = system.load_embeddings_from_file(glove_path) {
+            eprintln!("Failed to load embeddings: {}", e);
+        }
+        run_pipe(&mut system, io::stdin().lock(), io::stdout().lock())?;
+        return Ok(());
+    }
+
     println!("Hybrid NARS Rust REPL");
     println!("Type Narsese input or 'exit' to quit.");
 
-    // Increase similarity threshold to 0.55 to avoid matching random noise
-    let mut system = NarsSystem::new(0.1, 0.55);
+    // Cycles a question may block for while waiting for an answer (0: think 10 cycles and look)
+    let mut question_wait: usize = 0;
 
     // Load embeddings
-    let glove_path = "assets/glove.txt";
     if std::path::Path::new(glove_path).exists() {
         println!("Loading embeddings from {}...", glove_path);
         if let Err(e) = system.load_embeddings_from_file(glove_path) {
@@ -36,13 +50,100 @@ fn main() -> Result<()> {
         if trimmed == "exit" {
             break;
         } else if trimmed == ".rules" {
-            println!("Loaded Rules: {}", system.rules.len());
+            let enabled = (0..system.rules.len()).filter(|&i| system.is_rule_enabled(i)).count();
+            println!("Loaded Rules: {} ({} enabled, NAL level {})", system.rules.len(), enabled, system.nal_level);
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".nal ") {
+            match rest.trim().parse() {
+                Ok(level) => {
+                    system.set_nal_level(level);
+                    println!("Inference limited to NAL-{}", level);
+                }
+                Err(_) => println!("Usage: .nal <level>"),
+            }
+            continue;
+        } else if let Some((enabled, rest)) = trimmed.strip_prefix(".enable ").map(|r| (true, r))
+            .or_else(|| trimmed.strip_prefix(".disable ").map(|r| (false, r)))
+        {
+            let matched = system.set_rule_enabled(rest.trim(), enabled);
+            println!("{} {} rule(s) named {}", if enabled { "Enabled" } else { "Disabled" }, matched, rest.trim());
             continue;
         } else if trimmed == ".stats" {
             println!("Concepts in Memory: {}", system.memory.len());
+            println!("Derivations: {} (suppressed: {}, redundant: {})", system.stats.derivations,
+                system.stats.suppressed_derivations, system.stats.redundant_derivations);
+            println!("Invalidations: {}", system.stats.invalidations);
+            let inputs = system.backpressure;
+            println!("Inputs: {} admitted, {} merged, {} dropped (peak pending: {})", inputs.admitted, inputs.merged,
+                inputs.dropped, inputs.peak_pending);
+            continue;
+        } else if trimmed == ".trace" {
+            system.trace = !system.trace;
+            println!("Derivation trace {}", if system.trace { "on" } else { "off" });
+            continue;
+        } else if trimmed == ".metrics" {
+            print!("{}", system.metrics().to_prometheus());
+            continue;
+        } else if trimmed == ".hot" || trimmed.starts_with(".hot ") {
+            let n = trimmed[4..].trim().parse().unwrap_or(10);
+            println!("Most active concepts (last {} cycles):", system.activity.window);
+            for (term, c) in system.activity.hottest(n) {
+                println!("  {} total={} selected={} associated={} derived={}",
+                    term.to_display_string(), c.total(), c.selections, c.associations, c.derivations);
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".evidence ") {
+            match rest.trim().parse().ok().and_then(|id| system.evidence(id)) {
+                Some(record) => println!("#{}: {} {} (from {}{})", rest.trim(), record.sentence.term.to_display_string(),
+                    system.truth_representation.format(record.sentence.truth), record.source,
+                    record.line.map_or(String::new(), |line| format!(", line {}", line))),
+                None => println!("Unknown evidence id: {}", rest.trim()),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".heatmap ") {
+            match system.export_activity(rest.trim()) {
+                Ok(()) => println!("Activity exported to {}", rest.trim()),
+                Err(e) => println!("Failed to export activity: {}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".curiosity ") {
+            match rest.trim().parse() {
+                Ok(n) => {
+                    system.curiosity = n;
+                    println!("Unlinked pairs associated {} times are asked about (0: off)", n);
+                }
+                Err(_) => println!("Usage: .curiosity <associations>"),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".wait ") {
+            match rest.trim().parse() {
+                Ok(n) => {
+                    question_wait = n;
+                    println!("Questions wait up to {} cycles for an answer", n);
+                }
+                Err(_) => println!("Usage: .wait <cycles>"),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".truth ") {
+            system.truth_representation = match rest.trim() {
+                "interval" => TruthRepresentation::Interval,
+                "point" => TruthRepresentation::FrequencyConfidence,
+                _ => {
+                    println!("Usage: .truth <interval|point>");
+                    continue;
+                }
+            };
+            continue;
+        } else if trimmed == ".driftstats" {
+            for (term, drift, updates) in system.drift_report().iter().take(10) {
+                println!("{} drift={:.4} updates={}", term, drift, updates);
+            }
+            let stats = system.memory.similarity_stats(1000);
+            println!("similarity over {} random pairs: mean={:.4} sd={:.4} min={:.4} max={:.4}",
+                stats.pairs, stats.mean, stats.std_dev, stats.min, stats.max);
             continue;
-        } else if trimmed.starts_with(".export ") {
-            let filename = trimmed[8..].trim();
+        } else if let Some(rest) = trimmed.strip_prefix(".export ") {
+            let filename = rest.trim();
             if filename.is_empty() {
                 println!("Usage: .export <filename>");
                 continue;
@@ -58,7 +159,7 @@ fn main() -> Result<()> {
             let writer = std::io::BufWriter::new(file);
             
             let export_data: Vec<serde_json::Value> = system.memory.values().map(|concept| {
-                let term_str = match &concept.term {
+                let term_str = match concept.term.term() {
                     hybrid_nars_rust::nars::term::Term::Atom(s) => s.clone(),
                     _ => concept.term.to_display_string(),
                 };
@@ -76,8 +177,8 @@ fn main() -> Result<()> {
                 println!("Memory exported to {}", filename);
             }
             continue;
-        } else if trimmed.starts_with(".save ") {
-            let filename = trimmed[6..].trim();
+        } else if let Some(rest) = trimmed.strip_prefix(".save ") {
+            let filename = rest.trim();
             if filename.is_empty() {
                 println!("Usage: .save <filename>");
                 continue;
@@ -88,8 +189,8 @@ fn main() -> Result<()> {
                 println!("Memory saved to {}", filename);
             }
             continue;
-        } else if trimmed.starts_with(".load ") {
-            let filename = trimmed[6..].trim();
+        } else if let Some(rest) = trimmed.strip_prefix(".load ") {
+            let filename = rest.trim();
             if filename.is_empty() {
                 println!("Usage: .load <filename>");
                 continue;
@@ -100,6 +201,205 @@ fn main() -> Result<()> {
                 println!("Memory loaded from {}", filename);
             }
             continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".dump ") {
+            let mut args = rest.split_whitespace();
+            let Some(filename) = args.next() else {
+                println!("Usage: .dump <filename> [conf=<min>] [age=<cycles>] [eternal] [input|derived] [source=<prefix>]");
+                continue;
+            };
+            let mut filter = BeliefFilter::default();
+            for arg in args {
+                match arg.split_once('=') {
+                    Some(("conf", v)) => filter.min_confidence = v.parse().unwrap_or(0.0),
+                    Some(("age", v)) => filter.max_age = v.parse().ok(),
+                    Some(("source", v)) => filter.source = Some(v.to_string()),
+                    _ if arg == "eternal" => filter.eternal_only = true,
+                    _ if arg == "input" => filter.origin = Some(BeliefOrigin::Input),
+                    _ if arg == "derived" => filter.origin = Some(BeliefOrigin::Derived),
+                    _ => println!("Ignoring unknown filter: {}", arg),
+                }
+            }
+            let beliefs = system.export_beliefs(&filter);
+            match write_nal(filename, &beliefs) {
+                Ok(()) => println!("{} beliefs written to {}", beliefs.len(), filename),
+                Err(e) => println!("Failed to write beliefs: {}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".pack ") {
+            let filename = rest.trim();
+            if filename.is_empty() {
+                println!("Usage: .pack <filename>");
+                continue;
+            }
+            match system.export_pack(filename) {
+                Ok(n) => println!("Knowledge pack with {} concepts written to {}", n, filename),
+                Err(e) => println!("Failed to write knowledge pack: {}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".mount ") {
+            let filename = rest.trim();
+            if filename.is_empty() {
+                println!("Usage: .mount <filename>");
+                continue;
+            }
+            match system.mount_pack(filename) {
+                Ok(n) => println!("Mounted {} ({} concepts, read-only)", filename, n),
+                Err(e) => println!("Failed to mount knowledge pack: {}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".run ") {
+            let filename = rest.trim();
+            if filename.is_empty() {
+                println!("Usage: .run <filename>");
+                continue;
+            }
+            match run_script_file(&mut system, filename) {
+                Ok(report) => {
+                    for output in &report.outputs {
+                        println!("Output: {} %{:.2};{:.2}%", output.term, output.truth.frequency, output.truth.confidence);
+                    }
+                    println!("Ran {} inputs and {} cycles; expectations met: {}, unmet: {}",
+                        report.inputs, report.cycles, report.expectations_met.len(), report.expectations_unmet.len());
+                    for expected in &report.expectations_unmet {
+                        match report.closest_output(expected) {
+                            Some((closest, distance)) => println!("  Unmet: {} (closest: {}, distance {:.3})", expected, closest.to_narsese(), distance),
+                            None => println!("  Unmet: {} (nothing output on this term)", expected),
+                        }
+                    }
+                }
+                Err(e) => println!("Failed to run script: {}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".ground ") {
+            let parts: Vec<&str> = rest.split_whitespace().collect();
+            if parts.len() != 2 {
+                println!("Usage: .ground <term> <word>");
+                continue;
+            }
+            match parse_term(parts[0]) {
+                Ok((_, term)) => match system.ground(&term, parts[1]) {
+                    Ok(()) => println!("Grounded {} to '{}'", term, parts[1]),
+                    Err(e) => println!("Failed to ground: {}", e),
+                },
+                Err(e) => println!("Parse Error: {:?}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".inject ") {
+            // .inject <judgement> <vector>: the vector as hex or 0b-prefixed bits
+            let Some((narsese, vector)) = rest.trim().rsplit_once(char::is_whitespace) else {
+                println!("Usage: .inject <judgement> <hex|0b bits>");
+                continue;
+            };
+            match (system.parse(narsese.trim()), Hypervector::parse(vector)) {
+                (Ok(sentence), Ok(vector)) if sentence.punctuation == Punctuation::Judgement => {
+                    println!("Injected {}", sentence.to_narsese());
+                    system.insert_concept_with_vector(sentence.term, sentence.truth, vector);
+                }
+                (Ok(_), Ok(_)) => println!("Usage: .inject <judgement> <hex|0b bits>"),
+                (Err(e), _) | (_, Err(e)) => println!("Parse Error: {}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".forget ") {
+            match parse_term(rest.trim()) {
+                Ok((_, term)) => match system.retract(&term) {
+                    Ok(n) => println!("Forgot {} ({} concepts removed)", term, n),
+                    Err(e) => println!("Failed to forget: {}", e),
+                },
+                Err(e) => println!("Parse Error: {:?}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".pin ") {
+            match parse_term(rest.trim()) {
+                Ok((_, term)) => {
+                    system.pin(&term);
+                    println!("Pinned {}", term);
+                }
+                Err(e) => println!("Parse Error: {:?}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".unpin ") {
+            match parse_term(rest.trim()) {
+                Ok((_, term)) if system.unpin(&term) => println!("Unpinned {}", term),
+                Ok((_, term)) => println!("{} was not pinned", term),
+                Err(e) => println!("Parse Error: {:?}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".history ") {
+            match parse_term(rest.trim()) {
+                Ok((_, term)) => match system.history(&term) {
+                    Some(history) => {
+                        for r in history {
+                            println!("  cycle {}: %{:.2};{:.2}% -> %{:.2};{:.2}% evidence {:?}",
+                                r.time, r.previous.frequency, r.previous.confidence,
+                                r.revised.frequency, r.revised.confidence, r.stamp.evidence);
+                        }
+                    }
+                    None => println!("Unknown concept: {}", term),
+                },
+                Err(e) => println!("Parse Error: {:?}", e),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".nearest ") {
+            let mut parts = rest.split_whitespace();
+            let n = parts.clone().nth(1).and_then(|n| n.parse().ok()).unwrap_or(10);
+            match parts.next().map(parse_term) {
+                Some(Ok((_, term))) => {
+                    let vector = system.vector_for(&term);
+                    for (t, sim) in system.nearest_terms(&vector, n + 1).into_iter().filter(|(t, _)| *t != term).take(n) {
+                        println!("  {} ({:.4})", t, sim);
+                    }
+                }
+                _ => println!("Usage: .nearest <word> [n]"),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".analogy ") {
+            let terms: Vec<_> = rest.split_whitespace().map(parse_term).collect();
+            match terms.as_slice() {
+                [Ok((_, a)), Ok((_, b)), Ok((_, c))] => {
+                    println!("{} : {} :: {} : ?", a, b, c);
+                    for (term, sim) in system.analogize(a, b, c) {
+                        println!("  {} ({:.4})", term, sim);
+                    }
+                }
+                _ => println!("Usage: .analogy <a> <b> <c>"),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".path ") {
+            let terms: Vec<_> = rest.split_whitespace().map(parse_term).collect();
+            match terms.as_slice() {
+                [Ok((_, from)), Ok((_, to))] => match system.path(from, to) {
+                    Some(path) => {
+                        for step in &path.steps {
+                            println!("  {} {}", step.term.to_display_string(), system.truth_representation.format(step.truth));
+                        }
+                        println!("=> {} {}", path.steps.len(), system.truth_representation.format(path.truth));
+                    }
+                    None => println!("No chain from {} to {}", from, to),
+                },
+                _ => println!("Usage: .path <from> <to>"),
+            }
+            continue;
+        } else if let Some(rest) = trimmed.strip_prefix(".context") {
+            match rest.split_whitespace().collect::<Vec<_>>().as_slice() {
+                [] => match system.contexts().as_slice() {
+                    [] => println!("No active context"),
+                    names => println!("Contexts: {}", names.join(" > ")),
+                },
+                ["push", name] => {
+                    system.push_context(name);
+                    println!("Entered context {}", name);
+                }
+                ["commit"] => match system.commit_context() {
+                    Ok(name) => println!("Committed context {}", name),
+                    Err(e) => println!("Error: {}", e),
+                },
+                ["drop"] => match system.pop_context() {
+                    Ok(name) => println!("Dropped context {}", name),
+                    Err(e) => println!("Error: {}", e),
+                },
+                _ => println!("Usage: .context [push <name> | commit | drop]"),
+            }
+            continue;
         } else if trimmed.starts_with(".drift ") {
             let parts: Vec<&str> = trimmed.split_whitespace().collect();
             if parts.len() != 3 {
@@ -116,7 +416,7 @@ fn main() -> Result<()> {
                 if let Some(c) = sys.memory.get(t) {
                     c.vector
                 } else {
-                    Hypervector::from_term(t)
+                    Hypervector::from_term_with(t, sys.tie_break)
                 }
             };
 
@@ -128,7 +428,7 @@ fn main() -> Result<()> {
 
             let stmt = format!("<{} --> {}>.", t1_str, t2_str);
             println!("Injecting: {}", stmt);
-            match parse_narsese(&stmt) {
+            match system.parse(&stmt) {
                 Ok(sentence) => {
                     system.input(sentence);
                     
@@ -184,7 +484,7 @@ fn main() -> Result<()> {
                 if let Some(c) = sys.memory.get(t) {
                     c.vector
                 } else {
-                    Hypervector::from_term(t)
+                    Hypervector::from_term_with(t, sys.tie_break)
                 }
             };
 
@@ -232,41 +532,100 @@ fn main() -> Result<()> {
             continue;
         }
 
-        match parse_narsese(trimmed) {
-            Ok(sentence) => {
-                println!("Parsed: {:?}", sentence);
+        // Several sentences may share a line; each is handled as if entered on its own
+        let parsed: Vec<Result<Sentence, NarsError>> = match system.parse_all(trimmed) {
+            Ok(sentences) => sentences.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        for parsed in parsed {
+            match parsed {
+                Ok(sentence) if sentence.punctuation == Punctuation::Question && question_wait > 0 => {
+                    let answers = system.answer_question(&sentence.term, question_wait);
+                    if answers.is_empty() {
+                        println!("Answer: none within {} cycles.", question_wait);
+                    }
+                    for answer in answers {
+                        println!("Answer: {} {} (after {} cycles)",
+                            answer.sentence.term.to_display_string(),
+                            system.truth_representation.format(answer.sentence.truth),
+                            answer.cycles
+                        );
+                        for source in &answer.sources {
+                            let label = system.evidence_labels(&source.stamp).join(", ");
+                            println!("  from {} {} ({})", source.term.to_display_string(), system.truth_representation.format(source.truth), label);
+                        }
+                    }
+                },
+                Ok(sentence) => {
+                    println!("Parsed: {:?}", sentence);
                 
-                // Run the input
-                system.input(sentence.clone());
-
-                // Run inference cycles
-                print!("Thinking...");
-                io::stdout().flush().unwrap();
-                for _ in 0..10 {
-                    system.cycle();
-                    print!(".");
+                    // Run the input
+                    system.input_from(sentence.clone(), Source::User);
+
+                    // Run inference cycles
+                    print!("Thinking...");
                     io::stdout().flush().unwrap();
-                }
-                println!();
-
-                // IF it was a Question, look for the answer
-                if sentence.punctuation == Punctuation::Question {
-                    if let Some(answer) = system.answer_query(&sentence.term) {
-                        println!("Answer: {} %{:.2};{:.2}%", 
-                            answer.term.to_display_string(), 
-                            answer.truth.frequency, 
-                            answer.truth.confidence
-                        );
-                    } else {
-                        println!("Answer: I don't know.");
+                    for _ in 0..10 {
+                        system.cycle();
+                        print!(".");
+                        io::stdout().flush().unwrap();
+                    }
+                    println!();
+
+                    // IF it was a Question, look for the answer
+                    if sentence.punctuation == Punctuation::Question {
+                        if let Some(answer) = system.answer_query(&sentence.term) {
+                            println!("Answer: {} {}",
+                                answer.term.to_display_string(),
+                                system.truth_representation.format(answer.truth)
+                            );
+                            for alternative in system.ranked_answers(&sentence.term).iter().filter(|r| r.sentence.stamp != answer.stamp) {
+                                println!("  or: {} {} (score {:.2}{})", alternative.sentence.term.to_display_string(),
+                                    system.truth_representation.format(alternative.sentence.truth), alternative.score,
+                                    if alternative.dependent { ", dependent evidence" } else { "" });
+                            }
+                        } else {
+                            println!("Answer: I don't know.");
+                        }
+                    }
+                    if sentence.punctuation == Punctuation::Quest {
+                        match system.answer_quest(&sentence.term) {
+                            Some(goal) => println!("Desired: {}! {}", goal.term.to_display_string(), system.truth_representation.format(goal.truth)),
+                            None => println!("Desired: nothing known."),
+                        }
                     }
-                }
                 
-                // Print top concepts in memory (simple debug view)
-                println!("Memory Size: {}", system.memory.len());
-            },
-            Err(e) => {
-                println!("Parse Error: {:?}", e);
+                    // Earlier questions that got a better answer during these cycles
+                    for event in system.answers.drain(..).filter(|e| e.refinement) {
+                        println!("Improved answer: {} {}", event.answer.term.to_display_string(),
+                            system.truth_representation.format(event.answer.truth));
+                    }
+
+                    for conflict in system.conflicts.drain(..) {
+                        println!("Goal conflict: pursuing {}! {} over {}! {}", conflict.winner.term.to_display_string(),
+                            system.truth_representation.format(conflict.winner.truth), conflict.loser.term.to_display_string(),
+                            system.truth_representation.format(conflict.loser.truth));
+                    }
+
+                    for question in system.hypotheses.drain(..) {
+                        println!("Curious: {}?", question.term.to_display_string());
+                    }
+
+                    for event in system.unanswered.drain(..) {
+                        print!("No answer yet: {}", event.question.term.to_display_string());
+                        if let Some(m) = event.best_match {
+                            print!(" (closest: {} %{:.2};{:.2}% similarity {:.2}{})", m.term.to_display_string(),
+                                m.truth.frequency, m.truth.confidence, m.similarity, if m.unifies { ", unifies" } else { "" });
+                        }
+                        println!();
+                    }
+
+                    // Print top concepts in memory (simple debug view)
+                    println!("Memory Size: {}", system.memory.len());
+                },
+                Err(e) => {
+                    println!("Parse Error: {:?}", e);
+                }
             }
         }
     }
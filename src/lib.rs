@@ -1 +1,2 @@
 pub mod nars;
+pub mod prelude;
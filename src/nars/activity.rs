@@ -0,0 +1,131 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use serde::Serialize;
+use super::term::Term;
+use super::error::NarsError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    Selection,   // taken from the attention buffer
+    Association, // pulled in as a reasoning partner
+    Derivation,  // premise or conclusion of an accepted derivation
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ActivityCounts {
+    pub selections: u64,
+    pub associations: u64,
+    pub derivations: u64,
+}
+
+impl ActivityCounts {
+    pub fn total(&self) -> u64 {
+        self.selections + self.associations + self.derivations
+    }
+
+    fn slot(&mut self, kind: ActivityKind) -> &mut u64 {
+        match kind {
+            ActivityKind::Selection => &mut self.selections,
+            ActivityKind::Association => &mut self.associations,
+            ActivityKind::Derivation => &mut self.derivations,
+        }
+    }
+}
+
+/// Per-concept activation counts over the last `window` cycles.
+#[derive(Debug, Clone)]
+pub struct ActivityTracker {
+    pub window: u64,
+    events: VecDeque<(u64, Term, ActivityKind)>,
+    counts: HashMap<Term, ActivityCounts>,
+}
+
+impl ActivityTracker {
+    pub fn new(window: u64) -> Self {
+        Self { window, events: VecDeque::new(), counts: HashMap::new() }
+    }
+
+    pub fn record(&mut self, cycle: u64, term: &Term, kind: ActivityKind) {
+        *self.counts.entry(term.clone()).or_default().slot(kind) += 1;
+        self.events.push_back((cycle, term.clone(), kind));
+    }
+
+    /// Forgets events that fell out of the window ending at `cycle`.
+    pub fn expire(&mut self, cycle: u64) {
+        while let Some((time, _, _)) = self.events.front() {
+            if time + self.window > cycle {
+                break;
+            }
+            let (_, term, kind) = self.events.pop_front().unwrap();
+            if let Some(counts) = self.counts.get_mut(&term) {
+                *counts.slot(kind) -= 1;
+                if counts.total() == 0 {
+                    self.counts.remove(&term);
+                }
+            }
+        }
+    }
+
+    pub fn counts(&self, term: &Term) -> ActivityCounts {
+        self.counts.get(term).copied().unwrap_or_default()
+    }
+
+    /// The `n` most active concepts, most active first (ties in display order).
+    pub fn hottest(&self, n: usize) -> Vec<(Term, ActivityCounts)> {
+        let mut all: Vec<(Term, ActivityCounts)> = self.counts.iter().map(|(t, c)| (t.clone(), *c)).collect();
+        all.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+        all.truncate(n);
+        all
+    }
+
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<(), NarsError> {
+        writeln!(writer, "term,selections,associations,derivations,total")?;
+        for (term, c) in self.hottest(usize::MAX) {
+            writeln!(writer, "\"{}\",{},{},{},{}", term.to_string().replace('"', "\"\""), c.selections, c.associations, c.derivations, c.total())?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let rows: Vec<serde_json::Value> = self.hottest(usize::MAX).into_iter().map(|(term, c)| {
+            serde_json::json!({
+                "term": term.to_string(),
+                "selections": c.selections,
+                "associations": c.associations,
+                "derivations": c.derivations,
+            })
+        }).collect();
+        serde_json::json!({ "window": self.window, "concepts": rows })
+    }
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sliding_window() {
+        let a = Term::atom_from_str("a");
+        let b = Term::atom_from_str("b");
+        let mut tracker = ActivityTracker::new(10);
+        tracker.record(0, &a, ActivityKind::Selection);
+        tracker.record(5, &b, ActivityKind::Association);
+        tracker.record(5, &b, ActivityKind::Derivation);
+        assert_eq!(tracker.hottest(1)[0].0, b);
+
+        tracker.expire(10);
+        assert_eq!(tracker.counts(&a), ActivityCounts::default());
+        assert_eq!(tracker.counts(&b).total(), 2);
+
+        let mut csv = Vec::new();
+        tracker.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "term,selections,associations,derivations,total\n\"b\",0,1,1,2\n");
+        assert_eq!(tracker.to_json()["concepts"][0]["derivations"], 1);
+    }
+}
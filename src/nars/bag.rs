@@ -2,7 +2,7 @@ use rand::Rng;
 use std::collections::HashMap;
 use std::hash::Hash;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Bag<T: Clone + Eq + Hash> {
     pub levels: Vec<Vec<T>>, // 100 levels of priority (0.00 to 0.99)
     pub capacity: usize,
@@ -18,6 +18,12 @@ impl<T: Clone + Eq + Hash> Bag<T> {
     }
 
     pub fn put(&mut self, item: T, priority: f32) {
+        self.put_unless(item, priority, |_| false);
+    }
+
+    /// Same as `put`, but a full bag only evicts its weakest item for which `keep` is false; if
+    /// it keeps every item, it grows past its capacity.
+    pub fn put_unless(&mut self, item: T, priority: f32, keep: impl Fn(&T) -> bool) {
         // If exists, remove old version first (update)
         if self.name_map.contains_key(&item) {
             self.take_specific(&item);
@@ -25,7 +31,7 @@ impl<T: Clone + Eq + Hash> Bag<T> {
         
         // Evict if full
         if self.count >= self.capacity {
-            self.evict_weakest();
+            self.take_weakest_unless(keep);
         }
 
         // Insert new
@@ -35,10 +41,9 @@ impl<T: Clone + Eq + Hash> Bag<T> {
         self.count += 1;
     }
 
-    pub fn take(&mut self) -> Option<T> {
+    pub fn take(&mut self, rng: &mut impl Rng) -> Option<T> {
         if self.count == 0 { return None; }
         
-        let mut rng = rand::rng();
         // Probabilistic selection: Bias towards top levels
         // Try 3 times to pick a non-empty level biased towards 100
         for _ in 0..3 {
@@ -66,6 +71,29 @@ impl<T: Clone + Eq + Hash> Bag<T> {
         None
     }
     
+    /// Up to `n` distinct items drawn uniformly at random, whatever their priority, without
+    /// removing them. They come in bag order, weakest first.
+    pub fn sample(&self, n: usize, rng: &mut impl Rng) -> Vec<&T> {
+        let mut picks = rand::seq::index::sample(rng, self.count, n.min(self.count)).into_vec();
+        picks.sort_unstable();
+        let mut items = Vec::with_capacity(picks.len());
+        let (mut picks, mut offset) = (picks.into_iter().peekable(), 0);
+        for level in &self.levels {
+            while let Some(idx) = picks.next_if(|idx| *idx < offset + level.len()) {
+                items.push(&level[idx - offset]);
+            }
+            offset += level.len();
+        }
+        items
+    }
+
+    /// Removes a specific item, returning its priority if it was present.
+    pub fn remove(&mut self, item: &T) -> Option<f32> {
+        let priority = self.name_map.get(item).copied()?;
+        self.take_specific(item);
+        Some(priority)
+    }
+
     fn take_specific(&mut self, item: &T) {
         if let Some(&p) = self.name_map.get(item) {
             let level = (p * 99.0).clamp(0.0, 99.0) as usize;
@@ -77,18 +105,24 @@ impl<T: Clone + Eq + Hash> Bag<T> {
         }
     }
 
-    // Remove weakest item (for eviction)
-    fn evict_weakest(&mut self) {
+    /// Removes the weakest item for which `keep` is false, leaving kept items in place.
+    pub fn take_weakest_unless(&mut self, keep: impl Fn(&T) -> bool) -> Option<T> {
         for level in 0..100 {
-            if !self.levels[level].is_empty() {
-                let item = self.levels[level].remove(0); // FIFO in lowest bucket
+            if let Some(pos) = self.levels[level].iter().position(|x| !keep(x)) {
+                let item = self.levels[level].remove(pos);
                 self.name_map.remove(&item);
                 self.count -= 1;
-                return;
+                return Some(item);
             }
         }
+        None
     }
-    
+
+    /// Up to `n` of the weakest items for which `keep` is false, weakest first.
+    pub fn weakest_unless(&self, n: usize, keep: impl Fn(&T) -> bool) -> Vec<&T> {
+        self.levels.iter().flatten().filter(|x| !keep(x)).take(n).collect()
+    }
+
     // For ConceptStore eviction (public helper)
     pub fn take_weakest(&mut self) -> Option<T> {
         for level in 0..100 {
@@ -0,0 +1,60 @@
+//! Truth calculator: the revision, projection and expectation arithmetic of the reasoner as
+//! plain functions, for preparing input truth values from data without building a system.
+//!
+//! ```
+//! use hybrid_nars_rust::nars::calc::{self, Observation};
+//!
+//! // Two independent sources: 8 of 10 cases observed, and a belief of %0.6;0.5%
+//! let revised = calc::revise(&[Observation::Counts { positive: 8.0, total: 10.0 }, Observation::Truth(0.6, 0.5)]);
+//! assert!(revised.confidence > 0.9);
+//! println!("<a --> b>. %{:.2};{:.2}%", revised.frequency, revised.confidence);
+//! ```
+use super::truth::{self, TruthValue, EVIDENTIAL_HORIZON};
+
+/// Default confidence decay per time step used by `project`.
+pub const PROJECTION_DECAY: f32 = 0.8;
+
+/// One independent piece of evidence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Observation {
+    /// A frequency and confidence, e.g. from another reasoner.
+    Truth(f32, f32),
+    /// Counted cases: `positive` of `total` observations support the statement.
+    Counts { positive: f64, total: f64 },
+}
+
+impl Observation {
+    pub fn truth(&self) -> TruthValue {
+        match *self {
+            Observation::Truth(frequency, confidence) => TruthValue::new(frequency.clamp(0.0, 1.0), confidence.clamp(0.0, 1.0)),
+            Observation::Counts { positive, total } => TruthValue::from_observations(positive, total, EVIDENTIAL_HORIZON),
+        }
+    }
+}
+
+/// Revised truth of independent observations, as the reasoner would revise them one by one.
+/// No observations give total ignorance, `%0.5;0.0%`.
+pub fn revise(observations: &[Observation]) -> TruthValue {
+    observations.iter()
+        .map(Observation::truth)
+        .reduce(truth::revision)
+        .unwrap_or(TruthValue::new(0.5, 0.0))
+}
+
+/// Truth of an event observed at `occurred` as seen from time `at`: the confidence decays by
+/// `decay` per time step of distance, in either direction.
+pub fn project(truth: TruthValue, occurred: i64, at: i64, decay: f32) -> TruthValue {
+    let distance = occurred.abs_diff(at).min(i32::MAX as u64) as i32;
+    TruthValue::new(truth.frequency, truth.confidence * decay.clamp(0.0, 1.0).powi(distance))
+}
+
+/// Eternal (timeless) truth generalized from a single event: `c / (c + k)`.
+pub fn eternalize(truth: TruthValue) -> TruthValue {
+    let k = EVIDENTIAL_HORIZON as f32;
+    TruthValue::new(truth.frequency, truth.confidence / (truth.confidence + k))
+}
+
+/// Expected frequency `c * (f - 0.5) + 0.5`, the value decisions are made on.
+pub fn expectation(truth: TruthValue) -> f32 {
+    truth.expectation()
+}
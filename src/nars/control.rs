@@ -1,273 +1,2175 @@
-use std::collections::HashMap;
-use std::cmp::Ordering;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs::File;
-use std::error::Error;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use super::term::{Term, Operator};
-use super::memory::{Concept, Hypervector, ConceptStore};
+use super::grounding::Grounding;
+use super::memory::{Concept, Hypervector, ConceptStore, ConfidenceErosion, DriftControl, ProjectionMatrix, Revision, TieBreak};
 use super::bag::Bag;
-use super::rules::{InferenceRule, TruthFunction};
-use super::static_rules::get_all_rules;
+use super::rules::{InferenceRule, TruthFunction, TruthRegistry, RuleMeta, DesireSelector, MAX_NAL_LEVEL};
+use super::static_rules::{dedup_rules, get_all_rules, parse_rules, RuleLoadReport};
 use super::glove::load_embeddings;
 use super::unify::{unify_with_bindings, Bindings};
-use super::sentence::{Sentence, Punctuation, Stamp};
-use super::truth::{TruthValue, revision};
+use super::sentence::{Budget, Sentence, Punctuation, Stamp, Source, TruthDefaults};
+use super::parser::{parse_narsese_all_with, parse_narsese_with, ParserOptions};
+use super::truth::{TruthValue, TruthInterval, TruthRepresentation, revision, desire_strong, desire_weak, temporal_induction, discount, deduction};
+use super::error::NarsError;
+use super::task::{Task, TaskLanes, LaneRatios, QuestionContext, QuestionScorer, information_value, AdmissionControl, BackpressureStats, Overflow};
+use super::sensor::SensorChannel;
+use super::operations::{OperationRegistry, OperationHandler};
+use super::activity::{ActivityTracker, ActivityKind};
+use super::pack::KnowledgePack;
+use super::pipeline::{match_backward, match_pair, rank_partners, MatchArena, PairMatches, Pipeline, Retrieval, ShapeCache};
+use super::metrics::{Histogram, Metrics};
+use super::events::EventMemory;
+use super::snapshot::{QueryHandle, Snapshot};
+use super::persist::{read_snapshot, write_snapshot};
+use super::calc::eternalize;
+
+const DEFAULT_TASK_PRIORITY: f32 = 0.5;
+const TASK_DECAY: f32 = 0.9;
+const ANALOGY_RESULTS: usize = 10;
+const DEFAULT_RULE_PRIORITY: f32 = 0.5;
+const REFINEMENT_BUDGET: f32 = 0.5; // extra decay of a question kept alive after a weak answer
+const DEPENDENT_ANSWER_PENALTY: f32 = 0.5; // score factor of answers whose evidence another one already covers
+const EVENT_CAPACITY: usize = 1000;
+const EVENT_INDUCTIONS: usize = 3; // earlier events each new event is inducted with
+const MAX_PATH_LENGTH: usize = 8; // statements in a chain found by `path`
+const MAX_TRACKED_ASSOCIATIONS: usize = 10_000; // association counts are reset beyond this
+const MAX_UNDRAINED_EVENTS: usize = 1000; // events kept per queue for clients, oldest dropped first
+const MAX_EVALUATED_PREMISES: usize = 10_000; // premises and premise pairs `evaluate` draws conclusions from
+
+/// One operation of a plan returned by `NarsSystem::plan`, in execution order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    pub operation: Term,
+    pub precondition: Option<Term>, // state the operation is executed in
+    pub outcome: Term,              // state the operation is expected to bring about
+    pub truth: TruthValue,          // desire of executing this step for the overall goal
+}
+
+/// Immutable resources that many reasoner instances can share: the rule table and the LSH
+/// projection matrix for embeddings. Cloning only bumps reference counts.
+#[derive(Clone)]
+pub struct SharedResources {
+    pub rules: Arc<Vec<InferenceRule>>,
+    pub projector: Option<Arc<ProjectionMatrix>>,
+}
+
+impl SharedResources {
+    /// Built-in rules, no projector (one is built on the first embedding load).
+    pub fn new() -> Self {
+        Self {
+            rules: Arc::new(get_all_rules()),
+            projector: None,
+        }
+    }
+
+    pub fn with_projector(mut self, projector: ProjectionMatrix) -> Self {
+        self.projector = Some(Arc::new(projector));
+        self
+    }
+}
+
+impl Default for SharedResources {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemStats {
+    pub derivations: u64,
+    pub suppressed_derivations: u64,
+    pub redundant_derivations: u64, // dropped by the novelty check
+    pub invalidations: u64,
+}
+
+/// How often a rule was applied and what became of its derivations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuleUsage {
+    pub fired: u64,
+    pub accepted: u64, // derivations that entered memory
+    pub rejected: u64, // derivations dropped as redundant, weak or suppressed
+}
+
+/// Closest belief to an unanswered question.
+#[derive(Debug, Clone)]
+pub struct PartialMatch {
+    pub term: Term,
+    pub truth: TruthValue,
+    pub similarity: f32, // vector similarity to the question
+    pub unifies: bool,   // whether the question term unifies with it (it would be an answer)
+}
+
+/// Answer to a question asked through `answer_question`.
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub sentence: Sentence,
+    pub cycles: usize,          // cycles run before it was found
+    pub sources: Vec<Sentence>, // input judgements it was derived from
+}
+
+/// The input an evidence id was introduced by.
+#[derive(Debug, Clone)]
+pub struct EvidenceRecord {
+    pub sentence: Sentence,
+    pub source: Source,
+    pub line: Option<usize>, // line of the script or file it was read from
+}
+
+/// State saved by `push_context`, restored by `pop_context`. Concept memory is not copied:
+/// it keeps a copy-on-write layer of its own (`ConceptStore::push_layer`). The event queues
+/// drained by clients are only cut back to their length on entry, and evidence recorded
+/// inside is recognized by its id.
+struct ContextFrame {
+    name: String,
+    buffer: Bag<Term>,
+    lanes: TaskLanes,
+    events: EventMemory,
+    scheduled: BTreeMap<u64, Vec<(Sentence, Source)>>,
+    recent_inputs: RecentInputs,
+    next_evidence_id: u64,
+    rule_priorities: Vec<f32>,
+    rule_usage: Vec<RuleUsage>,
+    stats: SystemStats,
+    activity: ActivityTracker,
+    associations: HashMap<(Term, Term), usize>,
+    answered: HashMap<Term, TruthValue>,
+    arbitrated: HashMap<Term, Term>,
+    cycle_count: u64,
+    reported: (usize, usize, usize, usize, usize), // lengths of output_buffer, answers, unanswered, hypotheses, conflicts
+}
+
+/// What makes two inputs identical for `NarsSystem::dedup_window`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct InputKey {
+    term: Term,
+    punctuation: Punctuation,
+    frequency: u32, // bits of the truth value
+    confidence: u32,
+    creation_time: u64,
+}
+
+/// Judgements and goals received recently, with the cycle each was last received in. Expired
+/// entries are swept once per window rather than on every input.
+#[derive(Debug, Clone, Default)]
+struct RecentInputs {
+    seen: HashMap<InputKey, u64>,
+    swept: u64, // window (cycle / dedup_window) of the last sweep
+}
+
+impl RecentInputs {
+    /// Records `sentence` as received in cycle `now`; false if it was already received within
+    /// the last `window` cycles.
+    fn admit(&mut self, sentence: &Sentence, now: u64, window: u64) -> bool {
+        if now / window != self.swept {
+            self.seen.retain(|_, seen| *seen + window > now);
+            self.swept = now / window;
+        }
+        let key = InputKey {
+            term: sentence.term.clone(),
+            punctuation: sentence.punctuation,
+            frequency: sentence.truth.frequency.to_bits(),
+            confidence: sentence.truth.confidence.to_bits(),
+            creation_time: sentence.stamp.creation_time,
+        };
+        self.seen.insert(key, now).is_none_or(|seen| seen + window <= now)
+    }
+}
+
+/// Whether a belief rests on input alone or was (partly) derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeliefOrigin {
+    Input,
+    Derived,
+}
+
+/// Selects the beliefs returned by `NarsSystem::export_beliefs`; the default keeps everything.
+#[derive(Debug, Clone, Default)]
+pub struct BeliefFilter {
+    pub min_confidence: f32,
+    pub max_age: Option<u64>,          // only beliefs with evidence input within this many cycles
+    pub eternal_only: bool,            // leave out beliefs resting on input events (tensed judgements)
+    pub origin: Option<BeliefOrigin>,
+    pub source: Option<String>,        // only beliefs with a source label starting with this
+}
+
+/// Emitted for every answer reported to a question. Answers to a question that was already
+/// answered are refinements: they are only reported when more confident than the last one.
+#[derive(Debug, Clone)]
+pub struct AnswerEvent {
+    pub question: Term,
+    pub answer: Sentence,
+    pub refinement: bool,
+    pub alternatives: Vec<RankedAnswer>, // every candidate, best first (see `ranked_answers`)
+}
+
+/// A candidate answer with its ranking score.
+#[derive(Debug, Clone)]
+pub struct RankedAnswer {
+    pub sentence: Sentence,
+    pub score: f32,
+    pub dependent: bool, // its evidence is contained in another candidate's
+}
+
+/// A chain of statements leading from one term to another, see `NarsSystem::path`.
+#[derive(Debug, Clone)]
+pub struct InferencePath {
+    pub steps: Vec<Sentence>, // `<from --> x>`, `<x --> y>`, ..., `<z --> to>` with their truth
+    pub truth: TruthValue,    // deduction composed along the chain
+}
+
+/// Best-effort estimate of a statement, see `NarsSystem::evaluate`.
+#[derive(Debug, Clone)]
+pub struct Expectation {
+    pub value: f32,            // expectation of `truth`, 0.5 without evidence
+    pub truth: TruthValue,     // revision of the independent sources, %0.5;0.0% without any
+    pub bounds: TruthInterval, // frequencies the evidence still allows
+    pub sources: Vec<Sentence>, // the beliefs and one-step conclusions revised, most confident first
+}
+
+impl Expectation {
+    /// Revises the candidates, most confident first, skipping those sharing evidence with
+    /// the ones already taken.
+    fn combine(mut candidates: Vec<Sentence>) -> Self {
+        candidates.sort_by(|a, b| b.truth.confidence.total_cmp(&a.truth.confidence));
+        let mut truth = TruthValue::new(0.5, 0.0);
+        let mut stamp: Option<Stamp> = None;
+        let mut sources = Vec::new();
+        for candidate in candidates {
+            if let Some(s) = &stamp {
+                if s.overlaps(&candidate.stamp) {
+                    continue;
+                }
+                truth = revision(truth, candidate.truth);
+                stamp = Some(s.merge(&candidate.stamp));
+            } else {
+                truth = candidate.truth;
+                stamp = Some(candidate.stamp.clone());
+            }
+            sources.push(candidate);
+        }
+        Self { value: truth.expectation(), truth, bounds: truth.into(), sources }
+    }
+}
+
+/// Emitted when goals on a term and on its negation are both desired (above
+/// `conflict_threshold`): the one with the higher desire expectation (the positive one on a tie)
+/// is pursued, the other stays queued but is neither executed nor turned into subgoals while it
+/// loses.
+#[derive(Debug, Clone)]
+pub struct GoalConflict {
+    pub winner: Sentence,
+    pub loser: Sentence,
+    pub cycle: u64,
+}
+
+/// Emitted when a question runs out of budget without an answer, so clients can tell
+/// "don't know yet" apart from a negative answer.
+#[derive(Debug, Clone)]
+pub struct Unanswered {
+    pub question: Sentence,
+    pub best_match: Option<PartialMatch>,
+}
+
+/// Inference breadth of a single cycle, and the truth policy for inputs.
+#[derive(Debug, Clone, Copy)]
+pub struct ReasonerConfig {
+    /// Concepts sampled from memory as association candidates.
+    pub sample_size: usize,
+    /// Most similar candidates (above the similarity threshold) that are reasoned with.
+    pub max_partners: usize,
+    /// Rule applications per premise pair; `None` applies every matching rule.
+    pub max_rule_applications: Option<usize>,
+    /// Also reason with the premises swapped, `reason(b, a)`.
+    pub bidirectional: bool,
+    /// Match rules on a background thread while the previous partner's inferences execute,
+    /// and meanwhile select the next task and retrieve its partners on another. Matches are
+    /// the same as sequential matching; the next task is selected before this cycle's
+    /// associations activate their partners.
+    pub pipelined: bool,
+    /// Ask for the missing premises of questions through the rules marked `:allow-backward`.
+    pub backward: bool,
+    /// Truth of judgements and goals input without one (see `NarsSystem::parse`).
+    pub truth_defaults: TruthDefaults,
+}
+
+impl Default for ReasonerConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 20,
+            max_partners: 20,
+            max_rule_applications: None,
+            bidirectional: true,
+            pipelined: false,
+            backward: true,
+            truth_defaults: TruthDefaults::default(),
+        }
+    }
+}
 
 pub struct NarsSystem {
     pub memory: ConceptStore,
-    pub rules: Vec<InferenceRule>,
+    pub packs: Vec<KnowledgePack>, // read-only layers beneath memory, later mounts shadow earlier ones
+    pub rules: Arc<Vec<InferenceRule>>, // shared until this instance adds its own rules
+    pub projector: Option<Arc<ProjectionMatrix>>,
+    pub truth_registry: TruthRegistry, // truth functions available to loaded rule files
+    pub rule_priorities: Vec<f32>, // learned usefulness per rule index (missing = default)
+    pub rule_usage: Vec<RuleUsage>, // per rule index (missing = never fired)
+    pub rule_enabled: Vec<bool>,   // per rule index (missing = enabled); disabled rules are never matched
+    pub nal_level: u8,             // rules above this layer are disabled when added
+    pub feedback_rate: f32,
     pub buffer: Bag<Term>,
     pub learning_rate: f32,
     pub similarity_threshold: f32,
+    pub min_confidence: f32,
+    pub min_priority: f32,
+    pub complexity_budget: usize, // derived terms above this complexity get proportionally less priority
+    pub max_complexity: usize,    // derived terms above this complexity are suppressed
+    pub goal_activation: f32,
+    pub goal_chain_depth: usize, // max implication hops followed when deriving subgoals
+    pub stats: SystemStats,
     pub output_buffer: Vec<Sentence>,
+    pub truth_representation: TruthRepresentation, // how clients should report truth values
+    pub activity: ActivityTracker,
+    pub unanswered: VecDeque<Unanswered>, // questions dropped without an answer, drained by clients
+    pub answers: VecDeque<AnswerEvent>,   // answers and refinements, drained by clients
+    pub refinement_confidence: f32,  // answers below this confidence keep their question alive
+    pub answer_latency: Histogram,   // cycles from question input to its first answer
+    pub vocabulary: HashMap<String, Hypervector>, // word -> projected embedding
+    pub perceptual: HashMap<Term, Hypervector>, // terms grounded in perceptual features, see `attach_vector`
+    pub grounding_weight: f32,
+    pub tie_break: TieBreak, // how bundles of an even number of vectors (compounds, derivations) break ties
+    pub drift_control: DriftControl,
+    pub erosion: ConfidenceErosion,
+    pub reasoner: ReasonerConfig,
+    pub merge_similarity: f32,
+    pub novelty_threshold: f32, // vector similarity above which a derivation without new evidence is redundant (> 1 disables)
+    pub tms_threshold: f32, // expectation drop that triggers dependency invalidation
+    pub sensors: Vec<SensorChannel>,
+    pub operations: OperationRegistry,
+    pub decision_threshold: f32, // desire expectation above which an operation goal is executed
+    pub conflict_threshold: f32, // desire expectation above which opposing goals conflict
+    pub conflicts: VecDeque<GoalConflict>, // arbitrated goal conflicts, drained by clients
+    pub lanes: TaskLanes,
+    pub lane_ratios: LaneRatios,
+    pub question_scorer: QuestionScorer, // weighs questions in their lane, `information_value` by default
+    pub admission: AdmissionControl, // which inputs are queued while cycles fall behind (unlimited by default)
+    pub backpressure: BackpressureStats,
+    pub cycle_count: u64,
+    pub dedup_window: u64, // cycles within which identical judgements and goals are dropped (0 disables)
+    pub symmetric_copulas: bool, // `<a <-> b>` and `<b <-> a>` share one concept
+    pub curiosity: usize, // associations of an unlinked pair before it is asked about (0 disables)
+    pub hypotheses: Vec<Sentence>, // questions raised by curiosity, drained by clients
+    pub events: EventMemory, // recent tensed judgements; concepts only hold the latest one, eternalized
+    pub event_window: u64, // cycles back a new event is related to by temporal induction (0 disables)
+    pub snapshot_interval: u64, // cycles between snapshots published to query handles
+    pub evidence_capacity: usize, // input evidence ids `evidence` keeps a record of, oldest dropped first
+    pub trace: bool, // print every accepted derivation to stderr
+    associations: HashMap<(Term, Term), usize>, // unordered pair -> times associated
+    scheduled: BTreeMap<u64, Vec<(Sentence, Source)>>, // cycle -> inputs deferred to it
+    arrivals: (u64, usize), // inputs received in the given cycle, for `admission.rate`
+    answered: HashMap<Term, TruthValue>, // last answer reported to each live question
+    arbitrated: HashMap<Term, Term>, // positive term of a goal conflict -> goal term that won it last
+    started: Instant,
+    pipeline: Option<Pipeline>, // started by the first pipelined cycle
+    prefetched: Option<(Term, Retrieval)>, // next task, its partners being retrieved
+    shape_cache: ShapeCache, // rule premises known not to unify with a term shape
+    used: Vec<Term>, // concepts used this cycle, counted on them after it
+    next_task_id: u64,
+    next_evidence_id: u64,
+    recent_inputs: RecentInputs,
+    evidence_registry: BTreeMap<u64, EvidenceRecord>,
+    source_reliability: HashMap<Source, f32>,
+    contexts: Vec<ContextFrame>, // innermost last
+    query_handle: Option<QueryHandle>, // refreshed every `snapshot_interval` cycles while held
+    rng: StdRng, // behind every random choice of the cycle, see `seed`
 }
 
 impl NarsSystem {
     pub fn new(learning_rate: f32, similarity_threshold: f32) -> Self {
-        let rules = get_all_rules();
+        Self::with_resources(learning_rate, similarity_threshold, SharedResources::new())
+    }
+
+    /// Creates an instance on top of resources shared with other instances.
+    pub fn with_resources(learning_rate: f32, similarity_threshold: f32, resources: SharedResources) -> Self {
         Self {
             memory: ConceptStore::new(10000),
-            rules,
+            packs: Vec::new(),
+            rules: resources.rules,
+            projector: resources.projector,
+            truth_registry: TruthRegistry::new(),
+            rule_priorities: Vec::new(),
+            rule_usage: Vec::new(),
+            rule_enabled: Vec::new(),
+            nal_level: MAX_NAL_LEVEL,
+            feedback_rate: 0.1,
             buffer: Bag::new(100),
             learning_rate,
             similarity_threshold,
+            min_confidence: 0.01,
+            min_priority: 0.0,
+            complexity_budget: 10,
+            max_complexity: 50,
+            goal_activation: 1.5,
+            goal_chain_depth: 5,
+            stats: SystemStats::default(),
             output_buffer: Vec::new(),
+            truth_representation: TruthRepresentation::default(),
+            activity: ActivityTracker::default(),
+            unanswered: VecDeque::new(),
+            answers: VecDeque::new(),
+            refinement_confidence: 0.9,
+            answer_latency: Histogram::default(),
+            vocabulary: HashMap::new(),
+            perceptual: HashMap::new(),
+            grounding_weight: 0.3,
+            tie_break: TieBreak::PositionHash,
+            drift_control: DriftControl::default(),
+            erosion: ConfidenceErosion::default(),
+            reasoner: ReasonerConfig::default(),
+            merge_similarity: 0.9,
+            novelty_threshold: 0.95,
+            tms_threshold: 0.2,
+            sensors: Vec::new(),
+            operations: OperationRegistry::new(),
+            decision_threshold: 0.6,
+            conflict_threshold: 0.6,
+            conflicts: VecDeque::new(),
+            lanes: TaskLanes::new(100),
+            lane_ratios: LaneRatios::default(),
+            question_scorer: information_value,
+            admission: AdmissionControl::default(),
+            backpressure: BackpressureStats::default(),
+            cycle_count: 0,
+            dedup_window: 10,
+            symmetric_copulas: true,
+            curiosity: 0,
+            hypotheses: Vec::new(),
+            events: EventMemory::new(EVENT_CAPACITY),
+            event_window: 10,
+            snapshot_interval: 10,
+            evidence_capacity: 10_000,
+            trace: false,
+            associations: HashMap::new(),
+            scheduled: BTreeMap::new(),
+            arrivals: (0, 0),
+            answered: HashMap::new(),
+            arbitrated: HashMap::new(),
+            started: Instant::now(),
+            pipeline: None,
+            prefetched: None,
+            shape_cache: ShapeCache::new(),
+            used: Vec::new(),
+            next_task_id: 0,
+            next_evidence_id: 1,
+            recent_inputs: RecentInputs::default(),
+            evidence_registry: BTreeMap::new(),
+            source_reliability: HashMap::new(),
+            contexts: Vec::new(),
+            query_handle: None,
+            rng: StdRng::from_os_rng(),
         }
     }
 
+    /// Makes runs reproducible: every random choice of the cycle (which concept and task are
+    /// selected, which partners are associated, which events temporal induction relates)
+    /// is drawn from a generator seeded with `seed` from here on.
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     pub fn resolve_vector(&self, term: &Term) -> Hypervector {
         if let Some(concept) = self.memory.get(term) {
             return concept.vector;
         }
+        if let Some(vector) = self.perceptual.get(term) {
+            return *vector;
+        }
+        if let Some(vector) = self.packs.iter().rev().find_map(|pack| Some(pack.vector(pack.find(term)?))) {
+            return vector;
+        }
         match term {
             Term::Compound(op, args) => {
                 let arg_vectors: Vec<Hypervector> = args.iter().map(|a| self.resolve_vector(a)).collect();
-                Hypervector::compound(op, &arg_vectors)
+                Hypervector::compound_with(op, &arg_vectors, self.tie_break)
             },
-            _ => Hypervector::from_term(term),
+            _ => Hypervector::from_term_with(term, self.tie_break),
         }
     }
 
-    pub fn input(&mut self, sentence: Sentence) {
-        let vector = self.resolve_vector(&sentence.term);
-        let concept = Concept::new(sentence.term, vector, sentence.truth, sentence.stamp);
-        self.add_concept(concept, sentence.punctuation == Punctuation::Judgement);
+    /// Parses Narsese with this system's input policy: sentences written without a truth value
+    /// get `reasoner.truth_defaults`.
+    pub fn parse(&self, text: &str) -> Result<Sentence, NarsError> {
+        parse_narsese_with(text, &self.parser_options())
+    }
+
+    /// Parses every sentence of a snippet, see `parse_narsese_all`.
+    pub fn parse_all(&self, text: &str) -> Result<Vec<Sentence>, NarsError> {
+        parse_narsese_all_with(text, &self.parser_options())
+    }
+
+    fn parser_options(&self) -> ParserOptions {
+        ParserOptions { truth_defaults: self.reasoner.truth_defaults, ..ParserOptions::default() }
+    }
+
+    /// Queues a sentence in the lane matching its punctuation; it is processed by `cycle()`.
+    /// Unstamped sentences (as produced by the parser) are stamped here: judgements and goals
+    /// get a fresh evidential base so overlap checks and dependents work, and a zero creation
+    /// time becomes the current cycle. A judgement or goal identical to one received within the
+    /// last `dedup_window` cycles is dropped (questions and quests may be asked again), as is
+    /// one `admission` refuses and cannot merge (see `backpressure`); returns false in those cases.
+    pub fn input(&mut self, sentence: Sentence) -> bool {
+        self.input_from(sentence, Source::User)
+    }
+
+    /// Same as `input` for a sentence arriving through `source`: the source is added to its
+    /// stamp and registered as the origin of its evidence (see `evidence`), and the confidence
+    /// of judgements and goals is scaled by the source's reliability.
+    pub fn input_from(&mut self, sentence: Sentence, source: Source) -> bool {
+        self.receive(sentence, source, None)
+    }
+
+    /// Same as `input_from` for line `line` of a script or file read through `source`; the line
+    /// is kept in the evidence record.
+    pub fn input_from_line(&mut self, sentence: Sentence, source: Source, line: usize) -> bool {
+        self.receive(sentence, source, Some(line))
+    }
+
+    fn receive(&mut self, mut sentence: Sentence, source: Source, line: Option<usize>) -> bool {
+        sentence.term = self.canonical(&sentence.term);
+        if self.dedup_window > 0
+            && matches!(sentence.punctuation, Punctuation::Judgement | Punctuation::Goal)
+            && !self.recent_inputs.admit(&sentence, self.cycle_count, self.dedup_window)
+        {
+            return false;
+        }
+
+        if sentence.stamp.evidence.is_empty() && matches!(sentence.punctuation, Punctuation::Judgement | Punctuation::Goal) {
+            sentence.stamp.evidence.push(self.next_evidence_id);
+            self.next_evidence_id += 1;
+        }
+        if sentence.stamp.creation_time == 0 {
+            sentence.stamp.creation_time = self.cycle_count;
+        }
+        self.attribute(&mut sentence, source, line);
+        let priority = sentence.budget.map_or(DEFAULT_TASK_PRIORITY, |b| b.priority);
+        self.admit(Task::new(sentence, priority))
+    }
+
+    /// Queues an input task if `admission` lets it in, otherwise merges or drops it; returns
+    /// false if it was dropped.
+    fn admit(&mut self, task: Task) -> bool {
+        if self.arrivals.0 != self.cycle_count {
+            self.arrivals = (self.cycle_count, 0);
+        }
+        let pending = self.lanes.len();
+        self.backpressure.peak_pending = self.backpressure.peak_pending.max(pending);
+        let admitted = self.admission.admits(task.priority, pending, self.arrivals.1);
+        self.arrivals.1 += 1;
+        if admitted {
+            self.backpressure.admitted += 1;
+            self.enqueue_task(task);
+            return true;
+        }
+        let mut task = task;
+        task.sentence.term = self.canonical(&task.sentence.term);
+        if self.admission.overflow == Overflow::Merge
+            && self.lanes.lane_mut(task.sentence.punctuation).merge(&task.sentence, task.priority)
+        {
+            self.backpressure.merged += 1;
+            return true;
+        }
+        self.backpressure.dropped += 1;
+        false
     }
 
+    /// Defers `sentence` until logical cycle `cycle` (the value of `cycle_count`): it is input
+    /// at the start of the cycle run from then on, before the task lanes are processed, e.g.
+    /// a stimulus at `t` whose response is checked at `t + n`. Past cycles input right away.
+    pub fn input_at(&mut self, sentence: Sentence, cycle: u64) {
+        self.input_at_from(sentence, cycle, Source::User);
+    }
+
+    /// Same as `input_at` for a sentence arriving through `source` (see `input_from`).
+    pub fn input_at_from(&mut self, sentence: Sentence, cycle: u64, source: Source) {
+        if cycle <= self.cycle_count {
+            self.input_from(sentence, source);
+        } else {
+            self.scheduled.entry(cycle).or_default().push((sentence, source));
+        }
+    }
+
+    /// Number of inputs deferred to future cycles.
+    pub fn scheduled_inputs(&self) -> usize {
+        self.scheduled.values().map(Vec::len).sum()
+    }
+
+    fn inject_scheduled(&mut self) {
+        let later = self.scheduled.split_off(&(self.cycle_count + 1));
+        let due = std::mem::replace(&mut self.scheduled, later);
+        for (sentence, source) in due.into_values().flatten() {
+            self.input_from(sentence, source);
+        }
+    }
+
+    /// Weight in [0, 1] that scales the initial confidence of inputs from `source` (default 1).
+    pub fn set_source_reliability(&mut self, source: Source, weight: f32) {
+        self.source_reliability.insert(source, weight.clamp(0.0, 1.0));
+    }
+
+    pub fn source_reliability(&self, source: &Source) -> f32 {
+        self.source_reliability.get(source).copied().unwrap_or(1.0)
+    }
+
+    /// Reliability of evidence resting on the sources of `stamp`: that of its least reliable source.
+    fn stamp_reliability(&self, stamp: &Stamp) -> f32 {
+        stamp.sources.iter().map(|s| self.source_reliability(s)).fold(1.0, f32::min)
+    }
+
+    fn attribute(&mut self, sentence: &mut Sentence, source: Source, line: Option<usize>) {
+        if matches!(sentence.punctuation, Punctuation::Judgement | Punctuation::Goal) {
+            sentence.truth.confidence *= self.source_reliability(&source);
+        }
+        sentence.stamp.add_source(source.clone());
+        for id in &sentence.stamp.evidence {
+            self.evidence_registry.entry(*id).or_insert_with(|| EvidenceRecord { sentence: sentence.clone(), source: source.clone(), line });
+        }
+        while self.evidence_registry.len() > self.evidence_capacity {
+            self.evidence_registry.pop_first();
+        }
+    }
+
+    /// The input sentence and source that introduced evidence `id`, if it is among the latest
+    /// `evidence_capacity` input ids.
+    pub fn evidence(&self, id: u64) -> Option<&EvidenceRecord> {
+        self.evidence_registry.get(&id)
+    }
+
+    /// Source labels of the inputs behind `stamp`, in evidence order: the source, with the line
+    /// it was read from as `source:line` (unregistered ids as `#id`).
+    pub fn evidence_labels(&self, stamp: &Stamp) -> Vec<String> {
+        stamp.evidence.iter()
+            .map(|id| match self.evidence(*id) {
+                Some(EvidenceRecord { source, line: Some(line), .. }) => format!("{}:{}", source, line),
+                Some(record) => record.source.to_string(),
+                None => format!("#{}", id),
+            })
+            .collect()
+    }
+
+    /// Loads many statements at once, e.g. from an external knowledge base. Judgements get fresh
+    /// evidence ids, repeated terms are revised within the batch, and the result goes straight
+    /// into memory without passing through the task lanes or reporting each revision.
+    /// With `parallel`, the vectors are computed on worker threads before integration.
+    /// Other sentences are queued as by `input`. Returns the number of concepts integrated.
+    pub fn input_batch(&mut self, sentences: &[Sentence], parallel: bool) -> usize {
+        self.input_batch_from(sentences, parallel, Source::Import("batch".to_string()))
+    }
+
+    /// Same as `input_batch` for sentences imported from `source` (see `input_from`).
+    pub fn input_batch_from(&mut self, sentences: &[Sentence], parallel: bool, source: Source) -> usize {
+        let mut merged: Vec<Sentence> = Vec::new();
+        let mut index: HashMap<Term, usize> = HashMap::new();
+        for sentence in sentences {
+            if sentence.punctuation != Punctuation::Judgement {
+                self.input_from(sentence.clone(), source.clone());
+                continue;
+            }
+            let mut sentence = sentence.clone();
+            sentence.term = self.canonical(&sentence.term);
+            if sentence.stamp.evidence.is_empty() {
+                sentence.stamp.evidence.push(self.next_evidence_id);
+                self.next_evidence_id += 1;
+            }
+            self.attribute(&mut sentence, source.clone(), None);
+            match index.get(&sentence.term) {
+                Some(&i) => {
+                    let existing = &mut merged[i];
+                    existing.truth = revision(existing.truth, sentence.truth);
+                    existing.stamp = existing.stamp.merge(&sentence.stamp);
+                }
+                None => {
+                    index.insert(sentence.term.clone(), merged.len());
+                    merged.push(sentence);
+                }
+            }
+        }
+
+        let vectors = self.batch_vectors(&merged, parallel);
+        let integrated = merged.len();
+        let reported = self.output_buffer.len();
+        for (sentence, vector) in merged.into_iter().zip(vectors) {
+            self.add_concept(Concept::new(sentence.term, vector, sentence.truth, sentence.stamp), true);
+        }
+        self.output_buffer.truncate(reported);
+        integrated
+    }
+
+    fn batch_vectors(&self, sentences: &[Sentence], parallel: bool) -> Vec<Hypervector> {
+        let threads = if parallel { std::thread::available_parallelism().map_or(1, |n| n.get()) } else { 1 };
+        if threads <= 1 || sentences.len() < 2 {
+            return sentences.iter().map(|s| self.resolve_vector(&s.term)).collect();
+        }
+        let chunk = sentences.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = sentences.chunks(chunk)
+                .map(|part| scope.spawn(move || part.iter().map(|s| self.resolve_vector(&s.term)).collect::<Vec<_>>()))
+                .collect();
+            workers.into_iter().flat_map(|w| w.join().expect("vector worker panicked")).collect()
+        })
+    }
+
+    /// The concept key for `term`: commutative compounds in normal form, and symmetric
+    /// copulas ordered when `symmetric_copulas` is set.
+    pub fn canonical(&self, term: &Term) -> Term {
+        let term = term.with_commutative_order();
+        if self.symmetric_copulas { term.with_symmetric_order() } else { term }
+    }
+
+    fn enqueue_task(&mut self, mut task: Task) {
+        task.sentence.term = self.canonical(&task.sentence.term);
+        let id = self.next_task_id;
+        self.next_task_id += 1;
+        if task.sentence.punctuation == Punctuation::Question {
+            let value = (self.question_scorer)(&self.question_context(&task));
+            self.lanes.questions.put_weighted(id, task, value);
+            return;
+        }
+        self.lanes.lane_mut(task.sentence.punctuation).put(id, task);
+    }
+
+    /// What `question_scorer` weighs a question task by: its best answer so far and the goals
+    /// and statements in memory that wait on the answer. A goal waits on `q` if it is on `q`
+    /// itself or on the predicate of an implication `<q ==> g>` in memory.
+    pub fn question_context(&self, task: &Task) -> QuestionContext {
+        let term = &task.sentence.term;
+        let desire = |t: &Term| self.memory.get(t).and_then(Concept::desire).map_or(0.0, |g| g.truth.expectation());
+        let (mut blocked_goals, mut blocked_derivations) = (desire(term), 0);
+        for statement in self.memory.statements_from(term).filter(|c| !c.beliefs.is_empty()) {
+            blocked_derivations += 1;
+            if let Term::Compound(Operator::Implication, args) = statement.term.term() {
+                blocked_goals += desire(&args[1]);
+            }
+        }
+        QuestionContext {
+            priority: task.priority,
+            answer: self.answered.get(term).copied(),
+            blocked_goals,
+            blocked_derivations,
+            asked_by_user: task.sentence.stamp.sources.contains(&Source::User),
+        }
+    }
+
+    fn integrate(&mut self, sentence: Sentence) {
+        if sentence.is_event() && sentence.punctuation == Punctuation::Judgement {
+            self.integrate_event(sentence);
+            return;
+        }
+        if sentence.punctuation == Punctuation::Judgement {
+            let truth = sentence.truth;
+            let vector = self.resolve_vector(&sentence.term);
+            let (term, budget) = (sentence.term.clone(), sentence.budget);
+            let concept = Concept::new(sentence.term, vector, truth, sentence.stamp);
+            self.add_concept(concept, true);
+            if let Some(budget) = budget {
+                self.apply_budget(&term, budget);
+            }
+            return;
+        }
+
+        // Questions, goals and quests are attached to the concept without touching its beliefs
+        let mut concept = match self.memory.take(&sentence.term) {
+            Some(c) => c,
+            None => {
+                let vector = self.resolve_vector(&sentence.term);
+                Concept::new(sentence.term.clone(), vector, TruthValue::new(0.5, 0.0), Stamp::new(0, vec![]))
+            }
+        };
+        let budget = sentence.budget;
+        concept.add_task(sentence);
+        let priority = (concept.priority * concept.durability).clamp(0.01, 0.99);
+        self.attend(concept.term.term().clone(), priority);
+        let term = concept.term.clone();
+        self.memory.put(concept);
+        if let Some(budget) = budget {
+            self.apply_budget(&term, budget);
+        }
+    }
+
+    /// Integrates an event (tensed judgement): it goes to event memory, and its concept holds
+    /// the latest event as its present state. Each event replaces the previous one instead of
+    /// being revised in, so readings do not pile up as eternal evidence.
+    fn integrate_event(&mut self, event: Sentence) {
+        let truth = self.record_event(&event);
+        let (budget, stamp) = (event.budget, event.stamp.clone());
+        let mut concept = match self.memory.take(&event.term) {
+            Some(c) => c,
+            None => {
+                let vector = self.resolve_vector(&event.term);
+                Concept::new(event.term.clone(), vector, truth, stamp.clone())
+            }
+        };
+        concept.beliefs.retain(|b| !b.is_event());
+        concept.add_belief(Sentence { truth, budget: None, ..event });
+        concept.truth = truth;
+        concept.stamp = stamp;
+        let priority = (concept.priority * concept.durability).clamp(0.01, 0.99);
+        self.attend(concept.term.term().clone(), priority);
+        let term = concept.term.clone();
+        self.memory.put(concept);
+        if let Some(budget) = budget {
+            self.apply_budget(&term, budget);
+        }
+    }
+
+    /// Keeps an event in event memory and relates it to a few earlier ones by temporal
+    /// induction. Returns the truth its concept gets: the event generalized to eternal.
+    fn record_event(&mut self, event: &Sentence) -> TruthValue {
+        if self.event_window > 0 {
+            let earlier: Vec<Sentence> = self.events.sample_before(event, self.event_window, EVENT_INDUCTIONS, &mut self.rng)
+                .into_iter().cloned().collect();
+            for earlier in &earlier {
+                self.induce_temporal(earlier, event);
+            }
+        }
+        self.events.push(event.clone());
+        eternalize(event.truth)
+    }
+
+    /// Gives the concept of `term` the budget its input asked for, in memory and in the buffer.
+    fn apply_budget(&mut self, term: &Term, budget: Budget) {
+        if let Some(mut concept) = self.memory.take(term) {
+            concept.priority = budget.priority;
+            concept.durability = budget.durability;
+            concept.quality = concept.quality.max(budget.quality);
+            self.attend(term.clone(), (budget.priority * budget.durability).clamp(0.01, 0.99));
+            self.memory.put(concept);
+        }
+    }
+
+    /// Picks the lane scheduled for this cycle, falling through to the next non-empty one.
+    fn process_lanes(&mut self) {
+        let scheduled = self.lane_ratios.lane_for(self.cycle_count);
+        self.cycle_count += 1;
+
+        let start = LaneRatios::ORDER.iter().position(|p| *p == scheduled).unwrap_or(0);
+        for offset in 0..LaneRatios::ORDER.len() {
+            let punctuation = LaneRatios::ORDER[(start + offset) % LaneRatios::ORDER.len()];
+            if offset > 0 && self.lane_ratios.weight(punctuation) == 0 {
+                continue;
+            }
+            if let Some((_, task)) = self.lanes.lane_mut(punctuation).take(&mut self.rng) {
+                self.process_task(task);
+                return;
+            }
+        }
+    }
+
+    fn process_task(&mut self, task: Task) {
+        match task.sentence.punctuation {
+            Punctuation::Judgement => self.integrate(task.sentence),
+            Punctuation::Question => {
+                if let Some(answer) = self.answer_query(&task.sentence.term) {
+                    let term = task.sentence.term.clone();
+                    self.report_answer(&task.sentence, answer);
+                    // Anytime answering: a weak answer keeps the question alive at a lower budget
+                    let mut task = task;
+                    task.priority *= REFINEMENT_BUDGET;
+                    if self.answered[&term].confidence >= self.refinement_confidence || !self.requeue(task) {
+                        self.answered.remove(&term);
+                    }
+                    return;
+                }
+                self.integrate(task.sentence.clone());
+                let question = task.sentence.clone();
+                if !self.requeue(task) {
+                    let best_match = self.best_partial_match(&question.term);
+                    push_undrained(&mut self.unanswered, Unanswered { question, best_match });
+                }
+            },
+            Punctuation::Goal => {
+                self.integrate(task.sentence.clone());
+                if !self.arbitrate(&task.sentence.term) {
+                    // Kept for when the conflict turns its way, until its budget runs out
+                    self.requeue(task);
+                    return;
+                }
+                if task.sentence.term.is_operation() && task.sentence.truth.expectation() > self.decision_threshold {
+                    // Executed operations are done; unknown ones stay goals
+                    if self.execute(&task.sentence.term).is_ok() {
+                        return;
+                    }
+                }
+                // Expanded once: requeued, it waits for execution without re-deriving its subgoals
+                if !task.expanded {
+                    self.spread_goal_activation(&task.sentence.term, task.priority);
+                    self.derive_subgoals(&task.sentence, task.priority);
+                }
+                let mut task = task;
+                task.expanded = true;
+                self.requeue(task);
+            },
+            Punctuation::Quest => {
+                if let Some(answer) = self.answer_quest(&task.sentence.term) {
+                    self.output_buffer.push(answer);
+                    return;
+                }
+                self.integrate(task.sentence.clone());
+                self.requeue(task);
+            },
+        }
+    }
+
+    /// Whether the goal on `term` may be pursued: not if a goal on its negation (or, for a
+    /// negation, on the negated term) is more desired while both are above
+    /// `conflict_threshold`; a tie goes to the positive goal. A conflict is reported in
+    /// `conflicts` when it is first arbitrated and whenever the other side wins it.
+    fn arbitrate(&mut self, term: &Term) -> bool {
+        let (positive, opposite) = match term {
+            Term::Compound(Operator::Negation, args) if args.len() == 1 => (args[0].clone(), args[0].clone()),
+            _ => (term.clone(), self.canonical(&Term::negation(term.clone()))),
+        };
+        let desired = |t: &Term| self.memory.get(t).and_then(Concept::desire)
+            .filter(|g| g.truth.expectation() > self.conflict_threshold).cloned();
+        let (Some(goal), Some(rival)) = (desired(term), desired(&opposite)) else {
+            self.arbitrated.remove(&positive);
+            return true;
+        };
+        let wins = match goal.truth.expectation().total_cmp(&rival.truth.expectation()) {
+            std::cmp::Ordering::Equal => *term == positive,
+            ordering => ordering.is_gt(),
+        };
+        let (winner, loser) = if wins { (goal, rival) } else { (rival, goal) };
+        if self.arbitrated.get(&positive) != Some(&winner.term) {
+            self.arbitrated.insert(positive, winner.term.clone());
+            push_undrained(&mut self.conflicts, GoalConflict { winner, loser, cycle: self.cycle_count });
+        }
+        wins
+    }
+
+    /// Reports `answer` to `question` unless an answer at least as confident was reported
+    /// before; a later, better answer is flagged as a refinement.
+    fn report_answer(&mut self, question: &Sentence, answer: Sentence) {
+        let previous = self.answered.get(&question.term).copied();
+        if previous.is_some_and(|truth| answer.truth.confidence <= truth.confidence) {
+            return;
+        }
+        if previous.is_none() {
+            self.answer_latency.observe(self.cycle_count.saturating_sub(question.stamp.creation_time));
+        }
+        let question = &question.term;
+        self.answered.insert(question.clone(), answer.truth);
+        self.output_buffer.push(answer.clone());
+        let alternatives = self.ranked_answers(question);
+        push_undrained(&mut self.answers, AnswerEvent { question: question.clone(), answer, refinement: previous.is_some(), alternatives });
+    }
+
+    /// Spreading activation from an active goal: concepts related to the goal (see
+    /// `ConceptStore::related_to`) whose vectors are similar to it get their attention priority
+    /// raised, the most similar first, so the forward reasoner explores them first.
+    /// Returns the number of concepts boosted.
+    pub fn spread_goal_activation(&mut self, goal_term: &Term, goal_priority: f32) -> usize {
+        let goal_vector = self.resolve_vector(goal_term);
+        let sample_size = 50;
+        let mut candidates: Vec<(Term, f32)> = self.memory.related_to(goal_term).into_iter()
+            .filter(|c| c.term != *goal_term)
+            .map(|c| (c.term.term().clone(), goal_vector.similarity(&c.vector)))
+            .filter(|(_, sim)| *sim >= self.similarity_threshold)
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(sample_size);
+
+        let mut boosted = 0;
+        for (term, sim) in candidates {
+            let boost = (goal_priority * sim * self.goal_activation).clamp(0.01, 0.99);
+            let current = self.buffer.name_map.get(&term).copied().unwrap_or(0.0);
+            if boost > current {
+                self.attend(term, boost);
+                boosted += 1;
+            }
+        }
+        boosted
+    }
+
+    /// Propagates desire through implication beliefs, starting from `goal`:
+    /// goal `P!` with `<S ==> P>` gives `S!` (desire_strong), goal `S!` with `<S ==> P>` gives
+    /// `P!` (desire_weak); equivalences propagate strongly both ways. Subgoals are expanded best
+    /// desire first, so each term keeps its best-supported derivation. Each hop scales the budget
+    /// by the belief's confidence, and a chain stops after `goal_chain_depth` hops or once the
+    /// budget falls below the priority floor. Derived subgoals are attached to their concepts.
+    pub fn derive_subgoals(&mut self, goal: &Sentence, priority: f32) -> Vec<Sentence> {
+        let floor = self.min_priority.max(0.01);
+        let mut derived = Vec::new();
+        let mut visited = vec![goal.term.clone()];
+        let mut open = self.subgoals_of(goal, priority, 1);
+
+        while let Some(best) = (0..open.len()).max_by(|&a, &b| open[a].0.truth.confidence.total_cmp(&open[b].0.truth.confidence)) {
+            let (subgoal, budget, depth) = open.swap_remove(best);
+            if budget < floor || subgoal.truth.confidence < self.min_confidence || visited.contains(&subgoal.term) {
+                continue;
+            }
+            visited.push(subgoal.term.clone());
+            if depth < self.goal_chain_depth {
+                open.extend(self.subgoals_of(&subgoal, budget, depth + 1));
+            }
+
+            self.integrate(subgoal.clone());
+            self.output_buffer.push(subgoal.clone());
+            derived.push(subgoal);
+        }
+        derived
+    }
+
+    /// One step of goal derivation from `goal` through the implication/equivalence beliefs in memory.
+    fn subgoals_of(&self, goal: &Sentence, budget: f32, depth: usize) -> Vec<(Sentence, f32, usize)> {
+        let mut subgoals = Vec::new();
+        for concept in self.memory.statements_about(&goal.term).filter(|c| !c.beliefs.is_empty()) {
+            let Term::Compound(op, args) = concept.term.term() else { continue };
+            if args.len() != 2 || concept.stamp.overlaps(&goal.stamp) {
+                continue;
+            }
+            let (subject, predicate) = (&args[0], &args[1]);
+            let implication = matches!(op, Operator::Implication | Operator::PredictiveImplication | Operator::ConcurrentImplication);
+            let equivalence = matches!(op, Operator::Equivalence | Operator::PredictiveEquivalence | Operator::ConcurrentEquivalence);
+
+            let step = if (implication || equivalence) && *predicate == goal.term {
+                Some((subject, desire_strong(goal.truth, concept.truth)))
+            } else if equivalence && *subject == goal.term {
+                Some((predicate, desire_strong(goal.truth, concept.truth)))
+            } else if implication && *subject == goal.term {
+                Some((predicate, desire_weak(goal.truth, concept.truth)))
+            } else {
+                None
+            };
+
+            if let Some((term, desire)) = step {
+                let stamp = goal.stamp.merge(&concept.stamp);
+                subgoals.push((Sentence::new(term.clone(), Punctuation::Goal, desire, stamp), budget * concept.truth.confidence, depth));
+            }
+        }
+        subgoals
+    }
+
+    /// Backward-chains from `goal` through procedural beliefs `<(&/, state, ^op) =/> goal>` (and
+    /// plain implications between states) down to a state that currently holds. Returns the
+    /// operations in execution order for the best-supported chain, or an empty plan if the goal
+    /// already holds or cannot be reached within `goal_chain_depth` steps.
+    pub fn plan(&self, goal: &Term) -> Vec<PlanStep> {
+        if self.holds(goal) {
+            return Vec::new();
+        }
+        let mut visited = vec![goal.clone()];
+        self.plan_for(goal, TruthValue::new(1.0, 0.9), 0, &mut visited)
+            .map(|(steps, _)| steps)
+            .unwrap_or_default()
+    }
+
+    fn plan_for(&self, target: &Term, desire: TruthValue, depth: usize, visited: &mut Vec<Term>) -> Option<(Vec<PlanStep>, TruthValue)> {
+        if depth >= self.goal_chain_depth {
+            return None;
+        }
+        let mut best: Option<(Vec<PlanStep>, TruthValue)> = None;
+
+        for concept in self.memory.statements_into(target).filter(|c| !c.beliefs.is_empty()) {
+            let Term::Compound(op, args) = concept.term.term() else { continue };
+            let implication = matches!(op, Operator::PredictiveImplication | Operator::Implication | Operator::ConcurrentImplication);
+            if !implication || args.len() != 2 || args[1] != *target {
+                continue;
+            }
+            let step_desire = desire_strong(desire, concept.truth);
+            if step_desire.confidence < self.min_confidence {
+                continue;
+            }
+
+            let (precondition, operation) = split_procedure(&args[0]);
+            let step = operation.map(|operation| PlanStep {
+                operation,
+                precondition: precondition.clone(),
+                outcome: target.clone(),
+                truth: step_desire,
+            });
+
+            let candidate = match precondition {
+                Some(state) if !self.holds(&state) => {
+                    if visited.contains(&state) {
+                        continue;
+                    }
+                    visited.push(state.clone());
+                    let prefix = self.plan_for(&state, step_desire, depth + 1, visited);
+                    visited.pop();
+                    let Some((mut steps, truth)) = prefix else { continue };
+                    steps.extend(step);
+                    (steps, truth)
+                }
+                _ => match step {
+                    Some(step) => (vec![step], step_desire),
+                    None => continue, // plain implication from a state that already holds: nothing to do
+                },
+            };
+
+            if best.as_ref().is_none_or(|(_, t)| candidate.1.expectation() > t.expectation()) {
+                best = Some(candidate);
+            }
+        }
+        best
+    }
+
+    /// A state holds if it is believed with positive expectation.
+    fn holds(&self, term: &Term) -> bool {
+        self.memory.get(term).is_some_and(|c| !c.beliefs.is_empty() && c.truth.expectation() > 0.5)
+    }
+
+    /// Unresolved tasks go back into their lane with decayed priority until they fade out.
+    /// Puts a task back with decayed priority; returns false once its budget is exhausted.
+    fn requeue(&mut self, mut task: Task) -> bool {
+        // An input budget's durability can only make it decay faster
+        task.priority *= task.sentence.budget.map_or(TASK_DECAY, |b| TASK_DECAY.min(b.durability));
+        if task.priority > 0.01 {
+            self.enqueue_task(task);
+            return true;
+        }
+        false
+    }
+
+    /// The belief closest to a question among the concepts related to it (see
+    /// `ConceptStore::related_to`): unifying candidates first, then by vector similarity.
+    pub fn best_partial_match(&self, term: &Term) -> Option<PartialMatch> {
+        let question_vector = self.resolve_vector(term);
+        self.memory.related_to(term).into_iter()
+            .filter(|c| c.term != *term && c.truth.confidence > 0.01)
+            .map(|c| PartialMatch {
+                term: c.term.term().clone(),
+                truth: c.truth,
+                similarity: question_vector.similarity(&c.vector),
+                unifies: unify_with_bindings(term, &c.term, HashMap::new()).is_some(),
+            })
+            .max_by(|a, b| a.unifies.cmp(&b.unifies).then(a.similarity.total_cmp(&b.similarity)))
+    }
+
+    /// Puts the concept of `term` into memory with exactly `vector` and a single belief with
+    /// `truth` (none for zero confidence), replacing any concept on the term. Unlike input or
+    /// `add_concept` nothing is revised and no vector is assigned or learned, so experiments
+    /// can set up a precise memory state.
+    pub fn insert_concept_with_vector(&mut self, term: Term, truth: TruthValue, vector: Hypervector) {
+        let term = self.canonical(&term);
+        let mut stamp = Stamp::new(self.cycle_count, vec![self.next_evidence_id]);
+        self.next_evidence_id += 1;
+        stamp.add_source(Source::User);
+        let mut concept = Concept::new(term.clone(), vector, truth, stamp.clone());
+        if truth.confidence > 0.0 {
+            concept.add_belief(Sentence::new(term.clone(), Punctuation::Judgement, truth, stamp));
+        }
+        self.attend(term, (concept.priority * concept.durability).clamp(0.01, 0.99));
+        self.memory.put(concept);
+    }
+
+    /// Adds `concept` under its own term, revising an existing concept with it. Inputs and
+    /// conclusions arrive in canonical order (see `canonical`); orientations of a symmetric
+    /// copula added here as written are merged by `consolidate`.
     pub fn add_concept(&mut self, mut concept: Concept, is_judgement: bool) {
-        let existing_concept_opt = self.memory.get(&concept.term).cloned();
+        let term = concept.term.term().clone();
+        // A concept only found in a pack is copied into memory when revised
+        let existing_concept_opt = match self.memory.take(&concept.term) {
+            Some(existing) => Some(existing),
+            None => self.lookup(&concept.term).map(Cow::into_owned),
+        };
 
         if let Some(mut existing_concept) = existing_concept_opt {
-             if is_judgement {
+             // Revision needs distinct evidence: of overlapping judgements the more confident one is chosen
+             if is_judgement && existing_concept.stamp.overlaps(&concept.stamp) {
+                 if concept.truth.confidence > existing_concept.truth.confidence {
+                     existing_concept.truth = concept.truth;
+                 }
+                 existing_concept.add_belief(Sentence::new(concept.term.term().clone(), Punctuation::Judgement, concept.truth, concept.stamp.clone()));
+             } else if is_judgement {
+                 let previous_truth = existing_concept.truth;
                  let revised_truth = revision(existing_concept.truth, concept.truth);
                  existing_concept.truth = revised_truth;
-                 let belief = Sentence::new(concept.term.clone(), Punctuation::Judgement, concept.truth, concept.stamp.clone());
+                 existing_concept.record_revision(Revision {
+                     previous: previous_truth,
+                     revised: revised_truth,
+                     stamp: concept.stamp.clone(),
+                     time: self.cycle_count,
+                 });
+                 let belief = Sentence::new(concept.term.term().clone(), Punctuation::Judgement, concept.truth, concept.stamp.clone());
                  existing_concept.add_belief(belief);
-                 let sent = Sentence::new(existing_concept.term.clone(), Punctuation::Judgement, revised_truth, existing_concept.stamp.clone());
+                 let sent = Sentence::new(existing_concept.term.term().clone(), Punctuation::Judgement, revised_truth, existing_concept.stamp.clone());
                  self.output_buffer.push(sent);
+
+                 let drop = previous_truth.expectation() - revised_truth.expectation();
+                 if drop > self.tms_threshold {
+                     let source = existing_concept.stamp.clone();
+                     self.memory.put(existing_concept.clone());
+                     self.invalidate_dependents(&source, drop);
+                 }
              }
-             self.memory.put(existing_concept.clone());
-             
              let priority = (existing_concept.priority * existing_concept.durability).clamp(0.01, 0.99);
-             self.buffer.put(existing_concept.term.clone(), priority);
+             self.attend(existing_concept.term.term().clone(), priority);
+             self.memory.put(existing_concept);
         } else {
              if is_judgement {
-                 let belief = Sentence::new(concept.term.clone(), Punctuation::Judgement, concept.truth, concept.stamp.clone());
+                 let belief = Sentence::new(concept.term.term().clone(), Punctuation::Judgement, concept.truth, concept.stamp.clone());
                  concept.add_belief(belief);
              }
-             self.memory.put(concept.clone());
              let priority = (concept.priority * concept.durability).clamp(0.01, 0.99);
-             self.buffer.put(concept.term.clone(), priority);
+             self.attend(concept.term.term().clone(), priority);
+             self.memory.put(concept);
         }
         
         // Vector Learning Logic
-        if is_judgement {
-            if let Term::Compound(Operator::Inheritance, args) = &concept.term {
-                if args.len() == 2 {
-                    let subject_term = &args[0];
-                    let predicate_term = &args[1];
-                    
-                    let p_vector = self.resolve_vector(predicate_term);
-                    
-                    let subject_term = subject_term.clone();
-                    
-                    let mut s_concept = if let Some(c) = self.memory.get(&subject_term) {
-                        c.clone()
-                    } else {
-                        let vector = Hypervector::from_term(&subject_term);
-                        Concept::new(subject_term.clone(), vector, TruthValue::new(0.5, 0.0), Stamp::new(0, vec![]))
-                    };
-                    
-                    s_concept.vector.update(&p_vector, self.learning_rate);
-                    self.memory.put(s_concept);
-                }
+        if is_judgement
+            && let Term::Compound(Operator::Inheritance, args) = &term
+            && args.len() == 2
+        {
+            let subject_term = &args[0];
+            let predicate_term = &args[1];
+            
+            let p_vector = self.resolve_vector(predicate_term);
+            
+            let subject_term = subject_term.clone();
+            
+            let mut s_concept = if let Some(c) = self.memory.take(&subject_term) {
+                c
+            } else if let Some(c) = self.lookup(&subject_term) {
+                c.into_owned()
+            } else {
+                let vector = Hypervector::from_term_with(&subject_term, self.tie_break);
+                Concept::new(subject_term.clone(), vector, TruthValue::new(0.5, 0.0), Stamp::new(0, vec![]))
+            };
+            
+            s_concept.learn(&p_vector, self.learning_rate, &self.drift_control);
+            self.memory.put(s_concept);
+        }
+    }
+
+    /// Truth maintenance: every concept derived from `source` (its evidential base is a strict
+    /// superset of the source stamp) loses `penalty` of its confidence, and is put back into the
+    /// buffer so it can be re-derived from the corrected premises. Returns the number invalidated.
+    pub fn invalidate_dependents(&mut self, source: &Stamp, penalty: f32) -> usize {
+        if source.evidence.is_empty() {
+            return 0;
+        }
+        let keep = (1.0 - penalty).clamp(0.0, 1.0);
+        let depends_on = |stamp: &Stamp| {
+            stamp.evidence.len() > source.evidence.len() && source.evidence.iter().all(|id| stamp.evidence.contains(id))
+        };
+
+        let mut invalidated = Vec::new();
+        for term in self.memory.resting_on(&source.evidence) {
+            let Some(concept) = self.memory.get_mut(&term) else { continue };
+            let mut touched = false;
+            if depends_on(&concept.stamp) {
+                concept.truth.confidence *= keep;
+                touched = true;
+            }
+            for belief in concept.beliefs.iter_mut().filter(|b| depends_on(&b.stamp)) {
+                belief.truth.confidence *= keep;
+                touched = true;
+            }
+            if touched {
+                invalidated.push((term, (concept.priority * concept.durability).clamp(0.01, 0.99)));
+            }
+        }
+
+        for (term, priority) in &invalidated {
+            self.attend(term.clone(), *priority);
+        }
+        self.stats.invalidations += invalidated.len() as u64;
+        invalidated.len()
+    }
+
+    /// Puts `term` in the attention buffer at `priority`. A pinned term keeps a higher priority it
+    /// already has there, and a full buffer makes room by dropping its weakest unpinned term.
+    fn attend(&mut self, term: Term, priority: f32) {
+        let pinned = &self.memory.pinned;
+        let priority = match self.buffer.name_map.get(&term) {
+            Some(&current) if pinned.contains(&term) => priority.max(current),
+            _ => priority,
+        };
+        self.buffer.put_unless(term, priority, |t| pinned.contains(t));
+    }
+
+    /// Pins `term`: its concept is never evicted, forgotten, merged away by `consolidate`, nor
+    /// loses priority through negative inference feedback. The term need not be known yet.
+    pub fn pin(&mut self, term: &Term) {
+        let term = self.canonical(term);
+        self.memory.pinned.insert(term);
+    }
+
+    /// Removes a pin; returns whether the term was pinned.
+    pub fn unpin(&mut self, term: &Term) -> bool {
+        let term = self.canonical(term);
+        self.memory.pinned.remove(&term)
+    }
+
+    pub fn is_pinned(&self, term: &Term) -> bool {
+        self.memory.is_pinned(&self.canonical(term))
+    }
+
+    /// Forgets `term`: removes its concept and every concept built on it (statements and compounds
+    /// that contain it) from memory, the buffer and the pending task lanes. Conclusions derived
+    /// from the removed beliefs lose their support. Returns the number of concepts removed.
+    pub fn retract(&mut self, term: &Term) -> Result<usize, NarsError> {
+        let matching: Vec<Term> = self.memory.containing(term).into_iter().map(|c| c.term.term().clone()).collect();
+        if matching.is_empty() {
+            return Err(NarsError::NotFound(term.to_string()));
+        }
+        // Pinned concepts survive; the count only includes removed ones
+        let doomed: Vec<Term> = matching.into_iter().filter(|t| !self.memory.is_pinned(t)).collect();
+
+        for t in &doomed {
+            self.buffer.remove(t);
+            if let Some(concept) = self.memory.remove(t) {
+                self.invalidate_dependents(&concept.stamp, 1.0);
+            }
+        }
+        for punctuation in LaneRatios::ORDER {
+            self.lanes.lane_mut(punctuation).remove_where(|t| t.contains(term) && !self.memory.is_pinned(t));
+        }
+        Ok(doomed.len())
+    }
+
+    /// Enters a scoped working memory named `name`, layered over the current one: inputs and
+    /// derivations from here on are kept by `commit_context` or dropped wholesale by
+    /// `pop_context`. Contexts nest.
+    pub fn push_context(&mut self, name: &str) {
+        self.memory.push_layer();
+        self.contexts.push(ContextFrame {
+            name: name.to_string(),
+            buffer: self.buffer.clone(),
+            lanes: self.lanes.clone(),
+            events: self.events.clone(),
+            scheduled: self.scheduled.clone(),
+            recent_inputs: self.recent_inputs.clone(),
+            next_evidence_id: self.next_evidence_id,
+            rule_priorities: self.rule_priorities.clone(),
+            rule_usage: self.rule_usage.clone(),
+            stats: self.stats,
+            activity: self.activity.clone(),
+            associations: self.associations.clone(),
+            answered: self.answered.clone(),
+            arbitrated: self.arbitrated.clone(),
+            cycle_count: self.cycle_count,
+            reported: (self.output_buffer.len(), self.answers.len(), self.unanswered.len(), self.hypotheses.len(), self.conflicts.len()),
+        });
+    }
+
+    /// Leaves the innermost context, discarding everything input or derived in it, and what
+    /// reasoning in it taught the system (rule priorities, statistics, activity, the cycle
+    /// count) along with the output and events it reported. Evidence ids handed out inside are
+    /// not reused. Returns the context's name.
+    pub fn pop_context(&mut self) -> Result<String, NarsError> {
+        let frame = self.contexts.pop().ok_or_else(|| NarsError::NotFound("context".to_string()))?;
+        self.memory.pop_layer();
+        self.buffer = frame.buffer;
+        self.lanes = frame.lanes;
+        self.events = frame.events;
+        self.scheduled = frame.scheduled;
+        self.recent_inputs = frame.recent_inputs;
+        self.evidence_registry.retain(|id, _| *id < frame.next_evidence_id);
+        self.rule_priorities = frame.rule_priorities;
+        self.rule_usage = frame.rule_usage;
+        self.stats = frame.stats;
+        self.activity = frame.activity;
+        self.associations = frame.associations;
+        self.answered = frame.answered;
+        self.arbitrated = frame.arbitrated;
+        self.cycle_count = frame.cycle_count;
+        let (output, answers, unanswered, hypotheses, conflicts) = frame.reported;
+        self.output_buffer.truncate(output);
+        self.answers.truncate(answers);
+        self.unanswered.truncate(unanswered);
+        self.hypotheses.truncate(hypotheses);
+        self.conflicts.truncate(conflicts);
+        Ok(frame.name)
+    }
+
+    /// Leaves the innermost context, keeping everything input or derived in it in the enclosing
+    /// one (the base knowledge when it was the outermost). Returns the context's name.
+    pub fn commit_context(&mut self) -> Result<String, NarsError> {
+        let frame = self.contexts.pop().ok_or_else(|| NarsError::NotFound("context".to_string()))?;
+        self.memory.commit_layer();
+        Ok(frame.name)
+    }
+
+    /// Names of the active contexts, outermost first.
+    pub fn contexts(&self) -> Vec<&str> {
+        self.contexts.iter().map(|frame| frame.name.as_str()).collect()
+    }
+
+    /// Resources of this instance, for creating further instances that share them.
+    pub fn resources(&self) -> SharedResources {
+        SharedResources {
+            rules: Arc::clone(&self.rules),
+            projector: self.projector.clone(),
+        }
+    }
+
+    /// Revision history of a concept, oldest first (bounded by `HISTORY_CAPACITY`).
+    pub fn history(&self, term: &Term) -> Option<&[Revision]> {
+        self.memory.get(term).map(|c| c.history.as_slice())
+    }
+
+    /// Registers an additional inference rule, e.g. one built with `nars_rule!`.
+    pub fn add_rule(&mut self, rule: InferenceRule) {
+        Arc::make_mut(&mut self.rules).push(rule);
+        self.apply_nal_level(self.rules.len() - 1);
+    }
+
+    /// Restricts inference to NAL layers `1..=level` (e.g. 2 for strict NAL-1/2 experiments):
+    /// every rule is enabled exactly when its `nal_level` is within the limit, replacing any
+    /// per-rule setting. Rules added later follow the same limit.
+    pub fn set_nal_level(&mut self, level: u8) {
+        self.nal_level = level;
+        self.apply_nal_level(0);
+    }
+
+    fn apply_nal_level(&mut self, from: usize) {
+        self.rule_enabled.resize(self.rules.len(), true);
+        for (idx, rule) in self.rules.iter().enumerate().skip(from) {
+            self.rule_enabled[idx] = rule.nal_level() <= self.nal_level;
+        }
+    }
+
+    /// Enables or disables every rule called `name`; returns how many rules matched.
+    pub fn set_rule_enabled(&mut self, name: &str, enabled: bool) -> usize {
+        self.rule_enabled.resize(self.rules.len(), true);
+        let mut matched = 0;
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.name == name {
+                self.rule_enabled[idx] = enabled;
+                matched += 1;
+            }
+        }
+        matched
+    }
+
+    pub fn is_rule_enabled(&self, rule_idx: usize) -> bool {
+        self.rule_enabled.get(rule_idx).copied().unwrap_or(true)
+    }
+
+    /// Makes a custom truth function available to rule files under `name`.
+    pub fn register_truth_fn(&mut self, name: &str, truth_fn: TruthFunction) {
+        self.truth_registry.register(name, truth_fn);
+    }
+
+    /// Adds the rules defined in `text` (see `parse_rules`); unsupported ones are reported as skipped.
+    /// Rules the system already has are reported as duplicates, and rules that redefine the
+    /// truth function or annotations of a present rule as conflicts; neither is added.
+    /// Nothing is added if any line fails to parse.
+    pub fn load_rules(&mut self, text: &str) -> Result<RuleLoadReport, NarsError> {
+        let (rules, skipped) = parse_rules(text, &self.truth_registry)?;
+        let mut report = RuleLoadReport { skipped, ..RuleLoadReport::default() };
+        let rules = dedup_rules(&self.rules, rules, &mut report);
+        report.loaded = rules.len();
+        let first = self.rules.len();
+        Arc::make_mut(&mut self.rules).extend(rules);
+        self.apply_nal_level(first);
+        Ok(report)
+    }
+
+    /// Writes the activity heat map to `path`, as JSON for `.json` files and CSV otherwise.
+    pub fn export_activity(&self, path: &str) -> Result<(), NarsError> {
+        let file = File::create(path)?;
+        if path.ends_with(".json") {
+            serde_json::to_writer_pretty(file, &self.activity.to_json()).map_err(|e| NarsError::Serialization(e.to_string()))
+        } else {
+            self.activity.write_csv(std::io::BufWriter::new(file))
+        }
+    }
+
+    /// Replaces every rule with the rules of a rule file, see `replace_rules_from_file`.
+    #[deprecated(note = "replaces the rules; use `add_rules_from_file` to extend them or `replace_rules_from_file` to swap them")]
+    pub fn load_rules_from_file(&mut self, path: &str) -> Result<RuleLoadReport, NarsError> {
+        self.replace_rules_from_file(path)
+    }
+
+    /// Adds the rules of a rule file to the current ones, as `load_rules` does.
+    pub fn add_rules_from_file(&mut self, path: &str) -> Result<RuleLoadReport, NarsError> {
+        let text = std::fs::read_to_string(path)?;
+        self.load_rules(&text)
+    }
+
+    /// Replaces every rule, built-ins included, with the rules of a rule file.
+    pub fn replace_rules_from_file(&mut self, path: &str) -> Result<RuleLoadReport, NarsError> {
+        let text = std::fs::read_to_string(path)?;
+        self.replace_rules(&text)
+    }
+
+    /// Replaces every rule, built-ins included, with the rules defined in `text`; repeated
+    /// rules are reported as by `load_rules`. Usage counts, learned priorities and enabled
+    /// flags are per rule index and start over. Nothing changes if any line fails to parse.
+    pub fn replace_rules(&mut self, text: &str) -> Result<RuleLoadReport, NarsError> {
+        let (rules, skipped) = parse_rules(text, &self.truth_registry)?;
+        let mut report = RuleLoadReport { skipped, ..RuleLoadReport::default() };
+        let rules = dedup_rules(&[], rules, &mut report);
+        report.loaded = rules.len();
+        self.rules = Arc::new(rules);
+        self.rule_usage.clear();
+        self.rule_priorities.clear();
+        self.rule_enabled.clear();
+        self.apply_nal_level(0);
+        // Both shape caches are keyed by rule index
+        self.shape_cache.clear();
+        self.pipeline = None;
+        Ok(report)
+    }
+
+    pub fn register_operation(&mut self, name: &str, handler: OperationHandler) {
+        self.operations.register(name, handler);
+    }
+
+    /// Executes an operation term `(^name, args..)` (or a bare `^name`) and inputs the tasks
+    /// its handler returns. Returns the number of tasks produced.
+    pub fn execute(&mut self, operation: &Term) -> Result<usize, NarsError> {
+        let (name, args) = match operation {
+            Term::Atom(name) => (name.as_str(), &[][..]),
+            Term::Compound(Operator::Other(name), args) => (name.as_str(), args.as_slice()),
+            _ => return Err(NarsError::NotFound(operation.to_string())),
+        };
+        let handler = self.operations.get(name).ok_or_else(|| NarsError::NotFound(format!("operation {}", name)))?;
+
+        let tasks = handler(args);
+        let count = tasks.len();
+        for task in tasks {
+            self.input(task);
+        }
+        Ok(count)
+    }
+
+    /// Temporal induction from two events (occurrence time = `stamp.creation_time`): builds
+    /// `<(&/, A, +d) =/> B>` with the approximated delay `d`, or `<A =|> B>` for simultaneous
+    /// events. Intervals already inside `A` are merged with the new one. The implication is
+    /// integrated like any judgement, so repeated observations with similar delays revise it.
+    pub fn induce_temporal(&mut self, earlier: &Sentence, later: &Sentence) -> Option<Sentence> {
+        if earlier.stamp.overlaps(&later.stamp) || later.stamp.creation_time < earlier.stamp.creation_time {
+            return None;
+        }
+        let delay = later.stamp.creation_time - earlier.stamp.creation_time;
+        let term = if delay == 0 {
+            Term::Compound(Operator::ConcurrentImplication, vec![earlier.term.clone(), later.term.clone()])
+        } else {
+            let antecedent = Term::sequence(vec![earlier.term.clone(), Term::Interval(delay)]).approximated();
+            Term::Compound(Operator::PredictiveImplication, vec![antecedent, later.term.clone()])
+        };
+
+        let truth = temporal_induction(later.truth, earlier.truth);
+        let mut stamp = earlier.stamp.merge(&later.stamp);
+        stamp.creation_time = later.stamp.creation_time;
+        let vector = self.resolve_vector(&term);
+        self.add_concept(Concept::new(term.clone(), vector, truth, stamp.clone()), true);
+
+        let sentence = Sentence::new(term, Punctuation::Judgement, truth, stamp);
+        self.output_buffer.push(sentence.clone());
+        Some(sentence)
+    }
+
+    /// Registers a sensor; a channel with the same name is replaced.
+    pub fn add_sensor(&mut self, channel: SensorChannel) {
+        self.sensors.retain(|s| s.name != channel.name);
+        self.sensors.push(channel);
+    }
+
+    /// Records a reading on the named channel; it is injected as event judgements on the next cycle.
+    pub fn sense(&mut self, name: &str, value: f32) -> Result<(), NarsError> {
+        let channel = self.sensors.iter_mut().find(|s| s.name == name)
+            .ok_or_else(|| NarsError::NotFound(format!("sensor {}", name)))?;
+        channel.push(value);
+        Ok(())
+    }
+
+    /// Injects pending sensor readings, stamped with the current cycle as occurrence time.
+    fn tick_sensors(&mut self) {
+        let time = self.cycle_count;
+        let events: Vec<(Source, Sentence)> = self.sensors.iter_mut()
+            .flat_map(|s| {
+                let source = Source::Sensor(s.name.clone());
+                s.tick(time).into_iter().map(move |e| (source.clone(), e))
+            })
+            .collect();
+        for (source, event) in events {
+            self.input_from(event, source);
+        }
+    }
+
+    /// Runs `n` full cycles; returns the number of cycles run.
+    pub fn run_cycles(&mut self, n: usize) -> usize {
+        for i in 0..n {
+            self.cycle_until(None);
+            self.flush_usage();
+            self.publish_snapshot(i + 1 == n);
+        }
+        n
+    }
+
+    /// Runs cycles until the wall-clock budget is spent or there is nothing left to do.
+    /// A cycle that runs out of time yields between association candidates.
+    /// Returns the number of cycles started.
+    pub fn run_for(&mut self, budget: Duration) -> usize {
+        let deadline = Instant::now() + budget;
+        let mut cycles = 0;
+        while Instant::now() < deadline {
+            if self.buffer.count == 0 && self.lanes.is_empty() && self.prefetched.is_none() {
+                break;
             }
+            self.cycle_until(Some(deadline));
+            self.flush_usage();
+            self.publish_snapshot(false);
+            cycles += 1;
+        }
+        self.publish_snapshot(true);
+        cycles
+    }
+
+    pub fn cycle(&mut self) {
+        self.cycle_until(None);
+        self.flush_usage();
+        self.publish_snapshot(false);
+    }
+
+    /// Notes a use of the concept of `term` in this cycle's activity and, once the cycle no
+    /// longer holds the concepts it reasons with, on the concept itself (see `flush_usage`).
+    fn record_use(&mut self, term: &Term, kind: ActivityKind) {
+        self.activity.record(self.cycle_count, term, kind);
+        self.used.push(term.clone());
+    }
+
+    /// Counts the uses recorded this cycle on their concepts, for eviction.
+    fn flush_usage(&mut self) {
+        let mut used = std::mem::take(&mut self.used);
+        for term in used.drain(..) {
+            self.memory.touch(&term, self.cycle_count);
+        }
+        self.used = used;
+    }
+
+    /// A handle for querying this system from other threads while it cycles, e.g. for a UI.
+    /// While a handle is held, a snapshot of memory is published every `snapshot_interval`
+    /// cycles and at the end of `run_cycles` and `run_for`. Once all handles are dropped,
+    /// publishing stops until the next call.
+    pub fn query_handle(&mut self) -> QueryHandle {
+        if let Some(handle) = &self.query_handle
+            && handle.is_shared()
+        {
+            return handle.clone();
+        }
+        let handle = QueryHandle::new(self.snapshot());
+        self.query_handle = Some(handle.clone());
+        handle
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self.cycle_count, self.metrics(), self.memory.map.clone(), self.symmetric_copulas, self.tie_break)
+    }
+
+    /// Publishes a snapshot to the query handles if one is due (or `force`d). Copying the
+    /// concept index costs a pass over memory, so it is skipped when no reader is left.
+    fn publish_snapshot(&mut self, force: bool) {
+        let Some(handle) = &self.query_handle else { return };
+        if !handle.is_shared() {
+            self.query_handle = None;
+        } else if force || self.cycle_count.is_multiple_of(self.snapshot_interval.max(1)) {
+            handle.publish(self.snapshot());
         }
     }
 
-    pub fn cycle(&mut self) {
-        // 1. Selection (Probabilistic from Bag)
-        let term_a = match self.buffer.take() {
-            Some(t) => t,
-            None => return,
+    /// One reasoning cycle; returns false if it was cut short by `deadline`.
+    fn cycle_until(&mut self, deadline: Option<Instant>) -> bool {
+        // 0. Deferred inputs, sensor readings and task lanes (input judgements, questions, goals, quests)
+        self.inject_scheduled();
+        self.tick_sensors();
+        self.process_lanes();
+
+        let reground = self.drift_control.reground_interval;
+        if reground > 0 && self.cycle_count.is_multiple_of(reground) {
+            self.reground_vectors();
+        }
+        let interval = self.drift_control.renormalize_interval;
+        if interval > 0 && self.cycle_count.is_multiple_of(interval) {
+            self.renormalize_vectors();
+            self.consolidate();
+        }
+        let erosion = self.erosion.interval;
+        if erosion > 0 && self.cycle_count.is_multiple_of(erosion) {
+            self.erode_beliefs();
+        }
+
+        // 1. Selection (Probabilistic from Bag), unless the previous cycle selected ahead
+        self.activity.expire(self.cycle_count);
+        let config = self.reasoner;
+        let (term_a, retrieved) = match self.prefetched.take() {
+            Some((term, partners)) => (term, Some(partners)),
+            None => match self.buffer.take(&mut self.rng) {
+                Some(t) => (t, None),
+                None => return true,
+            },
         };
+        self.record_use(&term_a, ActivityKind::Selection);
         
-        // Retrieve Concept A
-        let concept_a = match self.memory.get(&term_a) {
-            Some(c) => c.clone(),
-            None => return,
+        // Retrieve Concept A (a shared handle: memory may be updated while reasoning with it)
+        let concept_a = match self.memory.get_shared(&term_a) {
+            Some(c) => c,
+            None => return true,
         };
 
         // 2. Association (Random Sampling for AIKR)
-        // We cannot scan all memory. We take a sample of keys.
-        let sample_size = 20;
-        let partners: Vec<Term> = self.memory.keys()
-            .take(sample_size * 3) // Grab a chunk (HashMap order is pseudo-random)
-            .filter(|t| **t != term_a)
-            .take(sample_size)
-            .cloned()
-            .collect();
+        let partners = match retrieved {
+            Some(partners) => partners.wait(),
+            None => {
+                let candidates = self.candidates(&term_a);
+                rank_partners(&concept_a.vector, candidates, self.similarity_threshold, config.max_partners)
+            }
+        };
+
+        // Pipelined: the matcher matches rules for upcoming partners while this loop executes,
+        // and the retriever ranks the partners of the next task
+        let pipeline = config.pipelined.then(|| self.submit_matches(&term_a, &partners));
+        if config.pipelined {
+            self.prefetch_task();
+        }
+
+        // 3. Geometric Attention ("The Pull"); unpipelined, this cycle matches in its own arena
+        let mut arena = MatchArena::new();
+        for (term_b, sim) in partners {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                // Out of time: give A back to the bag so the work is resumed later
+                let priority = (concept_a.priority * concept_a.durability).clamp(0.01, 0.99);
+                self.attend(term_a, priority);
+                return false;
+            }
+            let matched = pipeline.as_ref().map(|results| results.recv().unwrap_or_default());
+            if let Some(cb) = self.memory.get_shared(&term_b) {
+                // Activate B (Pull into Attention)
+                // If A is active, and A~B, then B becomes active.
+                let new_p = (sim * 0.9).clamp(0.01, 0.99);
+                self.attend(term_b.clone(), new_p);
+                self.record_use(&term_b, ActivityKind::Association);
 
-        // 3. Geometric Attention ("The Pull")
-        for term_b in partners {
-            if let Some(concept_b) = self.memory.get(&term_b) {
-                let sim = concept_a.vector.similarity(&concept_b.vector);
-                
-                if sim >= self.similarity_threshold {
-                    // Activate B (Pull into Attention)
-                    // If A is active, and A~B, then B becomes active.
-                    let new_p = (sim * 0.9).clamp(0.01, 0.99);
-                    self.buffer.put(term_b.clone(), new_p);
-                    
-                    // Reason
-                    // Cloning to satisfy borrow checker
-                    let cb = concept_b.clone();
-                    self.reason(&concept_a, &cb);
-                    self.reason(&cb, &concept_a);
-                    
-                    // Hebbian Learning
-                    if let Some(c_a) = self.memory.get_mut(&term_a) {
-                        c_a.vector.update(&cb.vector, self.learning_rate);
+                // Reason
+                if !concept_a.beliefs.is_empty() && !cb.beliefs.is_empty() {
+                    self.note_association(&term_a, &term_b);
+                }
+                match matched {
+                    Some((forward, backward)) if !concept_a.stamp.overlaps(&cb.stamp) => {
+                        self.execute_matches(forward, &concept_a, &cb);
+                        self.execute_matches(backward, &cb, &concept_a);
                     }
-                    if let Some(c_b) = self.memory.get_mut(&term_b) {
-                        c_b.vector.update(&concept_a.vector, self.learning_rate);
+                    Some(_) => {}
+                    None => {
+                        self.reason(&concept_a, &cb, &mut arena);
+                        if config.bidirectional {
+                            self.reason(&cb, &concept_a, &mut arena);
+                        }
                     }
                 }
+                self.reason_backward(&concept_a, &cb);
+                if config.bidirectional {
+                    self.reason_backward(&cb, &concept_a);
+                }
+
+                // Hebbian Learning (B is released first so updating it does not copy it)
+                let drift = self.drift_control;
+                let b_vector = cb.vector;
+                drop(cb);
+                if let Some(c_a) = self.memory.get_mut(&term_a) {
+                    c_a.learn(&b_vector, self.learning_rate, &drift);
+                }
+                if let Some(c_b) = self.memory.get_mut(&term_b) {
+                    c_b.learn(&concept_a.vector, self.learning_rate, &drift);
+                }
             }
         }
+        self.contrast_with_random(&term_a, &concept_a);
         
         self.reason_single(&concept_a);
+        true
     }
 
-    fn reason(&mut self, concept_a: &Concept, concept_b: &Concept) {
-        // println!("Inside reason function");
-        // Check for evidence overlap
-        if concept_a.stamp.overlaps(&concept_b.stamp) {
-            // println!("Overlap detected!");
+    /// Association candidates of the task on `term`: a sample of memory, as we cannot scan it
+    /// all.
+    fn candidates(&mut self, term: &Term) -> Vec<(Term, Arc<Concept>)> {
+        // One more than needed, in case the task's own concept is drawn
+        let sampled: Vec<Term> = self.memory.sample(self.reasoner.sample_size + 1, &mut self.rng).into_iter()
+            .filter(|t| *t != term)
+            .take(self.reasoner.sample_size)
+            .cloned()
+            .collect();
+        sampled.into_iter().filter_map(|t| {
+            let concept = self.memory.get_shared(&t)?;
+            Some((t, concept))
+        }).collect()
+    }
+
+    /// Hands the rule matching of the task on `term_a` with its partners to the pipeline.
+    fn submit_matches(&mut self, term_a: &Term, partners: &[(Term, f32)]) -> Receiver<PairMatches> {
+        let terms = partners.iter().map(|(t, _)| t.clone()).collect();
+        self.pipeline.get_or_insert_with(Pipeline::spawn)
+            .submit(self.rules.clone(), &self.rule_enabled, term_a.clone(), terms, self.reasoner.bidirectional)
+    }
+
+    /// Selects the next task now and has the pipeline retrieve its partners, by its vector as
+    /// of now, while this cycle reasons.
+    fn prefetch_task(&mut self) {
+        let Some(term) = self.buffer.take(&mut self.rng) else { return };
+        let Some(concept) = self.memory.get_shared(&term) else { return };
+        let candidates = self.candidates(&term);
+        let (threshold, max) = (self.similarity_threshold, self.reasoner.max_partners);
+        let partners = self.pipeline.get_or_insert_with(Pipeline::spawn).retrieve(concept.vector, candidates, threshold, max);
+        self.prefetched = Some((term, partners));
+    }
+
+    /// Negative sampling: pushes A's vector away from a random concept that is neither A nor
+    /// linked to it by a statement (see `DriftControl::contrastive_rate`).
+    fn contrast_with_random(&mut self, term_a: &Term, concept_a: &Concept) {
+        let rate = self.drift_control.contrastive_rate;
+        if rate <= 0.0 || self.memory.len() < 2 {
+            return;
+        }
+        let Some(other) = self.memory.sample(1, &mut self.rng).pop().cloned() else { return };
+        if other == *term_a || self.linked(term_a, &other) {
             return;
         }
+        let Some(other_vector) = self.memory.get(&other).map(|c| c.vector) else { return };
+        if concept_a.vector.similarity(&other_vector) <= 0.5 {
+            return;
+        }
+        let drift = self.drift_control;
+        if let Some(c_a) = self.memory.get_mut(term_a) {
+            c_a.contrast(&other_vector, rate, &drift);
+        }
+    }
 
-        // Collect applicable rules and bindings first to avoid borrowing self.rules while mutating self
-        let mut inferences_to_execute = Vec::new();
+    /// Curiosity: a pair of believed concepts that keeps being associated by similarity while no statement relates
+    /// the two terms becomes a question, `<a --> b>?` for terms and `<a ==> b>?` for
+    /// statements (mixed pairs are not asked about), so inference and clients (via
+    /// `hypotheses`) look for the missing link.
+    fn note_association(&mut self, a: &Term, b: &Term) {
+        if self.curiosity == 0 {
+            return;
+        }
+        let key = if a.to_string() <= b.to_string() { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+        if self.associations.len() >= MAX_TRACKED_ASSOCIATIONS && !self.associations.contains_key(&key) {
+            self.associations.clear();
+        }
+        let count = self.associations.entry(key).or_insert(0);
+        *count += 1;
+        let is_statement = |t: &Term| matches!(t, Term::Compound(op, _) if op.is_statement());
+        if *count != self.curiosity || is_statement(a) != is_statement(b) || self.linked(a, b) {
+            return;
+        }
+        let copula = if is_statement(a) { Operator::Implication } else { Operator::Inheritance };
+        let term = Term::Compound(copula, vec![a.clone(), b.clone()]);
+        let question = Sentence::new(term, Punctuation::Question, TruthValue::new(0.0, 0.0), Stamp::new(self.cycle_count, vec![]));
+        self.hypotheses.push(question.clone());
+        self.enqueue_task(Task::new(question, DEFAULT_TASK_PRIORITY));
+    }
 
-        // println!("Rules count: {}", self.rules.len());
+    /// Whether `a` and `b` share an atom, or memory (or a mounted pack) holds a statement
+    /// relating them either way.
+    fn linked(&self, a: &Term, b: &Term) -> bool {
+        a.shares_atom(b) || [Operator::Inheritance, Operator::Similarity, Operator::Implication, Operator::Equivalence].into_iter()
+            .flat_map(|op| [(op.clone(), a, b), (op, b, a)])
+            .any(|(op, s, p)| self.lookup(&self.canonical(&Term::Compound(op, vec![s.clone(), p.clone()]))).is_some())
+    }
 
-        for (rule_idx, rule) in self.rules.iter().enumerate() {
-            // Try to unify premises with (A, B)
-            // Rule premises: [P1, P2]
-            // We try P1 <-> A, P2 <-> B
-            
-            // println!("Rule {} premises: {}", rule_idx, rule.premises.len());
+    fn reason(&mut self, concept_a: &Concept, concept_b: &Concept, arena: &mut MatchArena) {
+        // Check for evidence overlap
+        if concept_a.stamp.overlaps(&concept_b.stamp) {
+            return;
+        }
+        let matches = match_pair(&self.rules, &self.rule_enabled, &concept_a.term, &concept_b.term, &mut self.shape_cache, arena);
+        self.execute_matches(matches, concept_a, concept_b);
+    }
 
-            if rule.premises.len() != 2 {
-                continue; 
+    /// Backward inference: a question on `concept_a` and a belief on `concept_b` raise questions
+    /// on the premises that would answer it, through the rules marked `:allow-backward`. The
+    /// raised questions are reported like derived subgoals (see `raise_question`).
+    fn reason_backward(&mut self, concept_a: &Concept, concept_b: &Concept) {
+        let Some(question) = concept_a.questions.first() else { return };
+        if !self.reasoner.backward || concept_b.beliefs.is_empty() {
+            return;
+        }
+        for (rule_idx, asked, bindings) in match_backward(&self.rules, &self.rule_enabled, &concept_a.term, &concept_b.term) {
+            self.rule_usage_mut(rule_idx).fired += 1;
+            let term = self.canonical(&substitute(&self.rules[rule_idx].premises[asked], &bindings));
+            let sentence = Sentence::new(term, Punctuation::Question, TruthValue::new(0.0, 0.0), question.stamp.clone());
+            if sentence.term == concept_a.term || !self.raise_question(sentence) {
+                self.rule_usage_mut(rule_idx).rejected += 1;
+                continue;
             }
+            self.rule_usage_mut(rule_idx).accepted += 1;
+        }
+    }
 
-            // Debug unification
-            // println!("Trying rule {} P1 with A: {:?}", rule_idx, concept_a.term);
-
-            // Try Unification
-            // 1. Unify P1 with A
-            if let Some(bindings_1) = unify_with_bindings(&rule.premises[0], &concept_a.term, HashMap::new()) {
-                // println!("  P1 matched! Bindings: {:?}", bindings_1);
-                // 2. Unify P2 with B, using bindings from 1
-                if let Some(final_bindings) = unify_with_bindings(&rule.premises[1], &concept_b.term, bindings_1) {
-                    // println!("  Rule {} ({}) matched! Executing inference.", rule_idx, rule.name);
-                    // Success!
-                    inferences_to_execute.push((rule_idx, final_bindings));
-                } else {
-                    // println!("  P2 failed to match B: {:?}", concept_b.term);
-                }
-            } else {
-                // println!("  P1 failed to match A: {:?}", concept_a.term);
-            }
+    /// Queues and reports a derived question, unless memory already believes or asks it, it
+    /// was answered, or it is pending (the pending task then keeps the higher priority).
+    fn raise_question(&mut self, mut sentence: Sentence) -> bool {
+        let priority = DEFAULT_TASK_PRIORITY * TASK_DECAY;
+        sentence.term = self.canonical(&sentence.term);
+        if self.memory.get(&sentence.term).is_some_and(|c| !c.beliefs.is_empty() || !c.questions.is_empty())
+            || self.answered.contains_key(&sentence.term)
+            || self.lanes.questions.merge(&sentence, priority)
+        {
+            return false;
         }
+        self.output_buffer.push(sentence.clone());
+        self.enqueue_task(Task::new(sentence, priority));
+        true
+    }
 
+    /// Applies the matched two-premise rules to `(concept_a, concept_b)`.
+    fn execute_matches(&mut self, mut inferences_to_execute: Vec<(usize, Bindings)>, concept_a: &Concept, concept_b: &Concept) {
         // Execute inferences
+        self.limit_rule_applications(&mut inferences_to_execute);
         for (rule_idx, bindings) in inferences_to_execute {
             let rule = &self.rules[rule_idx];
             let conclusion = rule.conclusion.clone();
-            
-            if let TruthFunction::Double(tf) = rule.truth_fn {
-                self.execute_inference_logic(conclusion, tf, &bindings, concept_a, concept_b);
+            let meta = rule.meta.clone();
+            let truth_fn = rule.truth_fn;
+            self.rule_usage_mut(rule_idx).fired += 1;
+
+            if let TruthFunction::Double(tf) = truth_fn {
+                if !meta.question_only {
+                    self.execute_inference_logic(rule_idx, conclusion.clone(), tf, &bindings, concept_a, concept_b);
+                }
+                self.derive_from_tasks(&meta, &conclusion, &bindings, concept_a, Some(concept_b));
             }
         }
     }
 
+    /// Keeps the `max_rule_applications` matches whose rules have the highest priority (see
+    /// `inference_feedback`), in table order among equals.
+    fn limit_rule_applications(&self, matches: &mut Vec<(usize, Bindings)>) {
+        let Some(limit) = self.reasoner.max_rule_applications else { return };
+        if matches.len() > limit {
+            matches.sort_by(|(a, _), (b, _)| self.rule_priority(*b).total_cmp(&self.rule_priority(*a)));
+            matches.truncate(limit);
+        }
+    }
+
+    /// Task derivations of annotated rules: a question on the first premise becomes a question
+    /// for the conclusion (`:question?`, `:allow-backward`), and a goal on it becomes a goal
+    /// whose desire combines the goal with the second premise (`:d/strong`, `:d/weak`).
+    fn derive_from_tasks(&mut self, meta: &RuleMeta, conclusion_template: &Term, bindings: &Bindings, concept_a: &Concept, concept_b: Option<&Concept>) {
+        let conclusion = substitute(conclusion_template, bindings);
+
+        if meta.derives_questions() && (meta.question_only || self.reasoner.backward)
+            && let Some(question) = concept_a.questions.first()
+        {
+            let sentence = Sentence::new(conclusion.clone(), Punctuation::Question, TruthValue::new(0.0, 0.0), question.stamp.clone());
+            self.raise_question(sentence);
+        }
+
+        if let Some(selector) = meta.desire
+            && let Some(belief) = concept_b
+            && let Some(goal) = concept_a.desire()
+            && !goal.stamp.overlaps(&belief.stamp)
+            && self.memory.get(&conclusion).is_none_or(|c| c.goals.is_empty())
+        {
+            let desire = match selector {
+                DesireSelector::Strong => desire_strong(goal.truth, belief.truth),
+                DesireSelector::Weak => desire_weak(goal.truth, belief.truth),
+            };
+            let sentence = Sentence::new(conclusion, Punctuation::Goal, desire, goal.stamp.merge(&belief.stamp));
+            self.enqueue_task(Task::new(sentence, DEFAULT_TASK_PRIORITY * TASK_DECAY));
+        }
+    }
+
     fn reason_single(&mut self, concept: &Concept) {
         let mut inferences_to_execute = Vec::new();
         for (rule_idx, rule) in self.rules.iter().enumerate() {
-            if rule.premises.len() != 1 { continue; }
+            if rule.premises.len() != 1 || !self.is_rule_enabled(rule_idx) { continue; }
             
-            if let Some(bindings) = unify_with_bindings(&rule.premises[0], &concept.term, HashMap::new()) {
+            if let Some(bindings) = unify_with_bindings(&rule.premises[0], &concept.term, HashMap::new())
+                && rule.meta.preconditions.iter().all(|p| p.holds(&bindings))
+            {
                 // println!("  Single Rule {} ({}) matched! Executing inference.", rule_idx, rule.name); // Added debug print
                 inferences_to_execute.push((rule_idx, bindings));
             }
         }
         
+        self.limit_rule_applications(&mut inferences_to_execute);
         for (rule_idx, bindings) in inferences_to_execute {
             let rule = &self.rules[rule_idx];
-            if let TruthFunction::Single(tf) = rule.truth_fn {
-                self.execute_single_inference(rule.conclusion.clone(), tf, &bindings, concept);
+            let conclusion = rule.conclusion.clone();
+            let meta = rule.meta.clone();
+            let truth_fn = rule.truth_fn;
+            self.rule_usage_mut(rule_idx).fired += 1;
+            if let TruthFunction::Single(tf) = truth_fn {
+                if !meta.question_only {
+                    self.execute_single_inference(rule_idx, conclusion.clone(), tf, &bindings, concept);
+                }
+                self.derive_from_tasks(&meta, &conclusion, &bindings, concept, None);
             }
         }
     }
 
-    fn execute_single_inference(&mut self, conclusion_template: Term, truth_fn: fn(TruthValue) -> TruthValue, bindings: &Bindings, concept: &Concept) {
-        let conclusion_term = substitute(&conclusion_template, bindings);
+    fn execute_single_inference(&mut self, rule_idx: usize, conclusion_template: Term, truth_fn: fn(TruthValue) -> TruthValue, bindings: &Bindings, concept: &Concept) {
+        let conclusion_term = self.canonical(&substitute(&conclusion_template, bindings));
         let new_truth = (truth_fn)(concept.truth);
         let new_stamp = concept.stamp.clone(); 
-        
-        // Debug Output
-        println!("[DEBUG] Derived: {:?} %{};{}%", conclusion_term, new_truth.frequency, new_truth.confidence);
-
+        let priority = concept.priority * new_truth.confidence * self.complexity_penalty(&conclusion_term);
         // For immediate inference, we can reuse the vector or project it. 
         // Reusing it implies semantic similarity which is often true for conversion/contraposition.
-        let new_vector = concept.vector.clone();
+        let new_vector = concept.vector;
+        if self.is_redundant(&conclusion_term, &new_vector, new_truth, &new_stamp)
+            || !self.accept_derivation(&conclusion_term, new_truth, priority)
+        {
+            self.rule_usage_mut(rule_idx).rejected += 1;
+            return;
+        }
+        self.rule_usage_mut(rule_idx).accepted += 1;
+        for term in [&concept.term, &conclusion_term] {
+            self.record_use(term, ActivityKind::Derivation);
+        }
+        
+        if self.trace {
+            eprintln!("[DEBUG] Derived: {:?} %{};{}% from [{}]", conclusion_term, new_truth.frequency, new_truth.confidence,
+                self.evidence_labels(&new_stamp).join(", "));
+        }
 
-        let new_concept = Concept::new(conclusion_term.clone(), new_vector, new_truth, new_stamp.clone());
+        let mut new_concept = Concept::new(conclusion_term.clone(), new_vector, new_truth, new_stamp.clone());
+        new_concept.priority = priority;
         
         let sentence = Sentence::new(conclusion_term, Punctuation::Judgement, new_truth, new_stamp);
         self.output_buffer.push(sentence);
         self.add_concept(new_concept, true);
     }
 
-    fn execute_inference_logic(&mut self, conclusion_template: Term, truth_fn: fn(TruthValue, TruthValue) -> TruthValue, bindings: &Bindings, concept_a: &Concept, concept_b: &Concept) {
+    /// Priority factor for a derived term: 1.0 up to `complexity_budget`, then budget/complexity.
+    pub fn complexity_penalty(&self, term: &Term) -> f32 {
+        let complexity = term.complexity();
+        if complexity <= self.complexity_budget {
+            1.0
+        } else {
+            self.complexity_budget as f32 / complexity as f32
+        }
+    }
+
+    /// Confidence/priority/complexity limits for derived conclusions; rejected ones are only counted.
+    fn accept_derivation(&mut self, term: &Term, truth: TruthValue, priority: f32) -> bool {
+        if truth.confidence < self.min_confidence || priority < self.min_priority || term.complexity() > self.max_complexity {
+            self.stats.suppressed_derivations += 1;
+            return false;
+        }
+        self.stats.derivations += 1;
+        true
+    }
+
+    /// Novelty check: a derivation is redundant when the concept of its term, or of a variant
+    /// of it (same normalized form), is within `novelty_threshold` in vector space, already
+    /// holds all of its evidence and is at least as confident, i.e. the conclusion re-derives
+    /// known content.
+    fn is_redundant(&mut self, term: &Term, vector: &Hypervector, truth: TruthValue, stamp: &Stamp) -> bool {
+        let redundant = self.memory.variants_of(term).into_iter().any(|c| {
+            truth.confidence <= c.truth.confidence
+                && stamp.evidence.iter().all(|id| c.stamp.evidence.contains(id))
+                && vector.similarity(&c.vector) >= self.novelty_threshold
+        });
+        if redundant {
+            self.stats.redundant_derivations += 1;
+        }
+        redundant
+    }
+
+    pub fn rule_priority(&self, rule_idx: usize) -> f32 {
+        self.rule_priorities.get(rule_idx).copied().unwrap_or(DEFAULT_RULE_PRIORITY)
+    }
+
+    pub fn rule_usage(&self, rule_idx: usize) -> RuleUsage {
+        self.rule_usage.get(rule_idx).copied().unwrap_or_default()
+    }
+
+    fn rule_usage_mut(&mut self, rule_idx: usize) -> &mut RuleUsage {
+        if self.rule_usage.len() <= rule_idx {
+            self.rule_usage.resize(rule_idx + 1, RuleUsage::default());
+        }
+        &mut self.rule_usage[rule_idx]
+    }
+
+    /// Reinforcement from an inference outcome: a positive `reward` moves the priority of the
+    /// parent concepts and the rule towards 1, a negative one towards 0, by `feedback_rate`.
+    fn inference_feedback(&mut self, rule_idx: usize, parents: [&Term; 2], reward: f32) {
+        let rate = self.feedback_rate * reward;
+        let adjust = |p: f32| {
+            let step = if rate > 0.0 { rate * (1.0 - p) } else { rate * p };
+            (p + step).clamp(0.01, 0.99)
+        };
+
+        for term in parents {
+            if rate < 0.0 && self.memory.is_pinned(term) {
+                continue;
+            }
+            if let Some(concept) = self.memory.get_mut(term) {
+                concept.priority = adjust(concept.priority);
+            }
+        }
+        if self.rule_priorities.len() <= rule_idx {
+            self.rule_priorities.resize(rule_idx + 1, DEFAULT_RULE_PRIORITY);
+        }
+        self.rule_priorities[rule_idx] = adjust(self.rule_priorities[rule_idx]);
+    }
+
+    fn execute_inference_logic(&mut self, rule_idx: usize, conclusion_template: Term, truth_fn: fn(TruthValue, TruthValue) -> TruthValue, bindings: &Bindings, concept_a: &Concept, concept_b: &Concept) {
         // Generate conclusion term
-        let conclusion_term = substitute(&conclusion_template, bindings);
+        let conclusion_term = self.canonical(&substitute(&conclusion_template, bindings));
         
-        // Calculate Truth
-        let new_truth = (truth_fn)(concept_a.truth, concept_b.truth);
+        // Calculate Truth; premises that share no atom were only paired by vector similarity,
+        // so the conclusion is discounted by how close that semantic leap was
+        let mut new_truth = (truth_fn)(concept_a.truth, concept_b.truth);
+        if !concept_a.term.shares_atom(&concept_b.term) {
+            new_truth = discount(new_truth, concept_a.vector.similarity(&concept_b.vector));
+        }
         
         // Merge Stamps
         let new_stamp = concept_a.stamp.merge(&concept_b.stamp);
 
-        // Debug Output
-        println!("[DEBUG] Derived: {:?} %{};{}%", conclusion_term, new_truth.frequency, new_truth.confidence);
+        // Filter weak derivations; rules that proved useful get a larger share of the budget
+        let rule_factor = 0.5 + self.rule_priority(rule_idx);
+        let priority = (concept_a.priority + concept_b.priority) / 2.0 * new_truth.confidence * rule_factor
+            * self.complexity_penalty(&conclusion_term);
+        let parents = [concept_a.term.term(), concept_b.term.term()];
+        let new_vector = Hypervector::bundle_with(&[concept_a.vector, concept_b.vector], self.tie_break);
+        if self.is_redundant(&conclusion_term, &new_vector, new_truth, &new_stamp) {
+            self.rule_usage_mut(rule_idx).rejected += 1;
+            self.inference_feedback(rule_idx, parents, -0.5);
+            return;
+        }
+        if !self.accept_derivation(&conclusion_term, new_truth, priority) {
+            self.rule_usage_mut(rule_idx).rejected += 1;
+            self.inference_feedback(rule_idx, parents, -1.0);
+            return;
+        }
+        self.rule_usage_mut(rule_idx).accepted += 1;
+        for term in [&concept_a.term, &concept_b.term, &conclusion_term] {
+            self.record_use(term, ActivityKind::Derivation);
+        }
+
+        // Novel conclusions are rewarded by their confidence, ones that add nothing are penalized
+        let reward = match self.memory.get(&conclusion_term) {
+            Some(existing) if !existing.beliefs.is_empty() => {
+                if new_truth.confidence <= existing.truth.confidence { -0.5 } else { 0.0 }
+            }
+            _ => new_truth.confidence,
+        };
+        if reward != 0.0 {
+            self.inference_feedback(rule_idx, parents, reward);
+        }
+
+        if self.trace {
+            eprintln!("[DEBUG] Derived: {:?} %{};{}% from [{}]", conclusion_term, new_truth.frequency, new_truth.confidence,
+                self.evidence_labels(&new_stamp).join(", "));
+        }
 
         // Create new Concept
-        let new_vector = Hypervector::bundle(&[concept_a.vector, concept_b.vector]);
-
-        let new_concept = Concept::new(conclusion_term.clone(), new_vector, new_truth, new_stamp.clone());
+        let mut new_concept = Concept::new(conclusion_term.clone(), new_vector, new_truth, new_stamp.clone());
+        new_concept.priority = priority;
         
         // Add to output buffer
         let sentence = Sentence::new(conclusion_term, Punctuation::Judgement, new_truth, new_stamp);
@@ -277,39 +2179,605 @@ impl NarsSystem {
         self.add_concept(new_concept, true);
     }
 
+    /// One confidence erosion pass (see `ConfidenceErosion`): concepts without new evidence for
+    /// `idle_cycles` and without recent activity (selection, association or derivation) lose
+    /// confidence by the policy of their namespace. Returns the number of concepts eroded.
+    pub fn erode_beliefs(&mut self) -> usize {
+        let now = self.cycle_count;
+        let idle: Vec<Term> = self.memory.values()
+            .filter(|c| c.last_reinforced() + self.erosion.idle_cycles <= now && self.activity.counts(&c.term).total() == 0)
+            .map(|c| c.term.term().clone())
+            .collect();
+        let mut eroded = 0;
+        for term in idle {
+            let Some(concept) = self.memory.get(&term) else { continue };
+            let policy = self.erosion.policy_for(concept);
+            if self.memory.get_mut(&term).is_some_and(|c| c.erode(policy)) {
+                eroded += 1;
+            }
+        }
+        eroded
+    }
 
-    pub fn load_embeddings_from_file(&mut self, path: &str) -> std::io::Result<()> {
-        load_embeddings(path, self)
+    /// Renormalization pass over the concepts changed since the last one: rebalances bit
+    /// density and re-applies the identity floor.
+    pub fn renormalize_vectors(&mut self) {
+        let drift = self.drift_control;
+        let due: Vec<Term> = self.memory.take_reshaped().into_iter()
+            .filter(|t| self.memory.get(t).is_some_and(|c| c.needs_renormalizing(&drift)))
+            .collect();
+        for term in due {
+            if let Some(concept) = self.memory.get_mut(&term) {
+                concept.renormalize(&drift);
+            }
+        }
     }
 
-    pub fn save_memory(&self, filename: &str) -> Result<(), Box<dyn Error>> {
-        let f = File::create(filename)?;
-        bincode::serialize_into(f, &self.memory)?;
+    /// Rebuilds the vectors of compound concepts from the current vectors of their components,
+    /// as `resolve_vector` builds them for new terms, innermost compounds first. Hebbian
+    /// learning on atoms thereby reaches the statements containing them; what compounds
+    /// learned themselves is replaced. Returns the number of vectors that changed.
+    pub fn reground_vectors(&mut self) -> usize {
+        let mut compounds: Vec<Term> = self.memory.values()
+            .filter(|c| matches!(c.term.term(), Term::Compound(..)) && !c.grounded && !self.perceptual.contains_key(&c.term))
+            .map(|c| c.term.term().clone()).collect();
+        compounds.sort_by_key(Term::complexity);
+        let mut changed = 0;
+        for term in compounds {
+            let Term::Compound(op, args) = &term else { continue };
+            let arg_vectors: Vec<Hypervector> = args.iter().map(|a| self.resolve_vector(a)).collect();
+            let grounded = Hypervector::compound_with(op, &arg_vectors, self.tie_break);
+            if let Some(concept) = self.memory.get_mut(&term)
+                && concept.vector != grounded
+            {
+                concept.set_vector(grounded);
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Merges concepts whose terms are logically equivalent (same normalized form) and whose
+    /// vectors are near-identical. Beliefs are combined by revision and attention moves to the
+    /// surviving concept. Returns the number of concepts merged away.
+    pub fn consolidate(&mut self) -> usize {
+        let mut merged = 0;
+        for mut terms in self.memory.variant_groups() {
+            // Deterministic survivor: the first pinned term, else the first in canonical order
+            // (so canonical lookups find it), else the first term in display order
+            terms.sort_by_key(|t| (!self.memory.is_pinned(t), self.canonical(t) != *t, t.to_string()));
+            let survivor_term = terms.remove(0);
+            for other_term in terms {
+                if self.memory.is_pinned(&other_term) {
+                    continue;
+                }
+                let similar = match (self.memory.get(&survivor_term), self.memory.get(&other_term)) {
+                    (Some(a), Some(b)) => a.vector.similarity(&b.vector) >= self.merge_similarity,
+                    _ => false,
+                };
+                if similar {
+                    self.merge_concepts(&survivor_term, &other_term);
+                    merged += 1;
+                }
+            }
+        }
+        merged
+    }
+
+    fn merge_concepts(&mut self, survivor_term: &Term, other_term: &Term) {
+        let (Some(mut survivor), Some(other)) = (self.memory.get(survivor_term).cloned(), self.memory.remove(other_term)) else {
+            return;
+        };
+
+        survivor.truth = if survivor.stamp.overlaps(&other.stamp) {
+            // Shared evidence: revision would double count, keep the stronger one
+            if other.truth.confidence > survivor.truth.confidence { other.truth } else { survivor.truth }
+        } else {
+            revision(survivor.truth, other.truth)
+        };
+        survivor.stamp = survivor.stamp.merge(&other.stamp);
+        survivor.priority = survivor.priority.max(other.priority);
+        survivor.durability = survivor.durability.max(other.durability);
+        for mut belief in other.beliefs {
+            belief.term = survivor.term.term().clone();
+            survivor.add_belief(belief);
+        }
+
+        // Redirect attention held by the merged concept
+        if let Some(p) = self.buffer.remove(other_term) {
+            let current = self.buffer.name_map.get(survivor_term).copied().unwrap_or(0.0);
+            self.attend(survivor_term.clone(), p.max(current));
+        }
+        self.memory.put(survivor);
+    }
+
+    /// Drift from the origin vector and number of learning updates per concept, most drifted first.
+    pub fn drift_report(&self) -> Vec<(Term, f32, u32)> {
+        let mut report: Vec<(Term, f32, u32)> = self.memory.values()
+            .map(|c| (c.term.term().clone(), c.drift(), c.update_count))
+            .collect();
+        report.sort_by(|a, b| b.1.total_cmp(&a.1));
+        report
+    }
+
+    /// Adds a word concept from the embedding file, with its confidence scaled by the
+    /// reliability of its stamp's sources, and remembers its projected vector.
+    pub fn add_embedding(&mut self, mut concept: Concept) {
+        concept.truth.confidence *= self.stamp_reliability(&concept.stamp);
+        if let Term::Atom(word) = concept.term.term() {
+            self.vocabulary.insert(word.clone(), concept.vector);
+        }
+        self.add_concept(concept, false);
+    }
+
+    /// Grounds `term` to the meaning of `text_label` by blending the label's embedding
+    /// into the concept's hypervector (weighted bundle, `grounding_weight`).
+    pub fn ground(&mut self, term: &Term, text_label: &str) -> Result<(), NarsError> {
+        let label_vector = *self.vocabulary.get(text_label)
+            .ok_or_else(|| NarsError::NotFound(text_label.to_string()))?;
+
+        let mut concept = match self.memory.take(term) {
+            Some(c) => c,
+            None => {
+                let vector = self.resolve_vector(term);
+                Concept::new(term.clone(), vector, TruthValue::new(0.5, 0.0), Stamp::new(0, vec![]))
+            }
+        };
+        concept.vector.update(&label_vector, self.grounding_weight);
+        // Grounding is deliberate, so the grounded vector becomes the new identity anchor
+        concept.set_origin(concept.vector);
+        self.memory.put(concept);
         Ok(())
     }
 
-    pub fn load_memory(&mut self, filename: &str) -> Result<(), Box<dyn Error>> {
-        let f = File::open(filename)?;
-        let mut store: ConceptStore = bincode::deserialize_from(f)?;
-        // Rebuild bag
-        for (term, concept) in store.map.iter() {
-             let utility = (concept.priority * concept.durability).clamp(0.01, 0.99);
-             store.priority_bag.put(term.clone(), utility);
+    /// Grounds `term`, typically an instance such as `{img_42}`, in perceptual features (a CNN
+    /// image embedding, MFCC means of a sound...) projected by `grounding`; see `attach_vector`.
+    /// Returns the attached vector.
+    pub fn attach_features(&mut self, term: &Term, features: &[f32], grounding: &impl Grounding) -> Hypervector {
+        let vector = grounding.project(features);
+        self.attach_vector(term, vector);
+        vector
+    }
+
+    /// Gives `term` `vector` as its meaning: the concept (created without beliefs if needed)
+    /// takes it as vector and identity anchor and is marked `grounded`, and the vector is kept
+    /// in `perceptual`, so that it outlives eviction and is not rebuilt from the term's
+    /// components by regrounding. Both survive saving and loading memory.
+    /// Statements about the term then relate the percept to symbolic knowledge.
+    pub fn attach_vector(&mut self, term: &Term, vector: Hypervector) {
+        let term = self.canonical(term);
+        let mut concept = self.memory.take(&term)
+            .unwrap_or_else(|| Concept::new(term.clone(), vector, TruthValue::new(0.5, 0.0), Stamp::new(0, vec![])));
+        concept.set_vector(vector);
+        concept.set_origin(vector);
+        concept.grounded = true;
+        self.perceptual.insert(term, vector);
+        self.memory.put(concept);
+    }
+
+    /// Relational analogy "a is to b as c is to ?": the query `V(b) ⊕ V(a) ⊕ V(c)` is decoded
+    /// against item memory (all concepts except the three inputs). Returns the closest
+    /// concepts with their similarity, best first.
+    pub fn analogize(&self, a: &Term, b: &Term, c: &Term) -> Vec<(Term, f32)> {
+        let query = self.resolve_vector(b)
+            .bind(&self.resolve_vector(a))
+            .bind(&self.resolve_vector(c));
+
+        let mut matches = self.nearest_terms(&query, ANALOGY_RESULTS + 3);
+        matches.retain(|(term, _)| term != a && term != b && term != c);
+        matches.truncate(ANALOGY_RESULTS);
+        matches
+    }
+
+    /// The `n` concepts whose vectors are most similar to `vector`, most similar first.
+    /// Mounted packs are searched too, for terms memory does not shadow.
+    pub fn nearest_terms(&self, vector: &Hypervector, n: usize) -> Vec<(Term, f32)> {
+        let mut matches: Vec<(Term, f32)> = self.memory.values()
+            .map(|concept| (concept.term.term().clone(), vector.similarity(&concept.vector)))
+            .collect();
+        let mut seen = HashSet::new();
+        for pack in self.packs.iter().rev() {
+            for (idx, similarity) in pack.nearest(vector, n) {
+                if let Some(term) = pack.term(idx)
+                    && self.memory.get(&term).is_none()
+                    && seen.insert(term.clone())
+                {
+                    matches.push((term, similarity));
+                }
+            }
+        }
+        matches.sort_by(|x, y| y.1.total_cmp(&x.1));
+        matches.truncate(n);
+        matches
+    }
+
+    /// Vector of a word or term: its grounded embedding if it has one, otherwise its concept
+    /// (or composed) vector.
+    pub fn vector_for(&self, term: &Term) -> Hypervector {
+        match term {
+            Term::Atom(word) => self.vocabulary.get(word).copied().unwrap_or_else(|| self.resolve_vector(term)),
+            _ => self.resolve_vector(term),
+        }
+    }
+
+    pub fn load_embeddings_from_file(&mut self, path: &str) -> Result<(), NarsError> {
+        load_embeddings(path, self)
+    }
+
+    /// Operational counters for monitoring, e.g. served as Prometheus text by a host process.
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            uptime: self.started.elapsed(),
+            cycles: self.cycle_count,
+            concepts: self.memory.len(),
+            concept_capacity: self.memory.capacity,
+            buffer_len: self.buffer.count,
+            buffer_capacity: self.buffer.capacity,
+            pending_tasks: self.lanes.len(),
+            inputs: self.backpressure,
+            derivations: self.stats.derivations,
+            answer_latency: self.answer_latency.clone(),
+        }
+    }
+
+    /// The current (revised) belief of every concept in memory that passes `filter`, sorted
+    /// by term, e.g. to be written out with `script::write_nal` and re-ingested later.
+    pub fn export_beliefs(&self, filter: &BeliefFilter) -> Vec<Sentence> {
+        let mut beliefs: Vec<Sentence> = self.memory.values()
+            .filter(|c| !c.beliefs.is_empty() && c.truth.confidence >= filter.min_confidence)
+            .filter(|c| {
+                let stamp = &c.stamp;
+                let inputs: Vec<&EvidenceRecord> = stamp.evidence.iter().filter_map(|id| self.evidence(*id)).collect();
+                let origin = if !inputs.is_empty() && inputs.len() == stamp.evidence.len() && inputs.iter().all(|r| r.sentence.term == c.term) {
+                    BeliefOrigin::Input
+                } else {
+                    BeliefOrigin::Derived
+                };
+                let newest = inputs.iter().map(|r| r.sentence.stamp.creation_time).max();
+                filter.origin.is_none_or(|o| o == origin)
+                    && filter.max_age.is_none_or(|age| newest.is_some_and(|t| t + age >= self.cycle_count))
+                    && !(filter.eternal_only && inputs.iter().any(|r| r.sentence.is_event()))
+                    && filter.source.as_ref().is_none_or(|prefix| stamp.sources.iter().any(|s| s.to_string().starts_with(prefix.as_str())))
+            })
+            .map(|c| Sentence::new(c.term.term().clone(), Punctuation::Judgement, c.truth, c.stamp.clone()))
+            .collect();
+        beliefs.sort_by_cached_key(|s| s.term.to_string());
+        beliefs
+    }
+
+    /// Writes every concept in memory to a knowledge pack file. Returns the number written.
+    pub fn export_pack(&self, path: &str) -> Result<usize, NarsError> {
+        KnowledgePack::write(path, self.memory.values())
+    }
+
+    /// Mounts a knowledge pack read-only beneath memory: lookups, answers and similarity
+    /// searches see its concepts, while revisions and learning write to memory. The entries
+    /// get evidence ids of their own. Returns the number of entries.
+    pub fn mount_pack(&mut self, path: &str) -> Result<usize, NarsError> {
+        let mut pack = KnowledgePack::open(path)?;
+        pack.evidence_base = self.next_evidence_id;
+        self.next_evidence_id += pack.len() as u64;
+        let entries = pack.len();
+        self.packs.push(pack);
+        Ok(entries)
+    }
+
+    /// The concept for `term` from memory, or else from the latest mounted pack holding it.
+    pub fn lookup(&self, term: &Term) -> Option<Cow<'_, Concept>> {
+        if let Some(concept) = self.memory.get(term) {
+            return Some(Cow::Borrowed(concept));
         }
+        self.packs.iter().rev().find_map(|pack| pack.concept(pack.find(term)?)).map(Cow::Owned)
+    }
+
+    /// Saves memory to a versioned snapshot file (see `SnapshotHeader`).
+    pub fn save_memory(&self, filename: &str) -> Result<(), NarsError> {
+        write_snapshot(filename, self.cycle_count, &self.memory)?;
+        Ok(())
+    }
+
+    /// Replaces memory with the snapshot in `filename`, migrating it from an older layout if
+    /// needed. Snapshots written by a newer version are refused.
+    pub fn load_memory(&mut self, filename: &str) -> Result<(), NarsError> {
+        let (_, mut store) = read_snapshot(filename)?;
+        store.rebuild_index();
         self.memory = store;
+        // Grounded concepts keep their percept, as attached, from eviction
+        let grounded: Vec<(Term, Hypervector)> = self.memory.values()
+            .filter(|c| c.grounded)
+            .map(|c| (c.term.term().clone(), c.origin()))
+            .collect();
+        self.perceptual.extend(grounded);
         Ok(())
     }
 
-    pub fn answer_query(&self, term: &Term) -> Option<Sentence> {
-        if let Some(concept) = self.memory.get(term) {
-            // Only return beliefs with actual confidence
-            return concept.beliefs.iter()
-                .filter(|b| b.truth.confidence > 0.01)
-                .max_by(|a, b| a.truth.confidence.partial_cmp(&b.truth.confidence).unwrap())
-                .cloned();
+    /// Asks a question and runs up to `max_cycles` until it has an answer. Returns every
+    /// belief answering it (the term itself, or terms its query variables unify with), best
+    /// first; empty if nothing was found in time.
+    pub fn answer_question(&mut self, term: &Term, max_cycles: usize) -> Vec<Answer> {
+        let question = Sentence::new(term.clone(), Punctuation::Question, TruthValue::new(0.0, 0.0), Stamp::new(self.cycle_count, vec![]));
+        self.input(question);
+        for cycles in 0..=max_cycles {
+            let mut answers: Vec<Sentence> = self.memory.candidates_for(term).into_iter()
+                .filter(|c| c.term == *term || unify_with_bindings(term, &c.term, HashMap::new()).is_some())
+                .filter_map(|c| self.answer_query(&c.term))
+                .collect();
+            if answers.is_empty() && let Some(answer) = self.answer_query(term) {
+                answers.push(answer); // only held by a pack
+            }
+            if !answers.is_empty() {
+                answers.sort_by(|a, b| b.truth.confidence.total_cmp(&a.truth.confidence));
+                return answers.into_iter()
+                    .map(|sentence| Answer { sources: self.evidence_sources(&sentence.stamp), sentence, cycles })
+                    .collect();
+            }
+            if cycles < max_cycles {
+                self.cycle();
+            }
+        }
+        Vec::new()
+    }
+
+    /// Answers `question` as if `premises` held: the premises are asserted in a scratch context,
+    /// `cycles` cycles are run and everything input, derived or learned meanwhile is discarded
+    /// again (see `pop_context`). Beyond what a context restores, pending sensor readings, the
+    /// answer latency and the random generator are kept as they were and no snapshot is
+    /// published, so the system is left exactly as it was.
+    pub fn suppose(&mut self, premises: &[Sentence], question: &Term, cycles: usize) -> Option<Sentence> {
+        let (sensors, answer_latency, rng) = (self.sensors.clone(), self.answer_latency.clone(), self.rng.clone());
+        let query_handle = self.query_handle.take();
+        self.push_context("suppose");
+        for premise in premises {
+            self.input(premise.clone());
+        }
+        self.input(Sentence::new(question.clone(), Punctuation::Question, TruthValue::new(0.0, 0.0), Stamp::new(self.cycle_count, vec![])));
+        self.run_cycles(cycles);
+        let answer = self.answer_query(question);
+
+        let _ = self.pop_context();
+        self.sensors = sensors;
+        self.answer_latency = answer_latency;
+        self.rng = rng;
+        self.query_handle = query_handle;
+        answer
+    }
+
+    /// The input judgements whose evidence `stamp` contains, i.e. the premises a derived belief
+    /// rests on. Registered ids give their input sentence; for the others the most confident
+    /// belief carrying only that id is taken, so single-premise derivations sharing the id are
+    /// not reported as inputs.
+    pub fn evidence_sources(&self, stamp: &Stamp) -> Vec<Sentence> {
+        let mut sources: HashMap<u64, &Sentence> = stamp.evidence.iter()
+            .filter_map(|id| Some((*id, &self.evidence(*id)?.sentence)))
+            .collect();
+        for belief in self.memory.values().flat_map(|c| c.beliefs.iter()) {
+            if let [id] = belief.stamp.evidence.as_slice()
+                && stamp.evidence.contains(id)
+                && !self.evidence_registry.contains_key(id)
+                && sources.get(id).is_none_or(|s| s.truth.confidence < belief.truth.confidence)
+            {
+                sources.insert(*id, belief);
+            }
+        }
+        let mut sources: Vec<(u64, Sentence)> = sources.into_iter().map(|(id, s)| (id, s.clone())).collect();
+        sources.sort_by_key(|(id, _)| *id);
+        sources.into_iter().map(|(_, s)| s).collect()
+    }
+
+    /// Answers a quest with the most desired goal on its term, or on a term its query
+    /// variables unify with; input goals and derived subgoals both count.
+    pub fn answer_quest(&self, term: &Term) -> Option<Sentence> {
+        let term = self.canonical(term);
+        self.memory.candidates_for(&term).into_iter()
+            .filter(|c| c.term == term || unify_with_bindings(&term, &c.term, HashMap::new()).is_some())
+            .filter_map(|c| c.desire())
+            .filter(|g| g.truth.confidence > 0.01)
+            .max_by(|a, b| a.truth.expectation().total_cmp(&b.truth.expectation()))
+            .cloned()
+    }
+
+    /// Candidate answers to `term` (its beliefs), best first, see `rank_answers`.
+    pub fn ranked_answers(&self, term: &Term) -> Vec<RankedAnswer> {
+        self.lookup(&self.canonical(term)).map_or_else(Vec::new, |concept| rank_answers(&concept.beliefs))
+    }
+
+    /// Immediate estimate of `term` for callers that cannot wait for the control loop: its
+    /// beliefs plus the conclusions on it that one enabled rule draws from beliefs in memory
+    /// (mounted packs only count as direct beliefs), revised together as far as their evidence
+    /// is independent. Nothing is stored or derived. Premises are looked up through the atoms
+    /// the rule's premise shares with `term` (and the first premise), and at most
+    /// `MAX_EVALUATED_PREMISES` premises or pairs are drawn from.
+    pub fn evaluate(&self, term: &Term) -> Expectation {
+        let term = self.canonical(term);
+        let mut candidates: Vec<Sentence> = self.lookup(&term)
+            .map(|c| c.beliefs.iter().filter(|b| b.truth.confidence > 0.01).cloned().collect())
+            .unwrap_or_default();
+        let premises = |pattern: &Term, bindings: &Bindings| {
+            let mut concepts = self.memory.candidates_for(&substitute(pattern, bindings));
+            concepts.retain(|c| !c.beliefs.is_empty() && c.term != term);
+            concepts
+        };
+        let judgement = |truth, stamp| Sentence::new(term.clone(), Punctuation::Judgement, truth, stamp);
+        let mut budget = MAX_EVALUATED_PREMISES;
+        'rules: for (rule_idx, rule) in self.rules.iter().enumerate() {
+            if rule.meta.question_only || !self.is_rule_enabled(rule_idx) {
+                continue;
+            }
+            let Some(bindings) = unify_with_bindings(&rule.conclusion, &term, HashMap::new()) else { continue };
+            let holds = |b: &Bindings| rule.meta.preconditions.iter().all(|p| p.holds(b));
+            match (rule.truth_fn, rule.premises.as_slice()) {
+                (TruthFunction::Single(tf), [premise]) => {
+                    for a in premises(premise, &bindings) {
+                        let Some(left) = budget.checked_sub(1) else { break 'rules };
+                        budget = left;
+                        if let Some(b) = unify_with_bindings(premise, &a.term, bindings.clone())
+                            && holds(&b)
+                        {
+                            candidates.push(judgement(tf(a.truth), a.stamp.clone()));
+                        }
+                    }
+                }
+                (TruthFunction::Double(tf), [first, second]) => {
+                    for a in premises(first, &bindings) {
+                        let Some(bindings) = unify_with_bindings(first, &a.term, bindings.clone()) else { continue };
+                        for b in premises(second, &bindings) {
+                            let Some(left) = budget.checked_sub(1) else { break 'rules };
+                            budget = left;
+                            if a.stamp.overlaps(&b.stamp)
+                                || !unify_with_bindings(second, &b.term, bindings.clone()).is_some_and(|b| holds(&b))
+                            {
+                                continue;
+                            }
+                            // Discounted as in `execute_inference_logic`
+                            let mut truth = tf(a.truth, b.truth);
+                            if !a.term.shares_atom(&b.term) {
+                                truth = discount(truth, a.vector.similarity(&b.vector));
+                            }
+                            candidates.push(judgement(truth, a.stamp.merge(&b.stamp)));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Expectation::combine(candidates)
+    }
+
+    /// The strongest chain of inheritance (or of implication) statements in memory from
+    /// `from` to `to`, by the confidence of deduction composed along it, or None if memory
+    /// does not connect them within `MAX_PATH_LENGTH` statements. Chains are searched best
+    /// first, do not mix copulas and only follow statements memory believes (see `believed`).
+    pub fn path(&self, from: &Term, to: &Term) -> Option<InferencePath> {
+        let mut open: Vec<(Term, Vec<Sentence>, TruthValue)> = vec![(from.clone(), Vec::new(), TruthValue::new(1.0, 1.0))];
+        let mut settled = HashSet::new(); // (node, copula of the chain reaching it)
+        while let Some(best) = (0..open.len()).max_by(|&i, &j| open[i].2.confidence.total_cmp(&open[j].2.confidence)) {
+            let (node, steps, truth) = open.swap_remove(best);
+            if node == *to && !steps.is_empty() {
+                return Some(InferencePath { steps, truth });
+            }
+            let copula = steps.first().and_then(|s| match &s.term {
+                Term::Compound(op, _) => Some(op.clone()),
+                _ => None,
+            });
+            if !settled.insert((node.clone(), copula.clone())) || steps.len() >= MAX_PATH_LENGTH {
+                continue;
+            }
+            for concept in self.memory.statements_from(&node) {
+                let Term::Compound(op, args) = concept.term.term() else { continue };
+                if copula.as_ref().is_some_and(|c| c != op) || !Self::believed(concept)
+                    || settled.contains(&(args[1].clone(), Some(op.clone())))
+                {
+                    continue;
+                }
+                let composed = if steps.is_empty() { concept.truth } else { deduction(truth, concept.truth) };
+                let mut chain = steps.clone();
+                chain.push(Sentence::new(concept.term.term().clone(), Punctuation::Judgement, concept.truth, concept.stamp.clone()));
+                open.push((args[1].clone(), chain, composed));
+            }
         }
         None
     }
+
+    /// Every term `term` is believed to inherit from, directly or through other inheritance
+    /// statements in memory, nearest first.
+    pub fn ancestors(&self, term: &Term) -> Vec<Term> {
+        self.inheritance_closure(term, |system, node| system.memory.statements_from(node)
+            .filter_map(|c| match c.term.term() {
+                Term::Compound(Operator::Inheritance, args) if Self::believed(c) => Some(args[1].clone()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Every term believed to inherit from `term`, directly or through other inheritance
+    /// statements in memory, nearest first.
+    pub fn descendants(&self, term: &Term) -> Vec<Term> {
+        self.inheritance_closure(term, |system, node| system.memory.candidates_for(node).into_iter()
+            .filter_map(|c| match c.term.term() {
+                Term::Compound(Operator::Inheritance, args) if args[1] == *node && Self::believed(c) => Some(args[0].clone()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Whether memory holds `concept` more true than false.
+    fn believed(concept: &Concept) -> bool {
+        concept.truth.confidence > 0.0 && concept.truth.expectation() > 0.5
+    }
+
+    /// Terms reachable from `term` by repeatedly following `step`, breadth first.
+    fn inheritance_closure(&self, term: &Term, step: impl Fn(&Self, &Term) -> Vec<Term>) -> Vec<Term> {
+        let term = self.canonical(term);
+        let (mut reached, mut seen) = (Vec::new(), HashSet::from([term.clone()]));
+        let mut frontier = VecDeque::from([term]);
+        while let Some(node) = frontier.pop_front() {
+            for next in step(self, &node) {
+                if seen.insert(next.clone()) {
+                    reached.push(next.clone());
+                    frontier.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// The answer to a question on `term`: the first of `ranked_answers`.
+    pub fn answer_query(&self, term: &Term) -> Option<Sentence> {
+        self.ranked_answers(term).into_iter().next().map(|answer| answer.sentence)
+    }
+}
+
+/// `beliefs` with actual confidence as candidate answers, best first. The score averages
+/// expectation and confidence; a candidate whose evidence is a subset of another's adds
+/// nothing independent and is scored down by `DEPENDENT_ANSWER_PENALTY`.
+pub(crate) fn rank_answers(beliefs: &[Sentence]) -> Vec<RankedAnswer> {
+    let beliefs: Vec<&Sentence> = beliefs.iter().filter(|b| b.truth.confidence > 0.01).collect();
+    let covers = |other: &Sentence, belief: &Sentence| {
+        !belief.stamp.evidence.is_empty()
+            && other.stamp.evidence.len() > belief.stamp.evidence.len()
+            && belief.stamp.evidence.iter().all(|id| other.stamp.evidence.contains(id))
+    };
+    let mut ranked: Vec<RankedAnswer> = beliefs.iter().map(|belief| {
+        let dependent = beliefs.iter().any(|other| covers(other, belief));
+        let score = (belief.truth.expectation() + belief.truth.confidence) / 2.0;
+        RankedAnswer {
+            sentence: (*belief).clone(),
+            score: if dependent { score * DEPENDENT_ANSWER_PENALTY } else { score },
+            dependent,
+        }
+    }).collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
+
+/// Appends an event to a queue drained by clients, dropping the oldest one once it holds
+/// `MAX_UNDRAINED_EVENTS`, so clients that never drain it do not grow it without bound.
+fn push_undrained<T>(queue: &mut VecDeque<T>, event: T) {
+    if queue.len() == MAX_UNDRAINED_EVENTS {
+        queue.pop_front();
+    }
+    queue.push_back(event);
+}
+
+/// Splits the antecedent of a procedural implication into precondition and operation:
+/// `(&/, a, b, ^op)` gives `((&/, a, b), ^op)`, `(&/, a, ^op)` gives `(a, ^op)`, a bare
+/// operation has no precondition, and a non-procedural antecedent has no operation.
+fn split_procedure(antecedent: &Term) -> (Option<Term>, Option<Term>) {
+    if antecedent.is_operation() {
+        return (None, Some(antecedent.clone()));
+    }
+    if let Term::Compound(Operator::SequentialEvents, args) = antecedent
+        && let Some((last, rest)) = args.split_last()
+        && last.is_operation()
+    {
+        let precondition = match rest {
+            [] => None,
+            [single] => Some(single.clone()),
+            _ => Some(Term::Compound(Operator::SequentialEvents, rest.to_vec())),
+        };
+        return (precondition, Some(last.clone()));
+    }
+    (Some(antecedent.clone()), None)
 }
 
 fn substitute(term: &Term, bindings: &Bindings) -> Term {
@@ -0,0 +1,67 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum NarsError {
+    /// Narsese or rule text that could not be parsed; `offset` is the byte position of the failure.
+    Parse { input: String, offset: usize, message: String },
+    RuleLoad { rule: String, message: String },
+    Io(io::Error),
+    Serialization(String),
+    NotFound(String),
+    /// A compound that breaks its operator's arity or structure, see `Term::compound`.
+    InvalidTerm(String),
+}
+
+impl NarsError {
+    pub fn parse(input: &str, remaining: &str, message: impl Into<String>) -> Self {
+        NarsError::Parse {
+            input: input.to_string(),
+            offset: input.len().saturating_sub(remaining.len()),
+            message: message.into(),
+        }
+    }
+
+    pub fn rule_load(rule: &str, message: impl Into<String>) -> Self {
+        NarsError::RuleLoad {
+            rule: rule.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for NarsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NarsError::Parse { input, offset, message } => {
+                write!(f, "Parse error at {}: {} in '{}'", offset, message, input)
+            }
+            NarsError::RuleLoad { rule, message } => write!(f, "Rule load error: {} in '{}'", message, rule),
+            NarsError::Io(e) => write!(f, "IO error: {}", e),
+            NarsError::Serialization(e) => write!(f, "Serialization error: {}", e),
+            NarsError::NotFound(name) => write!(f, "Not found: {}", name),
+            NarsError::InvalidTerm(message) => write!(f, "Invalid term: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NarsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NarsError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NarsError {
+    fn from(e: io::Error) -> Self {
+        NarsError::Io(e)
+    }
+}
+
+impl From<bincode::Error> for NarsError {
+    fn from(e: bincode::Error) -> Self {
+        NarsError::Serialization(e.to_string())
+    }
+}
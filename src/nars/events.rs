@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::sentence::Sentence;
+
+/// Recent events (tensed judgements), ordered by occurrence time (`stamp.creation_time`).
+/// Events are transient: they are kept here for temporal reasoning while concept memory only
+/// receives their eternalized truth. The oldest event is dropped when the buffer is full.
+#[derive(Debug, Clone)]
+pub struct EventMemory {
+    events: VecDeque<Sentence>,
+    capacity: usize,
+}
+
+impl EventMemory {
+    pub fn new(capacity: usize) -> Self {
+        Self { events: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds an event at its place in time; events arriving late are not lost to ordering.
+    pub fn push(&mut self, event: Sentence) {
+        if self.capacity == 0 {
+            return;
+        }
+        let time = event.stamp.creation_time;
+        let idx = self.events.partition_point(|e| e.stamp.creation_time <= time);
+        self.events.insert(idx, event);
+        if self.events.len() > self.capacity {
+            self.events.pop_front();
+        }
+    }
+
+    /// All events, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Sentence> {
+        self.events.iter()
+    }
+
+    pub fn latest(&self) -> Option<&Sentence> {
+        self.events.back()
+    }
+
+    /// Events that occurred in `from..=to`, oldest first.
+    pub fn between(&self, from: u64, to: u64) -> impl Iterator<Item = &Sentence> {
+        let start = self.events.partition_point(|e| e.stamp.creation_time < from);
+        self.events.range(start..).take_while(move |e| e.stamp.creation_time <= to)
+    }
+
+    /// Up to `n` random events that occurred at most `window` cycles before `later` (or at
+    /// the same time) and share no evidence with it: premises for temporal induction.
+    pub fn sample_before(&self, later: &Sentence, window: u64, n: usize, rng: &mut impl Rng) -> Vec<&Sentence> {
+        let time = later.stamp.creation_time;
+        let mut candidates: Vec<&Sentence> = self.between(time.saturating_sub(window), time)
+            .filter(|e| !e.stamp.overlaps(&later.stamp))
+            .collect();
+        candidates.shuffle(rng);
+        candidates.truncate(n);
+        candidates
+    }
+
+    /// Up to `n` random pairs `(earlier, later)` for temporal induction, where `later` is one
+    /// of the events of the last `window` cycles and `earlier` is sampled as by `sample_before`.
+    pub fn sample_pairs(&self, window: u64, n: usize, rng: &mut impl Rng) -> Vec<(&Sentence, &Sentence)> {
+        let Some(latest) = self.latest() else { return Vec::new() };
+        let recent = self.between(latest.stamp.creation_time.saturating_sub(window), u64::MAX).count();
+        (0..n)
+            .filter_map(|_| {
+                let later = &self.events[self.events.len() - 1 - rng.random_range(0..recent)];
+                self.sample_before(later, window, 1, rng).pop().map(|earlier| (earlier, later))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::sentence::{Punctuation, Stamp, Tense};
+    use crate::nars::term::Term;
+    use crate::nars::truth::TruthValue;
+
+    fn event(name: &str, time: u64, id: u64) -> Sentence {
+        Sentence::new(Term::atom_from_str(name), Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(time, vec![id]))
+            .with_tense(Tense::Present)
+    }
+
+    #[test]
+    fn test_event_memory_window() {
+        let mut events = EventMemory::new(4);
+        for (name, time, id) in [("a", 1, 1), ("c", 5, 3), ("b", 3, 2), ("d", 7, 4), ("e", 9, 5)] {
+            events.push(event(name, time, id));
+        }
+        // Kept in time order, the oldest dropped at capacity
+        let names = |it: Vec<&Sentence>| it.iter().map(|e| e.term.to_string()).collect::<Vec<_>>();
+        let rng = &mut rand::rng();
+        assert_eq!(names(events.iter().collect()), ["b", "c", "d", "e"]);
+        assert_eq!(names(events.between(4, 7).collect()), ["c", "d"]);
+        assert_eq!(events.latest().unwrap().term.to_string(), "e");
+
+        for (earlier, later) in events.sample_pairs(2, 20, rng) {
+            assert!(earlier.stamp.creation_time <= later.stamp.creation_time);
+            assert!(later.stamp.creation_time - earlier.stamp.creation_time <= 2);
+            assert!(!earlier.stamp.overlaps(&later.stamp));
+        }
+        assert!(!events.sample_pairs(2, 20, rng).is_empty());
+        assert!(events.sample_pairs(1, 20, rng).is_empty());
+        assert_eq!(names(events.sample_before(&event("f", 8, 6), 10, 10, rng)).len(), 3);
+    }
+}
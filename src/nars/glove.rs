@@ -1,31 +1,34 @@
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, BufWriter};
 use std::path::Path;
+use std::sync::Arc;
 use super::control::NarsSystem;
 use super::term::Term;
 use super::memory::{Concept, Hypervector, ProjectionMatrix};
 use super::truth::TruthValue;
-use super::sentence::Stamp;
+use super::sentence::{Source, Stamp};
+use super::error::NarsError;
 
-pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> io::Result<()> {
+pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> Result<(), NarsError> {
     let txt_path = Path::new(path);
     let bin_path = txt_path.with_extension("bin");
 
     // Try loading from binary cache first
     if bin_path.exists() {
-        println!("Loading cached embeddings from {:?}...", bin_path);
+        eprintln!("Loading cached embeddings from {:?}...", bin_path);
         let file = File::open(&bin_path)?;
         let reader = BufReader::new(file);
         match bincode::deserialize_from::<_, Vec<Concept>>(reader) {
             Ok(concepts) => {
-                println!("Loaded {} concepts from cache.", concepts.len());
-                for concept in concepts {
-                    system.add_concept(concept, false);
+                eprintln!("Loaded {} concepts from cache.", concepts.len());
+                for mut concept in concepts {
+                    concept.stamp.sources = vec![Source::Import(path.to_string())];
+                    system.add_embedding(concept);
                 }
                 return Ok(());
             },
             Err(e) => {
-                println!("Failed to load cache: {}. Falling back to text parsing.", e);
+                eprintln!("Failed to load cache: {}. Falling back to text parsing.", e);
                 // Fall through to text parsing
             }
         }
@@ -35,13 +38,13 @@ pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> io::Result<()> {
         return Ok(());
     }
 
-    println!("Parsing embeddings from {:?}...", txt_path);
+    eprintln!("Parsing embeddings from {:?}...", txt_path);
     let file = File::open(txt_path)?;
     let reader = BufReader::new(file);
     
     let mut concepts = Vec::new();
     let mut count = 0;
-    let mut projection_matrix: Option<ProjectionMatrix> = None;
+    let mut projection_matrix = system.projector.clone();
     
     // Limit to top 20,000 words for performance during demo
     // Full GloVe (400k words) would take hours to project on CPU
@@ -49,7 +52,7 @@ pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> io::Result<()> {
 
     for line in reader.lines() {
         if count >= max_words {
-            println!("Reached limit of {} words. Stopping.", max_words);
+            eprintln!("Reached limit of {} words. Stopping.", max_words);
             break;
         }
 
@@ -71,10 +74,12 @@ pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> io::Result<()> {
         let vector_values: Result<Vec<f32>, _> = parts[1..].iter().map(|s| s.parse::<f32>()).collect();
 
         if let Ok(values) = vector_values {
-            // Initialize projection matrix on first valid vector
-            if projection_matrix.is_none() {
-                println!("Initializing projection matrix for dimension {}...", values.len());
-                projection_matrix = Some(ProjectionMatrix::new(values.len()));
+            // Initialize projection matrix on first valid vector (unless a shared one fits)
+            if projection_matrix.as_ref().is_none_or(|m| m.input_dim() != values.len()) {
+                eprintln!("Initializing projection matrix for dimension {}...", values.len());
+                let matrix = Arc::new(ProjectionMatrix::new(values.len()));
+                system.projector = Some(Arc::clone(&matrix));
+                projection_matrix = Some(matrix);
             }
 
             let hypervector = if let Some(ref matrix) = projection_matrix {
@@ -89,6 +94,7 @@ pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> io::Result<()> {
             let stamp = Stamp {
                 creation_time: 0,
                 evidence: Vec::new(),
+                sources: vec![Source::Import(path.to_string())],
             };
             
             let concept = Concept::new(term, hypervector, truth, stamp);
@@ -97,17 +103,17 @@ pub fn load_embeddings(path: &str, system: &mut NarsSystem) -> io::Result<()> {
     }
 
     // Save to cache
-    println!("Saving cache to {:?}...", bin_path);
+    eprintln!("Saving cache to {:?}...", bin_path);
     if let Ok(file) = File::create(&bin_path) {
         let writer = BufWriter::new(file);
         if let Err(e) = bincode::serialize_into(writer, &concepts) {
-            println!("Failed to save cache: {}", e);
+            eprintln!("Failed to save cache: {}", e);
         }
     }
 
     // Add to system
     for concept in concepts {
-        system.add_concept(concept, false);
+        system.add_embedding(concept);
     }
 
     Ok(())
@@ -0,0 +1,107 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use super::memory::{Hypervector, ProjectionMatrix, HV_DIM_BITS, HV_DIM_U64};
+
+/// Maps dense perceptual features (word embeddings, CNN image embeddings, audio MFCC means...)
+/// to a hypervector, so that the angle between two feature vectors becomes the Hamming
+/// distance between their hypervectors and perception can be reasoned about alongside
+/// symbols. See `NarsSystem::attach_features`.
+pub trait Grounding {
+    fn project(&self, features: &[f32]) -> Hypervector;
+}
+
+/// Random hyperplane LSH: bit `i` is set if the features lie on the positive side of a random
+/// hyperplane drawn from `seed` and `i`. Hyperplanes are generated on the fly, so any input
+/// dimension works; a `ProjectionMatrix` is faster for many vectors of one dimension.
+///
+/// Modalities projected with different seeds get independent hyperplanes, so features that
+/// happen to share a dimension do not make an image and a sound look alike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RandomProjection {
+    pub seed: u64,
+}
+
+impl Grounding for RandomProjection {
+    fn project(&self, features: &[f32]) -> Hypervector {
+        let mut bits = [0; HV_DIM_U64];
+        for bit_idx in 0..HV_DIM_BITS {
+            // Seed 0 seeds hyperplane i with i, as embeddings were always projected
+            let mut rng = StdRng::seed_from_u64(self.seed.wrapping_mul(HV_DIM_BITS as u64).wrapping_add(bit_idx as u64));
+            let dot_product: f32 = features.iter().map(|&val| val * rng.random_range(-1.0f32..1.0)).sum();
+            if dot_product > 0.0 {
+                bits[bit_idx / 64] |= 1 << (bit_idx % 64);
+            }
+        }
+        Hypervector { bits }
+    }
+}
+
+impl Grounding for ProjectionMatrix {
+    fn project(&self, features: &[f32]) -> Hypervector {
+        Hypervector::project_with_matrix(features, self)
+    }
+}
+
+/// Standardizes every feature to zero mean and unit variance before projecting. Hyperplanes
+/// through the origin cannot tell apart features that all lie on one side of it: CNN
+/// embeddings after a ReLU are non-negative, and the first coefficients dominate MFCC means,
+/// so unrelated inputs would all get nearly the same hypervector.
+#[derive(Debug, Clone)]
+pub struct StandardizedProjection<G = RandomProjection> {
+    pub mean: Vec<f32>,
+    pub scale: Vec<f32>, // 1 / standard deviation (1 for constant features)
+    pub inner: G,
+}
+
+impl<G: Grounding> StandardizedProjection<G> {
+    /// Fits the per-feature statistics on `samples` (e.g. a batch of embeddings of the kind to
+    /// be grounded) and projects the standardized features with `inner`.
+    pub fn fit(samples: &[Vec<f32>], inner: G) -> Self {
+        let dim = samples.iter().map(Vec::len).max().unwrap_or(0);
+        let n = samples.len().max(1) as f32;
+        let feature = |s: &Vec<f32>, i: usize| s.get(i).copied().unwrap_or(0.0);
+        let mean: Vec<f32> = (0..dim).map(|i| samples.iter().map(|s| feature(s, i)).sum::<f32>() / n).collect();
+        let scale = (0..dim).map(|i| {
+            let variance = samples.iter().map(|s| (feature(s, i) - mean[i]).powi(2)).sum::<f32>() / n;
+            if variance > f32::EPSILON { 1.0 / variance.sqrt() } else { 1.0 }
+        }).collect();
+        Self { mean, scale, inner }
+    }
+}
+
+impl<G: Grounding> Grounding for StandardizedProjection<G> {
+    fn project(&self, features: &[f32]) -> Hypervector {
+        let standardized: Vec<f32> = features.iter().enumerate()
+            .map(|(i, &val)| match (self.mean.get(i), self.scale.get(i)) {
+                (Some(mean), Some(scale)) => (val - mean) * scale,
+                _ => val,
+            })
+            .collect();
+        self.inner.project(&standardized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_projections() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // Non-negative, CNN-like features sharing a large offset
+        let mut embedding = || (0..64).map(|_| 1.0 + rng.random_range(0.0f32..1.0)).collect::<Vec<f32>>();
+        let samples: Vec<Vec<f32>> = (0..50).map(|_| embedding()).collect();
+        let (a, b) = (&samples[0], &samples[1]);
+        let near: Vec<f32> = a.iter().map(|v| v + 0.01).collect();
+
+        let plain = RandomProjection::default();
+        assert_eq!(plain.project(a), Hypervector::project(a));
+        assert!(plain.project(a).similarity(&plain.project(b)) > 0.85);
+        assert!((plain.project(a).similarity(&RandomProjection { seed: 1 }.project(a)) - 0.5).abs() < 0.05);
+
+        // Standardized, unrelated inputs are told apart and close ones stay close
+        let standardized = StandardizedProjection::fit(&samples, plain);
+        assert!(standardized.project(a).similarity(&standardized.project(b)) < 0.75);
+        assert!(standardized.project(a).similarity(&standardized.project(&near)) > 0.9);
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use super::error::NarsError;
+use super::sentence::{Punctuation, Sentence, Stamp};
+use super::term::{Operator, Term, VarType};
+use super::truth::TruthValue;
+
+/// Relations read as inheritance rather than as a relation between two terms.
+const IS_A: [&str; 4] = ["isa", "is-a", "type", "subClassOf"];
+
+/// A subject-relation-object statement from a triple source (RDF, CSV).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triple {
+    pub subject: String,
+    pub relation: String,
+    pub object: String,
+    pub truth: Option<TruthValue>, // input default if absent
+}
+
+impl Triple {
+    /// `<subject --> object>` for is-a relations (`isa`, `rdf:type`, `rdfs:subClassOf`...),
+    /// otherwise `<(*, subject, object) --> relation>`.
+    pub fn to_term(&self) -> Term {
+        let (subject, object) = (Term::atom_from_str(&self.subject), Term::atom_from_str(&self.object));
+        if IS_A.contains(&self.relation.as_str()) {
+            return Term::Compound(Operator::Inheritance, vec![subject, object]);
+        }
+        let pair = Term::Compound(Operator::Product, vec![subject, object]);
+        Term::Compound(Operator::Inheritance, vec![pair, Term::atom_from_str(&self.relation)])
+    }
+
+    pub fn to_sentence(&self) -> Sentence {
+        let truth = self.truth.unwrap_or(TruthValue::new(1.0, 0.9));
+        Sentence::new(self.to_term(), Punctuation::Judgement, truth, Stamp::new(0, vec![]))
+    }
+}
+
+/// An IRI, prefixed name or literal as an atom name: the local part of an IRI (after the last
+/// `#` or `/`), the text of a literal, with characters atoms cannot hold replaced by `_`.
+fn atom_name(token: &str) -> String {
+    let text = if let Some(iri) = token.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+        iri.rsplit(['#', '/']).next().unwrap_or(iri)
+    } else if let Some(literal) = token.strip_prefix('"') {
+        literal.rsplit_once('"').map_or(literal, |(text, _)| text)
+    } else {
+        token.rsplit(':').next().unwrap_or(token)
+    };
+    sanitize(text)
+}
+
+/// `text` with characters atoms cannot hold replaced by `_`.
+fn sanitize(text: &str) -> String {
+    let name: String = text.trim().chars().map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '_' }).collect();
+    if name.is_empty() { "_".to_string() } else { name }
+}
+
+/// Splits an N-Triples line into its three terms, keeping quoted literals whole.
+fn ntriples_terms(line: &str) -> Option<[&str; 3]> {
+    let mut terms = Vec::with_capacity(3);
+    let mut rest = line.trim_start();
+    while terms.len() < 3 && !rest.is_empty() {
+        let end = if let Some(literal) = rest.strip_prefix('"') {
+            // Closing quote not preceded by a backslash, then any language tag or datatype
+            let close = literal.char_indices().find(|&(i, c)| c == '"' && !literal[..i].ends_with('\\'))?.0 + 1;
+            rest[close..].find(char::is_whitespace).map_or(rest.len(), |i| close + i)
+        } else {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+        terms.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    (terms.len() == 3 && rest.starts_with('.')).then(|| [terms[0], terms[1], terms[2]])
+}
+
+/// Reads RDF in N-Triples form, one `<s> <p> <o> .` statement per line; `#` starts a comment.
+pub fn parse_ntriples(text: &str) -> Result<Vec<Triple>, NarsError> {
+    let mut triples = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let [subject, relation, object] = ntriples_terms(line)
+            .ok_or_else(|| NarsError::Serialization(format!("line {}: not an N-Triples statement: {}", index + 1, line)))?;
+        triples.push(Triple { subject: atom_name(subject), relation: atom_name(relation), object: atom_name(object), truth: None });
+    }
+    Ok(triples)
+}
+
+/// Reads `subject,relation,object[,frequency[,confidence]]` rows; fields may be quoted as in
+/// `csv_fields`. A first row that does not parse as one (a header) is skipped, as are blank
+/// lines.
+pub fn parse_csv_triples(text: &str) -> Result<Vec<Triple>, NarsError> {
+    let mut triples = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = csv_fields(line);
+        let number = |i: usize| fields.get(i).map(|f| f.parse::<f32>());
+        let truth = match (number(3), number(4)) {
+            (None, _) => Ok(None),
+            (Some(Ok(f)), None) => Ok(Some(TruthValue::new(f, 0.9))),
+            (Some(Ok(f)), Some(Ok(c))) => Ok(Some(TruthValue::new(f, c))),
+            _ => Err(()),
+        };
+        match (fields.as_slice(), truth) {
+            ([subject, relation, object, ..], Ok(truth)) if fields.len() <= 5 && ![subject, relation, object].iter().any(|f| f.is_empty()) => {
+                triples.push(Triple { subject: atom_name(subject), relation: atom_name(relation), object: atom_name(object), truth });
+            }
+            _ if index == 0 => continue,
+            _ => return Err(NarsError::Serialization(format!("line {}: expected subject,relation,object[,frequency[,confidence]]", index + 1))),
+        }
+    }
+    Ok(triples)
+}
+
+/// Splits Prolog text into clauses, each with the line it starts on and without its final `.`;
+/// comments (`%`, `/* */`) are left out.
+fn horn_clauses(text: &str) -> Result<Vec<(usize, String)>, NarsError> {
+    let (mut clauses, mut clause, mut start) = (Vec::new(), String::new(), 1);
+    let (mut line, mut depth, mut quote) = (1, 0i32, None);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        if let Some(q) = quote {
+            clause.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '%' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    line += usize::from(c == '\n');
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                continue;
+            }
+            '.' if depth == 0 && chars.peek().is_none_or(|c| c.is_whitespace() || *c == '%') => {
+                clauses.push((start, std::mem::take(&mut clause).trim().to_string()));
+                continue;
+            }
+            '\'' | '"' => quote = Some(c),
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if clause.trim().is_empty() {
+            start = line;
+        }
+        clause.push(c);
+    }
+    if !clause.trim().is_empty() {
+        return Err(NarsError::Serialization(format!("line {}: clause does not end with '.'", start)));
+    }
+    Ok(clauses)
+}
+
+/// Splits `text` at the commas outside parentheses and quotes.
+fn split_arguments(text: &str) -> Vec<&str> {
+    let (mut parts, mut from, mut depth, mut quote) = (Vec::new(), 0, 0, None);
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(text[from..i].trim());
+                from = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[from..].trim());
+    parts
+}
+
+/// Variables of one clause: those of the head are independent, the others dependent, and
+/// every `_` is a variable of its own.
+struct ClauseVariables {
+    head: Vec<String>,
+    anonymous: usize,
+}
+
+impl ClauseVariables {
+    fn is_variable(arg: &str) -> bool {
+        arg.starts_with(|c: char| c.is_uppercase() || c == '_')
+    }
+
+    fn term(&mut self, arg: &str) -> Term {
+        if arg == "_" {
+            self.anonymous += 1;
+            return Term::var_from_str(VarType::Dependent, &format!("_{}", self.anonymous));
+        }
+        let kind = if self.head.iter().any(|v| v == arg) { VarType::Independent } else { VarType::Dependent };
+        Term::var_from_str(kind, &sanitize(&arg.to_lowercase()))
+    }
+}
+
+/// `name(args)` as `<arg --> name>` or `<(*, args) --> name>`, `\+ goal` as its negation.
+fn horn_goal(goal: &str, variables: &mut ClauseVariables) -> Result<Term, String> {
+    if let Some(goal) = goal.strip_prefix("\\+") {
+        return Ok(Term::negation(horn_goal(goal.trim(), variables)?));
+    }
+    let (name, args) = goal.strip_suffix(')').and_then(|g| g.split_once('('))
+        .ok_or_else(|| format!("expected predicate(arguments...), got {}", goal))?;
+    let mut terms = Vec::new();
+    for arg in split_arguments(args) {
+        if arg.is_empty() || arg.contains('(') {
+            return Err(format!("unsupported argument '{}' in {}", arg, goal));
+        }
+        terms.push(if ClauseVariables::is_variable(arg) { variables.term(arg) } else { Term::atom_from_str(&sanitize(arg.trim_matches(['\'', '"']))) });
+    }
+    let subject = if terms.len() == 1 { terms.remove(0) } else { Term::Compound(Operator::Product, terms) };
+    Ok(Term::inheritance(subject, Term::atom_from_str(&sanitize(name))))
+}
+
+/// Reads a Datalog/Prolog subset: facts `feline(tom).` and `parent(tom, bob).` become
+/// `<tom --> feline>` and `<(*, tom, bob) --> parent>`, rules `animal(X) :- feline(X).` become
+/// `<<$x --> feline> ==> <$x --> animal>>`, with a conjunction for bodies of several goals and
+/// `\+ goal` as a negation. Variables of the head are independent, the others dependent.
+/// Arguments must be constants or variables; `:-` directives and comments are skipped.
+pub fn parse_horn_clauses(text: &str) -> Result<Vec<Sentence>, NarsError> {
+    let mut sentences = Vec::new();
+    for (line, clause) in horn_clauses(text)? {
+        if clause.starts_with(":-") {
+            continue;
+        }
+        let error = |message: String| NarsError::Serialization(format!("line {}: {}", line, message));
+        let (head, body) = match clause.split_once(":-") {
+            Some((head, body)) => (head.trim(), Some(body)),
+            None => (clause.as_str(), None),
+        };
+        let head_variables = split_arguments(head.split_once('(').map_or("", |(_, args)| args.trim_end_matches(')')))
+            .into_iter().filter(|a| ClauseVariables::is_variable(a) && *a != "_").map(str::to_string).collect();
+        let mut variables = ClauseVariables { head: head_variables, anonymous: 0 };
+        let conclusion = horn_goal(head, &mut variables).map_err(error)?;
+        let term = match body {
+            None => conclusion,
+            Some(body) => {
+                let goals = split_arguments(body).into_iter().map(|g| horn_goal(g, &mut variables)).collect::<Result<Vec<_>, _>>().map_err(error)?;
+                let condition = if goals.len() == 1 { goals.into_iter().next().unwrap() } else { Term::conjunction(goals).map_err(|e| error(e.to_string()))? };
+                Term::implication(condition, conclusion)
+            }
+        };
+        sentences.push(Sentence::new(term, Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])));
+    }
+    Ok(sentences)
+}
+
+/// A row of tabular data: field name -> value.
+pub type Row = HashMap<String, String>;
+
+/// Narsese with `{field}` placeholders that a row of tabular data fills in, e.g.
+/// `<{name} --> {kind}>. %{score};0.9%`. Braces of the Narsese itself are written `{{` and `}}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowTemplate {
+    text: Vec<String>, // literal text before each field and after the last one
+    fields: Vec<String>,
+}
+
+impl RowTemplate {
+    pub fn parse(template: &str) -> Result<Self, NarsError> {
+        let error = |rest: &str, message: &str| NarsError::parse(template, rest, message);
+        let (mut text, mut fields, mut literal) = (Vec::new(), Vec::new(), String::new());
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let after = chars.as_str();
+                    let (name, rest) = after.split_once('}').ok_or_else(|| error(after, "unclosed '{' (write '{{' for a literal brace)"))?;
+                    if name.trim().is_empty() || name.contains('{') {
+                        return Err(error(after, "expected a field name in '{...}'"));
+                    }
+                    fields.push(name.trim().to_string());
+                    text.push(std::mem::take(&mut literal));
+                    chars = rest.chars();
+                }
+                '}' => return Err(error(chars.as_str(), "unmatched '}' (write '}}' for a literal brace)")),
+                _ => literal.push(c),
+            }
+        }
+        text.push(literal);
+        Ok(Self { text, fields })
+    }
+
+    /// Names of the fields the template uses, in order.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    /// The template with the fields of `row` filled in: numbers as they are (for truth values),
+    /// other values as atom names.
+    pub fn render(&self, row: &Row) -> Result<String, String> {
+        let mut out = self.text[0].clone();
+        for (field, text) in self.fields.iter().zip(&self.text[1..]) {
+            let value = row.get(field).ok_or_else(|| format!("no field '{}'", field))?;
+            if value.trim().parse::<f64>().is_ok() { out.push_str(value.trim()) } else { out.push_str(&sanitize(value)) }
+            out.push_str(text);
+        }
+        Ok(out)
+    }
+}
+
+/// Fields of a CSV line; double-quoted fields may hold commas and `""` for a quote.
+fn csv_fields(line: &str) -> Vec<String> {
+    let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Reads CSV with a header row into rows keyed by the header names and by position (`col1`,
+/// `col2`...), each with its line number. Blank lines are skipped.
+pub fn parse_csv_rows(text: &str) -> Result<Vec<(usize, Row)>, NarsError> {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else { return Ok(Vec::new()) };
+    let header = csv_fields(header);
+    let mut rows = Vec::new();
+    for (index, line) in lines {
+        let values = csv_fields(line);
+        if values.len() != header.len() {
+            return Err(NarsError::Serialization(format!("line {}: {} fields, the header has {}", index + 1, values.len(), header.len())));
+        }
+        let mut row = Row::new();
+        for (i, (name, value)) in header.iter().zip(values).enumerate() {
+            row.insert(format!("col{}", i + 1), value.clone());
+            row.insert(name.clone(), value);
+        }
+        rows.push((index + 1, row));
+    }
+    Ok(rows)
+}
+
+/// Reads JSON Lines, one object per line, into rows keyed by the object's keys (nulls left
+/// out, other non-string values in their JSON form), each with its line number.
+pub fn parse_jsonl_rows(text: &str) -> Result<Vec<(usize, Row)>, NarsError> {
+    let mut rows = Vec::new();
+    for (index, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let error = |message: String| NarsError::Serialization(format!("line {}: {}", index + 1, message));
+        let object = match serde_json::from_str(line).map_err(|e| error(e.to_string()))? {
+            serde_json::Value::Object(object) => object,
+            _ => return Err(error("expected a JSON object".to_string())),
+        };
+        let row = object.into_iter().filter(|(_, v)| !v.is_null())
+            .map(|(key, value)| (key, value.as_str().map_or_else(|| value.to_string(), str::to_string)))
+            .collect();
+        rows.push((index + 1, row));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::parser::parse_term;
+
+    #[test]
+    fn test_triple_sources() {
+        let rdf = "# animals\n\
+            <http://example.org/zoo#tiger> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/zoo#cat> .\n\
+            <http://example.org/zoo#tiger> <http://example.org/zoo#eats> \"raw meat\"@en .\n";
+        let terms: Vec<Term> = parse_ntriples(rdf).unwrap().iter().map(Triple::to_term).collect();
+        assert_eq!(terms, [parse_term("<tiger --> cat>").unwrap().1, parse_term("<(*, tiger, raw_meat) --> eats>").unwrap().1]);
+        assert!(parse_ntriples("<a> <b> .").is_err());
+
+        let csv = "subject,relation,object,frequency\ntiger,isa,cat\ntiger,eats,meat,0.8\n";
+        let triples = parse_csv_triples(csv).unwrap();
+        assert_eq!(triples.len(), 2);
+        assert_eq!(triples[0].to_sentence().truth, TruthValue::new(1.0, 0.9));
+        assert_eq!(triples[1].to_sentence().to_narsese(), "<(*, tiger, meat) --> eats>. %0.80;0.90%");
+        assert!(parse_csv_triples("a,b,c\nd,e\n").is_err());
+        // Quoted fields keep their commas
+        let triples = parse_csv_triples("\"tiger, bengal\",eats,\"meat, raw\",0.7\n").unwrap();
+        assert_eq!(triples[0].to_sentence().to_narsese(), "<(*, tiger__bengal, meat__raw) --> eats>. %0.70;0.90%");
+    }
+
+    #[test]
+    fn test_horn_clauses() {
+        let program = "% zoo\n\
+            :- dynamic feline/1.\n\
+            feline(tom).\n\
+            parent('Tom Cat', kitty). /* a pair */\n\
+            animal(X) :- feline(X).\n\
+            grandparent(X, Z) :-\n    parent(X, Y),\n    parent(Y, Z).\n\
+            stray(X) :- cat(X), \\+ owner(_, X).\n";
+        let sentences = parse_horn_clauses(program).unwrap();
+        let terms: Vec<String> = sentences.iter().map(|s| s.term.to_string()).collect();
+        let expected = ["<tom --> feline>", "<(*, Tom_Cat, kitty) --> parent>", "<<$x --> feline> ==> <$x --> animal>>",
+            "<(&&, <(*, $x, #y) --> parent>, <(*, #y, $z) --> parent>) ==> <(*, $x, $z) --> grandparent>>",
+            "<(&&, <$x --> cat>, (--, <(*, #_1, $x) --> owner>)) ==> <$x --> stray>>"];
+        assert_eq!(terms, expected);
+        for term in expected {
+            assert_eq!(parse_term(term).unwrap().1.to_string(), term);
+        }
+        assert_eq!(sentences[0].truth, TruthValue::new(1.0, 0.9));
+
+        let line = |text: &str| match parse_horn_clauses(text) { Err(NarsError::Serialization(e)) => e, other => panic!("{:?}", other) };
+        assert!(line("a(b).\nrain.\n").starts_with("line 2:"));
+        assert!(line("a(f(b)).").contains("unsupported argument"));
+        assert!(line("a(b)").contains("does not end"));
+    }
+
+    #[test]
+    fn test_row_templates() {
+        let template = RowTemplate::parse("<{{{name}}} --> {kind}>. %{col3};0.9%").unwrap();
+        assert_eq!(template.fields(), ["name", "kind", "col3"]);
+        let rows = parse_csv_rows("name,kind,score\ntom,cat,0.8\n\n\"Tom, Jr.\",\"big cat\",1\n").unwrap();
+        let rendered: Vec<String> = rows.iter().map(|(_, row)| template.render(row).unwrap()).collect();
+        assert_eq!(rendered, ["<{tom} --> cat>. %0.8;0.9%", "<{Tom__Jr_} --> big_cat>. %1;0.9%"]);
+        assert_eq!(rows[1].0, 4);
+        assert!(parse_csv_rows("a,b\n1\n").is_err());
+
+        let rows = parse_jsonl_rows("{\"name\": \"rex\", \"kind\": \"dog\", \"score\": 0.5, \"owner\": null}\n\n[1]\n");
+        assert!(matches!(rows, Err(NarsError::Serialization(e)) if e.starts_with("line 3:")));
+        let (_, row) = &parse_jsonl_rows("{\"name\": \"rex\", \"kind\": \"dog\", \"score\": 0.5, \"owner\": null}").unwrap()[0];
+        assert_eq!(RowTemplate::parse("<{name} --> {kind}>. %{score};0.9%").unwrap().render(row).unwrap(), "<rex --> dog>. %0.5;0.9%");
+        assert_eq!(template.render(row), Err("no field 'col3'".to_string()));
+
+        for bad in ["<{name --> x>.", "<a --> b}>.", "<{} --> x>."] {
+            assert!(RowTemplate::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
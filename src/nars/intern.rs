@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Weak};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use super::term::Term;
+
+const MIN_SWEEP: usize = 1024; // entries below which dead ones are never swept
+
+/// A term with its hash and complexity computed once.
+#[derive(Debug)]
+pub struct TermNode {
+    pub term: Term,
+    pub hash: u64,
+    pub complexity: usize,
+}
+
+/// Shared handle on an interned term. Handles from the same `TermInterner` are equal exactly
+/// when they point to the same node, so comparing them is a pointer comparison (handles from
+/// different interners fall back to comparing terms). Hashing feeds the stored hash.
+#[derive(Clone)]
+pub struct InternedTerm(Arc<TermNode>);
+
+impl InternedTerm {
+    /// A handle on a node of its own, outside any interner; `TermInterner::adopt` pools it.
+    pub fn new(term: Term) -> Self {
+        let node = TermNode { hash: fingerprint(&term), complexity: term.complexity(), term };
+        InternedTerm(Arc::new(node))
+    }
+
+    pub fn term(&self) -> &Term {
+        &self.0.term
+    }
+
+    pub fn complexity(&self) -> usize {
+        self.0.complexity
+    }
+
+    /// Live handles on the node, this one included.
+    pub fn handles(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+    /// Whether both handles point to the same node.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for InternedTerm {
+    type Target = Term;
+
+    fn deref(&self) -> &Term {
+        &self.0.term
+    }
+}
+
+impl From<Term> for InternedTerm {
+    fn from(term: Term) -> Self {
+        InternedTerm::new(term)
+    }
+}
+
+impl PartialEq<Term> for InternedTerm {
+    fn eq(&self, other: &Term) -> bool {
+        self.0.term == *other
+    }
+}
+
+impl PartialEq<InternedTerm> for Term {
+    fn eq(&self, other: &InternedTerm) -> bool {
+        *self == other.0.term
+    }
+}
+
+impl PartialEq for InternedTerm {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || (self.0.hash == other.0.hash && self.0.term == other.0.term)
+    }
+}
+
+impl Eq for InternedTerm {}
+
+impl Hash for InternedTerm {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.hash);
+    }
+}
+
+/// Serialized as the plain term, so snapshots do not change with interning.
+impl Serialize for InternedTerm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.term.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedTerm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Term::deserialize(deserializer).map(InternedTerm::new)
+    }
+}
+
+impl fmt::Debug for InternedTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.term.fmt(f)
+    }
+}
+
+impl fmt::Display for InternedTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.term.fmt(f)
+    }
+}
+
+/// Hash-consing pool: interning equal terms yields the same node, so a term kept under
+/// several indices is stored once. The pool holds weak references only; a node is freed with
+/// its last handle, and dead entries are swept whenever the pool has doubled since the last
+/// sweep.
+#[derive(Debug, Clone, Default)]
+pub struct TermInterner {
+    pool: HashMap<u64, Vec<Weak<TermNode>>>,
+    entries: usize,
+    sweep_at: usize,
+}
+
+fn fingerprint(term: &Term) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    term.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl TermInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The node for `term`, created on first use.
+    pub fn intern(&mut self, term: &Term) -> InternedTerm {
+        let hash = fingerprint(term);
+        if let Some(found) = self.find(hash, term) {
+            return found;
+        }
+        if self.entries >= self.sweep_at {
+            self.sweep();
+        }
+        let node = Arc::new(TermNode { term: term.clone(), hash, complexity: term.complexity() });
+        self.pool.entry(hash).or_default().push(Arc::downgrade(&node));
+        self.entries += 1;
+        InternedTerm(node)
+    }
+
+    /// The pooled handle equal to `term`: an existing node, or the node of `term` itself,
+    /// which joins the pool without copying.
+    pub fn adopt(&mut self, term: &InternedTerm) -> InternedTerm {
+        if let Some(found) = self.find(term.0.hash, &term.0.term) {
+            return found;
+        }
+        if self.entries >= self.sweep_at {
+            self.sweep();
+        }
+        self.pool.entry(term.0.hash).or_default().push(Arc::downgrade(&term.0));
+        self.entries += 1;
+        term.clone()
+    }
+
+    /// The node for `term` if one is alive, without creating it.
+    pub fn get(&self, term: &Term) -> Option<InternedTerm> {
+        self.find(fingerprint(term), term)
+    }
+
+    fn find(&self, hash: u64, term: &Term) -> Option<InternedTerm> {
+        self.pool.get(&hash)?.iter().filter_map(Weak::upgrade).find(|node| node.term == *term).map(InternedTerm)
+    }
+
+    /// Drops the entries of freed nodes.
+    pub fn sweep(&mut self) {
+        self.pool.retain(|_, nodes| {
+            nodes.retain(|node| node.strong_count() > 0);
+            !nodes.is_empty()
+        });
+        self.entries = self.pool.values().map(Vec::len).sum();
+        self.sweep_at = (self.entries * 2).max(MIN_SWEEP);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::parser::parse_term;
+
+    #[test]
+    fn test_interned_terms_are_shared() {
+        let term = |t: &str| parse_term(t).unwrap().1;
+        let mut interner = TermInterner::new();
+        let a = interner.intern(&term("<(&, x, y) --> z>"));
+        let b = interner.intern(&term("<(&, x, y) --> z>"));
+        let c = interner.intern(&term("<x --> z>"));
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.complexity(), 5);
+        assert_eq!(interner.entries, 2);
+
+        // Handles from another pool still compare by term
+        assert_eq!(TermInterner::new().intern(&term("<x --> z>")), c);
+
+        // Nodes go with their last handle
+        drop((a, b));
+        assert!(interner.get(&term("<(&, x, y) --> z>")).is_none());
+        interner.sweep();
+        assert_eq!(interner.entries, 1);
+
+        // A node made outside joins the pool as it is
+        let outside = InternedTerm::new(term("<y --> z>"));
+        assert!(Arc::ptr_eq(&interner.adopt(&outside).0, &outside.0));
+        assert!(Arc::ptr_eq(&interner.adopt(&InternedTerm::new(term("<y --> z>"))).0, &outside.0));
+        assert_eq!(interner.entries, 2);
+    }
+}
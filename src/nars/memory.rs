@@ -2,17 +2,42 @@ use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock};
 use super::bag::Bag;
+use super::error::NarsError;
+use super::grounding::{Grounding, RandomProjection};
+use super::intern::{InternedTerm, TermInterner};
 use super::term::{Term, Operator, deterministic_hash};
-use super::truth::TruthValue;
-use super::sentence::{Sentence, Stamp};
+use super::truth::{TruthValue, revision};
+use super::sentence::{Sentence, Punctuation, Stamp};
 use serde::{Serialize, Deserialize};
 use serde_big_array::BigArray;
 
-const HV_DIM_U64: usize = 157; // 157 * 64 = 10048 bits
-const HV_DIM_BITS: usize = HV_DIM_U64 * 64;
+pub const HV_DIM_U64: usize = 157; // 157 * 64 = 10048 bits
+pub(crate) const HV_DIM_BITS: usize = HV_DIM_U64 * 64;
+pub const DEFAULT_BELIEF_CAPACITY: usize = 100;
+pub const HISTORY_CAPACITY: usize = 20;
+/// Counter units per unit of learning weight (weight resolution 0.1).
+pub const COUNTER_SCALE: f32 = 10.0;
 
+/// Weakest concepts (by utility) among which eviction picks the least used one.
+const EVICTION_CANDIDATES: usize = 8;
+/// Cycles without use after which a concept counts as half as recently used.
+const RECENCY_HALF_LIFE: f32 = 100.0;
+/// Uses at which a concept counts as half as frequently used as possible.
+const USE_SATURATION: f32 = 10.0;
+pub const DEFAULT_USAGE_WEIGHT: f32 = 0.3;
+
+fn default_belief_capacity() -> usize {
+    DEFAULT_BELIEF_CAPACITY
+}
+
+fn default_usage_weight() -> f32 {
+    DEFAULT_USAGE_WEIGHT
+}
+
+#[derive(Debug)]
 pub struct ProjectionMatrix {
     weights: Vec<Vec<f32>>, // [bit_idx][input_dim]
 }
@@ -30,6 +55,37 @@ impl ProjectionMatrix {
         }
         Self { weights }
     }
+
+    pub fn input_dim(&self) -> usize {
+        self.weights.first().map_or(0, |row| row.len())
+    }
+}
+
+/// Seed of the vector `TieBreak::PositionHash` breaks ties with.
+const TIE_BREAK_SEED: u64 = 99999;
+
+/// The fixed pseudo-random vector `TieBreak::PositionHash` takes tied bits from.
+static TIE_BREAKER: LazyLock<Hypervector> = LazyLock::new(|| {
+    let mut rng = StdRng::seed_from_u64(TIE_BREAK_SEED);
+    let mut bits = [0; HV_DIM_U64];
+    for word in bits.iter_mut() {
+        *word = rng.random();
+    }
+    Hypervector { bits }
+});
+
+/// How bundling sets a bit on which an even number of inputs is split evenly. Either way a
+/// bundle of two vectors keeps a similarity of about 0.75 to each of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// Tied bits are 0: a bundle of two is their AND, a quarter dense, so repeated bundling
+    /// thins vectors out (see `Hypervector::rebalance`).
+    #[default]
+    Zero,
+    /// Tied bits take a fixed pseudo-random bit per position, as if a constant random vector
+    /// were added to the bundle, but without weakening the inputs when there is no tie.
+    /// Keeps bundles half dense and deterministic.
+    PositionHash,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -50,8 +106,8 @@ impl Hypervector {
     pub fn random() -> Self {
         let mut rng = rand::rng();
         let mut bits = [0; HV_DIM_U64];
-        for i in 0..HV_DIM_U64 {
-            bits[i] = rng.random();
+        for word in bits.iter_mut() {
+            *word = rng.random();
         }
         Self { bits }
     }
@@ -59,20 +115,30 @@ impl Hypervector {
     /// Bitwise XOR (Binding).
     pub fn bind(&self, other: &Hypervector) -> Hypervector {
         let mut result = [0; HV_DIM_U64];
-        for i in 0..HV_DIM_U64 {
-            result[i] = self.bits[i] ^ other.bits[i];
+        for (i, word) in result.iter_mut().enumerate() {
+            *word = self.bits[i] ^ other.bits[i];
         }
         Self { bits: result }
     }
 
-    /// The Majority Function (Bundling).
+    /// The Majority Function (Bundling), tied bits 0 (`TieBreak::Zero`).
     pub fn bundle(inputs: &[Hypervector]) -> Hypervector {
+        Self::bundle_with(inputs, TieBreak::default())
+    }
+
+    /// Majority of `inputs` per bit; bits on which an even number of inputs is split evenly
+    /// are set according to `tie_break`.
+    pub fn bundle_with(inputs: &[Hypervector], tie_break: TieBreak) -> Hypervector {
         if inputs.is_empty() {
             return Self::empty();
         }
 
         let mut result = [0; HV_DIM_U64];
         let threshold = inputs.len() / 2;
+        let ties = match tie_break {
+            TieBreak::PositionHash if inputs.len().is_multiple_of(2) => Some(&*TIE_BREAKER),
+            _ => None,
+        };
 
         // Iterate over each bit position (0 to 10047)
         for bit_idx in 0..HV_DIM_BITS {
@@ -86,7 +152,8 @@ impl Hypervector {
                 }
             }
 
-            if count > threshold {
+            let tied = count * 2 == inputs.len();
+            if count > threshold || (tied && ties.is_some_and(|t| t.get_bit(bit_idx))) {
                 result[u64_idx] |= 1 << bit_offset;
             }
         }
@@ -105,30 +172,9 @@ impl Hypervector {
         1.0 - (total_hamming_distance as f32 / HV_DIM_BITS as f32)
     }
 
-    /// Local Sensitive Hashing (LSH) projection from dense vector.
+    /// Local Sensitive Hashing (LSH) projection from dense vector, see `RandomProjection`.
     pub fn project(dense_vector: &[f32]) -> Hypervector {
-        let mut result = [0; HV_DIM_U64];
-
-        for bit_idx in 0..HV_DIM_BITS {
-            // Seed RNG with bit index for determinism
-            let mut rng = StdRng::seed_from_u64(bit_idx as u64);
-            
-            // Generate random vector R_i and compute dot product
-            let mut dot_product = 0.0;
-            for &val in dense_vector {
-                // Generate random weight in [-1.0, 1.0]
-                let weight: f32 = rng.random_range(-1.0..1.0);
-                dot_product += val * weight;
-            }
-
-            if dot_product > 0.0 {
-                let u64_idx = bit_idx / 64;
-                let bit_offset = bit_idx % 64;
-                result[u64_idx] |= 1 << bit_offset;
-            }
-        }
-
-        Self { bits: result }
+        RandomProjection::default().project(dense_vector)
     }
 
     /// Faster projection using pre-computed matrix
@@ -156,27 +202,120 @@ impl Hypervector {
         Self { bits: result }
     }
 
-    /// Weighted bundle update (Hebbian Learning).
+    /// Weighted bundle update (Hebbian Learning) without a counter vector: the expected
+    /// outcome of bundling self (weight 1) with `new_info` (weight `weight`), i.e. a fraction
+    /// `weight / (1 + weight)` of the differing bits takes the value of `new_info`.
     pub fn update(&mut self, new_info: &Hypervector, weight: f32) {
-        // Create a list of vectors for bundling
-        // 1 copy of self
-        // k copies of new_info
-        
-        let k = (weight * 10.0).round() as usize;
-        if k == 0 {
-            return; // No update if weight is too small
+        if weight <= 0.0 {
+            return;
+        }
+        let differing: Vec<usize> = (0..HV_DIM_BITS).filter(|&i| self.get_bit(i) != new_info.get_bit(i)).collect();
+        let count = (differing.len() as f32 * weight / (1.0 + weight)).round() as usize;
+        self.flip_spread(&differing, count);
+    }
+
+    /// Contrastive counterpart of `update`: a fraction `weight / (1 + weight)` of the bits
+    /// shared with `other` is flipped, but never more than brings the similarity down to 0.5
+    /// (unrelated), so repeated pushes cannot make vectors opposites.
+    pub fn push_away(&mut self, other: &Hypervector, weight: f32) {
+        if weight <= 0.0 {
+            return;
+        }
+        let shared: Vec<usize> = (0..HV_DIM_BITS).filter(|&i| self.get_bit(i) == other.get_bit(i)).collect();
+        let excess = shared.len().saturating_sub(HV_DIM_BITS / 2);
+        let count = ((shared.len() as f32 * weight / (1.0 + weight)).round() as usize).min(excess);
+        self.flip_spread(&shared, count);
+    }
+
+    /// Fraction of bits set (0.5 for a balanced vector).
+    pub fn density(&self) -> f32 {
+        let ones: u32 = self.bits.iter().map(|w| w.count_ones()).sum();
+        ones as f32 / HV_DIM_BITS as f32
+    }
+
+    /// Reads a vector written out bit by bit: `0b` followed by up to 10048 `0`/`1` digits, bit
+    /// 0 first, or hex (optionally `0x`-prefixed) as written by `to_hex`. Missing trailing
+    /// bits are zero; `_` and whitespace are ignored.
+    pub fn parse(text: &str) -> Result<Self, NarsError> {
+        let digits: String = text.chars().filter(|c| *c != '_' && !c.is_whitespace()).collect();
+        let mut vector = Self::empty();
+        if let Some(bits) = digits.strip_prefix("0b") {
+            if bits.len() > HV_DIM_BITS {
+                return Err(NarsError::parse(text, "", format!("more than {} bits", HV_DIM_BITS)));
+            }
+            // Every character before a bad one is an ASCII digit, so byte offsets are bit indexes
+            for (i, c) in bits.char_indices() {
+                match c {
+                    '0' => {}
+                    '1' => vector.flip_bit(i),
+                    _ => return Err(NarsError::parse(text, &bits[i..], "expected a bit")),
+                }
+            }
+            return Ok(vector);
+        }
+        let hex = digits.strip_prefix("0x").unwrap_or(&digits);
+        if hex.len() > HV_DIM_U64 * 16 {
+            return Err(NarsError::parse(text, "", format!("more than {} hex digits", HV_DIM_U64 * 16)));
+        }
+        for (i, c) in hex.char_indices() {
+            let nibble = c.to_digit(16).ok_or_else(|| NarsError::parse(text, &hex[i..], "expected a hex digit"))?;
+            vector.bits[i / 16] |= (nibble as u64) << (60 - 4 * (i % 16));
+        }
+        Ok(vector)
+    }
+
+    /// The words in order, 16 hex digits each, most significant digit first.
+    pub fn to_hex(&self) -> String {
+        self.bits.iter().map(|w| format!("{:016x}", w)).collect()
+    }
+
+    fn get_bit(&self, bit_idx: usize) -> bool {
+        (self.bits[bit_idx / 64] >> (bit_idx % 64)) & 1 == 1
+    }
+
+    fn flip_bit(&mut self, bit_idx: usize) {
+        self.bits[bit_idx / 64] ^= 1 << (bit_idx % 64);
+    }
+
+    /// Flips `count` of the candidate bits, spread evenly over the candidates.
+    fn flip_spread(&mut self, candidates: &[usize], count: usize) {
+        if candidates.is_empty() || count == 0 {
+            return;
+        }
+        let count = count.min(candidates.len());
+        let stride = candidates.len() as f32 / count as f32;
+        for i in 0..count {
+            self.flip_bit(candidates[(i as f32 * stride) as usize]);
         }
+    }
 
-        let mut inputs = Vec::with_capacity(1 + k);
-        inputs.push(*self);
-        for _ in 0..k {
-            inputs.push(*new_info);
+    /// Moves back towards `anchor` (flipping differing bits) until similarity reaches `target`.
+    pub fn pull_towards(&mut self, anchor: &Hypervector, target: f32) {
+        let max_distance = ((1.0 - target.clamp(0.0, 1.0)) * HV_DIM_BITS as f32) as usize;
+        let differing: Vec<usize> = (0..HV_DIM_BITS).filter(|&i| self.get_bit(i) != anchor.get_bit(i)).collect();
+        if differing.len() > max_distance {
+            self.flip_spread(&differing, differing.len() - max_distance);
         }
+    }
 
-        *self = Self::bundle(&inputs);
+    /// Restores a ~50% bit density; repeated majority bundling with ties biases vectors towards zeros.
+    pub fn rebalance(&mut self, tolerance: f32) {
+        let density = self.density();
+        if (density - 0.5).abs() <= tolerance {
+            return;
+        }
+        let too_dense = density > 0.5;
+        let candidates: Vec<usize> = (0..HV_DIM_BITS).filter(|&i| self.get_bit(i) == too_dense).collect();
+        let excess = ((density - 0.5).abs() * HV_DIM_BITS as f32) as usize;
+        self.flip_spread(&candidates, excess);
     }
 
     pub fn compound(op: &Operator, args: &[Hypervector]) -> Self {
+        Self::compound_with(op, args, TieBreak::default())
+    }
+
+    /// Bundle of the operator's vector and `args`, ties broken by `tie_break`.
+    pub fn compound_with(op: &Operator, args: &[Hypervector], tie_break: TieBreak) -> Self {
         let mut inputs = Vec::new();
         
         // Operator vector
@@ -184,8 +323,8 @@ impl Hypervector {
         let id = deterministic_hash(&op_str);
         let mut rng = StdRng::seed_from_u64(id);
         let mut bits = [0; HV_DIM_U64];
-        for i in 0..HV_DIM_U64 {
-            bits[i] = rng.random();
+        for word in bits.iter_mut() {
+            *word = rng.random();
         }
         inputs.push(Self { bits });
 
@@ -193,17 +332,41 @@ impl Hypervector {
             inputs.push(*arg);
         }
         
-        Self::bundle(&inputs)
+        Self::bundle_with(&inputs, tie_break)
     }
 
+    /// Initial vector of `term`, compounds bundled with tied bits 0 (`TieBreak::Zero`).
     pub fn from_term(term: &Term) -> Self {
+        Self::from_term_with(term, TieBreak::default())
+    }
+
+    /// Initial vector of `term`, compounds bundled with ties broken by `tie_break`.
+    pub fn from_term_with(term: &Term, tie_break: TieBreak) -> Self {
         match term {
             Term::Atom(s) => {
                 let id = deterministic_hash(s);
                 let mut rng = StdRng::seed_from_u64(id);
                 let mut bits = [0; HV_DIM_U64];
-                for i in 0..HV_DIM_U64 {
-                    bits[i] = rng.random();
+                for word in bits.iter_mut() {
+                    *word = rng.random();
+                }
+                Self { bits }
+            },
+            Term::Interval(n) => {
+                let id = deterministic_hash(&format!("+{}", n));
+                let mut rng = StdRng::seed_from_u64(id);
+                let mut bits = [0; HV_DIM_U64];
+                for word in bits.iter_mut() {
+                    *word = rng.random();
+                }
+                Self { bits }
+            },
+            Term::Placeholder => {
+                let id = deterministic_hash("_");
+                let mut rng = StdRng::seed_from_u64(id);
+                let mut bits = [0; HV_DIM_U64];
+                for word in bits.iter_mut() {
+                    *word = rng.random();
                 }
                 Self { bits }
             },
@@ -211,8 +374,8 @@ impl Hypervector {
                  let id = deterministic_hash(s);
                  let mut rng = StdRng::seed_from_u64(id);
                  let mut bits = [0; HV_DIM_U64];
-                 for i in 0..HV_DIM_U64 {
-                     bits[i] = rng.random();
+                 for word in bits.iter_mut() {
+                     *word = rng.random();
                  }
                  Self { bits }
             },
@@ -225,45 +388,212 @@ impl Hypervector {
                 let op_hash = hasher.finish();
                 let mut rng = StdRng::seed_from_u64(op_hash);
                 let mut op_bits = [0; HV_DIM_U64];
-                for i in 0..HV_DIM_U64 {
-                    op_bits[i] = rng.random();
+                for word in op_bits.iter_mut() {
+                    *word = rng.random();
                 }
                 inputs.push(Hypervector { bits: op_bits });
 
                 for arg in args {
-                    inputs.push(Self::from_term(arg));
-                }
-                
-                // Ensure odd number of inputs for better bundling properties
-                if inputs.len() % 2 == 0 {
-                    let mut rng = StdRng::seed_from_u64(99999); // Constant seed
-                    let mut bias_bits = [0; HV_DIM_U64];
-                    for i in 0..HV_DIM_U64 {
-                        bias_bits[i] = rng.random();
-                    }
-                    inputs.push(Hypervector { bits: bias_bits });
+                    inputs.push(Self::from_term_with(arg, tie_break));
                 }
 
-                Self::bundle(&inputs)
+                Self::bundle_with(&inputs, tie_break)
             }
         }
     }
 }
 
+/// Per-bit integer accumulator behind a concept's hypervector. Learning adds weighted votes
+/// and only the bits whose sign changes are flipped, so repeated updates are weighted exactly
+/// instead of being re-bundled. Counts saturate at `saturation` to bound both memory and how
+/// entrenched old evidence can become. Counts are one byte each, and they are not saved in
+/// snapshots: a reloaded concept starts counting again from its vector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterVector {
+    counts: Vec<i8>,
+    saturation: i8,
+    carry: f32, // weight of past updates not yet counted, in counter units (at most half a unit)
+}
+
+impl CounterVector {
+    /// Counters holding `vector` with weight 1.
+    pub fn from_vector(vector: &Hypervector, saturation: i8) -> Self {
+        let saturation = saturation.max(1);
+        let unit = (COUNTER_SCALE as i8).min(saturation);
+        let counts = (0..HV_DIM_BITS).map(|i| if vector.get_bit(i) { unit } else { -unit }).collect();
+        Self { counts, saturation, carry: 0.0 }
+    }
+
+    pub fn count(&self, bit_idx: usize) -> i8 {
+        self.counts[bit_idx]
+    }
+
+    /// Adds `new_info` with `weight` and re-thresholds `vector` where a count changed sign
+    /// (a count of zero keeps the current bit). Weight lost to rounding is carried over to the
+    /// next update, so that many updates too small for a whole unit still add up.
+    pub fn add(&mut self, vector: &mut Hypervector, new_info: &Hypervector, weight: f32) {
+        let units = weight * COUNTER_SCALE + self.carry;
+        let rounded = units.round().min(i8::MAX as f32);
+        self.carry = (units - rounded).clamp(-0.5, 0.5);
+        let delta = rounded as i8;
+        if delta <= 0 {
+            return;
+        }
+        for (i, count) in self.counts.iter_mut().enumerate() {
+            let vote = if new_info.get_bit(i) { delta } else { -delta };
+            *count = count.saturating_add(vote).clamp(-self.saturation, self.saturation);
+            if *count != 0 && (*count > 0) != vector.get_bit(i) {
+                vector.flip_bit(i);
+            }
+        }
+    }
+
+    /// Realigns the counters after `vector` was changed directly (drift correction,
+    /// rebalancing): bits that now disagree get the weakest count of their new sign.
+    pub fn sync(&mut self, vector: &Hypervector) {
+        for (i, count) in self.counts.iter_mut().enumerate() {
+            let bit = vector.get_bit(i);
+            if *count == 0 || (*count > 0) != bit {
+                *count = if bit { 1 } else { -1 };
+            }
+        }
+    }
+}
+
+/// Limits on how far learning may move a concept away from its original vector.
+#[derive(Debug, Clone, Copy)]
+pub struct DriftControl {
+    /// Learning weight is divided by `1 + decay * update_count`.
+    pub decay: f32,
+    /// Minimum similarity to the origin vector kept after every update.
+    pub identity_floor: f32,
+    /// Cycles between renormalization passes over memory (0 disables them).
+    pub renormalize_interval: u64,
+    /// Allowed deviation from 50% bit density before rebalancing.
+    pub density_tolerance: f32,
+    /// Saturation of the per-concept counter vectors used for learning (0 disables them and
+    /// learning falls back to `Hypervector::update`).
+    pub counter_saturation: i8,
+    /// Weight of the contrastive update pushing the selected concept away from a random
+    /// unrelated one each cycle (0 disables it). Hebbian learning alone only pulls vectors
+    /// together, so without it everything slowly becomes similar.
+    pub contrastive_rate: f32,
+    /// Cycles between passes that rebuild compound vectors from their components' learned
+    /// vectors (0 disables them). Off by default: a pass replaces what compounds learned.
+    pub reground_interval: u64,
+}
+
+impl Default for DriftControl {
+    fn default() -> Self {
+        Self {
+            decay: 0.01,
+            identity_floor: 0.6,
+            renormalize_interval: 100,
+            density_tolerance: 0.05,
+            counter_saturation: 100,
+            contrastive_rate: 0.0,
+            reground_interval: 0,
+        }
+    }
+}
+
+/// How fast an eroding belief loses confidence, and down to what.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErosionPolicy {
+    /// Fraction of the confidence above the floor lost per erosion pass.
+    pub rate: f32,
+    /// Confidence erosion stops at.
+    pub floor: f32,
+}
+
+impl Default for ErosionPolicy {
+    fn default() -> Self {
+        Self { rate: 0.05, floor: 0.1 }
+    }
+}
+
+/// Confidence erosion: beliefs that go unused and unreinforced slowly lose confidence toward
+/// a floor, so stale facts stop supporting strong conclusions. Off by default.
+#[derive(Debug, Clone, Default)]
+pub struct ConfidenceErosion {
+    /// Cycles between erosion passes (0 disables erosion).
+    pub interval: u64,
+    /// Cycles without new evidence before a belief starts eroding.
+    pub idle_cycles: u64,
+    pub policy: ErosionPolicy,
+    /// Policies for namespaces: concepts with a source label (`sensor:temp`, `file:kb.csv`...)
+    /// starting with the prefix. The longest matching prefix wins.
+    pub namespaces: Vec<(String, ErosionPolicy)>,
+}
+
+impl ConfidenceErosion {
+    /// The policy for `concept`, by the sources of its evidence.
+    pub fn policy_for(&self, concept: &Concept) -> ErosionPolicy {
+        let labels: Vec<String> = concept.stamp.sources.iter().map(ToString::to_string).collect();
+        self.namespaces.iter()
+            .filter(|(prefix, _)| labels.iter().any(|label| label.starts_with(prefix.as_str())))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.policy, |(_, policy)| *policy)
+    }
+}
+
+/// How recently and how often a concept was used. The store keeps it beside the concept, so
+/// that recording a use does not copy a shared concept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub last_used_cycle: u64, // last cycle it was selected, associated or took part in a derivation
+    pub use_count: u32,
+}
+
+impl Usage {
+    /// How much the concept has been used as of cycle `now`, in [0, 1]: the mean of how
+    /// recently and how often.
+    pub fn level(&self, now: u64) -> f32 {
+        let recency = 0.5f32.powf(now.saturating_sub(self.last_used_cycle) as f32 / RECENCY_HALF_LIFE);
+        let frequency = self.use_count as f32 / (self.use_count as f32 + USE_SATURATION);
+        (recency + frequency) / 2.0
+    }
+}
+
+/// One revision of a concept's truth: what it was, what it became, and the evidence that caused it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    pub previous: TruthValue,
+    pub revised: TruthValue,
+    pub stamp: Stamp, // contributing evidence
+    pub time: u64,    // system cycle
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Concept {
-    pub term: Term,
+    pub term: InternedTerm,
     pub vector: Hypervector,
     pub priority: f32,
     pub durability: f32,
     pub truth: TruthValue,
     pub stamp: Stamp,
     pub beliefs: Vec<Sentence>,
+    #[serde(default = "default_belief_capacity")]
+    pub belief_capacity: usize,
+    pub questions: Vec<Sentence>, // pending questions and quests
+    pub goals: Vec<Sentence>, // desire table: goals on this term, revised like beliefs
+    pub origin: Option<Box<Hypervector>>, // vector at creation (identity anchor), see `origin()`
+    pub update_count: u32,
+    #[serde(default)]
+    pub history: Vec<Revision>, // most recent last, at most HISTORY_CAPACITY entries
+    #[serde(skip)]
+    pub counters: Option<CounterVector>, // created on first learning update
+    #[serde(default)]
+    pub quality: f32, // long-term worth: memory never values the concept below this
+    #[serde(default)]
+    pub grounded: bool, // vector attached from perception, see `NarsSystem::attach_vector`
 }
 
 impl Concept {
-    pub fn new(term: Term, vector: Hypervector, truth: TruthValue, stamp: Stamp) -> Self {
+    pub fn new(term: impl Into<InternedTerm>, vector: Hypervector, truth: TruthValue, stamp: Stamp) -> Self {
+        let term = term.into();
         Self {
+            origin: Self::anchor(&term, vector),
             term,
             vector,
             priority: 0.5, // Default
@@ -271,26 +601,298 @@ impl Concept {
             truth,
             stamp,
             beliefs: Vec::new(),
+            belief_capacity: DEFAULT_BELIEF_CAPACITY,
+            questions: Vec::new(),
+            goals: Vec::new(),
+            update_count: 0,
+            history: Vec::new(),
+            counters: None,
+            quality: 0.0,
+            grounded: false,
         }
     }
 
+    /// How much memory values keeping the concept: its priority-durability product, but at
+    /// least its quality.
+    pub fn utility(&self) -> f32 {
+        (self.priority * self.durability).max(self.quality).clamp(0.01, 0.99)
+    }
+
+    pub fn record_revision(&mut self, revision: Revision) {
+        self.history.push(revision);
+        if self.history.len() > HISTORY_CAPACITY {
+            self.history.remove(0);
+        }
+    }
+
+    /// The vector the concept was created with, its identity anchor.
+    pub fn origin(&self) -> Hypervector {
+        self.origin.as_deref().copied().unwrap_or_else(|| Hypervector::from_term(&self.term))
+    }
+
+    /// Anchors the concept's identity at `origin`.
+    pub fn set_origin(&mut self, origin: Hypervector) {
+        self.origin = Self::anchor(&self.term, origin);
+    }
+
+    /// How a concept of `term` keeps `origin`: not at all if it is the vector atoms (and
+    /// variables, intervals...) start from, which `origin()` recomputes, so that the common
+    /// case does not double the memory taken by vectors.
+    fn anchor(term: &Term, origin: Hypervector) -> Option<Box<Hypervector>> {
+        let recomputed = !matches!(term, Term::Compound(..)) && origin == Hypervector::from_term(term);
+        (!recomputed).then(|| Box::new(origin))
+    }
+
+    /// Hebbian update with decaying weight and an identity-preservation floor.
+    pub fn learn(&mut self, new_info: &Hypervector, weight: f32, drift: &DriftControl) {
+        let effective = weight / (1.0 + drift.decay * self.update_count as f32);
+        if drift.counter_saturation > 0 {
+            let counters = self.counters.get_or_insert_with(|| CounterVector::from_vector(&self.vector, drift.counter_saturation));
+            counters.add(&mut self.vector, new_info, effective);
+        } else {
+            self.vector.update(new_info, effective);
+        }
+        self.update_count += 1;
+        if self.hold_identity(drift) {
+            self.sync_counters();
+        }
+    }
+
+    /// Pulls the vector back towards the origin if it drifted past the identity floor;
+    /// returns whether it did.
+    fn hold_identity(&mut self, drift: &DriftControl) -> bool {
+        let origin = self.origin();
+        let drifted = 1.0 - self.vector.similarity(&origin) > 1.0 - drift.identity_floor;
+        if drifted {
+            self.vector.pull_towards(&origin, drift.identity_floor);
+        }
+        drifted
+    }
+
+    /// Negative sampling: moves away from an unrelated concept's vector, within the identity floor.
+    /// Does not count as a learning update.
+    pub fn contrast(&mut self, unrelated: &Hypervector, weight: f32, drift: &DriftControl) {
+        self.vector.push_away(unrelated, weight);
+        self.hold_identity(drift);
+        self.sync_counters();
+    }
+
+    /// Whether `renormalize` would change the vector.
+    pub fn needs_renormalizing(&self, drift: &DriftControl) -> bool {
+        (self.vector.density() - 0.5).abs() > drift.density_tolerance || self.drift() > 1.0 - drift.identity_floor
+    }
+
+    /// Periodic maintenance: restore bit density, then re-apply the identity floor.
+    pub fn renormalize(&mut self, drift: &DriftControl) {
+        self.vector.rebalance(drift.density_tolerance);
+        self.hold_identity(drift);
+        self.sync_counters();
+    }
+
+    /// Cycle of the latest evidence for the concept: its creation, a revision or a stored belief.
+    pub fn last_reinforced(&self) -> u64 {
+        let revised = self.history.last().map_or(0, |r| r.time);
+        let believed = self.beliefs.iter().map(|b| b.stamp.creation_time).max().unwrap_or(0);
+        self.stamp.creation_time.max(revised).max(believed)
+    }
+
+    /// Moves the confidence of the concept and of its beliefs toward `policy.floor`. Returns
+    /// false if nothing was above the floor.
+    pub fn erode(&mut self, policy: ErosionPolicy) -> bool {
+        let erode = |truth: &mut TruthValue| {
+            if truth.confidence <= policy.floor {
+                return false;
+            }
+            truth.confidence = policy.floor + (truth.confidence - policy.floor) * (1.0 - policy.rate);
+            true
+        };
+        let mut eroded = erode(&mut self.truth);
+        for belief in &mut self.beliefs {
+            eroded |= erode(&mut belief.truth);
+        }
+        eroded
+    }
+
+    /// Replaces the vector (keeping the origin); learning counters follow it.
+    pub fn set_vector(&mut self, vector: Hypervector) {
+        self.vector = vector;
+        self.sync_counters();
+    }
+
+    fn sync_counters(&mut self) {
+        if let Some(counters) = &mut self.counters {
+            counters.sync(&self.vector);
+        }
+    }
+
+    /// Distance travelled from the origin vector (0.0 = unchanged, 0.5 = unrelated).
+    pub fn drift(&self) -> f32 {
+        1.0 - self.vector.similarity(&self.origin())
+    }
+
     pub fn add_belief(&mut self, belief: Sentence) {
         // Check if belief already exists (by stamp or content) to avoid duplicates?
         // For now, just add it as requested.
-        // Maybe limit the size of beliefs?
-        if self.beliefs.len() > 100 {
-            self.beliefs.remove(0);
-        }
         self.beliefs.push(belief);
+        while self.beliefs.len() > self.belief_capacity.max(1) {
+            self.evict_weakest_belief();
+        }
+    }
+
+    /// Stores a non-judgement sentence with its punctuation. A task with the same term and
+    /// evidential base replaces the stored one instead of being added twice; a goal with
+    /// evidence disjoint from a stored goal on the same term is revised into it.
+    pub fn add_task(&mut self, task: Sentence) {
+        let table = match task.punctuation {
+            Punctuation::Judgement => {
+                self.add_belief(task);
+                return;
+            }
+            Punctuation::Question | Punctuation::Quest => &mut self.questions,
+            Punctuation::Goal => {
+                if let Some(existing) = self.goals.iter_mut().find(|g| g.term == task.term && !g.stamp.overlaps(&task.stamp)) {
+                    existing.truth = revision(existing.truth, task.truth);
+                    existing.stamp = existing.stamp.merge(&task.stamp);
+                    return;
+                }
+                &mut self.goals
+            }
+        };
+        if let Some(existing) = table.iter_mut().find(|t| t.term == task.term && t.stamp.evidence == task.stamp.evidence) {
+            *existing = task;
+            return;
+        }
+        table.push(task);
+        if table.len() > self.belief_capacity.max(1) {
+            table.remove(0);
+        }
+    }
+
+    /// The goal with the highest desire expectation, if the term is desired at all.
+    pub fn desire(&self) -> Option<&Sentence> {
+        self.goals.iter().max_by(|a, b| a.truth.expectation().total_cmp(&b.truth.expectation()))
+    }
+
+    /// Quality of the belief at `idx`: confidence x recency. Recency halves every
+    /// `RECENCY_HALF_LIFE` cycles the belief was created before the newest one (newest = 1.0).
+    fn belief_quality(&self, idx: usize) -> f32 {
+        let newest = self.beliefs.iter().map(|b| b.stamp.creation_time).max().unwrap_or(0);
+        let age = newest.saturating_sub(self.beliefs[idx].stamp.creation_time);
+        let recency = 0.5f32.powf(age as f32 / RECENCY_HALF_LIFE);
+        self.beliefs[idx].truth.confidence * recency
+    }
+
+    fn evict_weakest_belief(&mut self) {
+        let weakest = (0..self.beliefs.len())
+            .min_by(|&a, &b| self.belief_quality(a).total_cmp(&self.belief_quality(b)));
+        if let Some(idx) = weakest {
+            self.beliefs.remove(idx);
+        }
+    }
+
+    /// Beliefs ranked by quality (confidence x recency), best first.
+    pub fn beliefs(&self) -> Vec<&Sentence> {
+        let mut ranked: Vec<usize> = (0..self.beliefs.len()).collect();
+        ranked.sort_by(|&a, &b| self.belief_quality(b).total_cmp(&self.belief_quality(a)));
+        ranked.into_iter().map(|i| &self.beliefs[i]).collect()
+    }
+}
+
+/// Distribution of vector similarity between randomly paired concepts; about 0.5 with a
+/// small spread for healthy memory, see `ConceptStore::similarity_stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimilarityStats {
+    pub pairs: usize,
+    pub mean: f32,
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Subject and predicate of a statement.
+fn statement_sides(term: &Term) -> Option<(&Term, &Term)> {
+    match term {
+        Term::Compound(op, args) if args.len() == 2 && op.is_statement() => Some((&args[0], &args[1])),
+        _ => None,
     }
 }
 
-#[derive(Serialize, Deserialize)]
+/// Evidence ids a concept's truth and beliefs rest on.
+fn evidence_ids(concept: &Concept) -> Vec<u64> {
+    let mut ids: Vec<u64> = concept.stamp.evidence.iter()
+        .chain(concept.beliefs.iter().flat_map(|b| b.stamp.evidence.iter()))
+        .copied()
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// What memory knows about one term: the concepts whose terms mention it (for atoms) and the
+/// statements with it as subject or as predicate.
+#[derive(Debug, Clone, Default)]
+struct Postings {
+    mentions: HashSet<InternedTerm>,
+    subject_of: HashSet<InternedTerm>,
+    predicate_of: HashSet<InternedTerm>,
+}
+
+impl Postings {
+    fn is_empty(&self) -> bool {
+        self.mentions.is_empty() && self.subject_of.is_empty() && self.predicate_of.is_empty()
+    }
+}
+
+/// Lookups over the concepts in memory, rebuilt on load: the postings of each term, the terms
+/// with variables, the concepts resting on each evidence id, and the compounds of each
+/// normalized form (with the forms shared by several, which `consolidate` merges).
+#[derive(Debug, Clone, Default)]
+struct TermIndex {
+    postings: HashMap<InternedTerm, Postings>,
+    open: HashSet<InternedTerm>,
+    evidence: HashMap<u64, HashSet<InternedTerm>>,
+    evidence_of: HashMap<InternedTerm, Vec<u64>>, // every indexed concept has an entry
+    variants: HashMap<Term, HashSet<InternedTerm>>,
+    shared_forms: HashSet<Term>,
+}
+
+/// Concepts are shared (`Arc`) so a cycle can hold on to the concepts it reasons about
+/// without copying them; writing to a concept that is still held copies it first.
+/// The attention bag and the term index hold interned terms, so a statement they both refer
+/// to is stored once and compared by pointer.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ConceptStore {
-    pub map: HashMap<Term, Concept>,
+    pub map: HashMap<InternedTerm, Arc<Concept>>, // keyed by the concept's own term handle
     #[serde(skip)] // Bag is rebuilt on load (or transient)
-    pub priority_bag: Bag<Term>, 
+    pub priority_bag: Bag<InternedTerm>,
     pub capacity: usize,
+    #[serde(default)]
+    pub pinned: HashSet<Term>, // never evicted; the store may exceed capacity if everything is pinned
+    pub usage: HashMap<InternedTerm, Usage>, // per concept, see `touch`
+    #[serde(skip, default = "default_usage_weight")]
+    pub usage_weight: f32, // share of usage against utility in what eviction keeps (0: utility alone)
+    #[serde(skip)]
+    clock: u64, // latest cycle a concept was used in
+    #[serde(skip)] // rebuilt on load
+    index: TermIndex,
+    #[serde(skip)]
+    stale: HashSet<Term>, // concepts handed out mutably since their evidence was indexed
+    #[serde(skip)]
+    reshaped: HashSet<Term>, // concepts put or handed out mutably since `take_reshaped`
+    #[serde(skip)]
+    interner: TermInterner,
+    #[serde(skip)]
+    layers: Vec<Layer>, // open copy-on-write layers, innermost last
+}
+
+/// Undo record of a layer of memory, see `ConceptStore::push_layer`: the concepts changed since
+/// it was opened, as they were before (`None`: absent), and what else it may change.
+#[derive(Debug, Clone, Default)]
+struct Layer {
+    changed: HashMap<Term, Option<Arc<Concept>>>,
+    used: HashMap<Term, Option<Usage>>,
+    clock: u64,
+    pinned: HashSet<Term>,
 }
 
 impl ConceptStore {
@@ -299,49 +901,463 @@ impl ConceptStore {
             map: HashMap::new(),
             priority_bag: Bag::new(capacity),
             capacity,
+            pinned: HashSet::new(),
+            usage: HashMap::new(),
+            usage_weight: DEFAULT_USAGE_WEIGHT,
+            clock: 0,
+            index: TermIndex::default(),
+            stale: HashSet::new(),
+            reshaped: HashSet::new(),
+            interner: TermInterner::new(),
+            layers: Vec::new(),
         }
     }
 
-    pub fn put(&mut self, concept: Concept) {
+    pub fn put(&mut self, mut concept: Concept) {
         // 1. Evict if needed (only if adding a NEW key)
         if !self.map.contains_key(&concept.term) && self.map.len() >= self.capacity {
             self.forget_weakest();
         }
 
         // 2. Update Priority Bag
-        // Utility = P * D (Stability)
-        let utility = (concept.priority * concept.durability).clamp(0.01, 0.99);
-        self.priority_bag.put(concept.term.clone(), utility);
+        // Utility = P * D (Stability), at least the quality
+        concept.term = self.interner.adopt(&concept.term);
+        let term = concept.term.clone();
+        self.priority_bag.put(term.clone(), concept.utility());
 
         // 3. Update Storage
-        self.map.insert(concept.term.clone(), concept);
+        self.record(&term);
+        // Not used yet: counts as used when it was created
+        let clock = self.clock;
+        self.usage.entry(term.clone()).or_insert(Usage { last_used_cycle: clock, use_count: 0 });
+        self.stale.remove(term.term());
+        self.reshaped.insert(term.term().clone());
+        self.link(term.clone(), &concept);
+        self.map.insert(term, Arc::new(concept));
+    }
+
+    pub fn remove(&mut self, term: &Term) -> Option<Concept> {
+        self.record(term);
+        let interned = self.interner.get(term)?;
+        self.priority_bag.remove(&interned);
+        self.unlink(&interned);
+        self.usage.remove(&interned);
+        self.map.remove(&interned).map(Arc::unwrap_or_clone)
+    }
+
+    /// The store's handle on `term`, if memory knows it.
+    fn key(&self, term: &Term) -> Option<InternedTerm> {
+        self.interner.get(term)
+    }
+
+    /// Indexes `concept` under `term`. A term indexed before (its concept was taken out or
+    /// replaced) only has its evidence updated.
+    fn link(&mut self, term: InternedTerm, concept: &Concept) {
+        let ids = evidence_ids(concept);
+        if let Some(old) = self.index.evidence_of.get(&term) {
+            if *old == ids {
+                return;
+            }
+            let old = old.clone();
+            self.unlink_evidence(&term, &old);
+        } else {
+            self.link_term(&term);
+        }
+        for id in &ids {
+            self.index.evidence.entry(*id).or_default().insert(term.clone());
+        }
+        self.index.evidence_of.insert(term, ids);
+    }
+
+    fn link_term(&mut self, term: &InternedTerm) {
+        for atom in term.atoms() {
+            let atom = self.interner.intern(atom);
+            self.index.postings.entry(atom).or_default().mentions.insert(term.clone());
+        }
+        if term.has_variables() {
+            self.index.open.insert(term.clone());
+        }
+        if let Some((subject, predicate)) = statement_sides(term) {
+            let (subject, predicate) = (self.interner.intern(subject), self.interner.intern(predicate));
+            self.index.postings.entry(subject).or_default().subject_of.insert(term.clone());
+            self.index.postings.entry(predicate).or_default().predicate_of.insert(term.clone());
+        }
+        if let Term::Compound(..) = term.term() {
+            let form = term.normalized();
+            let variants = self.index.variants.entry(form.clone()).or_default();
+            variants.insert(term.clone());
+            if variants.len() > 1 {
+                self.index.shared_forms.insert(form);
+            }
+        }
+    }
+
+    fn unlink(&mut self, term: &InternedTerm) {
+        let Some(ids) = self.index.evidence_of.remove(term) else { return };
+        self.unlink_evidence(term, &ids);
+        self.stale.remove(term.term());
+        let index = &mut self.index;
+        let mut drop_posting = |key: &Term, list: fn(&mut Postings) -> &mut HashSet<InternedTerm>| {
+            if let Some(key) = self.interner.get(key)
+                && let Some(postings) = index.postings.get_mut(&key)
+            {
+                list(postings).remove(term);
+                if postings.is_empty() {
+                    index.postings.remove(&key);
+                }
+            }
+        };
+        for atom in term.atoms() {
+            drop_posting(atom, |p| &mut p.mentions);
+        }
+        if let Some((subject, predicate)) = statement_sides(term) {
+            drop_posting(subject, |p| &mut p.subject_of);
+            drop_posting(predicate, |p| &mut p.predicate_of);
+        }
+        index.open.remove(term);
+        if let Term::Compound(..) = term.term() {
+            let form = term.normalized();
+            if let Some(variants) = index.variants.get_mut(&form) {
+                variants.remove(term);
+                if variants.len() < 2 {
+                    index.shared_forms.remove(&form);
+                }
+                if variants.is_empty() {
+                    index.variants.remove(&form);
+                }
+            }
+        }
+    }
+
+    fn unlink_evidence(&mut self, term: &InternedTerm, ids: &[u64]) {
+        for id in ids {
+            if let Some(terms) = self.index.evidence.get_mut(id) {
+                terms.remove(term);
+                if terms.is_empty() {
+                    self.index.evidence.remove(id);
+                }
+            }
+        }
+    }
+
+    /// Re-indexes the evidence of the concepts handed out mutably since it was indexed.
+    fn refresh_evidence(&mut self) {
+        for term in std::mem::take(&mut self.stale) {
+            let Some(interned) = self.key(&term) else { continue };
+            let Some(concept) = self.map.get(&interned).cloned() else { continue };
+            self.link(interned, &concept);
+        }
+    }
+
+    /// Inheritance and implication statements in memory whose subject is `subject`. Concepts
+    /// taken out for an update keep their entry, so this only yields what is in memory.
+    pub fn statements_from<'a>(&'a self, subject: &Term) -> impl Iterator<Item = &'a Concept> {
+        self.postings(subject).into_iter()
+            .flat_map(|p| p.subject_of.iter())
+            .filter(|t| matches!(t.term(), Term::Compound(Operator::Inheritance | Operator::Implication, _)))
+            .filter_map(|term| self.get(term))
+    }
+
+    /// Statements in memory (of any copula) with `term` as their subject or their predicate.
+    pub fn statements_about<'a>(&'a self, term: &Term) -> impl Iterator<Item = &'a Concept> {
+        self.postings(term).into_iter()
+            .flat_map(|p| p.subject_of.iter().chain(p.predicate_of.difference(&p.subject_of)))
+            .filter_map(|term| self.get(term))
+    }
+
+    /// Statements in memory (of any copula) with `predicate` as their predicate.
+    pub fn statements_into<'a>(&'a self, predicate: &Term) -> impl Iterator<Item = &'a Concept> {
+        self.postings(predicate).into_iter()
+            .flat_map(|p| p.predicate_of.iter())
+            .filter_map(|term| self.get(term))
+    }
+
+    fn postings(&self, term: &Term) -> Option<&Postings> {
+        self.interner.get(term).and_then(|t| self.index.postings.get(&t))
+    }
+
+    /// Concepts whose evidential base includes every id of `evidence` (none for no ids), looked
+    /// up through the rarest id.
+    pub fn resting_on(&mut self, evidence: &[u64]) -> Vec<Term> {
+        self.refresh_evidence();
+        let Some(rarest) = evidence.iter().map(|id| self.index.evidence.get(id)).collect::<Option<Vec<_>>>()
+            .and_then(|sets| sets.into_iter().min_by_key(|terms| terms.len())) else {
+            return Vec::new();
+        };
+        rarest.iter()
+            .filter(|t| self.index.evidence_of.get(*t).is_some_and(|ids| evidence.iter().all(|id| ids.binary_search(id).is_ok())))
+            .map(|t| t.term().clone())
+            .collect()
+    }
+
+    /// Concepts whose terms share the normalized form of `term` (itself, variable renamings
+    /// and reorderings of it).
+    pub fn variants_of<'a>(&'a self, term: &Term) -> Vec<&'a Concept> {
+        self.index.variants.get(&term.normalized()).into_iter().flatten().filter_map(|t| self.get(t)).collect()
+    }
+
+    /// Groups of compound terms in memory that share a normalized form.
+    pub fn variant_groups(&self) -> Vec<Vec<Term>> {
+        self.index.shared_forms.iter()
+            .filter_map(|form| self.index.variants.get(form))
+            .map(|variants| variants.iter().map(|t| t.term().clone()).collect())
+            .collect()
+    }
+
+    /// The terms of up to `n` distinct concepts drawn uniformly at random.
+    pub fn sample(&self, n: usize, rng: &mut impl Rng) -> Vec<&Term> {
+        self.priority_bag.sample(n, rng).into_iter().map(InternedTerm::term).collect()
+    }
+
+    /// Concepts in memory that can contain `term` or unify with it: those whose terms mention
+    /// every atom of it, looked up in the atom index through the rarest atom, and those with
+    /// variables, which may stand for the atoms they lack. A term without atoms gets every
+    /// concept.
+    pub fn candidates_for<'a>(&'a self, term: &Term) -> Vec<&'a Concept> {
+        let atoms = term.atoms();
+        if atoms.is_empty() {
+            return self.values().collect();
+        }
+        let postings: Option<Vec<_>> = atoms.iter()
+            .map(|atom| self.postings(atom).map(|p| &p.mentions))
+            .collect();
+        let rarest = postings.as_ref().and_then(|p| p.iter().copied().min_by_key(|concepts| concepts.len()));
+        let mentioning = rarest.into_iter().flatten().filter(|t| atoms.iter().all(|atom| t.contains(atom)));
+        let open = self.index.open.iter().filter(|t| !atoms.iter().all(|atom| t.contains(atom)));
+        mentioning.chain(open).filter_map(|t| self.get(t)).collect()
+    }
+
+    /// Concepts in memory that share an atom with `term`, and those with variables. A term
+    /// without atoms gets every concept.
+    pub fn related_to<'a>(&'a self, term: &Term) -> Vec<&'a Concept> {
+        let atoms = term.atoms();
+        if atoms.is_empty() {
+            return self.values().collect();
+        }
+        let mut related: HashSet<&InternedTerm> = self.index.open.iter().collect();
+        for atom in atoms {
+            related.extend(self.postings(atom).into_iter().flat_map(|p| p.mentions.iter()));
+        }
+        related.into_iter().filter_map(|t| self.get(t)).collect()
+    }
+
+    /// Concepts in memory whose terms contain `term` (its own concept included).
+    pub fn containing<'a>(&'a self, term: &Term) -> Vec<&'a Concept> {
+        let mut concepts = self.candidates_for(term);
+        concepts.retain(|c| c.term.contains(term));
+        concepts
+    }
+
+    /// Similarity statistics over up to `pairs` random pairs of distinct concepts.
+    pub fn similarity_stats(&self, pairs: usize) -> SimilarityStats {
+        let concepts: Vec<&Concept> = self.values().collect();
+        let mut rng = rand::rng();
+        let similarities: Vec<f32> = if concepts.len() < 2 { Vec::new() } else {
+            (0..pairs).map(|_| {
+                let a = rng.random_range(0..concepts.len());
+                let b = (a + rng.random_range(1..concepts.len())) % concepts.len();
+                concepts[a].vector.similarity(&concepts[b].vector)
+            }).collect()
+        };
+        let n = similarities.len().max(1) as f32;
+        let mean = similarities.iter().sum::<f32>() / n;
+        let variance = similarities.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / n;
+        SimilarityStats {
+            pairs: similarities.len(),
+            mean,
+            std_dev: variance.sqrt(),
+            min: similarities.iter().copied().reduce(f32::min).unwrap_or(0.0),
+            max: similarities.iter().copied().reduce(f32::max).unwrap_or(0.0),
+        }
+    }
+
+    /// Rebuilds the attention bag and the statement index, e.g. after deserializing.
+    pub fn rebuild_index(&mut self) {
+        self.clock = self.usage.values().map(|u| u.last_used_cycle).max().unwrap_or(0);
+        self.index = TermIndex::default();
+        self.stale.clear();
+        self.reshaped = self.map.keys().map(|t| t.term().clone()).collect();
+        // Deserialized terms are nodes of their own: pool them, the concepts' terms as keys
+        for (_, mut concept) in std::mem::take(&mut self.map) {
+            let term = self.interner.adopt(&concept.term);
+            if !term.ptr_eq(&concept.term) {
+                Arc::make_mut(&mut concept).term = term.clone();
+            }
+            self.priority_bag.put(term.clone(), concept.utility());
+            self.link(term.clone(), &concept);
+            self.map.insert(term, concept);
+        }
+        let usage = std::mem::take(&mut self.usage);
+        self.usage = usage.into_iter()
+            .filter_map(|(term, usage)| Some((self.key(&term)?, usage)))
+            .collect();
+    }
+
+    /// Takes a concept out for an update that `put`s it back, without copying it unless it is
+    /// still shared. Its attention entry stays in place.
+    pub fn take(&mut self, term: &Term) -> Option<Concept> {
+        self.record(term);
+        self.map.remove(&self.key(term)?).map(Arc::unwrap_or_clone)
     }
 
     pub fn get(&self, term: &Term) -> Option<&Concept> {
-        self.map.get(term)
+        self.map.get(&self.key(term)?).map(Arc::as_ref)
+    }
+
+    /// A handle on the concept that stays valid (unchanged) while memory is updated.
+    pub fn get_shared(&self, term: &Term) -> Option<Arc<Concept>> {
+        self.map.get(&self.key(term)?).cloned()
     }
     
     pub fn get_mut(&mut self, term: &Term) -> Option<&mut Concept> {
-        self.map.get_mut(term)
+        self.record(term);
+        let key = self.key(term)?;
+        let concept = self.map.get_mut(&key)?;
+        self.stale.insert(term.clone());
+        self.reshaped.insert(term.clone());
+        Some(Arc::make_mut(concept))
     }
     
-    pub fn values(&self) -> std::collections::hash_map::Values<Term, Concept> {
-        self.map.values()
+    pub fn values(&self) -> impl Iterator<Item = &Concept> {
+        self.map.values().map(Arc::as_ref)
+    }
+
+    /// Every concept, mutably: each is copied if still shared. Prefer `get_mut` on the
+    /// concepts that change.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Concept> {
+        let terms: Vec<Term> = self.map.keys().map(|t| t.term().clone()).collect();
+        for term in &terms {
+            self.record(term);
+        }
+        self.reshaped.extend(terms.iter().cloned());
+        self.stale.extend(terms);
+        self.map.values_mut().map(Arc::make_mut)
     }
     
-    pub fn keys(&self) -> std::collections::hash_map::Keys<Term, Concept> {
-        self.map.keys()
+    /// The concepts put or handed out mutably since the last call (all of them after loading),
+    /// still in memory: what passes over changed vectors need to look at.
+    pub fn take_reshaped(&mut self) -> Vec<Term> {
+        let reshaped = std::mem::take(&mut self.reshaped);
+        reshaped.into_iter().filter(|t| self.get(t).is_some()).collect()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Term> {
+        self.map.keys().map(InternedTerm::term)
     }
     
     pub fn len(&self) -> usize {
         self.map.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn is_pinned(&self, term: &Term) -> bool {
+        self.pinned.contains(term)
+    }
+
+    /// Records a use of the concept of `term` in cycle `cycle`. The concept itself is left
+    /// alone, so a concept shared with a published snapshot is not copied.
+    pub fn touch(&mut self, term: &Term, cycle: u64) {
+        self.clock = self.clock.max(cycle);
+        let Some(key) = self.key(term).filter(|key| self.map.contains_key(key)) else { return };
+        self.record(term);
+        let usage = self.usage.entry(key).or_default();
+        usage.last_used_cycle = cycle;
+        usage.use_count = usage.use_count.saturating_add(1);
+    }
+
+    /// How recently and how often the concept of `term` was used.
+    pub fn usage(&self, term: &Term) -> Usage {
+        self.key(term).and_then(|key| self.usage.get(&key).copied()).unwrap_or_default()
+    }
+
+    /// What eviction weighs a concept by: its utility blended with its usage.
+    fn retention(&self, term: &InternedTerm) -> f32 {
+        let utility = self.priority_bag.name_map.get(term).copied().unwrap_or(0.0);
+        let usage = self.usage.get(term).map_or(0.0, |u| u.level(self.clock));
+        (1.0 - self.usage_weight) * utility + self.usage_weight * usage
+    }
+
+    /// Evicts the least retained of the few weakest unpinned concepts: an approximate LRU
+    /// among low priorities, so concepts in frequent use outlive their priority.
     fn forget_weakest(&mut self) {
-        if let Some(weak_term) = self.priority_bag.take_weakest() {
+        let pinned = &self.pinned;
+        let victim = self.priority_bag.weakest_unless(EVICTION_CANDIDATES, |t| pinned.contains(t.term())).into_iter()
+            .min_by(|a, b| self.retention(a).total_cmp(&self.retention(b)))
+            .cloned();
+        if let Some(weak_term) = victim {
+            self.record(weak_term.term());
+            self.priority_bag.remove(&weak_term);
+            self.unlink(&weak_term);
+            self.usage.remove(&weak_term);
             self.map.remove(&weak_term);
         }
     }
+
+    /// Opens a copy-on-write layer over memory: from here on, each concept keeps the version it
+    /// had before its first change, so that `pop_layer` can put it back. Only changed concepts
+    /// are recorded, and they are shared rather than copied. Layers nest.
+    pub fn push_layer(&mut self) {
+        self.layers.push(Layer { changed: HashMap::new(), used: HashMap::new(), clock: self.clock, pinned: self.pinned.clone() });
+    }
+
+    /// Closes the innermost layer, undoing every change made to memory since it was opened.
+    /// Returns false if no layer was open.
+    pub fn pop_layer(&mut self) -> bool {
+        let Some(layer) = self.layers.pop() else { return false };
+        for (term, before) in layer.changed {
+            if let Some(interned) = self.key(&term) {
+                self.priority_bag.remove(&interned);
+                self.unlink(&interned);
+                self.map.remove(&interned);
+            }
+            if let Some(concept) = before {
+                self.reshaped.insert(term.clone());
+                let interned = self.interner.adopt(&concept.term);
+                self.priority_bag.put(interned.clone(), concept.utility());
+                self.link(interned.clone(), &concept);
+                self.map.insert(interned, concept);
+            }
+        }
+        for (term, before) in layer.used {
+            match before {
+                Some(usage) => { self.usage.insert(self.interner.adopt(&term.into()), usage); }
+                None => { self.key(&term).map(|key| self.usage.remove(&key)); }
+            }
+        }
+        self.clock = layer.clock;
+        self.pinned = layer.pinned;
+        true
+    }
+
+    /// Closes the innermost layer, keeping its changes; an enclosing layer can still undo them.
+    /// Returns false if no layer was open.
+    pub fn commit_layer(&mut self) -> bool {
+        let Some(layer) = self.layers.pop() else { return false };
+        if let Some(outer) = self.layers.last_mut() {
+            for (term, before) in layer.changed {
+                outer.changed.entry(term).or_insert(before);
+            }
+            for (term, before) in layer.used {
+                outer.used.entry(term).or_insert(before);
+            }
+        }
+        true
+    }
+
+    /// Notes the current version of the concept of `term` and of its usage in the innermost
+    /// layer, unless they were changed there before.
+    fn record(&mut self, term: &Term) {
+        if self.layers.last().is_some_and(|layer| !layer.changed.contains_key(term)) {
+            let before = self.get_shared(term);
+            let used = self.key(term).and_then(|key| self.usage.get(&key).copied());
+            let layer = self.layers.last_mut().unwrap();
+            layer.changed.insert(term.clone(), before);
+            layer.used.insert(term.clone(), used);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -360,7 +1376,7 @@ mod tests {
         assert!((initial_sim - 0.5).abs() < 0.1, "Random vectors should be approx orthogonal (0.5 similarity)");
 
         // 3. Update Tiger with Feline (simulating <Tiger --> Feline>)
-        // Using a weight of 0.5 (so k=5 copies of Feline vs 1 copy of Tiger)
+        // Using a weight of 0.5 (a third of the differing bits move to Feline)
         tiger.update(&feline, 0.5);
 
         // 4. Assert similarity has increased significantly
@@ -370,6 +1386,118 @@ mod tests {
         assert!(new_sim > 0.6, "Similarity should be significant");
     }
 
+    #[test]
+    fn test_eviction_keeps_used_concepts() {
+        let concept = |name: &str, priority: f32| {
+            let term = Term::atom_from_str(name);
+            let mut c = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+            c.priority = priority;
+            c
+        };
+        let mut store = ConceptStore::new(3);
+        store.put(concept("used", 0.2));
+        store.put(concept("idle", 0.3));
+        store.put(concept("strong", 0.9));
+        for cycle in 1..=20 {
+            store.touch(&Term::atom_from_str("used"), cycle);
+        }
+        assert_eq!(store.usage(&Term::atom_from_str("used")).use_count, 20);
+        // A use leaves a shared concept alone rather than copying it
+        let shared = store.get_shared(&Term::atom_from_str("used")).unwrap();
+        store.touch(&Term::atom_from_str("used"), 21);
+        assert!(Arc::ptr_eq(&shared, &store.get_shared(&Term::atom_from_str("used")).unwrap()));
+        assert_eq!(store.usage(&Term::atom_from_str("used")).last_used_cycle, 21);
+
+        // The least used of the weak concepts goes, not the one with the lowest priority
+        store.put(concept("new", 0.5));
+        assert!(store.get(&Term::atom_from_str("idle")).is_none());
+        assert!(store.get(&Term::atom_from_str("used")).is_some());
+
+        // Without the usage weight, priority alone decides
+        store.usage_weight = 0.0;
+        store.put(concept("newer", 0.5));
+        assert!(store.get(&Term::atom_from_str("used")).is_none());
+    }
+
+    #[test]
+    fn test_belief_capacity_eviction() {
+        let term = Term::atom_from_str("bird");
+        let mut concept = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+        concept.belief_capacity = 3;
+
+        // Belief `id` is created in cycle 100 * id
+        let belief = |c: f32, id: u64| Sentence::new(term.clone(), Punctuation::Judgement, TruthValue::new(1.0, c), Stamp::new(100 * id, vec![id]));
+        concept.add_belief(belief(0.9, 1));
+        concept.add_belief(belief(0.1, 2)); // weak, should be evicted first
+        concept.add_belief(belief(0.8, 3));
+        concept.add_belief(belief(0.7, 4));
+
+        assert_eq!(concept.beliefs.len(), 3);
+        assert!(concept.beliefs.iter().all(|b| b.stamp.evidence != vec![2]));
+
+        // Ranked best first
+        let ranked = concept.beliefs();
+        assert_eq!(ranked[0].stamp.evidence, vec![4]);
+        assert_eq!(ranked[2].stamp.evidence, vec![1]);
+
+        // Beliefs of the same age rank by confidence, whatever order they arrived in
+        concept.beliefs.clear();
+        for (c, id) in [(0.9, 5), (0.1, 6), (0.8, 7), (0.7, 8)] {
+            concept.add_belief(Sentence::new(term.clone(), Punctuation::Judgement, TruthValue::new(1.0, c), Stamp::new(500, vec![id])));
+        }
+        let ranked: Vec<u64> = concept.beliefs().iter().map(|b| b.stamp.evidence[0]).collect();
+        assert_eq!(ranked, [5, 7, 8]);
+    }
+
+    #[test]
+    fn test_drift_control_preserves_identity() {
+        let term = Term::atom_from_str("tiger");
+        let original = Hypervector::from_term(&term);
+        let mut concept = Concept::new(term, original, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+        let drift = DriftControl::default();
+        // The term gives the origin back: it is not stored, unlike an embedding's
+        assert!(concept.origin.is_none() && concept.origin() == original);
+        let embedded = Concept::new(Term::atom_from_str("lion"), original, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+        assert_eq!(embedded.origin.as_deref(), Some(&original));
+
+        for _ in 0..50 {
+            concept.learn(&Hypervector::random(), 0.5, &drift);
+        }
+        assert_eq!(concept.update_count, 50);
+        assert!(concept.vector.similarity(&original) >= drift.identity_floor - 1e-3);
+
+        // Raw updates, by contrast, wipe out the original identity
+        let mut raw = original;
+        for _ in 0..50 {
+            raw.update(&Hypervector::random(), 0.5);
+        }
+        assert!(raw.similarity(&original) < drift.identity_floor);
+    }
+
+    #[test]
+    fn test_push_away_is_bounded() {
+        let a = Hypervector::random();
+        let mut b = a;
+        b.update(&Hypervector::random(), 0.2); // similarity ~0.92
+        let before = b.similarity(&a);
+        b.push_away(&a, 0.1);
+        assert!(b.similarity(&a) < before);
+        for _ in 0..20 {
+            b.push_away(&a, 1.0);
+        }
+        assert!((b.similarity(&a) - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_rebalance_restores_density() {
+        let a = Hypervector::random();
+        let b = Hypervector::random();
+        let mut sparse = Hypervector::bundle_with(&[a, b], TieBreak::Zero); // tie -> AND, ~25% density
+        assert!(sparse.density() < 0.3);
+        sparse.rebalance(0.05);
+        assert!((sparse.density() - 0.5).abs() <= 0.05);
+    }
+
     #[test]
     fn test_bind_inverse() {
         let a = Hypervector::random();
@@ -381,6 +1509,47 @@ mod tests {
         assert_eq!(a, unbound, "XOR binding should be reversible");
     }
 
+    #[test]
+    fn test_vector_text_forms() {
+        let v = Hypervector::random();
+        assert_eq!(Hypervector::parse(&v.to_hex()).unwrap(), v);
+        assert_eq!(Hypervector::parse(&format!("0x{}", v.to_hex())).unwrap(), v);
+
+        // Bits in order from bit 0, hex most significant digit first; the rest is zero
+        let bits = Hypervector::parse("0b0110_1").unwrap();
+        assert_eq!((bits.bits[0], bits.bits[1]), (0b10110, 0));
+        assert_eq!(Hypervector::parse("8").unwrap().bits[0], 1 << 63);
+        assert!(Hypervector::parse("0b012").is_err());
+        assert!(Hypervector::parse("xyz").is_err());
+        assert!(Hypervector::parse(&"0".repeat(HV_DIM_U64 * 16 + 1)).is_err());
+    }
+
+    #[test]
+    fn test_bundle_tie_breaking() {
+        let (a, b) = (Hypervector::random(), Hypervector::random());
+        for tie_break in [TieBreak::Zero, TieBreak::PositionHash] {
+            let bundled = Hypervector::bundle_with(&[a, b], tie_break);
+            assert!((bundled.similarity(&a) - 0.75).abs() < 0.02, "{:?}: {}", tie_break, bundled.similarity(&a));
+            assert!((bundled.similarity(&b) - 0.75).abs() < 0.02, "{:?}: {}", tie_break, bundled.similarity(&b));
+        }
+        // Plain bundling keeps breaking ties to 0
+        assert_eq!(Hypervector::bundle(&[a, b]), Hypervector::bundle_with(&[a, b], TieBreak::Zero));
+        assert!(Hypervector::bundle(&[a, b]).density() < 0.3);
+        let hashed = Hypervector::bundle_with(&[a, b], TieBreak::PositionHash);
+        assert!((hashed.density() - 0.5).abs() < 0.02);
+        assert_eq!(hashed, Hypervector::bundle_with(&[b, a], TieBreak::PositionHash));
+
+        // Odd input counts never tie
+        let c = Hypervector::random();
+        assert_eq!(Hypervector::bundle_with(&[a, b, c], TieBreak::PositionHash), Hypervector::bundle(&[a, b, c]));
+
+        // Initial vectors of compounds break ties as asked: `(--, a)` bundles two vectors
+        let negation = Term::Compound(Operator::Negation, vec![Term::atom_from_str("a")]);
+        assert_eq!(Hypervector::from_term(&negation), Hypervector::from_term_with(&negation, TieBreak::Zero));
+        assert!(Hypervector::from_term(&negation).density() < 0.3);
+        assert!((Hypervector::from_term_with(&negation, TieBreak::PositionHash).density() - 0.5).abs() < 0.02);
+    }
+
     #[test]
     fn test_bundle_majority() {
         let a = Hypervector::random();
@@ -398,4 +1567,204 @@ mod tests {
         assert!(sim_a > sim_b, "Majority element should be more similar to bundle");
         assert!(sim_a > 0.8, "Bundle should be very similar to dominant element");
     }
+
+    #[test]
+    fn test_counter_vector_weighting() {
+        let term = Term::atom_from_str("tiger");
+        let original = Hypervector::from_term(&term);
+        let feline = Hypervector::random();
+        let mut concept = Concept::new(term, original, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+        let drift = DriftControl { decay: 0.0, identity_floor: 0.0, ..DriftControl::default() };
+
+        // Four updates at 0.3 outweigh the original vote; three do not
+        for _ in 0..3 {
+            concept.learn(&feline, 0.3, &drift);
+        }
+        assert_eq!(concept.vector, original);
+        concept.learn(&feline, 0.3, &drift);
+        assert_eq!(concept.vector, feline);
+
+        // Counts saturate, so opposing evidence can still win back
+        for _ in 0..100 {
+            concept.learn(&feline, 1.0, &drift);
+        }
+        let counters = concept.counters.as_ref().unwrap();
+        assert!((0..HV_DIM_BITS).all(|i| counters.count(i).abs() == drift.counter_saturation));
+        for _ in 0..11 {
+            concept.learn(&original, 1.0, &drift);
+        }
+        assert_eq!(concept.vector, original);
+
+        // Updates below the counter resolution still add up
+        let mut concept = Concept::new(Term::atom_from_str("lion"), original, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+        for _ in 0..100 {
+            concept.learn(&feline, 0.02, &drift);
+        }
+        assert_eq!(concept.vector, feline);
+    }
+
+    #[test]
+    fn test_pinned_concepts_survive_eviction() {
+        let mut store = ConceptStore::new(2);
+        let concept = |name: &str, priority: f32| {
+            let term = Term::atom_from_str(name);
+            let mut c = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+            c.priority = priority;
+            c
+        };
+        store.pinned.insert(Term::atom_from_str("axiom"));
+        store.put(concept("axiom", 0.01));
+        store.put(concept("a", 0.5));
+        store.put(concept("b", 0.9)); // evicts "a", the weakest unpinned concept
+        assert!(store.get(&Term::atom_from_str("axiom")).is_some());
+        assert!(store.get(&Term::atom_from_str("a")).is_none());
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn test_layers_undo_changes() {
+        let mut store = ConceptStore::new(2);
+        let atom = Term::atom_from_str;
+        let concept = |name: &str| Concept::new(atom(name), Hypervector::from_term(&atom(name)), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+        store.put(concept("a"));
+        store.put(concept("b"));
+        let a = store.get_shared(&atom("a")).unwrap();
+        assert_eq!(store.take_reshaped().len(), 2);
+        assert!(store.take_reshaped().is_empty());
+
+        store.push_layer();
+        store.get_mut(&atom("a")).unwrap().priority = 0.1;
+        store.remove(&atom("b"));
+        store.put(concept("c"));
+        store.push_layer();
+        store.put(concept("d")); // evicts
+        assert!(store.commit_layer());
+        assert_eq!(store.len(), 2);
+        assert!(store.pop_layer());
+
+        assert!(Arc::ptr_eq(&store.get_shared(&atom("a")).unwrap(), &a));
+        assert!(store.get(&atom("b")).is_some());
+        assert!(store.get(&atom("c")).is_none() && store.get(&atom("d")).is_none());
+        assert_eq!(store.priority_bag.count, 2);
+        assert_eq!(store.containing(&atom("b")).len(), 1);
+        let mut reshaped = store.take_reshaped();
+        reshaped.sort();
+        assert_eq!(reshaped, [atom("a"), atom("b")]);
+        assert!(!store.pop_layer());
+    }
+
+    #[test]
+    fn test_store_indices_share_interned_terms() {
+        let mut store = ConceptStore::new(10);
+        let statement = Term::Compound(Operator::Inheritance, vec![Term::atom_from_str("cat"), Term::atom_from_str("animal")]);
+        store.put(Concept::new(statement.clone(), Hypervector::from_term(&statement), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![1])));
+        store.put(store.get(&statement).unwrap().clone());
+
+        let in_bag = store.priority_bag.name_map.keys().next().unwrap();
+        let in_index = store.index.postings.values().flat_map(|p| p.subject_of.iter()).next().unwrap();
+        assert!(in_bag.ptr_eq(in_index));
+        let (key, concept) = store.map.iter().next().unwrap();
+        assert!(key.ptr_eq(in_bag) && concept.term.ptr_eq(key));
+        assert_eq!(store.priority_bag.count, 1);
+        assert_eq!(store.statements_from(&Term::atom_from_str("cat")).count(), 1);
+        assert_eq!(store.statements_into(&Term::atom_from_str("animal")).count(), 1);
+        assert_eq!(store.statements_into(&Term::atom_from_str("cat")).count(), 0);
+
+        // Terms read back from a snapshot are pooled again
+        let mut loaded: ConceptStore = bincode::deserialize(&bincode::serialize(&store).unwrap()).unwrap();
+        loaded.rebuild_index();
+        let (key, concept) = loaded.map.iter().next().unwrap();
+        assert!(concept.term.ptr_eq(key) && loaded.priority_bag.name_map.keys().next().unwrap().ptr_eq(key));
+
+        store.remove(&statement);
+        assert!(store.index.postings.is_empty() && store.index.evidence.is_empty() && store.priority_bag.count == 0);
+        assert!(store.interner.get(&statement).is_none());
+    }
+
+    #[test]
+    fn test_atom_index() {
+        use crate::nars::parser::parse_term;
+        let mut store = ConceptStore::new(10);
+        let terms: Vec<Term> = ["cat", "<cat --> animal>", "<dog --> animal>", "(&&, <cat --> pet>, <dog --> pet>)"].iter()
+            .map(|text| parse_term(text).unwrap().1)
+            .collect();
+        for term in &terms {
+            store.put(Concept::new(term.clone(), Hypervector::from_term(term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])));
+        }
+        let names = |concepts: Vec<&Concept>| {
+            let mut names: Vec<String> = concepts.iter().map(|c| c.term.to_string()).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(store.containing(&terms[0])), ["(&&, <cat --> pet>, <dog --> pet>)", "<cat --> animal>", "cat"]);
+        assert_eq!(names(store.containing(&parse_term("<dog --> pet>").unwrap().1)), ["(&&, <cat --> pet>, <dog --> pet>)"]);
+        assert_eq!(names(store.candidates_for(&parse_term("<?x --> animal>").unwrap().1)), ["<cat --> animal>", "<dog --> animal>"]);
+        assert!(store.candidates_for(&Term::atom_from_str("fish")).is_empty());
+        assert_eq!(store.candidates_for(&parse_term("<?x --> ?y>").unwrap().1).len(), 4);
+
+        // Terms with variables are candidates whatever atoms they lack
+        let rule = parse_term("<<$x --> cat> ==> <$x --> animal>>").unwrap().1;
+        store.put(Concept::new(rule.clone(), Hypervector::from_term(&rule), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])));
+        assert_eq!(names(store.candidates_for(&parse_term("<<tom --> cat> ==> ?y>").unwrap().1)), [rule.to_string()]);
+        assert!(store.containing(&Term::atom_from_str("tom")).is_empty());
+        store.remove(&rule);
+
+        store.remove(&terms[1]);
+        assert_eq!(names(store.containing(&terms[0])), ["(&&, <cat --> pet>, <dog --> pet>)", "cat"]);
+        store.remove(&terms[3]);
+        store.remove(&terms[0]);
+        assert!(!store.index.postings.keys().any(|atom| *atom.term() == terms[0]));
+    }
+
+    #[test]
+    fn test_evidence_and_variant_index() {
+        use crate::nars::parser::parse_term;
+        let mut store = ConceptStore::new(10);
+        let mut put = |text: &str, ids: Vec<u64>| {
+            let term = parse_term(text).unwrap().1;
+            store.put(Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, ids)));
+            term
+        };
+        let a = put("<cat --> animal>", vec![1]);
+        let b = put("<<$x --> cat> ==> <$x --> animal>>", vec![1, 2]);
+        let c = put("<<$y --> cat> ==> <$y --> animal>>", vec![3]);
+        let sorted = |mut terms: Vec<Term>| { terms.sort_by_key(Term::to_string); terms };
+
+        assert_eq!(sorted(store.resting_on(&[1])), sorted(vec![a.clone(), b.clone()]));
+        assert_eq!(store.resting_on(&[1, 2]), vec![b.clone()]);
+        assert!(store.resting_on(&[4]).is_empty());
+        // Evidence gained through a mutable handle is found once it is handed back
+        store.get_mut(&a).unwrap().add_belief(Sentence::new(a.clone(), Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![3])));
+        assert_eq!(sorted(store.resting_on(&[3])), sorted(vec![a.clone(), c.clone()]));
+
+        let groups = store.variant_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(sorted(groups[0].clone()), sorted(vec![b.clone(), c.clone()]));
+        assert_eq!(store.variants_of(&parse_term("<<$z --> cat> ==> <$z --> animal>>").unwrap().1).len(), 2);
+        assert_eq!(store.variants_of(&a).len(), 1);
+        store.remove(&c);
+        assert!(store.variant_groups().is_empty());
+        assert_eq!(store.resting_on(&[3]), vec![a]);
+        assert_eq!(store.statements_about(&parse_term("<$x --> animal>").unwrap().1).count(), 1);
+    }
+
+    #[test]
+    fn test_desire_table_revision() {
+        let term = Term::atom_from_str("eat");
+        let mut concept = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(0.5, 0.0), Stamp::new(0, vec![]));
+        let goal = |f: f32, ids: Vec<u64>| Sentence::new(term.clone(), Punctuation::Goal, TruthValue::new(f, 0.9), Stamp::new(0, ids));
+
+        concept.add_task(goal(1.0, vec![1]));
+        concept.add_task(goal(0.0, vec![1, 2])); // overlapping evidence: kept apart
+        assert_eq!(concept.goals.len(), 2);
+        assert_eq!(concept.desire().unwrap().truth.frequency, 1.0);
+
+        // Independent evidence is revised into the first disjoint goal
+        concept.add_task(goal(1.0, vec![3]));
+        assert_eq!(concept.goals.len(), 2);
+        let desire = concept.desire().unwrap();
+        assert!(desire.truth.confidence > 0.9);
+        assert_eq!(desire.stamp.evidence, vec![1, 3]);
+    }
 }
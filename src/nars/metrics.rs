@@ -0,0 +1,103 @@
+use std::fmt::Write;
+use std::time::Duration;
+use super::task::BackpressureStats;
+
+/// Cumulative histogram over fixed upper bounds, in the Prometheus sense: observations above
+/// the last bound only show up in `count` and `sum`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub bounds: Vec<u64>,
+    pub buckets: Vec<u64>, // observations <= bounds[i]
+    pub count: u64,
+    pub sum: u64,
+}
+
+impl Histogram {
+    pub fn new(bounds: Vec<u64>) -> Self {
+        let buckets = vec![0; bounds.len()];
+        Self { bounds, buckets, count: 0, sum: 0 }
+    }
+
+    pub fn observe(&mut self, value: u64) {
+        for (bound, bucket) in self.bounds.iter().zip(self.buckets.iter_mut()) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+impl Default for Histogram {
+    /// Buckets for answer latencies in cycles.
+    fn default() -> Self {
+        Self::new(vec![1, 2, 5, 10, 20, 50, 100, 200, 500, 1000])
+    }
+}
+
+/// Snapshot of operational counters of a running system, see `NarsSystem::metrics`.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    pub uptime: Duration,
+    pub cycles: u64,
+    pub concepts: usize,
+    pub concept_capacity: usize,
+    pub buffer_len: usize,
+    pub buffer_capacity: usize,
+    pub pending_tasks: usize,
+    pub inputs: BackpressureStats, // what admission control did with the inputs
+    pub derivations: u64,
+    pub answer_latency: Histogram, // cycles from question input to first answer
+}
+
+impl Metrics {
+    fn per_second(&self, count: u64) -> f64 {
+        let secs = self.uptime.as_secs_f64();
+        if secs > 0.0 { count as f64 / secs } else { 0.0 }
+    }
+
+    pub fn cycles_per_second(&self) -> f64 {
+        self.per_second(self.cycles)
+    }
+
+    pub fn derivations_per_second(&self) -> f64 {
+        self.per_second(self.derivations)
+    }
+
+    /// Fraction of the attention buffer in use.
+    pub fn buffer_occupancy(&self) -> f64 {
+        if self.buffer_capacity == 0 { 0.0 } else { self.buffer_len as f64 / self.buffer_capacity as f64 }
+    }
+
+    /// Prometheus text exposition format, ready to be served from a `/metrics` endpoint.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP nars_{} {}\n# TYPE nars_{} {}\nnars_{} {}", name, help, name, kind, name, value);
+        };
+        metric("uptime_seconds", "gauge", "Seconds since the system was created.", format!("{:.3}", self.uptime.as_secs_f64()));
+        metric("cycles_total", "counter", "Reasoning cycles run.", self.cycles.to_string());
+        metric("cycles_per_second", "gauge", "Average cycles per second since start.", format!("{:.3}", self.cycles_per_second()));
+        metric("concepts", "gauge", "Concepts in memory.", self.concepts.to_string());
+        metric("concept_capacity", "gauge", "Concept capacity of memory.", self.concept_capacity.to_string());
+        metric("buffer_occupancy", "gauge", "Fraction of the attention buffer in use.", format!("{:.4}", self.buffer_occupancy()));
+        metric("pending_tasks", "gauge", "Tasks waiting in the input lanes.", self.pending_tasks.to_string());
+        metric("inputs_admitted_total", "counter", "Inputs queued as tasks.", self.inputs.admitted.to_string());
+        metric("inputs_merged_total", "counter", "Inputs revised into a pending task by admission control.", self.inputs.merged.to_string());
+        metric("inputs_dropped_total", "counter", "Inputs dropped by admission control.", self.inputs.dropped.to_string());
+        metric("derivations_total", "counter", "Derivations accepted into memory.", self.derivations.to_string());
+        metric("derivations_per_second", "gauge", "Average derivations per second since start.", format!("{:.3}", self.derivations_per_second()));
+
+        let latency = &self.answer_latency;
+        let _ = writeln!(out, "# HELP nars_answer_latency_cycles Cycles from question input to its first answer.");
+        let _ = writeln!(out, "# TYPE nars_answer_latency_cycles histogram");
+        for (bound, bucket) in latency.bounds.iter().zip(&latency.buckets) {
+            let _ = writeln!(out, "nars_answer_latency_cycles_bucket{{le=\"{}\"}} {}", bound, bucket);
+        }
+        let _ = writeln!(out, "nars_answer_latency_cycles_bucket{{le=\"+Inf\"}} {}", latency.count);
+        let _ = writeln!(out, "nars_answer_latency_cycles_sum {}", latency.sum);
+        let _ = writeln!(out, "nars_answer_latency_cycles_count {}", latency.count);
+        out
+    }
+}
@@ -1,13 +1,50 @@
 pub mod term;
 pub mod sentence;
 pub mod truth;
-pub mod unify;
 pub mod memory;
 pub mod rules;
 pub mod control;
 pub mod parser;
-pub mod static_rules;
 pub mod glove;
+pub mod grounding;
+pub mod task;
+pub mod error;
+pub mod sensor;
+pub mod operations;
+pub mod script;
+pub mod activity;
+pub mod pack;
+pub mod pipeline;
+pub mod metrics;
+pub mod calc;
+pub mod events;
+pub mod snapshot;
+pub mod persist;
+pub mod ingest;
+
+// Engine internals: their shape changes freely between versions. The `unstable-internals`
+// feature exposes them for experiments and tooling.
+#[cfg(feature = "unstable-internals")]
 pub mod bag;
+#[cfg(not(feature = "unstable-internals"))]
+pub(crate) mod bag;
+#[cfg(feature = "unstable-internals")]
+pub mod intern;
+#[cfg(not(feature = "unstable-internals"))]
+pub(crate) mod intern;
+#[cfg(feature = "unstable-internals")]
+pub mod unify;
+#[cfg(not(feature = "unstable-internals"))]
+pub(crate) mod unify;
+#[cfg(feature = "unstable-internals")]
+pub mod static_rules;
+#[cfg(not(feature = "unstable-internals"))]
+pub(crate) mod static_rules;
+
+pub use static_rules::{build_rule, RuleLoadReport, SkippedRule, BUILTIN_RULES};
+pub use intern::InternedTerm;
+
+#[allow(clippy::module_inception)]
 mod tests;
 mod tests_integration;
+mod tests_truth;
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use super::term::Term;
+use super::truth::TruthValue;
+use super::sentence::{Sentence, Punctuation, Stamp};
+
+/// Executes an operation on its arguments and returns the tasks it produces
+/// (feedback events, or new beliefs/goals/questions for mental operations).
+pub type OperationHandler = fn(&[Term]) -> Vec<Sentence>;
+
+#[derive(Clone)]
+pub struct OperationRegistry {
+    handlers: HashMap<String, OperationHandler>,
+}
+
+impl OperationRegistry {
+    /// Registry with the NAL-9 mental operations `^believe`, `^want` and `^wonder`.
+    pub fn new() -> Self {
+        let mut registry = Self { handlers: HashMap::new() };
+        registry.register("^believe", believe);
+        registry.register("^want", want);
+        registry.register("^wonder", wonder);
+        registry
+    }
+
+    pub fn register(&mut self, name: &str, handler: OperationHandler) {
+        self.handlers.insert(name.to_string(), handler);
+    }
+
+    pub fn get(&self, name: &str) -> Option<OperationHandler> {
+        self.handlers.get(name).copied()
+    }
+}
+
+impl Default for OperationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn internal_task(args: &[Term], punctuation: Punctuation, truth: TruthValue) -> Vec<Sentence> {
+    args.iter()
+        .map(|term| Sentence::new(term.clone(), punctuation, truth, Stamp::new(0, vec![])))
+        .collect()
+}
+
+/// `(^believe, t)` adds the judgement `t.`
+fn believe(args: &[Term]) -> Vec<Sentence> {
+    internal_task(args, Punctuation::Judgement, TruthValue::new(1.0, 0.9))
+}
+
+/// `(^want, t)` adds the goal `t!`
+fn want(args: &[Term]) -> Vec<Sentence> {
+    internal_task(args, Punctuation::Goal, TruthValue::new(1.0, 0.9))
+}
+
+/// `(^wonder, t)` adds the question `t?`
+fn wonder(args: &[Term]) -> Vec<Sentence> {
+    internal_task(args, Punctuation::Question, TruthValue::new(0.0, 0.0))
+}
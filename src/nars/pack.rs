@@ -0,0 +1,321 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use memmap2::Mmap;
+use serde::{Serialize, Deserialize};
+use super::term::{Term, deterministic_hash};
+use super::truth::TruthValue;
+use super::memory::{Concept, Hypervector, HV_DIM_U64};
+use super::sentence::{Punctuation, Sentence, Source, Stamp};
+use super::control::NarsSystem;
+use super::ingest::{parse_csv_triples, parse_horn_clauses, parse_ntriples, Triple};
+use super::script::run_script_file;
+use super::error::NarsError;
+
+const MAGIC: &[u8; 8] = b"NARSPACK";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 32;
+const VECTOR_LEN: usize = HV_DIM_U64 * 8;
+
+/// Read-only knowledge pack: concepts (term, truth, vector) in a file that is memory-mapped
+/// rather than loaded, so a large knowledge base is usable as soon as it is mounted.
+///
+/// Layout (little endian), entries sorted by term hash:
+/// - header: magic, version (u32), reserved (u32), entry count (u64), term blob length (u64)
+/// - term hashes: one u64 per entry, ascending, for binary-search lookup
+/// - vectors: one hypervector per entry, scanned in place by similarity search
+/// - truths: frequency and confidence (f32 each) per entry
+/// - term offsets: count + 1 u64 offsets into the term blob
+/// - term blob: bincode-encoded terms
+pub struct KnowledgePack {
+    name: String,
+    map: Mmap,
+    count: usize,
+    /// First evidence id of the pack; entry `i` is the evidence `evidence_base + i`.
+    pub evidence_base: u64,
+}
+
+fn term_hash(term: &Term) -> u64 {
+    deterministic_hash(&term.to_string())
+}
+
+impl KnowledgePack {
+    /// Writes `concepts` to a pack file at `path`. Returns the number of entries.
+    pub fn write<'a>(path: &str, concepts: impl IntoIterator<Item = &'a Concept>) -> Result<usize, NarsError> {
+        let mut entries: Vec<(u64, &Concept)> = concepts.into_iter().map(|c| (term_hash(&c.term), c)).collect();
+        entries.sort_by_key(|(hash, _)| *hash);
+
+        let mut terms = Vec::new();
+        let mut offsets = vec![0u64];
+        for (_, concept) in &entries {
+            bincode::serialize_into(&mut terms, &concept.term)?;
+            offsets.push(terms.len() as u64);
+        }
+
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&0u32.to_le_bytes())?;
+        out.write_all(&(entries.len() as u64).to_le_bytes())?;
+        out.write_all(&(terms.len() as u64).to_le_bytes())?;
+        for (hash, _) in &entries {
+            out.write_all(&hash.to_le_bytes())?;
+        }
+        for (_, concept) in &entries {
+            for word in concept.vector.bits {
+                out.write_all(&word.to_le_bytes())?;
+            }
+        }
+        for (_, concept) in &entries {
+            out.write_all(&concept.truth.frequency.to_le_bytes())?;
+            out.write_all(&concept.truth.confidence.to_le_bytes())?;
+        }
+        for offset in &offsets {
+            out.write_all(&offset.to_le_bytes())?;
+        }
+        out.write_all(&terms)?;
+        out.flush()?;
+        Ok(entries.len())
+    }
+
+    /// Maps the pack at `path`, checking the header and section sizes. Terms are decoded lazily.
+    pub fn open(path: &str) -> Result<Self, NarsError> {
+        let file = File::open(path)?;
+        // Safety: the pack is only read; modifying the file while mounted is not supported
+        let map = unsafe { Mmap::map(&file)? };
+        let invalid = |message: &str| NarsError::Serialization(format!("{}: {}", path, message));
+
+        if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+            return Err(invalid("not a knowledge pack"));
+        }
+        let version = u32::from_le_bytes(map[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(invalid(&format!("unsupported pack version {}", version)));
+        }
+        let count = u64::from_le_bytes(map[16..24].try_into().unwrap()) as usize;
+        let terms_len = u64::from_le_bytes(map[24..32].try_into().unwrap()) as usize;
+        let expected = count.checked_mul(8 + VECTOR_LEN + 8 + 8)
+            .and_then(|n| n.checked_add(HEADER_LEN + 8))
+            .and_then(|n| n.checked_add(terms_len));
+        if expected != Some(map.len()) {
+            return Err(invalid("truncated or corrupt pack"));
+        }
+
+        let name = std::path::Path::new(path).file_stem().map_or_else(|| path.to_string(), |s| s.to_string_lossy().into_owned());
+        Ok(Self { name, map, count, evidence_base: 0 })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.map[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn vectors_start(&self) -> usize {
+        HEADER_LEN + self.count * 8
+    }
+
+    fn truths_start(&self) -> usize {
+        self.vectors_start() + self.count * VECTOR_LEN
+    }
+
+    fn offsets_start(&self) -> usize {
+        self.truths_start() + self.count * 8
+    }
+
+    pub fn term(&self, idx: usize) -> Option<Term> {
+        let blob = self.offsets_start() + (self.count + 1) * 8;
+        let start = self.read_u64(self.offsets_start() + idx * 8) as usize;
+        let end = self.read_u64(self.offsets_start() + (idx + 1) * 8) as usize;
+        let bytes = self.map.get(blob + start..blob + end)?;
+        bincode::deserialize(bytes).ok()
+    }
+
+    pub fn truth(&self, idx: usize) -> TruthValue {
+        let offset = self.truths_start() + idx * 8;
+        let frequency = f32::from_le_bytes(self.map[offset..offset + 4].try_into().unwrap());
+        let confidence = f32::from_le_bytes(self.map[offset + 4..offset + 8].try_into().unwrap());
+        TruthValue::new(frequency, confidence)
+    }
+
+    pub fn vector(&self, idx: usize) -> Hypervector {
+        let start = self.vectors_start() + idx * VECTOR_LEN;
+        let mut bits = [0u64; HV_DIM_U64];
+        for (i, word) in bits.iter_mut().enumerate() {
+            *word = self.read_u64(start + i * 8);
+        }
+        Hypervector { bits }
+    }
+
+    /// Index of the entry for `term`: binary search on the hash, then a term comparison.
+    pub fn find(&self, term: &Term) -> Option<usize> {
+        let hash = term_hash(term);
+        let (mut lo, mut hi) = (0, self.count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.read_u64(HEADER_LEN + mid * 8) < hash { lo = mid + 1 } else { hi = mid }
+        }
+        (lo..self.count)
+            .take_while(|&idx| self.read_u64(HEADER_LEN + idx * 8) == hash)
+            .find(|&idx| self.term(idx).as_ref() == Some(term))
+    }
+
+    /// The entry as a concept whose single belief carries the entry's evidence id.
+    pub fn concept(&self, idx: usize) -> Option<Concept> {
+        let term = self.term(idx)?;
+        let truth = self.truth(idx);
+        let mut stamp = Stamp::new(0, if truth.confidence > 0.0 { vec![self.evidence_base + idx as u64] } else { vec![] });
+        stamp.add_source(Source::Import(format!("pack:{}", self.name)));
+        let mut concept = Concept::new(term.clone(), self.vector(idx), truth, stamp.clone());
+        if truth.confidence > 0.0 {
+            concept.add_belief(Sentence::new(term, Punctuation::Judgement, truth, stamp));
+        }
+        Some(concept)
+    }
+
+    /// The `n` entries most similar to `vector`, most similar first.
+    pub fn nearest(&self, vector: &Hypervector, n: usize) -> Vec<(usize, f32)> {
+        let mut matches: Vec<(usize, f32)> = (0..self.count).map(|idx| (idx, vector.similarity(&self.vector(idx)))).collect();
+        matches.sort_by(|x, y| y.1.total_cmp(&x.1));
+        matches.truncate(n);
+        matches
+    }
+}
+
+/// Statistics of a pack build (`build_pack`), for the manifest distributed with the pack.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub sources: Vec<PackSource>,
+    pub cycles: usize,
+    pub derivations: u64,
+    pub merged: usize, // concepts merged away by consolidation
+    pub entries: usize, // concepts written to the pack
+    pub beliefs: usize, // entries with a belief (confidence above 0)
+}
+
+/// One input file of a pack build.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackSource {
+    pub path: String,
+    pub format: String, // `nal`, `rdf`, `csv` or `prolog`
+    pub statements: usize,
+    pub errors: usize, // lines skipped (`.nal` only; triple files must parse entirely)
+}
+
+impl PackManifest {
+    /// Writes the manifest as JSON.
+    pub fn write(&self, path: &str) -> Result<(), NarsError> {
+        let out = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(out, self).map_err(|e| NarsError::Serialization(e.to_string()))
+    }
+}
+
+/// Builds a knowledge pack at `path` from `sources`: `.nal` scripts (run as by the test
+/// runner), RDF N-Triples (`.nt`), CSV triples (`.csv`, see `parse_csv_triples`) and Prolog
+/// clauses (`.pl`, see `parse_horn_clauses`). Triples and clauses go straight into memory; then `cycles` reasoning cycles run, memory is consolidated and
+/// every concept is written to the pack.
+pub fn build_pack(system: &mut NarsSystem, sources: &[&str], cycles: usize, path: &str) -> Result<PackManifest, NarsError> {
+    let mut manifest = PackManifest { cycles, ..Default::default() };
+    for source in sources {
+        let extension = Path::new(source).extension().and_then(|e| e.to_str()).unwrap_or("");
+        let read_triples = |parse: fn(&str) -> Result<Vec<Triple>, NarsError>| -> Result<Vec<Sentence>, NarsError> {
+            Ok(parse(&std::fs::read_to_string(source)?)?.iter().map(Triple::to_sentence).collect())
+        };
+        let (format, statements, errors) = match extension {
+            "nal" => {
+                let report = run_script_file(system, source)?;
+                ("nal", report.inputs, report.parse_errors.len())
+            }
+            "nt" | "csv" => {
+                let sentences = read_triples(if extension == "nt" { parse_ntriples } else { parse_csv_triples })?;
+                system.input_batch_from(&sentences, true, Source::Import(format!("file:{}", source)));
+                (if extension == "nt" { "rdf" } else { "csv" }, sentences.len(), 0)
+            }
+            "pl" => {
+                let sentences = parse_horn_clauses(&std::fs::read_to_string(source)?)?;
+                system.input_batch_from(&sentences, true, Source::Import(format!("file:{}", source)));
+                ("prolog", sentences.len(), 0)
+            }
+            _ => return Err(NarsError::Serialization(format!("{}: unknown source format (expected .nal, .nt, .csv or .pl)", source))),
+        };
+        manifest.sources.push(PackSource { path: source.to_string(), format: format.to_string(), statements, errors });
+    }
+
+    let derivations = system.stats.derivations;
+    system.run_cycles(cycles);
+    manifest.derivations = system.stats.derivations - derivations;
+    manifest.merged = system.consolidate();
+    manifest.entries = system.export_pack(path)?;
+    manifest.beliefs = system.memory.values().filter(|c| c.truth.confidence > 0.0).count();
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::parser::parse_term;
+    use crate::nars::term::Operator;
+
+    #[test]
+    fn test_pack_round_trip() {
+        let concepts: Vec<Concept> = (0..50).map(|i| {
+            let term = Term::Compound(Operator::Inheritance, vec![Term::atom_from_str(&format!("w{}", i)), Term::atom_from_str("word")]);
+            Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.5 + i as f32 / 200.0), Stamp::new(0, vec![]))
+        }).collect();
+        let path = std::env::temp_dir().join(format!("nars_pack_{}.pack", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        assert_eq!(KnowledgePack::write(&path, &concepts).unwrap(), 50);
+
+        let pack = KnowledgePack::open(&path).unwrap();
+        assert_eq!(pack.len(), 50);
+        for concept in &concepts {
+            let idx = pack.find(&concept.term).expect("every term is found");
+            assert_eq!(pack.term(idx).as_ref(), Some(concept.term.term()));
+            assert_eq!(pack.truth(idx), concept.truth);
+            assert_eq!(pack.vector(idx), concept.vector);
+        }
+        assert!(pack.find(&Term::atom_from_str("missing")).is_none());
+        let (nearest, sim) = pack.nearest(&concepts[7].vector, 1)[0];
+        assert_eq!((pack.term(nearest).unwrap(), sim), (concepts[7].term.term().clone(), 1.0));
+
+        // Truncated files are rejected
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(matches!(KnowledgePack::open(&path), Err(NarsError::Serialization(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_build_pack() {
+        let dir = std::env::temp_dir();
+        let file = |name: &str| dir.join(format!("nars_build_{}_{}", std::process::id(), name)).to_string_lossy().into_owned();
+        let (nal, csv, pl, pack) = (file("kb.nal"), file("kb.csv"), file("kb.pl"), file("kb.pack"));
+        std::fs::write(&nal, "<cat --> animal>.\nnot narsese\n5\n").unwrap();
+        std::fs::write(&csv, "tiger,isa,cat\n").unwrap();
+        std::fs::write(&pl, "feline(tiger).\nmammal(X) :- feline(X).\n").unwrap();
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let manifest = build_pack(&mut system, &[&nal, &csv, &pl], 20, &pack).unwrap();
+        assert_eq!(manifest.sources.iter().map(|s| (s.format.as_str(), s.statements, s.errors)).collect::<Vec<_>>(),
+            [("nal", 1, 1), ("csv", 1, 0), ("prolog", 2, 0)]);
+        assert_eq!(manifest.cycles, 20);
+
+        let pack_file = KnowledgePack::open(&pack).unwrap();
+        assert_eq!(pack_file.len(), manifest.entries);
+        assert!(pack_file.find(&parse_term("<tiger --> cat>").unwrap().1).is_some());
+        assert!(build_pack(&mut system, &["kb.txt"], 0, &pack).is_err());
+        for path in [nal, csv, pl, pack] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
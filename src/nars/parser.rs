@@ -2,15 +2,32 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take_while, take_while1},
     character::complete::{char, digit1, multispace0, one_of},
-    combinator::{map, map_res, opt, recognize, value, all_consuming},
-    multi::separated_list0,
-    sequence::{delimited, pair, tuple, preceded},
+    combinator::{map_res, opt, recognize, value, all_consuming},
+    multi::{many1, separated_list0},
+    sequence::{delimited, pair, preceded},
     IResult,
     Parser,
 };
-use super::term::{Term, Operator, VarType};
-use super::sentence::{Sentence, Punctuation, Stamp};
+use super::term::{declared_operators, Term, Operator, VarType};
+use super::sentence::{Budget, Sentence, Punctuation, Stamp, Tense, TruthDefaults};
 use super::truth::TruthValue;
+use super::error::NarsError;
+
+// --- Options ---
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParserOptions {
+    /// Rewrite `{--`, `--]` and `{-]` into plain inheritance with sets.
+    pub desugar_copulas: bool,
+    /// Truth of sentences written without one; `NarsSystem::parse` uses the system's policy.
+    pub truth_defaults: TruthDefaults,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self { desugar_copulas: true, truth_defaults: TruthDefaults::default() }
+    }
+}
 
 // --- Helpers ---
 
@@ -18,9 +35,9 @@ fn is_alphanumeric_or_underscore(c: char) -> bool {
     c.is_alphanumeric() || c == '_' || c == '-' || c == '+'
 }
 
-fn ws<'a, F: 'a, O, E: nom::error::ParseError<&'a str>>(inner: F) -> impl Parser<&'a str, Output = O, Error = E>
+fn ws<'a, F, O, E: nom::error::ParseError<&'a str>>(inner: F) -> impl Parser<&'a str, Output = O, Error = E>
 where
-    F: Parser<&'a str, Output = O, Error = E>,
+    F: Parser<&'a str, Output = O, Error = E> + 'a,
 {
     delimited(multispace0, inner, multispace0)
 }
@@ -45,12 +62,58 @@ fn parse_truth_value(input: &str) -> IResult<&str, TruthValue> {
     Ok((input, TruthValue::new(frequency, confidence.unwrap_or(0.9))))
 }
 
+// --- Budget ---
+
+/// `$p;d;q$`; a missing durability or quality takes the default.
+fn parse_budget(input: &str) -> IResult<&str, Budget> {
+    let (input, _) = char('$')(input)?;
+    let (input, priority) = parse_float(input)?;
+    let (input, durability) = opt(preceded(char(';'), parse_float)).parse(input)?;
+    let (input, quality) = opt(preceded(char(';'), parse_float)).parse(input)?;
+    let (input, _) = char('$')(input)?;
+    let default = Budget::default();
+    Ok((input, Budget::new(priority, durability.unwrap_or(default.durability), quality.unwrap_or(default.quality))))
+}
+
 // --- Terms ---
 
 fn parse_atom(input: &str) -> IResult<&str, Term> {
-    map(take_while1(is_alphanumeric_or_underscore), |s: &str| {
-        Term::atom_from_str(s)
-    }).parse(input)
+    // `-` is a name character except where a copula starts, so `<a-->b>` reads as `<a --> b>`
+    let end = input.char_indices()
+        .find(|&(i, c)| !is_alphanumeric_or_underscore(c) || input[i..].starts_with("-->") || input[i..].starts_with("--]"))
+        .map_or(input.len(), |(i, _)| i);
+    if end == 0 {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::TakeWhile1)));
+    }
+    Ok((&input[end..], Term::atom_from_str(&input[..end])))
+}
+
+// Bare operation name, e.g. `^open` inside `(&/, door, ^open)`
+fn parse_operation_atom(input: &str) -> IResult<&str, Term> {
+    let (rest, _) = char('^')(input)?;
+    let (rest, name) = take_while1(is_alphanumeric_or_underscore)(rest)?;
+    Ok((rest, Term::atom_from_str(&format!("^{}", name))))
+}
+
+// `+N` inside a sequence, e.g. `(&/, a, +5, b)`
+fn parse_interval(input: &str) -> IResult<&str, Term> {
+    let (rest, _) = char('+')(input)?;
+    let (rest, digits) = digit1(rest)?;
+    // `+5x` is still an atom
+    if rest.starts_with(is_alphanumeric_or_underscore) {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Digit)));
+    }
+    let steps = digits.parse().map_err(|_| nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Digit)))?;
+    Ok((rest, Term::Interval(steps)))
+}
+
+fn parse_placeholder(input: &str) -> IResult<&str, Term> {
+    // A lone `_` marks the relation slot in an image term; `_foo` is still an atom
+    let (rest, _) = char('_')(input)?;
+    if rest.starts_with(is_alphanumeric_or_underscore) {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Char)));
+    }
+    Ok((rest, Term::Placeholder))
 }
 
 fn parse_variable(input: &str) -> IResult<&str, Term> {
@@ -73,7 +136,7 @@ fn parse_set_ext(input: &str) -> IResult<&str, Term> {
         separated_list0(ws(char(',')), parse_term),
         char('}')
     ).parse(input)?;
-    Ok((input, Term::Compound(Operator::ExtSet, args)))
+    Ok((input, Term::normalized_compound(Operator::ExtSet, args)))
 }
 
 fn parse_set_int(input: &str) -> IResult<&str, Term> {
@@ -82,7 +145,7 @@ fn parse_set_int(input: &str) -> IResult<&str, Term> {
         separated_list0(ws(char(',')), parse_term),
         char(']')
     ).parse(input)?;
-    Ok((input, Term::Compound(Operator::IntSet, args)))
+    Ok((input, Term::normalized_compound(Operator::IntSet, args)))
 }
 
 fn parse_copula(input: &str) -> IResult<&str, Operator> {
@@ -115,8 +178,9 @@ fn parse_term_operator(input: &str) -> IResult<&str, Operator> {
         value(Operator::IntIntersection, tag("&")),
         value(Operator::ExtImage, tag("/")),
         value(Operator::IntImage, tag("\\")),
-        value(Operator::Difference, tag("-")),
-        value(Operator::Difference, tag("~")),
+        value(Operator::DifferenceExt, tag("-")),
+        value(Operator::DifferenceInt, tag("~")),
+        value(Operator::Union, tag("+")),
         value(Operator::List, tag("#")),
     )).parse(input)
 }
@@ -127,31 +191,129 @@ fn parse_operation(input: &str) -> IResult<&str, Operator> {
     Ok((input, Operator::Other(format!("^{}", name))))
 }
 
+/// An operator declared with `declare_operator`, longest symbol first.
+fn parse_declared_operator(input: &str) -> IResult<&str, Operator> {
+    declared_operators().iter()
+        .find_map(|d| input.strip_prefix(d.symbol.as_str()).map(|rest| (rest, Operator::Other(d.symbol.clone()))))
+        .ok_or_else(|| nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)))
+}
+
+/// A built-in or declared operator, whichever is longer: a declared `-->>` is not read as
+/// `-->` followed by `>`, nor a declared `=` as the start of `==>`.
+fn parse_any_operator(input: &str) -> IResult<&str, Operator> {
+    let builtin = alt((parse_copula, parse_term_operator)).parse(input);
+    match (builtin, parse_declared_operator(input)) {
+        (Ok(builtin), Ok(declared)) => Ok(if declared.0.len() < builtin.0.len() { declared } else { builtin }),
+        (Ok(builtin), Err(_)) => Ok(builtin),
+        (Err(_), declared) => declared,
+    }
+}
+
 fn parse_prefix_compound(input: &str) -> IResult<&str, Term> {
     let (input, _) = char('(')(input)?;
     let (input, _) = multispace0(input)?;
-    let (input, op) = alt((parse_copula, parse_term_operator, parse_operation)).parse(input)?;
+    let (input, op) = alt((parse_any_operator, parse_operation)).parse(input)?;
     let (input, _) = multispace0(input)?;
     // Optional comma after operator
     let (input, _) = opt(char(',')).parse(input)?;
     let (input, _) = multispace0(input)?;
-    let (input, args) = separated_list0(ws(char(',')), parse_term).parse(input)?;
-    let (input, _) = multispace0(input)?;
+    let (rest, args) = separated_list0(ws(char(',')), parse_term).parse(input)?;
+    // Differences are binary: `(-, a, b, c)` has no reading; declared operators have their arity
+    let (min, max) = op.arity();
+    let declared = matches!(&op, Operator::Other(name) if !name.starts_with('^'));
+    if (is_difference(&op) && args.len() != 2) || (declared && (args.len() < min || max.is_some_and(|max| args.len() > max))) {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify)));
+    }
+    let (input, _) = multispace0(rest)?;
     let (input, _) = char(')')(input)?;
-    Ok((input, Term::Compound(op, args)))
+    Ok((input, Term::normalized_compound(op, args)))
+}
+
+fn is_difference(op: &Operator) -> bool {
+    matches!(op, Operator::DifferenceExt | Operator::DifferenceInt)
+}
+
+/// Binding strength of an operator written between its arguments; higher binds tighter, so
+/// `<a --> b && c --> d ==> e --> f>` reads as `<(&&, <a --> b>, <c --> d>) ==> <e --> f>>`.
+/// Negation, images, lists and operations only have the prefix form.
+fn infix_precedence(op: &Operator) -> Option<u8> {
+    match op {
+        Operator::Implication | Operator::Equivalence
+        | Operator::ConcurrentImplication | Operator::PredictiveImplication | Operator::RetrospectiveImplication
+        | Operator::ConcurrentEquivalence | Operator::PredictiveEquivalence | Operator::RetrospectiveEquivalence => Some(1),
+        Operator::Disjunction => Some(2),
+        Operator::Conjunction => Some(3),
+        Operator::ParallelEvents | Operator::SequentialEvents => Some(4),
+        Operator::Inheritance | Operator::Similarity | Operator::Instance | Operator::Property | Operator::InstanceProperty => Some(5),
+        Operator::Other(_) if op.is_statement() => Some(5),
+        Operator::ExtIntersection | Operator::IntIntersection | Operator::Union => Some(6),
+        Operator::DifferenceExt | Operator::DifferenceInt => Some(7),
+        Operator::Product => Some(8),
+        _ => None,
+    }
+}
+
+/// Operators whose infix chains collect into one compound: `(a && b && c)` is `(&&, a, b, c)`.
+/// Chaining any other operator (`<a --> b --> c>`) is rejected as ambiguous.
+fn is_infix_chainable(op: &Operator) -> bool {
+    matches!(op, Operator::Conjunction | Operator::Disjunction | Operator::ParallelEvents | Operator::SequentialEvents
+        | Operator::ExtIntersection | Operator::IntIntersection | Operator::Union | Operator::Product)
+}
+
+fn parse_infix_operator(input: &str) -> IResult<&str, Operator> {
+    let (rest, op) = parse_any_operator(input)?;
+    if infix_precedence(&op).is_none() {
+        return Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)));
+    }
+    Ok((rest, op))
+}
+
+/// Splits `args[0] ops[0] args[1] ...` at its loosest operator and builds the parts recursively.
+/// Returns `None` for chains that mix operators of equal precedence or chain a binary one.
+fn build_infix(args: Vec<Term>, ops: Vec<Operator>) -> Option<Term> {
+    let Some(loosest) = ops.iter().filter_map(infix_precedence).min() else {
+        return args.into_iter().next();
+    };
+    let split: Vec<&Operator> = ops.iter().filter(|op| infix_precedence(op) == Some(loosest)).collect();
+    let op = split[0].clone();
+    if split.iter().any(|other| **other != op) || (split.len() > 1 && !is_infix_chainable(&op)) {
+        return None;
+    }
+
+    let mut args = args.into_iter();
+    let mut parts = Vec::new();
+    let (mut part_args, mut part_ops) = (vec![args.next()?], Vec::new());
+    for (next_op, arg) in ops.into_iter().zip(args) {
+        if infix_precedence(&next_op) == Some(loosest) {
+            parts.push(build_infix(std::mem::take(&mut part_args), std::mem::take(&mut part_ops))?);
+        } else {
+            part_ops.push(next_op);
+        }
+        part_args.push(arg);
+    }
+    parts.push(build_infix(part_args, part_ops)?);
+    Some(Term::Compound(op, parts))
+}
+
+/// One or more terms joined by infix operators, e.g. `a --> b` or `<a --> b> && (c | d)`.
+fn parse_infix_chain(input: &str) -> IResult<&str, Term> {
+    let (rest, first) = parse_term(input)?;
+    let (rest, tail) = many1(pair(ws(parse_infix_operator), parse_term)).parse(rest)?;
+    let (ops, mut args): (Vec<Operator>, Vec<Term>) = tail.into_iter().unzip();
+    args.insert(0, first);
+    match build_infix(args, ops) {
+        Some(term) => Ok((rest, term)),
+        None => Err(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify))),
+    }
 }
 
 fn parse_infix_compound(input: &str) -> IResult<&str, Term> {
-    let (input, _) = char('<')(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, left) = parse_term(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, op) = alt((parse_copula, parse_term_operator)).parse(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, right) = parse_term(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, _) = char('>')(input)?;
-    Ok((input, Term::Compound(op, vec![left, right])))
+    delimited(char('<'), parse_infix_chain, char('>')).parse(input)
+}
+
+/// Infix chain in parentheses, e.g. `(a * b)` or `(<a --> b> ==> <c --> d>)`.
+fn parse_grouped_compound(input: &str) -> IResult<&str, Term> {
+    delimited(char('('), parse_infix_chain, char(')')).parse(input)
 }
 
 fn parse_term_recursive(input: &str) -> IResult<&str, Term> {
@@ -159,8 +321,12 @@ fn parse_term_recursive(input: &str) -> IResult<&str, Term> {
         parse_set_ext,
         parse_set_int,
         parse_prefix_compound,
+        parse_grouped_compound,
         parse_infix_compound,
         parse_variable,
+        parse_placeholder,
+        parse_operation_atom,
+        parse_interval,
         parse_atom,
     )).parse(input)
 }
@@ -180,48 +346,228 @@ fn parse_punctuation(input: &str) -> IResult<&str, Punctuation> {
     )).parse(input)
 }
 
-fn parse_tense(input: &str) -> IResult<&str, &str> {
+/// Other `:...:` markers (occurrence offsets) are read as present.
+fn parse_tense(input: &str) -> IResult<&str, Tense> {
     alt((
-        tag(":|:"),
-        tag(":/:"),
-        tag(":\\:"),
-        recognize(delimited(char(':'), take_while1(|c| c != ':'), char(':'))),
+        value(Tense::Present, tag(":|:")),
+        value(Tense::Future, tag(":/:")),
+        value(Tense::Past, tag(":\\:")),
+        value(Tense::Present, recognize(delimited(char(':'), take_while1(|c| c != ':'), char(':')))),
     )).parse(input)
 }
 
-pub fn parse_narsese(input: &str) -> Result<Sentence, String> {
-    let parser = tuple((
+// --- Normalization ---
+
+/// Converts instance/property copulas into inheritance:
+/// `<a {-- B>` to `<{a} --> B>`, `<A --] b>` to `<A --> [b]>`, `<a {-] b>` to `<{a} --> [b]>`.
+pub fn desugar_copulas(term: &Term) -> Term {
+    match term {
+        Term::Compound(op, args) => {
+            let args: Vec<Term> = args.iter().map(desugar_copulas).collect();
+            if args.len() != 2 {
+                return Term::Compound(op.clone(), args);
+            }
+            let ext = |t: &Term| Term::Compound(Operator::ExtSet, vec![t.clone()]);
+            let int = |t: &Term| Term::Compound(Operator::IntSet, vec![t.clone()]);
+            match op {
+                Operator::Instance => Term::Compound(Operator::Inheritance, vec![ext(&args[0]), args[1].clone()]),
+                Operator::Property => Term::Compound(Operator::Inheritance, vec![args[0].clone(), int(&args[1])]),
+                Operator::InstanceProperty => Term::Compound(Operator::Inheritance, vec![ext(&args[0]), int(&args[1])]),
+                _ => Term::Compound(op.clone(), args),
+            }
+        }
+        _ => term.clone(),
+    }
+}
+
+pub fn parse_narsese(input: &str) -> Result<Sentence, NarsError> {
+    parse_narsese_with(input, &ParserOptions::default())
+}
+
+/// Parses one sentence; a trailing `//` comment is ignored.
+pub fn parse_narsese_with(input: &str, options: &ParserOptions) -> Result<Sentence, NarsError> {
+    let text = strip_comment(input);
+    let (_, parts) = all_consuming(ws(parse_sentence)).parse(text)
+        .map_err(|e| sentence_error(text, e))?;
+    Ok(build_sentence(parts, options))
+}
+
+pub fn parse_narsese_all(input: &str) -> Result<Vec<Sentence>, NarsError> {
+    parse_narsese_all_with(input, &ParserOptions::default())
+}
+
+/// Parses a knowledge snippet: sentences separated by newlines or whitespace, e.g.
+/// `<a --> b>. <b --> c>.` on one line. `//` starts a comment running to the end of the line,
+/// and lines starting with `'` are comments. Fails on the first sentence that does not parse.
+pub fn parse_narsese_all_with(input: &str, options: &ParserOptions) -> Result<Vec<Sentence>, NarsError> {
+    let mut sentences = Vec::new();
+    for line in input.lines() {
+        // Offsets of errors are counted from the start of the line, as written
+        let text = strip_comment(line).trim_end();
+        let mut rest = text;
+        while !rest.trim_start().is_empty() {
+            let (next, parts) = ws(parse_sentence).parse(rest).map_err(|e| sentence_error(text, e))?;
+            sentences.push(build_sentence(parts, options));
+            rest = next;
+        }
+    }
+    Ok(sentences)
+}
+
+/// The Narsese part of a line: a `'` comment line is empty, and `//` ends the line.
+fn strip_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('\'') {
+        return "";
+    }
+    line.split("//").next().unwrap_or("")
+}
+
+fn sentence_error(input: &str, e: nom::Err<nom::error::Error<&str>>) -> NarsError {
+    match e {
+        nom::Err::Error(err) | nom::Err::Failure(err) => NarsError::parse(input, err.input, format!("{:?}", err.code)),
+        nom::Err::Incomplete(_) => NarsError::parse(input, "", "incomplete input"),
+    }
+}
+
+type SentenceParts = (Option<Budget>, Option<Tense>, Term, Punctuation, Option<Tense>, Option<TruthValue>);
+
+fn parse_sentence(input: &str) -> IResult<&str, SentenceParts> {
+    (
+        opt(ws(parse_budget)),
         opt(ws(parse_tense)),
         parse_term,
         ws(parse_punctuation),
         opt(ws(parse_tense)), // Tense can be after punctuation too
         opt(ws(parse_truth_value)),
-    ));
+    ).parse(input)
+}
 
-    let (_, (tense1, term, punctuation, tense2, truth_opt)) = all_consuming(ws(parser)).parse(input)
-        .map_err(|e| format!("Parse error: {}", e))?;
+fn build_sentence((budget, tense1, term, punctuation, tense2, truth_opt): SentenceParts, options: &ParserOptions) -> Sentence {
+    let term = if options.desugar_copulas { desugar_copulas(&term) } else { term };
 
     // Default truth value if not present
-    let truth = truth_opt.unwrap_or_else(|| {
-        match punctuation {
-            Punctuation::Judgement => TruthValue::new(1.0, 0.9),
-            Punctuation::Goal => TruthValue::new(1.0, 0.9),
-            Punctuation::Question => TruthValue::new(0.0, 0.0),
-            Punctuation::Quest => TruthValue::new(0.0, 0.0),
-        }
-    });
+    let tense = tense1.or(tense2);
+    let truth = truth_opt.unwrap_or_else(|| options.truth_defaults.for_input(punctuation, tense.is_some()));
 
+    // Unstamped: NarsSystem::input assigns the evidential base and creation time
     let stamp = Stamp {
         creation_time: 0,
         evidence: vec![],
+        sources: vec![],
     };
 
-    Ok(Sentence::new(term, punctuation, truth, stamp))
+    let mut sentence = Sentence::new(term, punctuation, truth, stamp);
+    sentence.tense = tense;
+    match budget {
+        Some(budget) => sentence.with_budget(budget),
+        None => sentence,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    fn arb_statement_op() -> impl Strategy<Value = Operator> {
+        prop_oneof![
+            Just(Operator::Inheritance),
+            Just(Operator::Implication),
+            Just(Operator::Similarity),
+            Just(Operator::Equivalence),
+            Just(Operator::Instance),
+            Just(Operator::Property),
+            Just(Operator::InstanceProperty),
+            Just(Operator::ConcurrentImplication),
+            Just(Operator::PredictiveImplication),
+            Just(Operator::RetrospectiveImplication),
+            Just(Operator::ConcurrentEquivalence),
+            Just(Operator::PredictiveEquivalence),
+            Just(Operator::RetrospectiveEquivalence),
+        ]
+    }
+
+    fn arb_compound_op() -> impl Strategy<Value = Operator> {
+        prop_oneof![
+            Just(Operator::Product),
+            Just(Operator::ExtIntersection),
+            Just(Operator::IntIntersection),
+            Just(Operator::Union),
+            Just(Operator::ExtSet),
+            Just(Operator::IntSet),
+            Just(Operator::Negation),
+            Just(Operator::Conjunction),
+            Just(Operator::Disjunction),
+            Just(Operator::ExtImage),
+            Just(Operator::IntImage),
+            Just(Operator::ParallelEvents),
+            Just(Operator::SequentialEvents),
+            Just(Operator::List),
+            "[a-z][a-z0-9]{0,4}".prop_map(|name| Operator::Other(format!("^{}", name))),
+        ]
+    }
+
+    fn arb_term() -> impl Strategy<Value = Term> {
+        let var_type = prop_oneof![Just(VarType::Independent), Just(VarType::Dependent), Just(VarType::Query)];
+        let leaf = prop_oneof![
+            "[a-zA-Z][a-zA-Z0-9_]{0,5}".prop_map(Term::Atom),
+            "-?[0-9]{1,3}".prop_map(Term::Atom),
+            (var_type, "[a-z][a-z0-9]{0,3}").prop_map(|(t, name)| Term::Var(t, name)),
+            Just(Term::Placeholder),
+            (0u64..1000).prop_map(Term::Interval),
+        ];
+        leaf.prop_recursive(4, 32, 4, |inner| {
+            prop_oneof![
+                (arb_statement_op(), inner.clone(), inner.clone()).prop_map(|(op, s, p)| Term::Compound(op, vec![s, p])),
+                (prop_oneof![Just(Operator::DifferenceExt), Just(Operator::DifferenceInt)], inner.clone(), inner.clone())
+                    .prop_map(|(op, a, b)| Term::Compound(op, vec![a, b])),
+                (arb_compound_op(), prop::collection::vec(inner, 1..4)).prop_map(|(op, args)| Term::Compound(op, args)),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_display_round_trip(term in arb_term()) {
+            let printed = term.to_string();
+            let parsed = all_consuming(parse_term).parse(printed.as_str());
+            prop_assert!(parsed.is_ok(), "failed to re-parse {}", printed);
+            prop_assert_eq!(parsed.unwrap().1, term.with_commutative_order(), "round trip mismatch for {}", printed);
+        }
+    }
+
+    #[test]
+    fn test_intervals() {
+        use crate::nars::term::approximate_interval;
+
+        let (_, term) = parse_term("(&/, a, +3, +2, b)").unwrap();
+        let Term::Compound(Operator::SequentialEvents, parts) = &term else { panic!("not a sequence") };
+        assert_eq!(parts[1], Term::Interval(3));
+        assert_eq!(term.interval_sum(), 5);
+        assert_eq!(Term::sequence(parts.clone()).to_string(), "(&/, a, +5, b)");
+        assert_eq!(parse_term("+5x").unwrap().1, Term::atom_from_str("+5x"));
+
+        assert_eq!(approximate_interval(3), 3);
+        assert_eq!(approximate_interval(10), approximate_interval(12));
+        assert_ne!(approximate_interval(6), approximate_interval(12));
+    }
+
+    #[test]
+    fn test_complexity_and_depth() {
+        let (_, atom) = parse_term("bird").unwrap();
+        assert_eq!((atom.complexity(), atom.depth()), (1, 0));
+        let (_, term) = parse_term("<(&, a, b) --> {c}>").unwrap();
+        assert_eq!(term.complexity(), 6);
+        assert_eq!(term.depth(), 2);
+    }
+
+    #[test]
+    fn test_display_examples() {
+        for text in ["<bird --> animal>", "(/, R, _, b)", "{a, b}", "(--, <$x --> [yellow]>)", "<(*, a, b) =/> #y>", "<(&/, door, ^open) =/> inside>", "<(&/, a, +5, b) =/> c>"] {
+            let (_, term) = parse_term(text).unwrap();
+            assert_eq!(term.to_string(), text);
+        }
+    }
 
     #[test]
     fn test_variables() {
@@ -241,8 +587,184 @@ mod tests {
         assert!(parse_narsese("<<$x --> S> ==> <$x --> P>>.").is_ok());
     }
 
+    #[test]
+    fn test_image_placeholder() {
+        let (_, ext) = parse_term("(/,R,_,b)").unwrap();
+        assert_eq!(ext, Term::Compound(Operator::ExtImage, vec![
+            Term::atom_from_str("R"),
+            Term::Placeholder,
+            Term::atom_from_str("b"),
+        ]));
+        assert_eq!(ext.placeholder_index(), Some(1));
+
+        let (_, int) = parse_term("(\\,R,a,_)").unwrap();
+        assert_eq!(int.placeholder_index(), Some(2));
+
+        // Underscore-prefixed names remain atoms
+        assert_eq!(parse_term("_a").unwrap().1, Term::atom_from_str("_a"));
+
+        assert!(parse_narsese("<(/,REPRESENT,_,CAT) --> cat>.").is_ok());
+    }
+
+    #[test]
+    fn test_copula_desugaring() {
+        let tweety = Term::atom_from_str("Tweety");
+        let bird = Term::atom_from_str("bird");
+        let yellow = Term::atom_from_str("yellow");
+        let ext = |t: &Term| Term::Compound(Operator::ExtSet, vec![t.clone()]);
+        let int = |t: &Term| Term::Compound(Operator::IntSet, vec![t.clone()]);
+
+        let s = parse_narsese("<Tweety {-- bird>.").unwrap();
+        assert_eq!(s.term, Term::Compound(Operator::Inheritance, vec![ext(&tweety), bird.clone()]));
+
+        let s = parse_narsese("<bird --] yellow>.").unwrap();
+        assert_eq!(s.term, Term::Compound(Operator::Inheritance, vec![bird.clone(), int(&yellow)]));
+
+        let s = parse_narsese("<Tweety {-] yellow>.").unwrap();
+        assert_eq!(s.term, Term::Compound(Operator::Inheritance, vec![ext(&tweety), int(&yellow)]));
+
+        // Nested statements are rewritten too
+        let s = parse_narsese("<<$x {-- bird> ==> <$x --> animal>>.").unwrap();
+        assert!(matches!(&s.term, Term::Compound(Operator::Implication, args)
+            if matches!(&args[0], Term::Compound(Operator::Inheritance, _))));
+
+        // Raw forms are kept on request
+        let raw = ParserOptions { desugar_copulas: false, ..ParserOptions::default() };
+        let s = parse_narsese_with("<Tweety {-- bird>.", &raw).unwrap();
+        assert_eq!(s.term, Term::Compound(Operator::Instance, vec![tweety, bird]));
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        match parse_narsese("<bird --> animal> %") {
+            Err(NarsError::Parse { offset, .. }) => assert_eq!(offset, 18),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+        // Comments and indentation do not shift the position
+        for (text, expected) in [("<bird --> animal> % // note", 18), ("<a --> b>.\n  <bird --> animal> % // note", 20)] {
+            match parse_narsese_all(text) {
+                Err(NarsError::Parse { offset, .. }) => assert_eq!(offset, expected, "{}", text),
+                other => panic!("expected parse error, got {:?}", other),
+            }
+        }
+        match parse_narsese("<bird --> animal> % // note") {
+            Err(NarsError::Parse { offset, .. }) => assert_eq!(offset, 18),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_term_indices() {
         assert!(parse_term("key_101").is_ok());
     }
+
+    #[test]
+    fn test_nested_statement_forms() {
+        let parse = |text: &str| all_consuming(parse_term).parse(text).map(|(_, term)| term)
+            .unwrap_or_else(|e| panic!("failed to parse {}: {:?}", text, e));
+        let atom = Term::atom_from_str;
+        let compound = |op, args: Vec<Term>| Term::Compound(op, args);
+        let ab = compound(Operator::Inheritance, vec![atom("a"), atom("b")]);
+        let cd = compound(Operator::Inheritance, vec![atom("c"), atom("d")]);
+        let rule = compound(Operator::Implication, vec![ab.clone(), cd.clone()]);
+
+        // Angle brackets, prefix and parenthesized infix notation, with or without spaces
+        for text in [
+            "<<a --> b> ==> <c --> d>>",
+            "<<a-->b>==><c-->d>>",
+            "(==>, <a --> b>, <c --> d>)",
+            "(==>,<a-->b>,<c-->d>)",
+            "(==>, (-->, a, b), (-->, c, d))",
+            "(<a --> b> ==> (c --> d))",
+            "((a --> b) ==> (c --> d))",
+            "<a --> b ==> c --> d>",
+            "(a-->b ==> c-->d)",
+        ] {
+            assert_eq!(parse(text), rule, "{}", text);
+        }
+
+        // Precedence: product over intersection over copula over conjunction over implication
+        assert_eq!(parse("<a * b --> c>"), compound(Operator::Inheritance, vec![
+            compound(Operator::Product, vec![atom("a"), atom("b")]), atom("c")]));
+        assert_eq!(parse("<a | b * c --> d>"), compound(Operator::Inheritance, vec![
+            compound(Operator::ExtIntersection, vec![atom("a"), compound(Operator::Product, vec![atom("b"), atom("c")])]),
+            atom("d")]));
+        assert_eq!(parse("<a --> b && c --> d ==> e>"), compound(Operator::Implication, vec![
+            compound(Operator::Conjunction, vec![ab.clone(), cd.clone()]), atom("e")]));
+        assert_eq!(parse("(a && b && c)"), compound(Operator::Conjunction, vec![atom("a"), atom("b"), atom("c")]));
+        assert_eq!(parse("(a && (b || c))"), compound(Operator::Conjunction, vec![
+            atom("a"), compound(Operator::Disjunction, vec![atom("b"), atom("c")])]));
+        assert_eq!(parse("<a - b --> c>"), compound(Operator::Inheritance, vec![
+            compound(Operator::DifferenceExt, vec![atom("a"), atom("b")]), atom("c")]));
+        assert_eq!(parse("(a &/ +5 &/ ^go)"), compound(Operator::SequentialEvents, vec![atom("a"), Term::Interval(5), atom("^go")]));
+
+        // Statements and sets nest inside any compound
+        assert_eq!(parse("{<a --> b>, (c --> d)}"), compound(Operator::ExtSet, vec![ab.clone(), cd.clone()]));
+        assert_eq!(parse("(--, (a --> b))"), compound(Operator::Negation, vec![ab.clone()]));
+        assert_eq!(parse("<[x] --> {a, b}>"), compound(Operator::Inheritance, vec![
+            compound(Operator::IntSet, vec![atom("x")]), compound(Operator::ExtSet, vec![atom("a"), atom("b")])]));
+        assert_eq!(parse("<(*, <a --> b>, c) --> (/, R, _, d)>").to_string(), "<(*, <a --> b>, c) --> (/, R, _, d)>");
+
+        // Hyphenated names are still atoms
+        assert_eq!(parse("<well-known --> a-b>"), compound(Operator::Inheritance, vec![atom("well-known"), atom("a-b")]));
+        // A leading `-` that does not make a binary difference leaves the grouped infix form
+        assert_eq!(parse("(-1 --> x)"), compound(Operator::Inheritance, vec![atom("-1"), atom("x")]));
+        assert_eq!(parse_narsese("(-1 --> x).").unwrap().term, parse_narsese("<-1 --> x>.").unwrap().term);
+
+        // Ambiguous or incomplete forms are rejected
+        for text in ["<a --> b --> c>", "(a && b || c && d ==> e <=> f)", "(a &| b &/ c)", "(a)", "<a>", "<a -->>", "(a - b - c)", "(-, a, b, c)", "(~, a)"] {
+            assert!(all_consuming(parse_term).parse(text).is_err(), "{} should not parse", text);
+        }
+        assert!(parse_narsese("<a-->b> && <c-->d>.").is_err());
+        assert!(parse_narsese("(<a --> b> && <c --> d>)!").is_ok());
+    }
+
+    #[test]
+    fn test_truth_defaults() {
+        let defaults = TruthDefaults {
+            tensed_judgement: TruthValue::new(1.0, 0.8),
+            goal: TruthValue::new(0.9, 0.7),
+            ..TruthDefaults::default()
+        };
+        let options = ParserOptions { truth_defaults: defaults, ..ParserOptions::default() };
+        let truth = |text: &str| parse_narsese_with(text, &options).unwrap().truth;
+        assert_eq!(truth("<a --> b>."), TruthValue::new(1.0, 0.9));
+        assert_eq!(truth("<a --> b>. :|:"), TruthValue::new(1.0, 0.8));
+        assert_eq!(truth(":|: <a --> b>."), TruthValue::new(1.0, 0.8));
+        assert_eq!(truth("<a --> b>!"), TruthValue::new(0.9, 0.7));
+        assert_eq!(truth("<a --> b>! :|:"), TruthValue::new(1.0, 0.9));
+        assert_eq!(truth("<a --> b>?"), TruthValue::new(0.0, 0.0));
+        // An explicit truth value always wins
+        assert_eq!(truth("<a --> b>. :|: %0.3;0.4%"), TruthValue::new(0.3, 0.4));
+    }
+
+    #[test]
+    fn test_budget_prefix() {
+        let sentence = parse_narsese("$0.80;0.50;0.95$ <a --> b>. %1.0;0.9%").unwrap();
+        assert_eq!(sentence.budget, Some(Budget::new(0.8, 0.5, 0.95)));
+        assert_eq!(sentence.to_narsese(), "$0.80;0.50;0.95$ <a --> b>. %1.00;0.90%");
+        assert_eq!(parse_narsese("$0.9$ <a --> b>?").unwrap().budget, Some(Budget::new(0.9, 0.5, 0.0)));
+        assert_eq!(parse_narsese("$0.9;0.2$ :|: <a --> b>.").unwrap().budget, Some(Budget::new(0.9, 0.2, 0.0)));
+        assert_eq!(parse_narsese("<a --> b>.").unwrap().budget, None);
+        // An independent variable is not a budget
+        assert_eq!(parse_narsese("<$x --> b>.").unwrap().budget, None);
+        assert!(parse_narsese("$0.8;0.5 <a --> b>.").is_err());
+    }
+
+    #[test]
+    fn test_parse_all_with_comments() {
+        let snippet = "' birds\n<robin --> bird>. %1.0;0.9% // observed\n\n<bird --> animal>. <bird --> [flying]>. :|:\n<robin --> animal>? // and?\n";
+        let sentences = parse_narsese_all(snippet).unwrap();
+        let texts: Vec<String> = sentences.iter().map(|s| s.to_narsese()).collect();
+        assert_eq!(texts, ["<robin --> bird>. %1.00;0.90%", "<bird --> animal>. %1.00;0.90%", "<bird --> [flying]>. :|: %1.00;0.90%", "<robin --> animal>?"]);
+        assert!(parse_narsese_all("// nothing\n").unwrap().is_empty());
+
+        assert_eq!(parse_narsese("<a --> b>. // trailing").unwrap().term.to_string(), "<a --> b>");
+        assert!(parse_narsese("<a --> b>. <b --> c>.").is_err());
+        // The failing line is reported
+        match parse_narsese_all("<a --> b>.\n<b --> c> <c --> d>.") {
+            Err(NarsError::Parse { input, .. }) => assert_eq!(input, "<b --> c> <c --> d>."),
+            other => panic!("{:?}", other),
+        }
+    }
 }
@@ -0,0 +1,743 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use super::memory::ConceptStore;
+use super::error::NarsError;
+
+const MAGIC: &[u8; 8] = b"NARSSNAP";
+const HEADER_LEN: usize = 40;
+
+/// Layout version of the memory snapshots written by `save_memory`. Bump it whenever the
+/// serialized form of `ConceptStore` (concepts, stamps, sentences...) changes, and append the
+/// migration from the previous version to `MIGRATIONS`.
+pub const SNAPSHOT_VERSION: u32 = 6;
+
+/// Rewrites a payload of one version into the layout of the next. A migration decodes with a
+/// frozen copy of the old types and re-encodes with the new ones.
+type Migration = fn(Vec<u8>) -> Result<Vec<u8>, NarsError>;
+
+/// `MIGRATIONS[v]` upgrades a version `v` payload to version `v + 1`.
+const MIGRATIONS: [Migration; SNAPSHOT_VERSION as usize] = [
+    // 0 -> 1: version 0 is the bare bincode store saved before snapshots had a header
+    v0::migrate,
+    // 1 -> 2: concepts gained usage counters (`last_used_cycle`, `use_count`)
+    v1::migrate,
+    // 2 -> 3: learning counters are no longer saved
+    v2::migrate,
+    // 3 -> 4: usage counters moved from the concepts to a table of the store
+    v3::migrate,
+    // 4 -> 5: concepts gained the `grounded` flag
+    v4::migrate,
+    // 5 -> 6: origins that the term gives back are no longer saved
+    v5::migrate,
+];
+
+/// Layout of version 0 snapshots: concepts without attention, desire or learning state,
+/// stamps without sources and sentences without tense. Terms, truth values and vectors
+/// are laid out as in version 1.
+mod v0 {
+    use std::collections::HashMap;
+    use serde::Deserialize;
+    use crate::nars::error::NarsError;
+    use super::v1::{self, Hypervector, Punctuation, Term, TruthValue};
+
+    #[derive(Deserialize)]
+    struct Stamp {
+        creation_time: u64,
+        evidence: Vec<u64>,
+    }
+
+    #[derive(Deserialize)]
+    struct Sentence {
+        term: Term,
+        punctuation: Punctuation,
+        truth: TruthValue,
+        stamp: Stamp,
+    }
+
+    #[derive(Deserialize)]
+    struct Concept {
+        term: Term,
+        vector: Hypervector,
+        priority: f32,
+        durability: f32,
+        truth: TruthValue,
+        stamp: Stamp,
+        beliefs: Vec<Sentence>,
+    }
+
+    #[derive(Deserialize)]
+    struct Store {
+        map: HashMap<Term, Concept>,
+        capacity: usize,
+    }
+
+    impl From<Stamp> for v1::Stamp {
+        fn from(stamp: Stamp) -> Self {
+            v1::Stamp { creation_time: stamp.creation_time, evidence: stamp.evidence, sources: Vec::new() }
+        }
+    }
+
+    impl From<Sentence> for v1::Sentence {
+        fn from(s: Sentence) -> Self {
+            v1::Sentence { term: s.term, punctuation: s.punctuation, truth: s.truth, stamp: s.stamp.into(), tense: None }
+        }
+    }
+
+    /// Concepts get the defaults of new ones: their vector as origin, an empty history and
+    /// no counters, questions or goals.
+    pub(super) fn migrate(payload: Vec<u8>) -> Result<Vec<u8>, NarsError> {
+        let old: Store = bincode::deserialize(&payload)?;
+        let map = old.map.into_iter().map(|(term, c)| (term, v1::Concept {
+            term: c.term, vector: c.vector, priority: c.priority, durability: c.durability, truth: c.truth,
+            stamp: c.stamp.into(), beliefs: c.beliefs.into_iter().map(Into::into).collect(),
+            belief_capacity: v1::DEFAULT_BELIEF_CAPACITY, questions: Vec::new(), goals: Vec::new(), origin: c.vector,
+            update_count: 0, history: Vec::new(), counters: None, quality: 0.0,
+        })).collect();
+        Ok(bincode::serialize(&v1::Store { map, capacity: old.capacity, pinned: Default::default() })?)
+    }
+}
+
+/// Layout of version 1 snapshots. These types are frozen copies: when a live type changes,
+/// the snapshot version is bumped and this layout stays readable.
+mod v1 {
+    use std::collections::{HashMap, HashSet};
+    use serde::{Deserialize, Serialize};
+    use serde_big_array::BigArray;
+    use crate::nars::error::NarsError;
+    use crate::nars::memory::{self, HV_DIM_U64};
+    use crate::nars::sentence;
+    use crate::nars::term::{self, Operator as Op};
+    use crate::nars::truth;
+    use super::v2;
+
+    pub(super) const DEFAULT_BELIEF_CAPACITY: usize = 100;
+
+    #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+    pub(super) enum VarType { Independent, Dependent, Query }
+
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+    pub(super) enum Operator {
+        Inheritance, Implication, Similarity, Equivalence, Instance, Property, InstanceProperty,
+        Product, ExtIntersection, IntIntersection, DifferenceExt, DifferenceInt, Union, ExtSet,
+        IntSet, Negation, Conjunction, Disjunction, ExtImage, IntImage, ConcurrentImplication,
+        PredictiveImplication, RetrospectiveImplication, ConcurrentEquivalence, PredictiveEquivalence,
+        RetrospectiveEquivalence, ParallelEvents, SequentialEvents, List, Op, Other(String),
+    }
+
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+    pub(super) enum Term {
+        Atom(String),
+        Var(VarType, String),
+        Compound(Operator, Vec<Term>),
+        Placeholder,
+        Interval(u64),
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Copy)]
+    pub(super) struct TruthValue {
+        frequency: f32,
+        confidence: f32,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Copy)]
+    pub(super) struct Hypervector {
+        #[serde(with = "BigArray")]
+        bits: [u64; 157],
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Copy)]
+    pub(super) enum Punctuation { Judgement, Question, Goal, Quest }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) enum Tense { Past, Present, Future }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) enum Source {
+        User,
+        Sensor(String),
+        Import(String),
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Stamp {
+        pub(super) creation_time: u64,
+        pub(super) evidence: Vec<u64>,
+        pub(super) sources: Vec<Source>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Sentence {
+        pub(super) term: Term,
+        pub(super) punctuation: Punctuation,
+        pub(super) truth: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) tense: Option<Tense>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Revision {
+        pub(super) previous: TruthValue,
+        pub(super) revised: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) time: u64,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct CounterVector {
+        counts: Vec<i16>,
+        saturation: i16,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Concept {
+        pub(super) term: Term,
+        pub(super) vector: Hypervector,
+        pub(super) priority: f32,
+        pub(super) durability: f32,
+        pub(super) truth: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) beliefs: Vec<Sentence>,
+        pub(super) belief_capacity: usize,
+        pub(super) questions: Vec<Sentence>,
+        pub(super) goals: Vec<Sentence>,
+        pub(super) origin: Hypervector,
+        pub(super) update_count: u32,
+        pub(super) history: Vec<Revision>,
+        pub(super) counters: Option<CounterVector>,
+        pub(super) quality: f32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Store {
+        pub(super) map: HashMap<Term, Concept>,
+        pub(super) capacity: usize,
+        pub(super) pinned: HashSet<Term>,
+    }
+
+    impl From<Operator> for Op {
+        fn from(op: Operator) -> Self {
+            match op {
+                Operator::Inheritance => Op::Inheritance,
+                Operator::Implication => Op::Implication,
+                Operator::Similarity => Op::Similarity,
+                Operator::Equivalence => Op::Equivalence,
+                Operator::Instance => Op::Instance,
+                Operator::Property => Op::Property,
+                Operator::InstanceProperty => Op::InstanceProperty,
+                Operator::Product => Op::Product,
+                Operator::ExtIntersection => Op::ExtIntersection,
+                Operator::IntIntersection => Op::IntIntersection,
+                Operator::DifferenceExt => Op::DifferenceExt,
+                Operator::DifferenceInt => Op::DifferenceInt,
+                Operator::Union => Op::Union,
+                Operator::ExtSet => Op::ExtSet,
+                Operator::IntSet => Op::IntSet,
+                Operator::Negation => Op::Negation,
+                Operator::Conjunction => Op::Conjunction,
+                Operator::Disjunction => Op::Disjunction,
+                Operator::ExtImage => Op::ExtImage,
+                Operator::IntImage => Op::IntImage,
+                Operator::ConcurrentImplication => Op::ConcurrentImplication,
+                Operator::PredictiveImplication => Op::PredictiveImplication,
+                Operator::RetrospectiveImplication => Op::RetrospectiveImplication,
+                Operator::ConcurrentEquivalence => Op::ConcurrentEquivalence,
+                Operator::PredictiveEquivalence => Op::PredictiveEquivalence,
+                Operator::RetrospectiveEquivalence => Op::RetrospectiveEquivalence,
+                Operator::ParallelEvents => Op::ParallelEvents,
+                Operator::SequentialEvents => Op::SequentialEvents,
+                Operator::List => Op::List,
+                Operator::Op => Op::Op,
+                Operator::Other(symbol) => Op::Other(symbol),
+            }
+        }
+    }
+
+    impl From<Term> for term::Term {
+        fn from(t: Term) -> Self {
+            match t {
+                Term::Atom(name) => term::Term::Atom(name),
+                Term::Var(kind, name) => term::Term::Var(match kind {
+                    VarType::Independent => term::VarType::Independent,
+                    VarType::Dependent => term::VarType::Dependent,
+                    VarType::Query => term::VarType::Query,
+                }, name),
+                Term::Compound(op, args) => term::Term::Compound(op.into(), args.into_iter().map(Into::into).collect()),
+                Term::Placeholder => term::Term::Placeholder,
+                Term::Interval(n) => term::Term::Interval(n),
+            }
+        }
+    }
+
+    impl From<TruthValue> for truth::TruthValue {
+        fn from(t: TruthValue) -> Self {
+            truth::TruthValue { frequency: t.frequency, confidence: t.confidence }
+        }
+    }
+
+    impl From<Hypervector> for memory::Hypervector {
+        fn from(v: Hypervector) -> Self {
+            const _: () = assert!(HV_DIM_U64 == 157, "version 1 vectors have 157 words");
+            memory::Hypervector { bits: v.bits }
+        }
+    }
+
+    impl From<Stamp> for sentence::Stamp {
+        fn from(stamp: Stamp) -> Self {
+            let sources = stamp.sources.into_iter().map(|source| match source {
+                Source::User => sentence::Source::User,
+                Source::Sensor(name) => sentence::Source::Sensor(name),
+                Source::Import(name) => sentence::Source::Import(name),
+            }).collect();
+            sentence::Stamp { creation_time: stamp.creation_time, evidence: stamp.evidence, sources }
+        }
+    }
+
+    impl From<Sentence> for sentence::Sentence {
+        fn from(s: Sentence) -> Self {
+            let punctuation = match s.punctuation {
+                Punctuation::Judgement => sentence::Punctuation::Judgement,
+                Punctuation::Question => sentence::Punctuation::Question,
+                Punctuation::Goal => sentence::Punctuation::Goal,
+                Punctuation::Quest => sentence::Punctuation::Quest,
+            };
+            let mut sentence = sentence::Sentence::new(s.term.into(), punctuation, s.truth.into(), s.stamp.into());
+            sentence.tense = s.tense.map(|tense| match tense {
+                Tense::Past => sentence::Tense::Past,
+                Tense::Present => sentence::Tense::Present,
+                Tense::Future => sentence::Tense::Future,
+            });
+            sentence
+        }
+    }
+
+    /// Concepts start out unused.
+    pub(super) fn migrate(payload: Vec<u8>) -> Result<Vec<u8>, NarsError> {
+        let old: Store = bincode::deserialize(&payload)?;
+        let map = old.map.into_iter().map(|(term, c)| (term, v2::Concept {
+            term: c.term, vector: c.vector, priority: c.priority, durability: c.durability, truth: c.truth,
+            stamp: c.stamp, beliefs: c.beliefs, belief_capacity: c.belief_capacity, questions: c.questions,
+            goals: c.goals, origin: c.origin, update_count: c.update_count, history: c.history,
+            counters: c.counters, quality: c.quality, last_used_cycle: 0, use_count: 0,
+        })).collect();
+        Ok(bincode::serialize(&v2::Store { map, capacity: old.capacity, pinned: old.pinned })?)
+    }
+}
+
+/// Layout of version 2 snapshots: version 1 concepts with usage counters.
+mod v2 {
+    use std::collections::{HashMap, HashSet};
+    use serde::{Deserialize, Serialize};
+    use crate::nars::error::NarsError;
+    use super::v3;
+    use super::v1::{CounterVector, Hypervector, Revision, Sentence, Stamp, Term, TruthValue};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Concept {
+        pub(super) term: Term,
+        pub(super) vector: Hypervector,
+        pub(super) priority: f32,
+        pub(super) durability: f32,
+        pub(super) truth: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) beliefs: Vec<Sentence>,
+        pub(super) belief_capacity: usize,
+        pub(super) questions: Vec<Sentence>,
+        pub(super) goals: Vec<Sentence>,
+        pub(super) origin: Hypervector,
+        pub(super) update_count: u32,
+        pub(super) history: Vec<Revision>,
+        pub(super) counters: Option<CounterVector>,
+        pub(super) quality: f32,
+        pub(super) last_used_cycle: u64,
+        pub(super) use_count: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Store {
+        pub(super) map: HashMap<Term, Concept>,
+        pub(super) capacity: usize,
+        pub(super) pinned: HashSet<Term>,
+    }
+
+    /// Learning counters are dropped: they are no longer saved, and concepts start counting
+    /// again from their vector on the next update.
+    pub(super) fn migrate(payload: Vec<u8>) -> Result<Vec<u8>, NarsError> {
+        let old: Store = bincode::deserialize(&payload)?;
+        let map = old.map.into_iter().map(|(term, c)| (term, v3::Concept {
+            term: c.term, vector: c.vector, priority: c.priority, durability: c.durability, truth: c.truth,
+            stamp: c.stamp, beliefs: c.beliefs, belief_capacity: c.belief_capacity, questions: c.questions,
+            goals: c.goals, origin: c.origin, update_count: c.update_count, history: c.history,
+            quality: c.quality, last_used_cycle: c.last_used_cycle, use_count: c.use_count,
+        })).collect();
+        Ok(bincode::serialize(&v3::Store { map, capacity: old.capacity, pinned: old.pinned })?)
+    }
+}
+
+/// Layout of version 3 snapshots: version 2 concepts without learning counters.
+mod v3 {
+    use std::collections::{HashMap, HashSet};
+    use serde::{Deserialize, Serialize};
+    use crate::nars::error::NarsError;
+    use super::v4;
+    use super::v1::{Hypervector, Revision, Sentence, Stamp, Term, TruthValue};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Concept {
+        pub(super) term: Term,
+        pub(super) vector: Hypervector,
+        pub(super) priority: f32,
+        pub(super) durability: f32,
+        pub(super) truth: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) beliefs: Vec<Sentence>,
+        pub(super) belief_capacity: usize,
+        pub(super) questions: Vec<Sentence>,
+        pub(super) goals: Vec<Sentence>,
+        pub(super) origin: Hypervector,
+        pub(super) update_count: u32,
+        pub(super) history: Vec<Revision>,
+        pub(super) quality: f32,
+        pub(super) last_used_cycle: u64,
+        pub(super) use_count: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Store {
+        pub(super) map: HashMap<Term, Concept>,
+        pub(super) capacity: usize,
+        pub(super) pinned: HashSet<Term>,
+    }
+
+    /// Each concept's usage moves to the store's usage table.
+    pub(super) fn migrate(payload: Vec<u8>) -> Result<Vec<u8>, NarsError> {
+        let old: Store = bincode::deserialize(&payload)?;
+        let mut store = v4::Store { map: HashMap::new(), capacity: old.capacity, pinned: old.pinned, usage: HashMap::new() };
+        for (term, c) in old.map {
+            store.usage.insert(term.clone(), v4::Usage { last_used_cycle: c.last_used_cycle, use_count: c.use_count });
+            store.map.insert(term, v4::Concept {
+                term: c.term, vector: c.vector, priority: c.priority, durability: c.durability, truth: c.truth,
+                stamp: c.stamp, beliefs: c.beliefs, belief_capacity: c.belief_capacity, questions: c.questions,
+                goals: c.goals, origin: c.origin, update_count: c.update_count, history: c.history, quality: c.quality,
+            });
+        }
+        Ok(bincode::serialize(&store)?)
+    }
+}
+
+/// Layout of version 4 snapshots: version 3 concepts without usage, which the store keeps.
+mod v4 {
+    use std::collections::{HashMap, HashSet};
+    use serde::{Deserialize, Serialize};
+    use crate::nars::error::NarsError;
+    use super::v5;
+    use super::v1::{Hypervector, Revision, Sentence, Stamp, Term, TruthValue};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Concept {
+        pub(super) term: Term,
+        pub(super) vector: Hypervector,
+        pub(super) priority: f32,
+        pub(super) durability: f32,
+        pub(super) truth: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) beliefs: Vec<Sentence>,
+        pub(super) belief_capacity: usize,
+        pub(super) questions: Vec<Sentence>,
+        pub(super) goals: Vec<Sentence>,
+        pub(super) origin: Hypervector,
+        pub(super) update_count: u32,
+        pub(super) history: Vec<Revision>,
+        pub(super) quality: f32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Usage {
+        pub(super) last_used_cycle: u64,
+        pub(super) use_count: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Store {
+        pub(super) map: HashMap<Term, Concept>,
+        pub(super) capacity: usize,
+        pub(super) pinned: HashSet<Term>,
+        pub(super) usage: HashMap<Term, Usage>,
+    }
+
+    /// No concept was grounded in perception: their flag is unset.
+    pub(super) fn migrate(payload: Vec<u8>) -> Result<Vec<u8>, NarsError> {
+        let old: Store = bincode::deserialize(&payload)?;
+        let map = old.map.into_iter().map(|(term, c)| (term, v5::Concept {
+            term: c.term, vector: c.vector, priority: c.priority, durability: c.durability, truth: c.truth,
+            stamp: c.stamp, beliefs: c.beliefs, belief_capacity: c.belief_capacity, questions: c.questions,
+            goals: c.goals, origin: c.origin, update_count: c.update_count, history: c.history,
+            quality: c.quality, grounded: false,
+        })).collect();
+        Ok(bincode::serialize(&v5::Store { map, capacity: old.capacity, pinned: old.pinned, usage: old.usage })?)
+    }
+}
+
+/// Layout of version 5 snapshots: version 4 concepts with the `grounded` flag.
+mod v5 {
+    use std::collections::{HashMap, HashSet};
+    use serde::{Deserialize, Serialize};
+    use crate::nars::error::NarsError;
+    use crate::nars::memory::{self, ConceptStore};
+    use crate::nars::term;
+    use crate::nars::InternedTerm;
+    use super::v1::{Hypervector, Revision, Sentence, Stamp, Term, TruthValue};
+    use super::v4::Usage;
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Concept {
+        pub(super) term: Term,
+        pub(super) vector: Hypervector,
+        pub(super) priority: f32,
+        pub(super) durability: f32,
+        pub(super) truth: TruthValue,
+        pub(super) stamp: Stamp,
+        pub(super) beliefs: Vec<Sentence>,
+        pub(super) belief_capacity: usize,
+        pub(super) questions: Vec<Sentence>,
+        pub(super) goals: Vec<Sentence>,
+        pub(super) origin: Hypervector,
+        pub(super) update_count: u32,
+        pub(super) history: Vec<Revision>,
+        pub(super) quality: f32,
+        pub(super) grounded: bool,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct Store {
+        pub(super) map: HashMap<Term, Concept>,
+        pub(super) capacity: usize,
+        pub(super) pinned: HashSet<Term>,
+        pub(super) usage: HashMap<Term, Usage>,
+    }
+
+    /// Origins are only kept where they cannot be recomputed from the term.
+    pub(super) fn migrate(payload: Vec<u8>) -> Result<Vec<u8>, NarsError> {
+        let old: Store = bincode::deserialize(&payload)?;
+        let mut store = ConceptStore::new(old.capacity);
+        store.pinned = old.pinned.into_iter().map(Into::into).collect();
+        store.usage = old.usage.into_iter()
+            .map(|(term, u)| (InternedTerm::from(term::Term::from(term)), memory::Usage { last_used_cycle: u.last_used_cycle, use_count: u.use_count }))
+            .collect();
+        for (term, c) in old.map {
+            let sentences = |sentences: Vec<Sentence>| sentences.into_iter().map(Into::into).collect();
+            let mut concept = memory::Concept {
+                term: term::Term::from(c.term).into(), vector: c.vector.into(), priority: c.priority, durability: c.durability,
+                truth: c.truth.into(), stamp: c.stamp.into(), beliefs: sentences(c.beliefs), belief_capacity: c.belief_capacity,
+                questions: sentences(c.questions), goals: sentences(c.goals), origin: None, update_count: c.update_count,
+                history: c.history.into_iter().map(|r| memory::Revision {
+                    previous: r.previous.into(), revised: r.revised.into(), stamp: r.stamp.into(), time: r.time,
+                }).collect(),
+                counters: None, quality: c.quality, grounded: c.grounded,
+            };
+            concept.set_origin(c.origin.into());
+            store.map.insert(term::Term::from(term).into(), concept.into());
+        }
+        Ok(bincode::serialize(&store)?)
+    }
+}
+
+/// Header of a memory snapshot file, readable without decoding the concepts.
+///
+/// Layout (little endian): magic, version (u32), reserved (u32), cycle (u64), concept count
+/// (u64), payload length (u64), then the bincode-encoded `ConceptStore`. Files without the
+/// magic are version 0 snapshots, whose header fields are unknown and read as zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotHeader {
+    pub version: u32,
+    pub cycle: u64,
+    pub concepts: u64,
+    pub payload_len: u64,
+}
+
+impl SnapshotHeader {
+    /// Reads the header of the snapshot at `path`.
+    pub fn read(path: &str) -> Result<Self, NarsError> {
+        let mut file = File::open(path)?;
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        (&mut file).take(HEADER_LEN as u64).read_to_end(&mut bytes)?;
+        let header = Self::parse(&bytes).unwrap_or(Self { version: 0, cycle: 0, concepts: 0, payload_len: file.metadata()?.len() });
+        header.check(path)?;
+        Ok(header)
+    }
+
+    /// The header at the start of `bytes`, if they start with one.
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return None;
+        }
+        let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        Some(Self {
+            version: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            cycle: u64_at(16),
+            concepts: u64_at(24),
+            payload_len: u64_at(32),
+        })
+    }
+
+    /// Rejects snapshots from a newer layout than this build knows.
+    fn check(&self, path: &str) -> Result<(), NarsError> {
+        if self.version > SNAPSHOT_VERSION {
+            return Err(NarsError::Serialization(format!("{}: snapshot version {} is newer than supported version {}",
+                path, self.version, SNAPSHOT_VERSION)));
+        }
+        Ok(())
+    }
+
+    fn write(&self, out: &mut impl Write) -> Result<(), NarsError> {
+        out.write_all(MAGIC)?;
+        out.write_all(&self.version.to_le_bytes())?;
+        out.write_all(&0u32.to_le_bytes())?;
+        out.write_all(&self.cycle.to_le_bytes())?;
+        out.write_all(&self.concepts.to_le_bytes())?;
+        out.write_all(&self.payload_len.to_le_bytes())?;
+        Ok(())
+    }
+}
+
+/// Writes `store` to a snapshot file at `path`, recording `cycle` in the header.
+pub fn write_snapshot(path: &str, cycle: u64, store: &ConceptStore) -> Result<SnapshotHeader, NarsError> {
+    let payload = bincode::serialize(store)?;
+    let header = SnapshotHeader { version: SNAPSHOT_VERSION, cycle, concepts: store.len() as u64, payload_len: payload.len() as u64 };
+    let mut out = BufWriter::new(File::create(path)?);
+    header.write(&mut out)?;
+    out.write_all(&payload)?;
+    out.flush()?;
+    Ok(header)
+}
+
+/// Reads the snapshot at `path`, migrating older layouts to the current one. The store's
+/// indexes are not rebuilt.
+pub fn read_snapshot(path: &str) -> Result<(SnapshotHeader, ConceptStore), NarsError> {
+    let mut bytes = Vec::new();
+    BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+    let (header, mut payload) = match SnapshotHeader::parse(&bytes) {
+        Some(header) => {
+            header.check(path)?;
+            if bytes.len() - HEADER_LEN != header.payload_len as usize {
+                return Err(NarsError::Serialization(format!("{}: truncated or corrupt snapshot", path)));
+            }
+            bytes.drain(..HEADER_LEN);
+            (header, bytes)
+        }
+        None => (SnapshotHeader { version: 0, cycle: 0, concepts: 0, payload_len: bytes.len() as u64 }, bytes),
+    };
+    for migration in &MIGRATIONS[header.version as usize..] {
+        payload = migration(payload)?;
+    }
+    Ok((header, bincode::deserialize(&payload)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::control::NarsSystem;
+    use crate::nars::parser::parse_narsese;
+
+    #[test]
+    fn test_snapshot_versions() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        for text in ["<a --> b>.", "<b --> c>."] {
+            system.input(parse_narsese(text).unwrap());
+        }
+        system.run_cycles(5);
+        let path = std::env::temp_dir().join(format!("nars_snapshot_{}.bin", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+
+        system.save_memory(&path).unwrap();
+        let header = SnapshotHeader::read(&path).unwrap();
+        assert_eq!((header.version, header.cycle, header.concepts), (SNAPSHOT_VERSION, 5, system.memory.len() as u64));
+        let (_, store) = read_snapshot(&path).unwrap();
+        assert_eq!(store.len(), system.memory.len());
+
+        // Newer and truncated snapshots are rejected
+        let mut bytes = Vec::new();
+        let newer = SnapshotHeader { version: SNAPSHOT_VERSION + 1, ..header };
+        newer.write(&mut bytes).unwrap();
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(SnapshotHeader::read(&path), Err(NarsError::Serialization(_))));
+        assert!(matches!(NarsSystem::new(0.1, 0.5).load_memory(&path), Err(NarsError::Serialization(_))));
+        system.save_memory(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(matches!(read_snapshot(&path), Err(NarsError::Serialization(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Reads a fixture saved by an older build through the migrations.
+    fn read_fixture(name: &str, bytes: &[u8]) -> ConceptStore {
+        let path = std::env::temp_dir().join(format!("nars_{}_{}.bin", name, std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        std::fs::write(&path, bytes).unwrap();
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.load_memory(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        system.memory
+    }
+
+    #[test]
+    fn test_migrate_old_snapshots() {
+        let term = |text: &str| crate::nars::parser::parse_term(text).unwrap().1;
+
+        // Version 0: a headerless store holding <cat --> animal>. and <dog --> animal>. %0.8;0.7%
+        let bytes = include_bytes!("../../tests/fixtures/snapshot_v0.bin");
+        assert_eq!(SnapshotHeader::parse(bytes), None);
+        let store = read_fixture("v0", bytes);
+        assert_eq!(store.len(), 4);
+        let dog = store.get(&term("<dog --> animal>")).unwrap();
+        assert_eq!((dog.truth.frequency, dog.truth.confidence), (0.8, 0.7));
+        assert_eq!(dog.beliefs.len(), 1);
+        assert!(dog.stamp.sources.is_empty() && dog.beliefs[0].tense.is_none());
+        assert_eq!(dog.origin(), dog.vector);
+        assert!(store.get(&term("dog")).is_some_and(|c| c.beliefs.is_empty()));
+
+        // Version 1: the same judgements, <cat --> animal>. %0.9;0.5% and a few cycles
+        let bytes = include_bytes!("../../tests/fixtures/snapshot_v1.bin");
+        assert_eq!(SnapshotHeader::parse(bytes).map(|h| (h.version, h.cycle)), Some((1, 3)));
+        let store = read_fixture("v1", bytes);
+        assert_eq!(store.len(), 8);
+        let cat = store.get(&term("<cat --> animal>")).unwrap();
+        assert_eq!(cat.beliefs.len(), 2);
+        assert_eq!(cat.history.len(), 1);
+        assert_eq!(cat.stamp.sources, vec![crate::nars::sentence::Source::User]);
+        assert!((cat.truth.confidence - 0.909).abs() < 1e-3);
+        assert!(store.get(&term("<animal --> cat>")).is_some());
+        assert!(store.values().all(|c| store.usage(&c.term) == Default::default() && c.counters.is_none()));
+
+        // Version 2: <cat --> animal>. with learning counters and some use, <dog --> animal>. %0.8;0.7%
+        let bytes = include_bytes!("../../tests/fixtures/snapshot_v2.bin");
+        assert_eq!(SnapshotHeader::parse(bytes).map(|h| h.version), Some(2));
+        let store = read_fixture("v2", bytes);
+        let cat = store.get(&term("<cat --> animal>")).unwrap();
+        assert_eq!(store.usage(&cat.term), crate::nars::memory::Usage { last_used_cycle: 2, use_count: 3 });
+        assert!(store.values().all(|c| c.counters.is_none()));
+
+        // Version 3: <cat --> animal>. with some use
+        let bytes = include_bytes!("../../tests/fixtures/snapshot_v3.bin");
+        assert_eq!(SnapshotHeader::parse(bytes).map(|h| h.version), Some(3));
+        let store = read_fixture("v3", bytes);
+        assert_eq!(store.usage.len(), store.len());
+        assert_eq!(store.usage(&term("<cat --> animal>")), crate::nars::memory::Usage { last_used_cycle: 3, use_count: 4 });
+
+        // Version 4: <cat --> animal>. used in cycles 2 and 5, no grounded concept
+        let bytes = include_bytes!("../../tests/fixtures/snapshot_v4.bin");
+        assert_eq!(SnapshotHeader::parse(bytes).map(|h| h.version), Some(4));
+        let store = read_fixture("v4", bytes);
+        assert_eq!(store.usage(&term("<cat --> animal>")).last_used_cycle, 5);
+        assert!(store.values().all(|c| !c.grounded));
+
+        // Version 5: <cat --> animal>. after a cycle and the percept {img_1}, grounded
+        let bytes = include_bytes!("../../tests/fixtures/snapshot_v5.bin");
+        assert_eq!(SnapshotHeader::parse(bytes).map(|h| h.version), Some(5));
+        let store = read_fixture("v5", bytes);
+        let image = store.get(&term("{img_1}")).unwrap();
+        assert!(image.grounded && image.origin.is_some());
+        assert_eq!(image.origin(), crate::nars::memory::Hypervector::from_term(&term("percept")));
+        let cat = store.get(&term("cat")).unwrap();
+        assert!(cat.origin.is_none() && cat.origin() == cat.vector);
+    }
+}
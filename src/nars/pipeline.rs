@@ -0,0 +1,385 @@
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use super::memory::{Concept, Hypervector};
+use super::rules::{collect_vars, InferenceRule};
+use super::term::VarType;
+use super::term::Term;
+use super::unify::{is_associative_commutative, unify_with_bindings, Bindings, ScratchBindings};
+use bumpalo::Bump;
+
+const MAX_CACHED_SHAPES: usize = 100_000; // the cache is reset beyond this many entries
+
+/// Structural signature of a term: operators and arities, with every leaf reduced to its
+/// kind. Whether a rule premise can unify with a term at all only depends on this; whether it
+/// does also depends on the atoms and on repeated variables.
+fn shape(term: &Term) -> u64 {
+    fn feed(term: &Term, hasher: &mut DefaultHasher) {
+        std::mem::discriminant(term).hash(hasher);
+        if let Term::Compound(op, args) = term {
+            op.hash(hasher);
+            args.len().hash(hasher);
+            for arg in args {
+                feed(arg, hasher);
+            }
+        }
+    }
+    let mut hasher = DefaultHasher::new();
+    feed(term, &mut hasher);
+    hasher.finish()
+}
+
+/// False only if `premise` cannot unify with any term of the shape of `term`; mirrors the
+/// structural cases of `unify` (variables, AC compounds and symmetric copulas included).
+fn shape_matches(premise: &Term, term: &Term) -> bool {
+    match (premise, term) {
+        (Term::Var(..), _) | (_, Term::Var(..)) => true,
+        (Term::Compound(op1, args1), Term::Compound(op2, args2)) => {
+            if op1 != op2 {
+                return false;
+            }
+            if is_associative_commutative(op1) {
+                return true;
+            }
+            if op1.is_symmetric_copula() && args1.len() == 2 && args2.len() == 2 {
+                return (shape_matches(&args1[0], &args2[0]) && shape_matches(&args1[1], &args2[1]))
+                    || (shape_matches(&args1[0], &args2[1]) && shape_matches(&args1[1], &args2[0]));
+            }
+            args1.len() == args2.len() && args1.iter().zip(args2).all(|(x, y)| shape_matches(x, y))
+        }
+        (Term::Atom(_), Term::Atom(_)) | (Term::Placeholder, Term::Placeholder) | (Term::Interval(_), Term::Interval(_)) => true,
+        _ => false,
+    }
+}
+
+/// Memo of rule premises that cannot unify with a term shape, keyed by (rule index, premise
+/// index, shape). The same statements meet the same rules over and over across cycles, and
+/// most pairs fail on structure alone. Rule indices must keep their meaning, which holds as
+/// long as rules are only ever appended.
+#[derive(Debug, Default)]
+pub struct ShapeCache {
+    impossible: HashSet<(usize, usize, u64)>,
+    hits: u64,
+}
+
+impl ShapeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.impossible.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.impossible.is_empty()
+    }
+
+    /// Unification attempts skipped so far.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn clear(&mut self) {
+        self.impossible.clear();
+    }
+
+    /// Whether premise `premise_idx` of rule `rule` is known not to unify with terms of
+    /// shape `term_shape`, so the attempt can be skipped.
+    fn skips(&mut self, rule: usize, premise_idx: usize, term_shape: u64) -> bool {
+        let known = self.impossible.contains(&(rule, premise_idx, term_shape));
+        if known {
+            self.hits += 1;
+        }
+        known
+    }
+
+    /// Remembers that a failed match of the premise with `term` failed on structure alone.
+    fn record_failure(&mut self, rule: usize, premise_idx: usize, premise: &Term, term: &Term, term_shape: u64) {
+        if !shape_matches(premise, term) {
+            if self.impossible.len() >= MAX_CACHED_SHAPES {
+                self.impossible.clear();
+            }
+            self.impossible.insert((rule, premise_idx, term_shape));
+        }
+    }
+}
+
+/// Scratch memory for rule matching, owned by whoever runs the matches (the cycle, or the
+/// background matcher) and reused from one premise pair to the next. It is not `Sync`: each
+/// thread that matches keeps its own. Bindings and AC
+/// remainders of every attempt live here; only the bindings of successful matches are copied
+/// out, so after warm-up the rules that fail cost no heap allocations.
+#[derive(Debug, Default)]
+pub struct MatchArena {
+    bump: Bump,
+}
+
+impl MatchArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Two-premise rules matching the ordered pair `(a, b)`, with their bindings, in rule order.
+/// `enabled` is indexed by rule; missing entries count as enabled.
+pub fn match_pair(rules: &[InferenceRule], enabled: &[bool], a: &Term, b: &Term, cache: &mut ShapeCache, arena: &mut MatchArena) -> Vec<(usize, Bindings)> {
+    arena.bump.reset();
+    let bump = &arena.bump;
+    let (shape_a, shape_b) = (shape(a), shape(b));
+    rules.iter().enumerate()
+        .filter(|(idx, rule)| rule.premises.len() == 2 && enabled.get(*idx).copied().unwrap_or(true))
+        .filter_map(|(idx, rule)| {
+            let (first, second) = (&rule.premises[0], &rule.premises[1]);
+            if cache.skips(idx, 0, shape_a) || cache.skips(idx, 1, shape_b) {
+                return None;
+            }
+            // Every way the first premise matches is tried until the second one and the
+            // preconditions agree with it
+            let mut scratch = ScratchBindings::new(bump);
+            let mut matched = None;
+            scratch.unify_then(first, a, &mut |s| s.unify_then(second, b, &mut |s| {
+                let bindings = s.to_bindings();
+                let holds = rule.meta.preconditions.iter().all(|p| p.holds(&bindings));
+                matched = holds.then_some(bindings);
+                holds
+            }));
+            if matched.is_none() {
+                cache.record_failure(idx, 0, first, a, shape_a);
+                cache.record_failure(idx, 1, second, b, shape_b);
+            }
+            Some((idx, matched?))
+        })
+        .collect()
+}
+
+/// Backward matches of a question on `question` and a belief on `belief`: two-premise rules
+/// marked `:allow-backward` whose conclusion unifies with the question and one of whose
+/// premises unifies with the belief. Yields the rule, the index of the other premise, which is
+/// to be asked about, and the bindings, where rule variables the match leaves open are bound to
+/// query variables (`?name`).
+pub fn match_backward(rules: &[InferenceRule], enabled: &[bool], question: &Term, belief: &Term) -> Vec<(usize, usize, Bindings)> {
+    let mut matches = Vec::new();
+    for (idx, rule) in rules.iter().enumerate() {
+        if rule.premises.len() != 2 || !rule.meta.allow_backward || !enabled.get(idx).copied().unwrap_or(true) {
+            continue;
+        }
+        let Some(bindings) = unify_with_bindings(&rule.conclusion, question, HashMap::new()) else { continue };
+        for (known, asked) in [(0, 1), (1, 0)] {
+            let Some(mut bindings) = unify_with_bindings(&rule.premises[known], belief, bindings.clone()) else { continue };
+            let mut open = Vec::new();
+            collect_vars(&rule.premises[asked], &mut open);
+            for var in open {
+                if let Term::Var(_, name) = var
+                    && !bindings.contains_key(var)
+                {
+                    bindings.insert(var.clone(), Term::Var(VarType::Query, name.clone()));
+                }
+            }
+            if rule.meta.preconditions.iter().all(|p| p.holds(&bindings)) {
+                matches.push((idx, asked, bindings));
+            }
+        }
+    }
+    matches
+}
+
+/// Matches of one premise pair: `(a, b)` and, for bidirectional reasoning, `(b, a)`.
+pub type PairMatches = (Vec<(usize, Bindings)>, Vec<(usize, Bindings)>);
+
+/// Association partners of a task: the candidates at least `threshold` similar to `query`,
+/// most similar first, at most `max` of them.
+pub fn rank_partners(query: &Hypervector, candidates: Vec<(Term, Arc<Concept>)>, threshold: f32, max: usize) -> Vec<(Term, f32)> {
+    let mut partners: Vec<(Term, f32)> = candidates.into_iter()
+        .map(|(term, concept)| {
+            let sim = query.similarity(&concept.vector);
+            (term, sim)
+        })
+        .filter(|(_, sim)| *sim >= threshold)
+        .collect();
+    partners.sort_by(|a, b| b.1.total_cmp(&a.1));
+    partners.truncate(max);
+    partners
+}
+
+struct MatchJob {
+    rules: Arc<Vec<InferenceRule>>,
+    enabled: Arc<Vec<bool>>,
+    subject: Term,
+    partners: Vec<Term>,
+    bidirectional: bool,
+    results: Sender<PairMatches>,
+}
+
+struct RetrievalJob {
+    query: Hypervector,
+    candidates: Vec<(Term, Arc<Concept>)>,
+    threshold: f32,
+    max: usize,
+    results: Sender<Vec<(Term, f32)>>,
+}
+
+/// Partners of a task being ranked by the pipeline, see `Pipeline::retrieve`.
+pub struct Retrieval {
+    results: Mutex<Receiver<Vec<(Term, f32)>>>, // only ever read by value; the mutex just keeps the owner `Sync`
+}
+
+impl Retrieval {
+    /// Waits for the ranked partners; none if the pipeline died.
+    pub fn wait(self) -> Vec<(Term, f32)> {
+        self.results.into_inner().unwrap_or_else(PoisonError::into_inner).recv().unwrap_or_default()
+    }
+}
+
+/// A thread working through the jobs sent to it, in order. Dropping the stage closes its
+/// queue and waits for the thread to finish the jobs already queued.
+struct Stage<J> {
+    jobs: Option<Sender<J>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<J: Send + 'static> Stage<J> {
+    fn spawn(mut work: impl FnMut(J) + Send + 'static) -> Self {
+        let (jobs, queue) = mpsc::channel::<J>();
+        let thread = std::thread::spawn(move || queue.into_iter().for_each(&mut work));
+        Self { jobs: Some(jobs), thread: Some(thread) }
+    }
+
+    fn send(&self, job: J) {
+        // A dead stage drops the job's sender, so the receiver reports the failure on recv
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(job);
+        }
+    }
+}
+
+impl<J> Drop for Stage<J> {
+    fn drop(&mut self) {
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Background stages of pipelined cycles. Rule matching and retrieval only read terms and
+/// vectors, so each runs on its own thread: the matcher streams one result per partner, in
+/// order, while the cycle executes the inferences of the previous partner, and the retriever
+/// ranks the partners of the next task meanwhile. The threads are joined when the pipeline is
+/// dropped.
+pub struct Pipeline {
+    matcher: Stage<MatchJob>,
+    retriever: Stage<RetrievalJob>,
+    enabled: Arc<Vec<bool>>, // last rule flags sent, shared by the jobs until they change
+}
+
+impl Pipeline {
+    pub fn spawn() -> Self {
+        let mut cache = ShapeCache::new();
+        let mut arena = MatchArena::new();
+        let matcher = Stage::spawn(move |job: MatchJob| {
+            for partner in &job.partners {
+                let forward = match_pair(&job.rules, &job.enabled, &job.subject, partner, &mut cache, &mut arena);
+                let backward = if job.bidirectional {
+                    match_pair(&job.rules, &job.enabled, partner, &job.subject, &mut cache, &mut arena)
+                } else {
+                    Vec::new()
+                };
+                // The cycle stopped listening (deadline): skip the rest of this job
+                if job.results.send((forward, backward)).is_err() {
+                    break;
+                }
+            }
+        });
+        let retriever = Stage::spawn(|job: RetrievalJob| {
+            let _ = job.results.send(rank_partners(&job.query, job.candidates, job.threshold, job.max));
+        });
+        Self { matcher, retriever, enabled: Arc::default() }
+    }
+
+    /// Queues the pairs `(subject, partner)`; results arrive on the returned channel in
+    /// partner order.
+    pub fn submit(&mut self, rules: Arc<Vec<InferenceRule>>, enabled: &[bool], subject: Term, partners: Vec<Term>, bidirectional: bool) -> Receiver<PairMatches> {
+        if self.enabled.as_slice() != enabled {
+            self.enabled = Arc::new(enabled.to_vec());
+        }
+        let (results, receiver) = mpsc::channel();
+        let enabled = Arc::clone(&self.enabled);
+        self.matcher.send(MatchJob { rules, enabled, subject, partners, bidirectional, results });
+        receiver
+    }
+
+    /// Queues the ranking of `candidates` by similarity to `query`, see `rank_partners`.
+    pub fn retrieve(&self, query: Hypervector, candidates: Vec<(Term, Arc<Concept>)>, threshold: f32, max: usize) -> Retrieval {
+        let (results, receiver) = mpsc::channel();
+        self.retriever.send(RetrievalJob { query, candidates, threshold, max, results });
+        Retrieval { results: Mutex::new(receiver) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::control::NarsSystem;
+    use crate::nars::parser::parse_term;
+
+    #[test]
+    fn test_shape_cache_preserves_matches() {
+        let system = NarsSystem::new(0.1, 0.5);
+        let terms: Vec<Term> = ["<a --> b>", "<b --> c>", "<a <-> c>", "<<a --> b> ==> <b --> c>>", "(&&, <a --> b>, <c --> d>)",
+            "<(*, a, b) --> r>", "<d --> (|, a, b)>", "<$x --> b>", "(--, <a --> b>)", "<{a} --> [b]>"]
+            .iter().map(|t| parse_term(t).unwrap().1).collect();
+
+        let mut cache = ShapeCache::new();
+        let mut arena = MatchArena::new();
+        for _ in 0..2 {
+            for a in &terms {
+                for b in &terms {
+                    let cached = match_pair(&system.rules, &[], a, b, &mut cache, &mut arena);
+                    assert_eq!(cached, match_pair(&system.rules, &[], a, b, &mut ShapeCache::new(), &mut MatchArena::new()), "{} {}", a, b);
+                }
+            }
+        }
+        assert!(!cache.is_empty());
+        assert!(cache.hits() > 0);
+    }
+
+    #[test]
+    fn test_second_premise_picks_the_ac_assignment() {
+        use crate::nars::static_rules::parse_rules;
+        use crate::nars::rules::TruthRegistry;
+
+        // The first premise alone would bind `:S = x`; the second needs `:S = y`
+        let (rules, _) = parse_rules("((& :S :P) --> :M) (:S --> :M) !- (:P --> :M) decomposition", &TruthRegistry::new()).unwrap();
+        let term = |t: &str| parse_term(t).unwrap().1;
+        let matches = match_pair(&rules, &[], &term("<(&, x, y) --> m>"), &term("<y --> m>"), &mut ShapeCache::new(), &mut MatchArena::new());
+        assert_eq!(matches.len(), 1);
+        let bindings = &matches[0].1;
+        assert_eq!(bindings.values().filter(|t| **t == term("x")).count(), 1);
+        assert_eq!(bindings.values().filter(|t| **t == term("y")).count(), 1);
+        assert!(match_pair(&rules, &[], &term("<(&, x, y) --> m>"), &term("<z --> m>"), &mut ShapeCache::new(), &mut MatchArena::new()).is_empty());
+    }
+
+    #[test]
+    fn test_shape_cache_with_declared_commutative_operators() {
+        use crate::nars::static_rules::parse_rules;
+        use crate::nars::rules::TruthRegistry;
+        use crate::nars::term::{declare_operator, OperatorSpec};
+
+        declare_operator(OperatorSpec::compound("bundle", (2, None), true)).unwrap();
+        let (rules, _) = parse_rules("((bundle :S :P) --> :M) (:S --> :M) !- (:P --> :M) decomposition", &TruthRegistry::new()).unwrap();
+        let term = |t: &str| parse_term(t).unwrap().1;
+        let bundle = term("<(bundle, x, y, z) --> m>");
+
+        // The failed match records nothing for the shape of `bundle`: the later one, which
+        // needs another argument first and the rest as a remainder, still goes through
+        let mut cache = ShapeCache::new();
+        let mut arena = MatchArena::new();
+        assert!(match_pair(&rules, &[], &bundle, &term("<w --> m>"), &mut cache, &mut arena).is_empty());
+        assert_eq!(match_pair(&rules, &[], &bundle, &term("<z --> m>"), &mut cache, &mut arena).len(), 1);
+        assert_eq!(match_pair(&rules, &[], &bundle, &term("<y --> m>"), &mut cache, &mut arena).len(), 1);
+    }
+}
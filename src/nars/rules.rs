@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+use std::fmt;
 use super::term::{Term, Operator, VarType};
 use super::truth::{self, TruthValue};
+use super::static_rules::get_truth_fn;
+use super::unify::Bindings;
+use super::error::NarsError;
 
 #[derive(Clone, Copy)]
 pub enum TruthFunction {
@@ -7,11 +12,127 @@ pub enum TruthFunction {
     Double(fn(TruthValue, TruthValue) -> TruthValue),
 }
 
+/// Named truth functions available to rule definitions. Lookups fall back to the built-in
+/// table, so registering a built-in name overrides it.
+#[derive(Clone, Default)]
+pub struct TruthRegistry {
+    custom: HashMap<String, TruthFunction>,
+}
+
+impl TruthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: &str, truth_fn: TruthFunction) {
+        self.custom.insert(name.to_string(), truth_fn);
+    }
+
+    pub fn get(&self, name: &str) -> Result<TruthFunction, NarsError> {
+        match self.custom.get(name) {
+            Some(truth_fn) => Ok(*truth_fn),
+            None => get_truth_fn(name),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_ok()
+    }
+}
+
+/// Desire function used when a rule derives a goal (`:d/strong`, `:d/weak`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesireSelector {
+    Strong,
+    Weak,
+}
+
+/// Condition on the bindings of a matched rule (`:pre`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Precondition {
+    NotEqual(Term, Term), // (:!= a b)
+}
+
+impl Precondition {
+    pub fn holds(&self, bindings: &Bindings) -> bool {
+        let resolve = |t: &Term| bindings.get(t).cloned().unwrap_or_else(|| t.clone());
+        match self {
+            Precondition::NotEqual(a, b) => resolve(a) != resolve(b),
+        }
+    }
+}
+
+/// Annotations of a rule loaded from a rule file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleMeta {
+    pub preconditions: Vec<Precondition>,
+    /// Turns a goal on the first premise into a goal for the conclusion (two-premise rules).
+    pub desire: Option<DesireSelector>,
+    /// Only derives questions, from a question on the first premise (`:question?`).
+    pub question_only: bool,
+    /// Derives beliefs and also questions from questions (`:allow-backward`). The rule is sound
+    /// in reverse, so backward inference may invert it: a question on the conclusion and a
+    /// belief on one premise raise a question on the other premise.
+    pub allow_backward: bool,
+}
+
+impl RuleMeta {
+    pub fn derives_questions(&self) -> bool {
+        self.question_only || self.allow_backward
+    }
+}
+
+#[derive(Clone)]
 pub struct InferenceRule {
     pub name: String,
     pub premises: Vec<Term>,
     pub conclusion: Term,
     pub truth_fn: TruthFunction,
+    pub meta: RuleMeta,
+}
+
+/// Highest NAL layer, at which every rule is enabled.
+pub const MAX_NAL_LEVEL: u8 = 8;
+
+impl InferenceRule {
+    /// Premises and conclusion as one term with variables renamed in order of appearance:
+    /// rules with equal signatures are the same rule up to variable names. (Normalized twice:
+    /// the first pass renames, the second orders commutative arguments by the new names.)
+    pub fn signature(&self) -> Term {
+        let mut parts = self.premises.clone();
+        parts.push(self.conclusion.clone());
+        Term::Compound(Operator::Product, parts).normalized().normalized()
+    }
+
+    /// The NAL layer the rule belongs to: the highest layer of the operators it mentions, and
+    /// at least NAL-6 when the conclusion introduces a variable not bound by the premises.
+    pub fn nal_level(&self) -> u8 {
+        let level = self.premises.iter().map(|p| p.nal_level()).fold(self.conclusion.nal_level(), u8::max);
+        let mut bound = Vec::new();
+        for premise in &self.premises {
+            collect_vars(premise, &mut bound);
+        }
+        let mut introduced = Vec::new();
+        collect_vars(&self.conclusion, &mut introduced);
+        if introduced.iter().any(|v| !bound.contains(v)) { level.max(6) } else { level }
+    }
+}
+
+impl fmt::Display for InferenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for premise in &self.premises {
+            write!(f, "{} ", premise)?;
+        }
+        write!(f, "!- {} {}", self.conclusion, self.name)
+    }
+}
+
+pub(crate) fn collect_vars<'a>(term: &'a Term, vars: &mut Vec<&'a Term>) {
+    match term {
+        Term::Var(..) => vars.push(term),
+        Term::Compound(_, args) => args.iter().for_each(|a| collect_vars(a, vars)),
+        _ => {}
+    }
 }
 
 pub fn load_default_rules() -> Vec<InferenceRule> {
@@ -35,6 +156,7 @@ pub fn load_default_rules() -> Vec<InferenceRule> {
         premises: vec![ded_p1, ded_p2],
         conclusion: ded_concl,
         truth_fn: TruthFunction::Double(truth::deduction),
+        meta: RuleMeta::default(),
     });
 
     // Abduction: ((:P --> :M), (:S --> :M)) |- (:S --> :P)
@@ -50,6 +172,7 @@ pub fn load_default_rules() -> Vec<InferenceRule> {
         premises: vec![abd_p1, abd_p2],
         conclusion: abd_concl,
         truth_fn: TruthFunction::Double(truth::abduction),
+        meta: RuleMeta::default(),
     });
 
     // Induction: ((:M --> :P), (:M --> :S)) |- (:S --> :P)
@@ -65,6 +188,7 @@ pub fn load_default_rules() -> Vec<InferenceRule> {
         premises: vec![ind_p1, ind_p2],
         conclusion: ind_concl,
         truth_fn: TruthFunction::Double(truth::induction),
+        meta: RuleMeta::default(),
     });
 
     rules
@@ -0,0 +1,249 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use super::control::{NarsSystem, RuleUsage};
+use super::parser::parse_narsese;
+use super::sentence::{Punctuation, Sentence, Source};
+use super::term::Term;
+use super::truth::TruthValue;
+use super::error::NarsError;
+
+const TRUTH_TOLERANCE: f32 = 0.01; // of an expectation that gives none
+
+/// Outcome of running a `.nal` script.
+#[derive(Debug, Default)]
+pub struct ExecutionReport {
+    pub inputs: usize,
+    pub cycles: usize,
+    pub expectations_met: Vec<String>,
+    pub expectations_unmet: Vec<String>,
+    pub outputs: Vec<Sentence>, // everything the system reported (derivations, answers)
+    pub parse_errors: Vec<(usize, NarsError)>, // (line number, error) of skipped input lines
+}
+
+impl ExecutionReport {
+    pub fn passed(&self) -> bool {
+        self.expectations_unmet.is_empty()
+    }
+
+    /// The output nearest to an expected sentence: of the outputs on its term (up to variable
+    /// names), the one with the closest truth, and the distance, the larger of the frequency and
+    /// confidence differences. `None` if nothing was output on that term.
+    pub fn closest_output(&self, expected: &str) -> Option<(&Sentence, f32)> {
+        let expected = parse_narsese(expected).ok()?;
+        self.outputs.iter()
+            .filter(|output| terms_match(&output.term, &expected.term))
+            .map(|output| (output, truth_distance(output.truth, expected.truth)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Rule usage summed over every system recorded, e.g. all tests of a `.nal` corpus. Rules are
+/// identified by their index in the rule table, which is the same for systems built alike.
+#[derive(Debug, Default)]
+pub struct RuleCoverage {
+    pub rules: Vec<String>, // `#index name` per rule
+    pub usage: Vec<RuleUsage>,
+}
+
+impl RuleCoverage {
+    /// Adds the usage counters of `system` to the totals.
+    pub fn record(&mut self, system: &NarsSystem) {
+        for (idx, rule) in system.rules.iter().enumerate() {
+            if self.rules.len() <= idx {
+                self.rules.push(format!("#{} {}", idx, rule.name));
+                self.usage.push(RuleUsage::default());
+            }
+            let used = system.rule_usage(idx);
+            let total = &mut self.usage[idx];
+            total.fired += used.fired;
+            total.accepted += used.accepted;
+            total.rejected += used.rejected;
+        }
+    }
+
+    /// Rules that were never applied.
+    pub fn never_fired(&self) -> Vec<&str> {
+        self.select(|u| u.fired == 0)
+    }
+
+    /// Rules whose derivations were all dropped. Rules that fired without deriving anything
+    /// (question-only rules) are not listed.
+    pub fn only_rejected(&self) -> Vec<&str> {
+        self.select(|u| u.rejected > 0 && u.accepted == 0)
+    }
+
+    fn select(&self, pred: impl Fn(&RuleUsage) -> bool) -> Vec<&str> {
+        self.rules.iter().zip(&self.usage)
+            .filter(|(_, usage)| pred(usage))
+            .map(|(label, _)| label.as_str())
+            .collect()
+    }
+}
+
+/// Runs a script line by line:
+/// - `'` and `//` start a comment, except `''outputMustContain('<sentence>'[, tolerance])`,
+///   which expects the system to report a matching sentence (same punctuation, same term up to
+///   variable names, and for judgements and goals frequency and confidence within the
+///   tolerance, 0.01 by default) at any point after it is declared
+/// - a number runs that many cycles
+/// - anything else is Narsese input; unparsable lines (malformed expectations too) are recorded
+///   and skipped. Its evidence is attributed to the source `script`, with its line number.
+pub fn run_script<R: BufRead>(system: &mut NarsSystem, reader: R) -> Result<ExecutionReport, NarsError> {
+    run_script_from(system, reader, Source::Import("script".to_string()))
+}
+
+fn run_script_from<R: BufRead>(system: &mut NarsSystem, reader: R, source: Source) -> Result<ExecutionReport, NarsError> {
+    let mut report = ExecutionReport::default();
+    let mut pending: Vec<(String, f32)> = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("''outputMustContain") {
+            match parse_expectation(rest) {
+                Some(expectation) => {
+                    pending.push(expectation);
+                    check_expectations(&mut report, &mut pending);
+                }
+                None => report.parse_errors.push((index + 1,
+                    NarsError::parse(trimmed, rest, "expected ''outputMustContain('<sentence>'[, tolerance])"))),
+            }
+            continue;
+        }
+        if trimmed.starts_with('\'') || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if let Ok(steps) = trimmed.parse::<usize>() {
+            for _ in 0..steps {
+                system.cycle();
+                report.cycles += 1;
+                report.outputs.append(&mut system.output_buffer);
+                check_expectations(&mut report, &mut pending);
+            }
+            continue;
+        }
+
+        match system.parse(trimmed) {
+            Ok(sentence) => {
+                system.input_from_line(sentence, source.clone(), index + 1);
+                report.inputs += 1;
+                report.outputs.append(&mut system.output_buffer);
+            }
+            Err(e) => report.parse_errors.push((index + 1, e)),
+        }
+        check_expectations(&mut report, &mut pending);
+    }
+
+    report.expectations_unmet = pending.into_iter().map(|(expected, _)| expected).collect();
+    Ok(report)
+}
+
+/// Arguments of `''outputMustContain`: `('<sentence>')` or `('<sentence>', tolerance)`.
+fn parse_expectation(args: &str) -> Option<(String, f32)> {
+    let start = args.find("('")? + 2;
+    let end = start + args[start..].rfind('\'')?;
+    let rest = args[end + 1..].trim().strip_suffix(')')?.trim();
+    let tolerance = match rest.strip_prefix(',') {
+        Some(tolerance) => tolerance.trim().parse().ok()?,
+        None if rest.is_empty() => TRUTH_TOLERANCE,
+        None => return None,
+    };
+    Some((args[start..end].to_string(), tolerance))
+}
+
+/// Writes `sentences` as a `.nal` script that `run_script_file` can load again.
+pub fn write_nal(path: &str, sentences: &[Sentence]) -> Result<(), NarsError> {
+    let mut out = std::io::BufWriter::new(File::create(path)?);
+    for sentence in sentences {
+        writeln!(out, "{}", sentence.to_narsese())?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+pub fn run_script_str(system: &mut NarsSystem, script: &str) -> Result<ExecutionReport, NarsError> {
+    run_script(system, script.as_bytes())
+}
+
+/// Same as `run_script` for the script at `path`, which is the source of its evidence.
+pub fn run_script_file(system: &mut NarsSystem, path: &str) -> Result<ExecutionReport, NarsError> {
+    let file = File::open(path)?;
+    run_script_from(system, BufReader::new(file), Source::Import(path.to_string()))
+}
+
+/// Drives the system with the OpenNARS shell line protocol, for tools written against OpenNARS:
+/// - input: Narsese lines, numbers (cycles to run) and `'` or `//` comments
+/// - output: `IN: ` for accepted input, `Answer: ` for answers to questions, `OUT: ` for other
+///   derivations and `ERR: ` for unparsable lines
+///
+/// Output is flushed after every input line so a driving process can read it interactively.
+pub fn run_pipe<R: BufRead, W: Write>(system: &mut NarsSystem, reader: R, mut out: W) -> Result<(), NarsError> {
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('\'') || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if let Ok(steps) = trimmed.parse::<usize>() {
+            for _ in 0..steps {
+                system.cycle();
+                write_outputs(system, &mut out)?;
+            }
+        } else {
+            match system.parse(trimmed) {
+                Ok(sentence) => {
+                    writeln!(out, "IN: {}", sentence.to_narsese())?;
+                    system.input_from(sentence, Source::User);
+                    write_outputs(system, &mut out)?;
+                }
+                Err(e) => writeln!(out, "ERR: {}", e)?,
+            }
+        }
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn write_outputs<W: Write>(system: &mut NarsSystem, out: &mut W) -> Result<(), NarsError> {
+    let answers: Vec<Sentence> = system.answers.drain(..).map(|event| event.answer).collect();
+    for answer in &answers {
+        writeln!(out, "Answer: {}", answer.to_narsese())?;
+    }
+    // Answers are also reported as outputs; print each sentence once
+    for sentence in system.output_buffer.drain(..) {
+        if !answers.contains(&sentence) {
+            writeln!(out, "OUT: {}", sentence.to_narsese())?;
+        }
+    }
+    Ok(())
+}
+
+fn check_expectations(report: &mut ExecutionReport, pending: &mut Vec<(String, f32)>) {
+    pending.retain(|(expected, tolerance)| {
+        // Expectations that do not parse can never be met
+        let Ok(expected_sentence) = parse_narsese(expected) else { return true };
+        let met = report.outputs.iter().any(|output| {
+            output.punctuation == expected_sentence.punctuation
+                && terms_match(&output.term, &expected_sentence.term)
+                && (matches!(expected_sentence.punctuation, Punctuation::Question | Punctuation::Quest) || truth_distance(output.truth, expected_sentence.truth) < *tolerance)
+        });
+        if met {
+            report.expectations_met.push(expected.clone());
+        }
+        !met
+    });
+}
+
+fn terms_match(t1: &Term, t2: &Term) -> bool {
+    t1.normalized() == t2.normalized()
+}
+
+fn truth_distance(t1: TruthValue, t2: TruthValue) -> f32 {
+    (t1.frequency - t2.frequency).abs().max((t1.confidence - t2.confidence).abs())
+}
@@ -0,0 +1,116 @@
+use super::term::{Term, Operator};
+use super::truth::TruthValue;
+use super::sentence::{Sentence, Punctuation, Stamp, Tense};
+
+/// Triangular fuzzy set over a numeric range: membership rises from `low` to 1.0 at `peak`
+/// and falls back to 0.0 at `high`. An infinite `low`/`high` (or one equal to `peak`) gives a shoulder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzySet {
+    pub label: String,
+    pub low: f32,
+    pub peak: f32,
+    pub high: f32,
+}
+
+impl FuzzySet {
+    pub fn triangle(label: &str, low: f32, peak: f32, high: f32) -> Self {
+        Self { label: label.to_string(), low, peak, high }
+    }
+
+    pub fn membership(&self, x: f32) -> f32 {
+        if x < self.low || x > self.high {
+            0.0
+        } else if x <= self.peak {
+            if self.peak > self.low && self.low.is_finite() { (x - self.low) / (self.peak - self.low) } else { 1.0 }
+        } else if self.high > self.peak && self.high.is_finite() {
+            (self.high - x) / (self.high - self.peak)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// A named numeric input stream. Each reading is turned into one event judgement per fuzzy
+/// set, `<{name} --> [label]>` with the membership degree as frequency.
+#[derive(Debug, Clone)]
+pub struct SensorChannel {
+    pub name: String,
+    pub sets: Vec<FuzzySet>,
+    pub confidence: f32,
+    pending: Option<f32>,
+}
+
+impl SensorChannel {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            sets: Vec::new(),
+            confidence: 0.9,
+            pending: None,
+        }
+    }
+
+    /// Evenly spaced sets covering `[min, max]`; the first and last labels are shoulders.
+    pub fn uniform(name: &str, min: f32, max: f32, labels: &[&str]) -> Self {
+        let mut channel = Self::new(name);
+        let steps = labels.len().saturating_sub(1).max(1) as f32;
+        let width = (max - min) / steps;
+        for (i, label) in labels.iter().enumerate() {
+            let peak = min + width * i as f32;
+            let low = if i == 0 { f32::NEG_INFINITY } else { peak - width };
+            let high = if i + 1 == labels.len() { f32::INFINITY } else { peak + width };
+            channel.sets.push(FuzzySet::triangle(label, low, peak, high));
+        }
+        channel
+    }
+
+    pub fn with_set(mut self, set: FuzzySet) -> Self {
+        self.sets.push(set);
+        self
+    }
+
+    /// Records a reading; only the latest one is kept until the next tick.
+    pub fn push(&mut self, value: f32) {
+        self.pending = Some(value);
+    }
+
+    pub fn term_for(&self, label: &str) -> Term {
+        Term::Compound(Operator::Inheritance, vec![
+            Term::Compound(Operator::ExtSet, vec![Term::atom_from_str(&self.name)]),
+            Term::Compound(Operator::IntSet, vec![Term::atom_from_str(label)]),
+        ])
+    }
+
+    /// Drains the pending reading into judgements occurring at `time`.
+    pub fn tick(&mut self, time: u64) -> Vec<Sentence> {
+        let Some(value) = self.pending.take() else { return Vec::new() };
+        self.sets.iter().map(|set| {
+            let truth = TruthValue::new(set.membership(value), self.confidence);
+            Sentence::new(self.term_for(&set.label), Punctuation::Judgement, truth, Stamp::new(time, vec![])).with_tense(Tense::Present)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_membership() {
+        let mut channel = SensorChannel::uniform("temp", 0.0, 40.0, &["cold", "warm", "hot"]);
+        let set = |label: &str| channel.sets.iter().find(|s| s.label == label).unwrap().clone();
+        assert_eq!(set("cold").membership(-10.0), 1.0);
+        assert_eq!(set("warm").membership(20.0), 1.0);
+        assert!((set("warm").membership(30.0) - 0.5).abs() < 1e-6);
+        assert!((set("hot").membership(30.0) - 0.5).abs() < 1e-6);
+        assert_eq!(set("hot").membership(100.0), 1.0);
+
+        channel.push(10.0);
+        channel.push(30.0);
+        let events = channel.tick(7);
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e.stamp.creation_time == 7));
+        assert_eq!(events[2].term.to_string(), "<{temp} --> [hot]>");
+        assert!(channel.tick(8).is_empty());
+    }
+}
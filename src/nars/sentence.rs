@@ -1,6 +1,7 @@
 use super::term::Term;
 use super::truth::TruthValue;
 use serde::{Serialize, Deserialize};
+use std::fmt;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -11,10 +12,113 @@ pub enum Punctuation {
     Quest,     // @
 }
 
+impl Punctuation {
+    pub fn symbol(&self) -> char {
+        match self {
+            Punctuation::Judgement => '.',
+            Punctuation::Question => '?',
+            Punctuation::Goal => '!',
+            Punctuation::Quest => '@',
+        }
+    }
+}
+
+/// Time a sentence refers to, relative to when it was input: `:\:`, `:|:` or `:/:`.
+/// Sentences without one are eternal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tense {
+    Past,
+    Present,
+    Future,
+}
+
+impl Tense {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Tense::Past => ":\\:",
+            Tense::Present => ":|:",
+            Tense::Future => ":/:",
+        }
+    }
+}
+
+/// Truth given to judgements and goals input without one, separately for eternal sentences
+/// and events (sentences with a tense such as `:|:`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TruthDefaults {
+    pub judgement: TruthValue,
+    pub tensed_judgement: TruthValue,
+    pub goal: TruthValue,
+    pub tensed_goal: TruthValue,
+}
+
+impl Default for TruthDefaults {
+    fn default() -> Self {
+        let default = TruthValue::new(1.0, 0.9);
+        Self { judgement: default, tensed_judgement: default, goal: default, tensed_goal: default }
+    }
+}
+
+impl TruthDefaults {
+    /// Default truth of an input sentence; questions and quests carry none (`%0.0;0.0%`).
+    pub fn for_input(&self, punctuation: Punctuation, tensed: bool) -> TruthValue {
+        match (punctuation, tensed) {
+            (Punctuation::Judgement, false) => self.judgement,
+            (Punctuation::Judgement, true) => self.tensed_judgement,
+            (Punctuation::Goal, false) => self.goal,
+            (Punctuation::Goal, true) => self.tensed_goal,
+            (Punctuation::Question | Punctuation::Quest, _) => TruthValue::new(0.0, 0.0),
+        }
+    }
+}
+
+/// Attention an input asks for, written `$priority;durability;quality$` before the sentence:
+/// how urgent it is, how slowly that fades, and how much the concept is worth keeping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub priority: f32,
+    pub durability: f32,
+    pub quality: f32,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self { priority: 0.5, durability: 0.5, quality: 0.0 }
+    }
+}
+
+impl Budget {
+    pub fn new(priority: f32, durability: f32, quality: f32) -> Self {
+        Self { priority: priority.clamp(0.0, 1.0), durability: durability.clamp(0.0, 1.0), quality: quality.clamp(0.0, 1.0) }
+    }
+}
+
+/// Channel an input arrived through. Stamps carry the sources of their evidence, so derived
+/// conclusions keep track of which channels they rest on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Source {
+    #[default]
+    User,
+    Sensor(String), // sensor channel name
+    Import(String), // file or import label
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::User => write!(f, "user"),
+            Source::Sensor(name) => write!(f, "sensor:{}", name),
+            Source::Import(name) => write!(f, "{}", name),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Stamp {
     pub creation_time: u64,
     pub evidence: Vec<u64>, 
+    #[serde(default)]
+    pub sources: Vec<Source>,
 }
 
 impl Stamp {
@@ -22,6 +126,13 @@ impl Stamp {
         Self {
             creation_time,
             evidence,
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn add_source(&mut self, source: Source) {
+        if !self.sources.contains(&source) {
+            self.sources.push(source);
         }
     }
 
@@ -54,9 +165,17 @@ impl Stamp {
             .unwrap()
             .as_secs();
 
+        let mut sources = self.sources.clone();
+        for source in &other.sources {
+            if !sources.contains(source) {
+                sources.push(source.clone());
+            }
+        }
+
         Stamp {
             creation_time: current_time,
             evidence: new_evidence,
+            sources,
         }
     }
 }
@@ -68,6 +187,10 @@ pub struct Sentence {
     pub punctuation: Punctuation,
     pub truth: TruthValue, 
     pub stamp: Stamp,
+    #[serde(skip)]
+    pub budget: Option<Budget>, // explicit budget of an input; derived sentences have none
+    #[serde(default)]
+    pub tense: Option<Tense>, // events occur at `stamp.creation_time`
 }
 
 impl Sentence {
@@ -77,6 +200,34 @@ impl Sentence {
             punctuation,
             truth,
             stamp,
+            budget: None,
+            tense: None,
+        }
+    }
+
+    pub fn with_tense(mut self, tense: Tense) -> Self {
+        self.tense = Some(tense);
+        self
+    }
+
+    pub fn is_event(&self) -> bool {
+        self.tense.is_some()
+    }
+
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Narsese as OpenNARS prints it, e.g. `<a --> b>. %1.00;0.90%`; questions and quests
+    /// carry no truth value.
+    pub fn to_narsese(&self) -> String {
+        let budget = self.budget.map_or(String::new(), |b| format!("${:.2};{:.2};{:.2}$ ", b.priority, b.durability, b.quality));
+        let tense = self.tense.map_or(String::new(), |t| format!(" {}", t.symbol()));
+        match self.punctuation {
+            Punctuation::Judgement | Punctuation::Goal => format!("{}{}{}{} %{:.2};{:.2}%", budget,
+                self.term, self.punctuation.symbol(), tense, self.truth.frequency, self.truth.confidence),
+            Punctuation::Question | Punctuation::Quest => format!("{}{}{}{}", budget, self.term, self.punctuation.symbol(), tense),
         }
     }
 }
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use super::control::rank_answers;
+use super::intern::InternedTerm;
+use super::memory::{Concept, Hypervector, TieBreak};
+use super::metrics::Metrics;
+use super::sentence::Sentence;
+use super::term::Term;
+
+/// Read-only view of concept memory as of the end of a cycle, see `NarsSystem::query_handle`.
+/// Concepts are shared with memory, so taking a snapshot only copies the index.
+#[derive(Debug)]
+pub struct Snapshot {
+    pub cycle: u64,
+    pub metrics: Metrics,
+    concepts: HashMap<InternedTerm, Arc<Concept>>,
+    symmetric_copulas: bool,
+    tie_break: TieBreak,
+}
+
+impl Snapshot {
+    pub(crate) fn new(cycle: u64, metrics: Metrics, concepts: HashMap<InternedTerm, Arc<Concept>>, symmetric_copulas: bool, tie_break: TieBreak) -> Self {
+        Self { cycle, metrics, concepts, symmetric_copulas, tie_break }
+    }
+
+    pub fn len(&self) -> usize {
+        self.concepts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.concepts.is_empty()
+    }
+
+    pub fn concept(&self, term: &Term) -> Option<&Concept> {
+        let term = if self.symmetric_copulas { term.with_symmetric_order() } else { term.clone() };
+        self.concepts.get(&InternedTerm::new(term)).map(|c| c.as_ref())
+    }
+
+    /// The best-ranked belief on `term`, as `NarsSystem::answer_query` gives it (mounted
+    /// packs aside).
+    pub fn belief(&self, term: &Term) -> Option<Sentence> {
+        rank_answers(&self.concept(term)?.beliefs).into_iter().next().map(|answer| answer.sentence)
+    }
+
+    /// The `n` concepts whose vectors are most similar to that of `term` (its own concept
+    /// excluded), most similar first. Unknown terms are compared by their initial vector.
+    pub fn find_similar(&self, term: &Term, n: usize) -> Vec<(Term, f32)> {
+        let own = self.concept(term).map(|c| c.term.clone());
+        let vector = self.concept(term).map_or_else(|| Hypervector::from_term_with(term, self.tie_break), |c| c.vector);
+        let mut matches: Vec<(Term, f32)> = self.concepts.values()
+            .filter(|c| own.as_ref() != Some(&c.term))
+            .map(|c| (c.term.term().clone(), vector.similarity(&c.vector)))
+            .collect();
+        matches.sort_by(|x, y| y.1.total_cmp(&x.1));
+        matches.truncate(n);
+        matches
+    }
+}
+
+/// Queries a running system from other threads without pausing it. The cycle thread
+/// publishes a new `Snapshot` every `NarsSystem::snapshot_interval` cycles; readers never
+/// block it nor each other. Cheap to clone, one per reader.
+#[derive(Clone)]
+pub struct QueryHandle {
+    current: Arc<ArcSwap<Snapshot>>,
+}
+
+impl QueryHandle {
+    pub(crate) fn new(snapshot: Snapshot) -> Self {
+        Self { current: Arc::new(ArcSwap::from_pointee(snapshot)) }
+    }
+
+    /// Whether a handle other than the system's own is still alive.
+    pub(crate) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.current) > 1
+    }
+
+    pub(crate) fn publish(&self, snapshot: Snapshot) {
+        self.current.store(Arc::new(snapshot));
+    }
+
+    /// The latest snapshot, for several queries against the same state.
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        self.current.load_full()
+    }
+
+    pub fn belief(&self, term: &Term) -> Option<Sentence> {
+        self.current.load().belief(term)
+    }
+
+    pub fn find_similar(&self, term: &Term, n: usize) -> Vec<(Term, f32)> {
+        self.current.load().find_similar(term, n)
+    }
+
+    /// Operational counters as of the latest snapshot.
+    pub fn stats(&self) -> Metrics {
+        self.current.load().metrics.clone()
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
 use nom::{
     branch::alt,
     bytes::complete::{take_while, take_while1},
@@ -8,9 +10,10 @@ use nom::{
     IResult,
     Parser,
 };
-use super::rules::{InferenceRule, TruthFunction};
-use super::term::{Term, Operator, VarType};
+use super::rules::{InferenceRule, TruthFunction, TruthRegistry, RuleMeta, DesireSelector, Precondition};
+use super::term::{declared_operator, Term, Operator, VarType};
 use super::truth;
+use super::error::NarsError;
 
 // --- Parsing Logic (Adapted from rule_loader.rs) ---
 
@@ -55,14 +58,12 @@ fn parse_sexp(input: &str) -> IResult<&str, Sexp> {
 fn parse_term_from_sexp(sexp: &Sexp) -> Option<Term> {
     match sexp {
         Sexp::Atom(s) => {
-            if s.starts_with(':') {
-                Some(Term::var_from_str(VarType::Independent, &s[1..]))
-            } else if s.starts_with("$") {
-                Some(Term::var_from_str(VarType::Independent, &s[1..]))
-            } else if s.starts_with("#") {
-                Some(Term::var_from_str(VarType::Dependent, &s[1..]))
-            } else if s.starts_with("?") {
-                Some(Term::var_from_str(VarType::Query, &s[1..]))
+            if let Some(name) = s.strip_prefix(':').or_else(|| s.strip_prefix('$')) {
+                Some(Term::var_from_str(VarType::Independent, name))
+            } else if let Some(name) = s.strip_prefix('#') {
+                Some(Term::var_from_str(VarType::Dependent, name))
+            } else if let Some(name) = s.strip_prefix('?') {
+                Some(Term::var_from_str(VarType::Query, name))
             } else {
                 Some(Term::atom_from_str(s))
             }
@@ -78,21 +79,21 @@ fn parse_term_from_sexp(sexp: &Sexp) -> Option<Term> {
             }
 
             // Check for infix notation like (:S --> :P)
-            if list.len() == 3 {
-                if let Sexp::Atom(op_str) = &list[1] {
-                    let op = match op_str.as_str() {
-                        "-->" => Some(Operator::Inheritance),
-                        "==>" => Some(Operator::Implication),
-                        "<->" => Some(Operator::Similarity),
-                        "<=>" => Some(Operator::Equivalence),
-                        _ => None,
-                    };
-                    
-                    if let Some(operator) = op {
-                        let subject = parse_term_from_sexp(&list[0])?;
-                        let predicate = parse_term_from_sexp(&list[2])?;
-                        return Some(Term::Compound(operator, vec![subject, predicate]));
-                    }
+            if list.len() == 3
+                && let Sexp::Atom(op_str) = &list[1]
+            {
+                let op = match op_str.as_str() {
+                    "-->" => Some(Operator::Inheritance),
+                    "==>" => Some(Operator::Implication),
+                    "<->" => Some(Operator::Similarity),
+                    "<=>" => Some(Operator::Equivalence),
+                    symbol => declared_operator(symbol).filter(|spec| spec.copula).map(|spec| Operator::Other(spec.symbol)),
+                };
+                
+                if let Some(operator) = op {
+                    let subject = parse_term_from_sexp(&list[0])?;
+                    let predicate = parse_term_from_sexp(&list[2])?;
+                    return Some(Term::Compound(operator, vec![subject, predicate]));
                 }
             }
 
@@ -102,10 +103,14 @@ fn parse_term_from_sexp(sexp: &Sexp) -> Option<Term> {
                     "&" => Operator::IntIntersection,
                     "|" => Operator::ExtIntersection,
                     "+" => Operator::Union,
-                    "-" => Operator::Difference,
-                    "~" => Operator::DifferenceInt,
+                    "-" if list.len() == 3 => Operator::DifferenceExt,
+                    "~" if list.len() == 3 => Operator::DifferenceInt,
+                    "-" | "~" => return None, // differences are binary
                     "--" => Operator::Negation,
-                    _ => return None, // Unknown operator
+                    symbol => match declared_operator(symbol) {
+                        Some(spec) if !spec.copula => Operator::Other(spec.symbol),
+                        _ => return None, // Unknown operator
+                    },
                 };
                 
                 let mut args = Vec::new();
@@ -120,13 +125,18 @@ fn parse_term_from_sexp(sexp: &Sexp) -> Option<Term> {
     }
 }
 
-fn parse_term_str(input: &str) -> Term {
-    let (_, sexp) = parse_sexp(input).expect(&format!("Failed to parse term string: {}", input));
-    parse_term_from_sexp(&sexp).expect(&format!("Failed to convert Sexp to Term: {}", input))
+/// Parses a rule term in the S-expression syntax, e.g. `(:S --> (& :P :M))`.
+pub fn parse_term_str(input: &str) -> Result<Term, NarsError> {
+    let (rest, sexp) = parse_sexp(input).map_err(|e| match e {
+        nom::Err::Error(err) | nom::Err::Failure(err) => NarsError::parse(input, err.input, "Failed to parse term string"),
+        nom::Err::Incomplete(_) => NarsError::parse(input, "", "Failed to parse term string"),
+    })?;
+    parse_term_from_sexp(&sexp).ok_or_else(|| NarsError::parse(input, rest, "Failed to convert Sexp to Term"))
 }
 
-fn get_truth_fn(name: &str) -> TruthFunction {
-    match name {
+/// Looks up a built-in truth function by name.
+pub fn get_truth_fn(name: &str) -> Result<TruthFunction, NarsError> {
+    let truth_fn = match name {
         "deduction" => TruthFunction::Double(truth::deduction),
         "abduction" => TruthFunction::Double(truth::abduction),
         "induction" => TruthFunction::Double(truth::induction),
@@ -141,97 +151,246 @@ fn get_truth_fn(name: &str) -> TruthFunction {
         "union" => TruthFunction::Double(truth::union),
         "difference" => TruthFunction::Double(truth::difference),
         "decomposition" => TruthFunction::Double(truth::decompose_ppp),
+        "decompose_pnp" => TruthFunction::Double(truth::decompose_pnp),
+        "decompose_nnn" => TruthFunction::Double(truth::decompose_nnn),
         "reduce_disjunction" => TruthFunction::Double(truth::reduce_disjunction),
         "structural_deduction" => TruthFunction::Single(truth::structural_deduction),
-        _ => panic!("Unknown truth function: {}", name),
+        _ => return Err(NarsError::rule_load(name, "Unknown truth function")),
+    };
+    Ok(truth_fn)
+}
+
+/// A rule file line that was not loaded because it uses something the engine does not support.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRule {
+    pub line: String,
+    pub reason: String,
+}
+
+/// Outcome of loading a rule file into a system.
+#[derive(Debug, Clone, Default)]
+pub struct RuleLoadReport {
+    pub loaded: usize,
+    pub skipped: Vec<SkippedRule>,
+    /// Rules already present (same premises, conclusion, truth function and annotations up to
+    /// variable names); not added again.
+    pub duplicates: Vec<SkippedRule>,
+    /// Rules with the premises and conclusion of a present rule but another truth function or
+    /// other annotations; the present rule is kept.
+    pub conflicts: Vec<SkippedRule>,
+}
+
+/// Drops the rules of `incoming` that `existing` (or an earlier incoming rule) already has,
+/// recording them as duplicates or conflicts in `report`; returns the rules to add.
+pub fn dedup_rules(existing: &[InferenceRule], incoming: Vec<InferenceRule>, report: &mut RuleLoadReport) -> Vec<InferenceRule> {
+    let mut known: HashMap<Term, usize> = HashMap::new();
+    for (idx, rule) in existing.iter().enumerate() {
+        known.entry(rule.signature()).or_insert(idx);
     }
+    let mut added: Vec<InferenceRule> = Vec::new();
+    for rule in incoming {
+        let signature = rule.signature();
+        let Some(&idx) = known.get(&signature) else {
+            known.insert(signature, existing.len() + added.len());
+            added.push(rule);
+            continue;
+        };
+        let present = existing.get(idx).unwrap_or_else(|| &added[idx - existing.len()]);
+        let line = rule.to_string();
+        if present.name == rule.name && present.meta == rule.meta {
+            report.duplicates.push(SkippedRule { line, reason: format!("duplicate of rule #{}", idx) });
+        } else {
+            report.conflicts.push(SkippedRule { line, reason: format!("conflicts with rule #{} ({})", idx, present) });
+        }
+    }
+    added
 }
 
-// --- Macro and Rules ---
+/// Parses rule definitions, one per line: premises, `!-`, conclusion, a truth function and
+/// optional annotations, e.g. `(:M --> :P) (:S --> :M) !- (:S --> :P) deduction :d/strong`.
+/// The truth function is a name resolved through `registry`, or a `:t/` selector such as
+/// `:t/belief-structural-deduction`. Supported annotations are `:d/strong`, `:d/weak`,
+/// `:question?`, `:allow-backward` and `:pre ((:!= a b) ...)`; lines using any other are
+/// skipped and returned instead of being loaded. Blank lines and `;` comments are skipped.
+pub fn parse_rules(text: &str, registry: &TruthRegistry) -> Result<(Vec<InferenceRule>, Vec<SkippedRule>), NarsError> {
+    let mut rules = Vec::new();
+    let mut skipped = Vec::new();
+    for line in text.lines() {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (lhs, rhs) = line.split_once("!-").ok_or_else(|| NarsError::rule_load(line, "Missing '!-'"))?;
 
-macro_rules! rule {
-    ($p1:literal !- $conc:literal $truth:literal) => {
-        InferenceRule {
-            name: $truth.to_string(),
-            premises: vec![parse_term_str($p1)],
-            conclusion: parse_term_str($conc),
-            truth_fn: get_truth_fn($truth),
+        let (rest, premises) = many0(parse_sexp).parse(lhs).map_err(|_| NarsError::rule_load(line, "Invalid premises"))?;
+        if !rest.trim().is_empty() || premises.is_empty() {
+            return Err(NarsError::rule_load(line, "Invalid premises"));
         }
-    };
-    ($p1:literal $p2:literal !- $conc:literal $truth:literal) => {
-        InferenceRule {
-            name: $truth.to_string(),
-            premises: vec![parse_term_str($p1), parse_term_str($p2)],
-            conclusion: parse_term_str($conc),
-            truth_fn: get_truth_fn($truth),
+        let (rest, conclusion) = parse_sexp(rhs).map_err(|_| NarsError::rule_load(line, "Invalid conclusion"))?;
+        let (rest, annotations) = many0(parse_sexp).parse(rest).map_err(|_| NarsError::rule_load(line, "Invalid annotations"))?;
+        if !rest.trim().is_empty() {
+            return Err(NarsError::rule_load(line, "Invalid annotations"));
+        }
+        let Some((Sexp::Atom(truth_name), annotations)) = annotations.split_first() else {
+            return Err(NarsError::rule_load(line, "Expected a truth function name"));
+        };
+
+        let truth_fn = match truth_name.strip_prefix(":t/") {
+            Some(selector) => {
+                let name = selector.strip_prefix("belief-").unwrap_or(selector).replace('-', "_");
+                match registry.get(&name) {
+                    Ok(truth_fn) => truth_fn,
+                    Err(_) => {
+                        skipped.push(SkippedRule { line: line.to_string(), reason: format!("unsupported truth selector {}", truth_name) });
+                        continue;
+                    }
+                }
+            }
+            None => registry.get(truth_name)?,
+        };
+        let meta = match parse_annotations(annotations) {
+            Ok(meta) => meta,
+            Err(reason) => {
+                skipped.push(SkippedRule { line: line.to_string(), reason });
+                continue;
+            }
+        };
+
+        let to_term = |sexp: &Sexp| parse_term_from_sexp(sexp).ok_or_else(|| NarsError::rule_load(line, "Failed to convert Sexp to Term"));
+        rules.push(InferenceRule {
+            name: truth_name.trim_start_matches(":t/").to_string(),
+            premises: premises.iter().map(to_term).collect::<Result<_, _>>()?,
+            conclusion: to_term(&conclusion)?,
+            truth_fn,
+            meta,
+        });
+    }
+    Ok((rules, skipped))
+}
+
+fn parse_annotations(annotations: &[Sexp]) -> Result<RuleMeta, String> {
+    let mut meta = RuleMeta::default();
+    let mut iter = annotations.iter();
+    while let Some(annotation) = iter.next() {
+        match annotation {
+            Sexp::Atom(a) if a == ":d/strong" => meta.desire = Some(DesireSelector::Strong),
+            Sexp::Atom(a) if a == ":d/weak" => meta.desire = Some(DesireSelector::Weak),
+            Sexp::Atom(a) if a == ":question?" => meta.question_only = true,
+            Sexp::Atom(a) if a == ":allow-backward" => meta.allow_backward = true,
+            Sexp::Atom(a) if a == ":pre" => {
+                let Some(Sexp::List(conditions)) = iter.next() else {
+                    return Err(":pre expects a list of conditions".to_string());
+                };
+                for condition in conditions {
+                    meta.preconditions.push(parse_precondition(condition)?);
+                }
+            }
+            Sexp::Atom(a) => return Err(format!("unsupported annotation {}", a)),
+            Sexp::List(_) => return Err("unexpected list in annotations".to_string()),
         }
+    }
+    Ok(meta)
+}
+
+fn parse_precondition(condition: &Sexp) -> Result<Precondition, String> {
+    if let Sexp::List(parts) = condition
+        && let [Sexp::Atom(op), a, b] = parts.as_slice()
+        && op == ":!="
+        && let (Some(a), Some(b)) = (parse_term_from_sexp(a), parse_term_from_sexp(b))
+    {
+        return Ok(Precondition::NotEqual(a, b));
+    }
+    Err(format!("unsupported precondition {:?}", condition))
+}
+
+// --- Macro and Rules ---
+
+/// Truth function argument of [`build_rule`]: a built-in name or a custom function.
+pub trait IntoTruthFunction {
+    fn rule_name(&self) -> String;
+    fn into_truth_fn(self) -> Result<TruthFunction, NarsError>;
+}
+
+impl IntoTruthFunction for &str {
+    fn rule_name(&self) -> String {
+        self.to_string()
+    }
+
+    fn into_truth_fn(self) -> Result<TruthFunction, NarsError> {
+        get_truth_fn(self)
+    }
+}
+
+impl IntoTruthFunction for TruthFunction {
+    fn rule_name(&self) -> String {
+        "custom".to_string()
+    }
+
+    fn into_truth_fn(self) -> Result<TruthFunction, NarsError> {
+        Ok(self)
+    }
+}
+
+/// Builds a rule from S-expression premises and conclusion.
+pub fn build_rule(premises: &[&str], conclusion: &str, truth: impl IntoTruthFunction) -> Result<InferenceRule, NarsError> {
+    Ok(InferenceRule {
+        name: truth.rule_name(),
+        premises: premises.iter().map(|p| parse_term_str(p)).collect::<Result<_, _>>()?,
+        conclusion: parse_term_str(conclusion)?,
+        truth_fn: truth.into_truth_fn()?,
+        meta: RuleMeta::default(),
+    })
+}
+
+/// Defines an inference rule in the same notation as the built-in rule table:
+///
+/// ```
+/// use hybrid_nars_rust::nars_rule;
+///
+/// let rule = nars_rule!("(:M --> :P)" "(:S --> :M)" !- "(:S --> :P)" "deduction").unwrap();
+/// assert_eq!(rule.premises.len(), 2);
+/// ```
+///
+/// The truth function is either a built-in name or a `TruthFunction` value.
+/// Evaluates to `Result<InferenceRule, NarsError>`.
+#[macro_export]
+macro_rules! nars_rule {
+    ($($premise:literal)+ !- $conc:literal $truth:expr) => {
+        $crate::nars::build_rule(&[$($premise),+], $conc, $truth)
     };
 }
 
+/// The canonical rule table, embedded at build time. Rules loaded at runtime (see
+/// `NarsSystem::load_rules`) use the same format and extend or override it.
+pub const BUILTIN_RULES: &str = include_str!("../../assets/rules.lisp");
+
+/// The built-in rules, parsed once on first use: the offending line and reason if one does not
+/// load (`test_builtin_rules_load` keeps the table valid).
+static BUILTIN: LazyLock<Result<Vec<InferenceRule>, (String, String)>> = LazyLock::new(|| {
+    let (rules, skipped) = parse_rules(BUILTIN_RULES, &TruthRegistry::new())
+        .map_err(|e| ("assets/rules.lisp".to_string(), e.to_string()))?;
+    match skipped.into_iter().next() {
+        Some(rule) => Err((rule.line, rule.reason)),
+        None => Ok(rules),
+    }
+});
+
 pub fn get_all_rules() -> Vec<InferenceRule> {
-    let mut rules = Vec::new();
+    load_rules().expect("built-in rules are valid")
+}
 
-    // --- IMMEDIATE INFERENCE ---
-    rules.push(rule!("(-- :M)"                  !- "(:M)"                    "negation"));
-    rules.push(rule!("(:S --> :P)"              !- "(:P --> :S)"             "conversion"));
-    rules.push(rule!("(:S ==> :P)"              !- "(:P ==> :S)"             "conversion"));
-    rules.push(rule!("(:S ==> :P)"              !- "((-- :P) ==> (-- :S))"   "contraposition"));
-
-    // --- SYLLOGISMS (NAL-1) ---
-    rules.push(rule!("(:M --> :P)" "(:S --> :M)"  !- "(:S --> :P)"             "deduction"));
-    rules.push(rule!("(:P --> :M)" "(:S --> :M)"  !- "(:S --> :P)"             "abduction"));
-    rules.push(rule!("(:M --> :P)" "(:M --> :S)"  !- "(:S --> :P)"             "induction"));
-    rules.push(rule!("(:P --> :M)" "(:M --> :S)"  !- "(:S --> :P)"             "exemplification"));
-
-    // --- SYLLOGISMS (NAL-2) ---
-    rules.push(rule!("(:S --> :P)" "(:P --> :S)"  !- "(:P <-> :S)"             "intersection"));
-    rules.push(rule!("(:M --> :P)" "(:S <-> :M)"  !- "(:S --> :P)"             "analogy"));
-    rules.push(rule!("(:P --> :M)" "(:S <-> :M)"  !- "(:P --> :S)"             "analogy"));
-    rules.push(rule!("(:M <-> :P)" "(:S <-> :M)"  !- "(:P <-> :S)"             "resemblance"));
-
-    // --- HIGHER ORDER (NAL-5) ---
-    rules.push(rule!("(:M ==> :P)" "(:S ==> :M)"  !- "(:S ==> :P)"             "deduction"));
-    rules.push(rule!("(:P ==> :M)" "(:S ==> :M)"  !- "(:S ==> :P)"             "abduction"));
-    rules.push(rule!("(:M ==> :P)" "(:M ==> :S)"  !- "(:S ==> :P)"             "induction"));
-    rules.push(rule!("(:S ==> :P)" "(:P ==> :S)"  !- "(:S <=> :P)"             "intersection"));
-    rules.push(rule!("(:M ==> :P)" "(:S <=> :M)"  !- "(:S ==> :P)"             "analogy"));
-    rules.push(rule!("(:M <=> :P)" "(:S <=> :M)"  !- "(:S <=> :P)"             "resemblance"));
-
-    // --- VARIABLES (NAL-6) ---
-    rules.push(rule!("(:S --> :M)" "(:P --> :M)"  !- "((:P --> $X) ==> (:S --> $X))" "abduction"));
-    rules.push(rule!("(:S --> :M)" "(:P --> :M)"  !- "((:S --> $X) ==> (:P --> $X))" "induction"));
-    rules.push(rule!("(:M --> :S)" "(:M --> :P)"  !- "(($X --> :S) ==> ($X --> :P))" "induction"));
-    rules.push(rule!("(:M --> :S)" "(:M --> :P)"  !- "(($X --> :P) ==> ($X --> :S))" "abduction"));
-
-    // --- SETS & COMPOSITION (NAL-3) ---
-    // Intersection (&)
-    rules.push(rule!("(:P --> :M) (:S --> :M)" !- "((& :S :P) --> :M)" "intersection"));
-    rules.push(rule!("(:M --> :P) (:M --> :S)" !- "(:M --> (& :P :S))" "intersection"));
-    
-    // Union (+) - mapped to 'union' truth fn
-    rules.push(rule!("(:P --> :M) (:S --> :M)" !- "((+ :S :P) --> :M)" "union"));
-    rules.push(rule!("(:M --> :P) (:M --> :S)" !- "(:M --> (+ :P :S))" "union"));
-    
-    // Difference (-) and (~)
-    rules.push(rule!("(:P --> :M) (:S --> :M)" !- "((~ :P :S) --> :M)" "difference"));
-    rules.push(rule!("(:M --> :P) (:M --> :S)" !- "(:M --> (- :P :S))" "difference"));
-
-    // --- DECOMPOSITION (NAL-3) ---
-    // Simplification for Sets
-    rules.push(rule!("(:S --> :M) ((& :S :P) --> :M)" !- "(:P --> :M)" "decomposition"));
-    rules.push(rule!("(:M --> :S) (:M --> (& :S :P))" !- "(:M --> :P)" "decomposition"));
-
-    // Disjunction Decomposition
-    rules.push(rule!("(:S --> (| :P :M)) (:S --> :M)" !- "(:S --> :P)" "reduce_disjunction"));
-    rules.push(rule!("(:S --> (| :M :P)) (:S --> :M)" !- "(:S --> :P)" "reduce_disjunction"));
-
-    // Structural Decomposition (Single Premise)
-    rules.push(rule!("((| :S :P) --> :M)" !- "(:S --> :M)" "structural_deduction"));
-    rules.push(rule!("((| :P :S) --> :M)" !- "(:S --> :M)" "structural_deduction"));
-    rules.push(rule!("(:M --> (& :S :P))" !- "(:M --> :S)" "structural_deduction"));
-    rules.push(rule!("(:M --> (& :P :S))" !- "(:M --> :S)" "structural_deduction"));
-    rules.push(rule!("(:M --> (| :S :P))" !- "(:M --> :S)" "structural_deduction"));
-    rules.push(rule!("(:M --> (| :P :S))" !- "(:M --> :S)" "structural_deduction"));
-
-    rules
+pub fn load_rules() -> Result<Vec<InferenceRule>, NarsError> {
+    BUILTIN.clone().map_err(|(rule, reason)| NarsError::rule_load(&rule, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules_load() {
+        let (rules, skipped) = parse_rules(BUILTIN_RULES, &TruthRegistry::new()).unwrap();
+        assert!(skipped.is_empty(), "{:?}", skipped);
+        assert_eq!(load_rules().unwrap().len(), rules.len());
+    }
 }
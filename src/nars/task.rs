@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use rand::Rng;
+use super::bag::Bag;
+use super::sentence::{Sentence, Punctuation};
+use super::term::Term;
+use super::truth::{revision, TruthValue};
+
+const INTERNAL_QUESTION_WEIGHT: f32 = 0.5; // questions nobody asked (curiosity) against user questions
+const DERIVATION_WEIGHT: f32 = 0.1; // demand per statement an answer would take part in
+const MAX_DEMAND_BOOST: f32 = 2.0;
+const MIN_UNCERTAINTY: f32 = 0.1; // answered questions still get refinement effort
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub sentence: Sentence,
+    pub priority: f32,
+    pub expanded: bool, // a goal whose activation was spread and subgoals derived
+}
+
+impl Task {
+    pub fn new(sentence: Sentence, priority: f32) -> Self {
+        Self { sentence, priority, expanded: false }
+    }
+}
+
+/// A bag of pending tasks of one punctuation type.
+/// Tasks are keyed by id so that two sentences with the same term (e.g. for revision) can coexist.
+#[derive(Debug, Clone)]
+pub struct TaskLane {
+    pub bag: Bag<u64>,
+    pub tasks: HashMap<u64, Task>,
+}
+
+impl TaskLane {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            bag: Bag::new(capacity),
+            tasks: HashMap::new(),
+        }
+    }
+
+    pub fn put(&mut self, id: u64, task: Task) {
+        let priority = task.priority;
+        self.put_weighted(id, task, priority);
+    }
+
+    /// Queues `task` to be taken with probability proportional to `weight` rather than to its
+    /// priority, e.g. the information value of a question.
+    pub fn put_weighted(&mut self, id: u64, task: Task, weight: f32) {
+        // Evict here rather than inside the bag so the task table stays in sync
+        if self.bag.count >= self.bag.capacity
+            && let Some(weak_id) = self.bag.take_weakest()
+        {
+            self.tasks.remove(&weak_id);
+        }
+        self.bag.put(id, weight.clamp(0.01, 0.99));
+        self.tasks.insert(id, task);
+    }
+
+    pub fn take(&mut self, rng: &mut impl Rng) -> Option<(u64, Task)> {
+        let id = self.bag.take(rng)?;
+        self.tasks.remove(&id).map(|task| (id, task))
+    }
+
+    /// Revises `sentence` into a pending task with the same term instead of queueing it: the
+    /// truth of judgements and goals is revised (if their evidence does not overlap) and the
+    /// task keeps the higher priority. Returns false if there is no such task.
+    pub fn merge(&mut self, sentence: &Sentence, priority: f32) -> bool {
+        let revisable = matches!(sentence.punctuation, Punctuation::Judgement | Punctuation::Goal);
+        let Some((&id, task)) = self.tasks.iter_mut().find(|(_, t)| t.sentence.term == sentence.term
+            && !(revisable && t.sentence.stamp.overlaps(&sentence.stamp))) else {
+            return false;
+        };
+        if revisable {
+            task.sentence.truth = revision(task.sentence.truth, sentence.truth);
+            task.sentence.stamp = task.sentence.stamp.merge(&sentence.stamp);
+        }
+        task.priority = task.priority.max(priority);
+        let weight = self.bag.name_map.get(&id).copied().unwrap_or(0.0).max(priority);
+        self.bag.put(id, weight.clamp(0.01, 0.99));
+        true
+    }
+
+    /// Drops every pending task whose term satisfies `pred`; returns how many were dropped.
+    pub fn remove_where(&mut self, pred: impl Fn(&Term) -> bool) -> usize {
+        let ids: Vec<u64> = self.tasks.iter().filter(|(_, t)| pred(&t.sentence.term)).map(|(id, _)| *id).collect();
+        for id in &ids {
+            self.bag.remove(id);
+            self.tasks.remove(id);
+        }
+        ids.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskLanes {
+    pub judgements: TaskLane,
+    pub questions: TaskLane,
+    pub goals: TaskLane,
+    pub quests: TaskLane,
+}
+
+impl TaskLanes {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            judgements: TaskLane::new(capacity),
+            questions: TaskLane::new(capacity),
+            goals: TaskLane::new(capacity),
+            quests: TaskLane::new(capacity),
+        }
+    }
+
+    pub fn lane_mut(&mut self, punctuation: Punctuation) -> &mut TaskLane {
+        match punctuation {
+            Punctuation::Judgement => &mut self.judgements,
+            Punctuation::Question => &mut self.questions,
+            Punctuation::Goal => &mut self.goals,
+            Punctuation::Quest => &mut self.quests,
+        }
+    }
+
+    pub fn lane(&self, punctuation: Punctuation) -> &TaskLane {
+        match punctuation {
+            Punctuation::Judgement => &self.judgements,
+            Punctuation::Question => &self.questions,
+            Punctuation::Goal => &self.goals,
+            Punctuation::Quest => &self.quests,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.judgements.len() + self.questions.len() + self.goals.len() + self.quests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// What becomes of an input `AdmissionControl` does not admit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    Drop,
+    /// Revised into a pending task on the same term (see `TaskLane::merge`), dropped if there is none.
+    #[default]
+    Merge,
+}
+
+/// Admission of inputs arriving faster than cycles process them (a stream, a pipe). Inputs are
+/// admitted freely until `rate` have arrived in the current cycle or `max_pending` tasks are
+/// queued; beyond that only inputs with at least `min_priority` are, and the rest are merged
+/// or dropped according to `overflow`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdmissionControl {
+    pub rate: usize,        // inputs per cycle admitted freely (0 disables the limit)
+    pub max_pending: usize, // queued tasks from which inputs are no longer admitted freely (0 disables)
+    pub min_priority: f32,  // inputs with at least this priority are always admitted
+    pub overflow: Overflow,
+}
+
+impl Default for AdmissionControl {
+    fn default() -> Self {
+        Self { rate: 0, max_pending: 0, min_priority: 0.8, overflow: Overflow::default() }
+    }
+}
+
+impl AdmissionControl {
+    /// Whether an input of `priority` is queued, with `pending` tasks queued and `arrived`
+    /// inputs already received this cycle.
+    pub fn admits(&self, priority: f32, pending: usize, arrived: usize) -> bool {
+        let throttled = (self.rate > 0 && arrived >= self.rate) || (self.max_pending > 0 && pending >= self.max_pending);
+        !throttled || priority >= self.min_priority
+    }
+}
+
+/// What admission control did with the inputs received so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackpressureStats {
+    pub admitted: u64,
+    pub merged: u64,         // revised into a pending task
+    pub dropped: u64,
+    pub peak_pending: usize, // most tasks queued when an input arrived
+}
+
+impl BackpressureStats {
+    /// Share of the inputs that were not queued as tasks of their own.
+    pub fn pressure(&self) -> f32 {
+        let total = self.admitted + self.merged + self.dropped;
+        if total == 0 { 0.0 } else { (self.merged + self.dropped) as f32 / total as f32 }
+    }
+}
+
+/// What the information value of a pending question is judged by, see `QuestionScorer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuestionContext {
+    pub priority: f32,              // the question task's budget
+    pub answer: Option<TruthValue>, // best answer reported so far
+    pub blocked_goals: f32,         // summed desire expectation of goals waiting on the answer
+    pub blocked_derivations: usize, // statements in memory an answer would be chained with
+    pub asked_by_user: bool,        // asked through an input channel rather than raised internally
+}
+
+/// Weighs a question in the question lane: the lane picks questions with probability
+/// proportional to the score (clamped to 0.01..0.99). Replace `NarsSystem::question_scorer`
+/// to customize what the reasoner spends its question cycles on.
+pub type QuestionScorer = fn(&QuestionContext) -> f32;
+
+/// Default `QuestionScorer`: expected information gain. The budget is scaled by how uncertain
+/// the best answer is (unanswered = 1) and boosted by the goals and derivations that wait on
+/// the answer; questions raised internally get half the weight of asked ones.
+pub fn information_value(context: &QuestionContext) -> f32 {
+    let uncertainty = context.answer.map_or(1.0, |t| 1.0 - t.confidence).max(MIN_UNCERTAINTY);
+    let demand = context.blocked_goals + DERIVATION_WEIGHT * context.blocked_derivations as f32;
+    let origin = if context.asked_by_user { 1.0 } else { INTERNAL_QUESTION_WEIGHT };
+    context.priority * uncertainty * (1.0 + demand).min(MAX_DEMAND_BOOST) * origin
+}
+
+/// How many cycles each lane gets out of every `total()` cycles.
+#[derive(Debug, Clone, Copy)]
+pub struct LaneRatios {
+    pub judgement: u32,
+    pub question: u32,
+    pub goal: u32,
+    pub quest: u32,
+}
+
+impl Default for LaneRatios {
+    fn default() -> Self {
+        Self { judgement: 4, question: 2, goal: 1, quest: 1 }
+    }
+}
+
+impl LaneRatios {
+    pub const ORDER: [Punctuation; 4] = [
+        Punctuation::Judgement,
+        Punctuation::Question,
+        Punctuation::Goal,
+        Punctuation::Quest,
+    ];
+
+    pub fn weight(&self, punctuation: Punctuation) -> u32 {
+        match punctuation {
+            Punctuation::Judgement => self.judgement,
+            Punctuation::Question => self.question,
+            Punctuation::Goal => self.goal,
+            Punctuation::Quest => self.quest,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        Self::ORDER.iter().map(|p| self.weight(*p)).sum()
+    }
+
+    /// Lane scheduled for the given cycle (weighted round-robin).
+    pub fn lane_for(&self, cycle: u64) -> Punctuation {
+        let total = self.total();
+        if total == 0 {
+            return Punctuation::Judgement;
+        }
+        let mut slot = (cycle % total as u64) as u32;
+        for p in Self::ORDER {
+            let w = self.weight(p);
+            if slot < w {
+                return p;
+            }
+            slot -= w;
+        }
+        Punctuation::Judgement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nars::sentence::Stamp;
+
+    #[test]
+    fn test_lane_ratios_interleave() {
+        let ratios = LaneRatios { judgement: 2, question: 1, goal: 0, quest: 1 };
+        let schedule: Vec<Punctuation> = (0..4).map(|c| ratios.lane_for(c)).collect();
+        assert_eq!(schedule, vec![
+            Punctuation::Judgement,
+            Punctuation::Judgement,
+            Punctuation::Question,
+            Punctuation::Quest,
+        ]);
+    }
+
+    #[test]
+    fn test_lane_keeps_same_term_tasks() {
+        let mut lane = TaskLane::new(2);
+        let term = Term::atom_from_str("bird");
+        let sentence = |f: f32| Sentence::new(term.clone(), Punctuation::Judgement, TruthValue::new(f, 0.9), Stamp::new(0, vec![]));
+        lane.put(1, Task::new(sentence(1.0), 0.5));
+        lane.put(2, Task::new(sentence(0.0), 0.5));
+        assert_eq!(lane.len(), 2);
+
+        // Overflow evicts from both the bag and the task table
+        lane.put(3, Task::new(sentence(0.5), 0.9));
+        assert_eq!(lane.len(), 2);
+        assert_eq!(lane.bag.count, 2);
+    }
+
+    #[test]
+    fn test_information_value() {
+        let open = QuestionContext { priority: 0.5, answer: None, blocked_goals: 0.0, blocked_derivations: 0, asked_by_user: true };
+        let value = information_value(&open);
+        assert_eq!(value, 0.5);
+
+        // Confident answers, internal origin lower it; waiting goals raise it up to a cap
+        assert!(information_value(&QuestionContext { answer: Some(TruthValue::new(1.0, 0.8)), ..open }) < value * 0.25);
+        assert_eq!(information_value(&QuestionContext { asked_by_user: false, ..open }), value * 0.5);
+        assert!(information_value(&QuestionContext { blocked_goals: 0.6, blocked_derivations: 2, ..open }) > value * 1.5);
+        assert_eq!(information_value(&QuestionContext { blocked_goals: 10.0, ..open }), value * 2.0);
+    }
+}
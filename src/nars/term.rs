@@ -1,24 +1,29 @@
-use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use arc_swap::ArcSwapOption;
 use serde::{Serialize, Deserialize};
+use super::error::NarsError;
 
 // Deterministic hash function (FNV-1a)
 pub fn deterministic_hash(s: &str) -> u64 {
     let mut hash: u64 = 0xcbf29ce484222325;
     for byte in s.bytes() {
-        hash = hash ^ (byte as u64);
+        hash ^= byte as u64;
         hash = hash.wrapping_mul(0x100000001b3);
     }
     hash
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum VarType {
     Independent, // $
     Dependent,   // #
     Query,       // ?
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Operator {
     Inheritance,      // -->
     Implication,      // ==>
@@ -30,7 +35,7 @@ pub enum Operator {
     Product,          // *
     ExtIntersection,  // |
     IntIntersection,  // &
-    Difference,       // -
+    DifferenceExt,    // -
     DifferenceInt,    // ~
     Union,            // +
     ExtSet,           // {}
@@ -50,14 +55,216 @@ pub enum Operator {
     SequentialEvents, // &/
     List,             // #
     Op,               // ^
-    Other(String),
+    Other(String),    // `^name` operations and operators declared with `declare_operator`
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Arity and symmetry of a user-declared operator, see `declare_operator`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorSpec {
+    pub symbol: String,
+    pub copula: bool,    // a statement, written `<S symbol P>` (and `(symbol, S, P)`)
+    pub symmetric: bool, // argument order carries no meaning
+    pub arity: (usize, Option<usize>),
+}
+
+impl OperatorSpec {
+    /// A copula; the sides of a symmetric one can be swapped, as with `<->`.
+    pub fn copula(symbol: &str, symmetric: bool) -> Self {
+        Self { symbol: symbol.to_string(), copula: true, symmetric, arity: (2, Some(2)) }
+    }
+
+    /// A compound operator written `(symbol, args..)`; the arguments of a commutative one are
+    /// sorted and deduplicated, and matched regardless of order, as with `&&`.
+    pub fn compound(symbol: &str, arity: (usize, Option<usize>), commutative: bool) -> Self {
+        Self { symbol: symbol.to_string(), copula: false, symmetric: commutative, arity }
+    }
+}
+
+/// Terms are plain values, parsed, printed and normalized without a system at hand, so the
+/// declared operators are shared by the whole process. Readers (every `is_commutative`,
+/// `arity` or `is_statement` on a declared operator) load them without locking; declarations
+/// replace the whole list, one at a time.
+static DECLARED_OPERATORS: ArcSwapOption<Vec<OperatorSpec>> = ArcSwapOption::const_empty();
+static DECLARING: Mutex<()> = Mutex::new(());
+
+const BUILTIN_OPERATORS: [Operator; 30] = [
+    Operator::Inheritance, Operator::Implication, Operator::Similarity, Operator::Equivalence, Operator::Instance,
+    Operator::Property, Operator::InstanceProperty, Operator::Product, Operator::ExtIntersection, Operator::IntIntersection,
+    Operator::DifferenceExt, Operator::DifferenceInt, Operator::Union, Operator::ExtSet, Operator::IntSet,
+    Operator::Negation, Operator::Conjunction, Operator::Disjunction, Operator::ExtImage, Operator::IntImage,
+    Operator::ConcurrentImplication, Operator::PredictiveImplication, Operator::RetrospectiveImplication,
+    Operator::ConcurrentEquivalence, Operator::PredictiveEquivalence, Operator::RetrospectiveEquivalence,
+    Operator::ParallelEvents, Operator::SequentialEvents, Operator::List, Operator::Op,
+];
+
+/// Declares a custom operator for every parser, rule file and system of the process. Terms
+/// with it (`Operator::Other(symbol)`) are then parsed, checked against its arity, normalized,
+/// unified and printed according to `spec`. Declaring a symbol again with the same spec does
+/// nothing; built-in symbols and conflicting redeclarations are errors.
+///
+/// Undeclared operators other than `^name` operations are rejected by `Term::compound`.
+pub fn declare_operator(spec: OperatorSpec) -> Result<(), NarsError> {
+    let symbol = &spec.symbol;
+    let invalid = symbol.is_empty() || symbol.starts_with(['^', '$', '#', '?', '+'])
+        || symbol.chars().any(|c| c.is_whitespace() || "(){}[],\"'%.!@;:".contains(c));
+    if invalid || BUILTIN_OPERATORS.iter().any(|op| op.symbol() == symbol) {
+        return Err(NarsError::InvalidTerm(format!("{} cannot be declared as an operator", symbol)));
+    }
+    if spec.copula && spec.arity != (2, Some(2)) {
+        return Err(NarsError::InvalidTerm(format!("copula {} must take 2 arguments", symbol)));
+    }
+    let _declaring = DECLARING.lock().unwrap_or_else(|e| e.into_inner());
+    let mut declared = declared_operators().as_ref().clone();
+    match declared.iter().find(|d| d.symbol == *symbol) {
+        Some(existing) if *existing == spec => Ok(()),
+        Some(_) => Err(NarsError::InvalidTerm(format!("{} is already declared differently", symbol))),
+        None => {
+            declared.push(spec);
+            // Longest first, so the parser prefers `=>>` over `=>`
+            declared.sort_by_key(|d| std::cmp::Reverse(d.symbol.len()));
+            DECLARED_OPERATORS.store(Some(Arc::new(declared)));
+            Ok(())
+        }
+    }
+}
+
+/// The declaration of `symbol`, if it was declared.
+pub fn declared_operator(symbol: &str) -> Option<OperatorSpec> {
+    with_declaration(symbol, OperatorSpec::clone)
+}
+
+/// Every declared operator, longest symbol first.
+pub fn declared_operators() -> Arc<Vec<OperatorSpec>> {
+    DECLARED_OPERATORS.load_full().unwrap_or_default()
+}
+
+/// `f` applied to the declaration of `symbol`, if it was declared. `^name` operations are
+/// never declared, so they are not looked up.
+fn with_declaration<T>(symbol: &str, f: impl FnOnce(&OperatorSpec) -> T) -> Option<T> {
+    if symbol.starts_with('^') {
+        return None;
+    }
+    DECLARED_OPERATORS.load().as_ref()?.iter().find(|d| d.symbol == symbol).map(f)
+}
+
+impl Operator {
+    /// Narsese spelling of the operator.
+    pub fn symbol(&self) -> &str {
+        match self {
+            Operator::Inheritance => "-->",
+            Operator::Implication => "==>",
+            Operator::Similarity => "<->",
+            Operator::Equivalence => "<=>",
+            Operator::Instance => "{--",
+            Operator::Property => "--]",
+            Operator::InstanceProperty => "{-]",
+            Operator::Product => "*",
+            Operator::ExtIntersection => "|",
+            Operator::IntIntersection => "&",
+            Operator::DifferenceExt => "-",
+            Operator::DifferenceInt => "~",
+            Operator::Union => "+",
+            Operator::ExtSet => "{}",
+            Operator::IntSet => "[]",
+            Operator::Negation => "--",
+            Operator::Conjunction => "&&",
+            Operator::Disjunction => "||",
+            Operator::ExtImage => "/",
+            Operator::IntImage => "\\",
+            Operator::ConcurrentImplication => "=|>",
+            Operator::PredictiveImplication => "=/>",
+            Operator::RetrospectiveImplication => "=\\>",
+            Operator::ConcurrentEquivalence => "<|>",
+            Operator::PredictiveEquivalence => "</>",
+            Operator::RetrospectiveEquivalence => "<\\>",
+            Operator::ParallelEvents => "&|",
+            Operator::SequentialEvents => "&/",
+            Operator::List => "#",
+            Operator::Op => "^",
+            Operator::Other(s) => s,
+        }
+    }
+
+    /// Operators whose argument order carries no meaning.
+    pub fn is_commutative(&self) -> bool {
+        if let Operator::Other(name) = self {
+            return with_declaration(name, |d| d.symmetric).unwrap_or(false);
+        }
+        matches!(self,
+            Operator::Similarity | Operator::Equivalence | Operator::ConcurrentEquivalence |
+            Operator::ExtSet | Operator::IntSet | Operator::ExtIntersection | Operator::IntIntersection |
+            Operator::Union | Operator::Conjunction | Operator::Disjunction | Operator::ParallelEvents)
+    }
+
+    /// The NAL layer that introduces the operator (1-8).
+    pub fn nal_level(&self) -> u8 {
+        match self {
+            Operator::Inheritance => 1,
+            Operator::Similarity | Operator::Instance | Operator::Property | Operator::InstanceProperty |
+            Operator::ExtSet | Operator::IntSet => 2,
+            Operator::ExtIntersection | Operator::IntIntersection | Operator::DifferenceExt | Operator::DifferenceInt |
+            Operator::Union => 3,
+            Operator::Product | Operator::ExtImage | Operator::IntImage => 4,
+            Operator::Implication | Operator::Equivalence | Operator::Negation | Operator::Conjunction |
+            Operator::Disjunction => 5,
+            Operator::ConcurrentImplication | Operator::PredictiveImplication | Operator::RetrospectiveImplication |
+            Operator::ConcurrentEquivalence | Operator::PredictiveEquivalence | Operator::RetrospectiveEquivalence |
+            Operator::ParallelEvents | Operator::SequentialEvents => 7,
+            Operator::List | Operator::Op | Operator::Other(_) => 8,
+        }
+    }
+
+    /// Allowed number of arguments: minimum and, if bounded, maximum.
+    pub fn arity(&self) -> (usize, Option<usize>) {
+        match self {
+            op if op.is_statement() => (2, Some(2)),
+            Operator::Negation => (1, Some(1)),
+            Operator::DifferenceExt | Operator::DifferenceInt => (2, Some(2)),
+            Operator::ExtImage | Operator::IntImage => (2, None),
+            Operator::Other(name) => with_declaration(name, |d| d.arity).unwrap_or((0, None)),
+            Operator::Op => (0, None),
+            _ => (1, None),
+        }
+    }
+
+    /// Copulas whose two sides can be swapped: `<->`, `<=>` and `<|>`.
+    pub fn is_symmetric_copula(&self) -> bool {
+        if let Operator::Other(name) = self {
+            return with_declaration(name, |d| d.copula && d.symmetric).unwrap_or(false);
+        }
+        matches!(self, Operator::Similarity | Operator::Equivalence | Operator::ConcurrentEquivalence)
+    }
+
+    /// Copulas, written infix as `<S copula P>`.
+    pub fn is_statement(&self) -> bool {
+        if let Operator::Other(name) = self {
+            return with_declaration(name, |d| d.copula).unwrap_or(false);
+        }
+        matches!(self,
+            Operator::Inheritance | Operator::Implication | Operator::Similarity | Operator::Equivalence |
+            Operator::Instance | Operator::Property | Operator::InstanceProperty |
+            Operator::ConcurrentImplication | Operator::PredictiveImplication | Operator::RetrospectiveImplication |
+            Operator::ConcurrentEquivalence | Operator::PredictiveEquivalence | Operator::RetrospectiveEquivalence)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Term {
     Atom(String),
     Var(VarType, String),
     Compound(Operator, Vec<Term>),
+    Placeholder, // _ (image relation slot)
+    Interval(u64), // +N (time steps between events in a sequence)
+}
+
+/// Learned delays are stored approximately: exact up to 4 steps, then rounded to the
+/// nearest half power of two, so nearby delays map to the same interval term.
+pub fn approximate_interval(steps: u64) -> u64 {
+    if steps <= 4 {
+        return steps;
+    }
+    let magnitude = ((steps as f64).log2() * 2.0).round() / 2.0;
+    2f64.powf(magnitude).round() as u64
 }
 
 impl Term {
@@ -69,10 +276,309 @@ impl Term {
         Term::Var(type_, s.to_string())
     }
     
+    /// Operations are `^name` atoms or `(^name, args..)` compounds.
+    pub fn is_operation(&self) -> bool {
+        match self {
+            Term::Atom(name) => name.starts_with('^'),
+            Term::Compound(Operator::Op, _) => true,
+            Term::Compound(Operator::Other(name), _) => name.starts_with('^'),
+            _ => false,
+        }
+    }
+
+    /// True if `other` is this term or occurs anywhere inside it.
+    pub fn contains(&self, other: &Term) -> bool {
+        if self == other {
+            return true;
+        }
+        match self {
+            Term::Compound(_, args) => args.iter().any(|a| a.contains(other)),
+            _ => false,
+        }
+    }
+
+    /// True if an atom of this term also occurs in `other`, i.e. the two are linked by a
+    /// shared component rather than only by meaning.
+    pub fn shares_atom(&self, other: &Term) -> bool {
+        match self {
+            Term::Atom(_) => other.contains(self),
+            Term::Compound(_, args) => args.iter().any(|a| a.shares_atom(other)),
+            _ => false,
+        }
+    }
+
+    /// The distinct atoms of this term, in order of first occurrence.
+    pub fn atoms(&self) -> Vec<&Term> {
+        fn collect<'a>(term: &'a Term, out: &mut Vec<&'a Term>) {
+            match term {
+                Term::Atom(_) if !out.contains(&term) => out.push(term),
+                Term::Compound(_, args) => args.iter().for_each(|a| collect(a, out)),
+                _ => {}
+            }
+        }
+        let mut atoms = Vec::new();
+        collect(self, &mut atoms);
+        atoms
+    }
+
+    /// True if any variable occurs in this term.
+    pub fn has_variables(&self) -> bool {
+        match self {
+            Term::Var(..) => true,
+            Term::Compound(_, args) => args.iter().any(Term::has_variables),
+            _ => false,
+        }
+    }
+
+    /// Builds a compound, checking the operator's arity (and the single `_` of an image) and
+    /// normalizing it: arguments of commutative compounds are sorted and deduplicated,
+    /// intersections, unions, conjunctions and disjunctions of one term are that term, and
+    /// sequences are built by `sequence`. Copula sides keep their order.
+    ///
+    /// ```
+    /// use hybrid_nars_rust::nars::term::{Term, Operator};
+    ///
+    /// let (cat, animal) = (Term::atom_from_str("cat"), Term::atom_from_str("animal"));
+    /// let both = Term::conjunction(vec![Term::inheritance(cat.clone(), animal.clone()), Term::ext_set(vec![cat.clone()])?])?;
+    /// assert_eq!(both.to_string(), "(&&, <cat --> animal>, {cat})");
+    /// assert!(Term::compound(Operator::Negation, vec![cat, animal]).is_err());
+    /// # Ok::<(), hybrid_nars_rust::nars::error::NarsError>(())
+    /// ```
+    pub fn compound(op: Operator, args: Vec<Term>) -> Result<Term, NarsError> {
+        if let Operator::Other(name) = &op
+            && !name.starts_with('^')
+            && with_declaration(name, |_| ()).is_none()
+        {
+            return Err(NarsError::InvalidTerm(format!("unknown operator {}, see declare_operator", name)));
+        }
+        let (min, max) = op.arity();
+        if args.len() < min || max.is_some_and(|max| args.len() > max) {
+            let expected = match max {
+                Some(max) if max == min => min.to_string(),
+                Some(max) => format!("{} to {}", min, max),
+                None => format!("at least {}", min),
+            };
+            return Err(NarsError::InvalidTerm(format!("{} takes {} arguments, got {}", op.symbol(), expected, args.len())));
+        }
+        match op {
+            Operator::ExtImage | Operator::IntImage if args[1..].iter().filter(|a| **a == Term::Placeholder).count() != 1 => {
+                return Err(NarsError::InvalidTerm(format!("{} needs exactly one _ after the relation", op.symbol())));
+            }
+            Operator::SequentialEvents => return Ok(Term::sequence(args)),
+            _ => {}
+        }
+        Ok(Term::normalized_compound(op, args))
+    }
+
+    pub fn inheritance(subject: Term, predicate: Term) -> Term {
+        Term::Compound(Operator::Inheritance, vec![subject, predicate])
+    }
+
+    pub fn similarity(a: Term, b: Term) -> Term {
+        Term::Compound(Operator::Similarity, vec![a, b])
+    }
+
+    pub fn implication(antecedent: Term, consequent: Term) -> Term {
+        Term::Compound(Operator::Implication, vec![antecedent, consequent])
+    }
+
+    pub fn equivalence(a: Term, b: Term) -> Term {
+        Term::Compound(Operator::Equivalence, vec![a, b])
+    }
+
+    pub fn negation(term: Term) -> Term {
+        Term::Compound(Operator::Negation, vec![term])
+    }
+
+    pub fn product(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::Product, args)
+    }
+
+    pub fn conjunction(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::Conjunction, args)
+    }
+
+    pub fn disjunction(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::Disjunction, args)
+    }
+
+    pub fn ext_set(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::ExtSet, args)
+    }
+
+    pub fn int_set(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::IntSet, args)
+    }
+
+    pub fn ext_intersection(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::ExtIntersection, args)
+    }
+
+    pub fn int_intersection(args: Vec<Term>) -> Result<Term, NarsError> {
+        Term::compound(Operator::IntIntersection, args)
+    }
+
+    /// Builds `(&/, ..)` from `parts`: consecutive intervals are merged, `+0` is dropped and
+    /// nested sequences are flattened. A single remaining part is returned as is.
+    pub fn sequence(parts: Vec<Term>) -> Term {
+        let mut merged: Vec<Term> = Vec::with_capacity(parts.len());
+        let flat = parts.into_iter().flat_map(|p| match p {
+            Term::Compound(Operator::SequentialEvents, args) => args,
+            other => vec![other],
+        });
+        for part in flat {
+            match (merged.last_mut(), part) {
+                (_, Term::Interval(0)) => {}
+                (Some(Term::Interval(prev)), Term::Interval(n)) => *prev += n,
+                (_, part) => merged.push(part),
+            }
+        }
+        if merged.len() == 1 {
+            merged.pop().unwrap()
+        } else {
+            Term::Compound(Operator::SequentialEvents, merged)
+        }
+    }
+
+    /// Replaces every interval with its approximation (see `approximate_interval`).
+    pub fn approximated(&self) -> Term {
+        match self {
+            Term::Interval(n) => Term::Interval(approximate_interval(*n)),
+            Term::Compound(op, args) => Term::Compound(op.clone(), args.iter().map(|a| a.approximated()).collect()),
+            _ => self.clone(),
+        }
+    }
+
+    /// Total of the intervals directly inside a sequence.
+    pub fn interval_sum(&self) -> u64 {
+        match self {
+            Term::Interval(n) => *n,
+            Term::Compound(Operator::SequentialEvents, args) => args.iter().map(|a| match a {
+                Term::Interval(n) => *n,
+                _ => 0,
+            }).sum(),
+            _ => 0,
+        }
+    }
+
+    /// Syntactic complexity: 1 per atom/variable plus 1 per compound node.
+    pub fn complexity(&self) -> usize {
+        match self {
+            Term::Compound(_, args) => 1 + args.iter().map(|a| a.complexity()).sum::<usize>(),
+            _ => 1,
+        }
+    }
+
+    /// Nesting depth: 0 for atomic terms, 1 + the deepest argument for compounds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Term::Compound(_, args) => 1 + args.iter().map(|a| a.depth()).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// The highest NAL layer among the operators of the term (1 for atoms).
+    pub fn nal_level(&self) -> u8 {
+        match self {
+            Term::Compound(op, args) => args.iter().map(|a| a.nal_level()).fold(op.nal_level(), u8::max),
+            Term::Interval(_) => 7,
+            _ => 1,
+        }
+    }
+
+    /// Position of the `_` slot within an image term, e.g. 1 for `(/,R,_,b)`.
+    pub fn placeholder_index(&self) -> Option<usize> {
+        match self {
+            Term::Compound(Operator::ExtImage | Operator::IntImage, args) => {
+                args.iter().position(|a| *a == Term::Placeholder)
+            }
+            _ => None,
+        }
+    }
+    
+    /// Canonical form for equivalence checks: arguments of commutative operators are sorted
+    /// (sets are also deduplicated) and variables are renamed in order of first appearance.
+    pub fn normalized(&self) -> Term {
+        let mut mapping = HashMap::new();
+        self.sort_commutative().rename_vars(&mut mapping)
+    }
+
+    /// `op` applied to `args` in normal form, as `compound` and the parser build it but
+    /// unchecked: the arguments of commutative compounds (copulas aside) are sorted and
+    /// deduplicated, and intersections, unions, conjunctions and disjunctions of one term are
+    /// that term.
+    pub fn normalized_compound(op: Operator, mut args: Vec<Term>) -> Term {
+        if op.is_commutative() && !op.is_symmetric_copula() {
+            args.sort();
+            args.dedup();
+            if args.len() == 1 && !matches!(op, Operator::ExtSet | Operator::IntSet | Operator::Other(_)) {
+                return args.pop().unwrap();
+            }
+        }
+        Term::Compound(op, args)
+    }
+
+    /// The term with every commutative compound in it in normal form, see
+    /// `normalized_compound`. Derived terms are brought into this form before they meet
+    /// memory, so they find the concepts of the same terms input in another order.
+    pub fn with_commutative_order(&self) -> Term {
+        match self {
+            Term::Compound(op, args) => Term::normalized_compound(op.clone(), args.iter().map(Term::with_commutative_order).collect()),
+            _ => self.clone(),
+        }
+    }
+
+    /// Orders the two sides of every symmetric copula, so `<b <-> a>` becomes `<a <-> b>`.
+    /// Unlike `normalized`, variables and other commutative compounds are left as written.
+    pub fn with_symmetric_order(&self) -> Term {
+        match self {
+            Term::Compound(op, args) => {
+                let mut args: Vec<Term> = args.iter().map(|a| a.with_symmetric_order()).collect();
+                if op.is_symmetric_copula() {
+                    args.sort();
+                }
+                Term::Compound(op.clone(), args)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    fn sort_commutative(&self) -> Term {
+        match self {
+            Term::Compound(op, args) => {
+                let mut args: Vec<Term> = args.iter().map(|a| a.sort_commutative()).collect();
+                if op.is_commutative() {
+                    args.sort();
+                    if matches!(op, Operator::ExtSet | Operator::IntSet) {
+                        args.dedup();
+                    }
+                }
+                Term::Compound(op.clone(), args)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    fn rename_vars(&self, mapping: &mut HashMap<(VarType, String), String>) -> Term {
+        match self {
+            Term::Var(t, name) => {
+                let next = mapping.keys().filter(|(vt, _)| vt == t).count() + 1;
+                let new_name = mapping.entry((*t, name.clone())).or_insert_with(|| next.to_string());
+                Term::Var(*t, new_name.clone())
+            }
+            Term::Compound(op, args) => {
+                Term::Compound(op.clone(), args.iter().map(|a| a.rename_vars(mapping)).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+    
     pub fn to_display_string(&self) -> String {
         match self {
             Term::Atom(s) => s.clone(),
             Term::Var(t, s) => format!("{:?}:{}", t, s),
+            Term::Placeholder => "_".to_string(),
+            Term::Interval(n) => format!("+{}", n),
             Term::Compound(op, args) => {
                 let args_str: Vec<String> = args.iter().map(|a| a.to_display_string()).collect();
                 format!("({:?} {:?})", op, args_str)
@@ -80,3 +586,33 @@ impl Term {
         }
     }
 }
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Atom(s) => write!(f, "{}", s),
+            Term::Var(t, s) => {
+                let prefix = match t {
+                    VarType::Independent => '$',
+                    VarType::Dependent => '#',
+                    VarType::Query => '?',
+                };
+                write!(f, "{}{}", prefix, s)
+            }
+            Term::Placeholder => write!(f, "_"),
+            Term::Interval(n) => write!(f, "+{}", n),
+            Term::Compound(op, args) => {
+                let joined = |sep: &str| args.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(sep);
+                match op {
+                    Operator::ExtSet => write!(f, "{{{}}}", joined(", ")),
+                    Operator::IntSet => write!(f, "[{}]", joined(", ")),
+                    _ if op.is_statement() && args.len() == 2 => {
+                        write!(f, "<{} {} {}>", args[0], op.symbol(), args[1])
+                    }
+                    _ if args.is_empty() => write!(f, "({})", op.symbol()),
+                    _ => write!(f, "({}, {})", op.symbol(), joined(", ")),
+                }
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@
 mod tests {
     use crate::nars::term::{Term, Operator, VarType};
     use crate::nars::truth::{self, TruthValue};
-    use crate::nars::unify::unify;
+    use crate::nars::unify::{unify, ScratchBindings};
 
     #[test]
     fn test_math_deduction() {
@@ -22,15 +22,15 @@ mod tests {
     #[test]
     fn test_unification() {
         // Helper to create atoms with fixed IDs for determinism
-        let atom = |id| Term::Atom(id);
-        let var = |id| Term::Var(VarType::Independent, id);
+        let atom = |id: &str| Term::Atom(id.to_string());
+        let var = |id: &str| Term::Var(VarType::Independent, id.to_string());
         
         // IDs
-        let id_x = 100;
-        let id_duck = 1;
-        let id_bird = 2;
-        let id_swimmer = 3;
-        let id_fish = 4;
+        let id_x = "x";
+        let id_duck = "duck";
+        let id_bird = "bird";
+        let id_swimmer = "swimmer";
+        let id_fish = "fish";
 
         // Terms
         let x = var(id_x);
@@ -76,4 +76,120 @@ mod tests {
         let bindings_neg = unify(&rule, &fact_neg);
         assert!(bindings_neg.is_none(), "Unification should have failed for negative test");
     }
+
+    #[test]
+    fn test_ac_unification() {
+        let atom = |id: &str| Term::Atom(id.to_string());
+        let var = |id: &str| Term::Var(VarType::Independent, id.to_string());
+        let conj = |args: Vec<Term>| Term::Compound(Operator::Conjunction, args);
+        let (x, y, z) = (atom("x"), atom("y"), atom("z"));
+
+        // Argument order is ignored
+        let bindings = unify(&conj(vec![var("a"), x.clone()]), &conj(vec![x.clone(), y.clone()])).unwrap();
+        assert_eq!(bindings.get(&var("a")), Some(&y));
+
+        // A variable takes the remaining arguments
+        let bindings = unify(&conj(vec![x.clone(), var("a")]), &conj(vec![x.clone(), y.clone(), z.clone()])).unwrap();
+        assert_eq!(bindings.get(&var("a")), Some(&conj(vec![y.clone(), z.clone()])));
+
+        // A variable bound to a remainder only matches that remainder again
+        let prod = |args: Vec<Term>| Term::Compound(Operator::Product, args);
+        let pattern = prod(vec![conj(vec![x.clone(), var("a")]), var("a")]);
+        assert!(unify(&pattern, &prod(vec![conj(vec![x.clone(), y.clone(), z.clone()]), conj(vec![z.clone(), y.clone()])])).is_some());
+        assert!(unify(&pattern, &prod(vec![conj(vec![x.clone(), y.clone(), z.clone()]), conj(vec![x.clone(), y.clone()])])).is_none());
+
+        // Without a variable the arities must agree
+        assert!(unify(&conj(vec![x.clone(), y.clone()]), &conj(vec![x.clone(), y.clone(), z.clone()])).is_none());
+
+        // Non-AC operators still match positionally
+        assert!(unify(&prod(vec![var("a"), x.clone()]), &prod(vec![x.clone(), y.clone()])).is_none());
+        assert!(unify(&prod(vec![var("a")]), &prod(vec![x.clone(), y.clone()])).is_none());
+
+        // Later matches choose among the assignments of an AC match
+        let (pattern, fact) = (conj(vec![var("a"), var("b")]), conj(vec![x.clone(), y.clone()]));
+        let (a, b) = (var("a"), var("b"));
+        let arena = bumpalo::Bump::new();
+        let mut scratch = ScratchBindings::new(&arena);
+        assert!(scratch.unify_then(&pattern, &fact, &mut |s| s.unify(&a, &y)));
+        assert_eq!(scratch.to_bindings().get(&b), Some(&x));
+        assert!(!ScratchBindings::new(&arena).unify_then(&pattern, &fact, &mut |s| s.unify(&a, &z)));
+
+        // One-off matches (no arena) choose the same way
+        let bindings = unify(&prod(vec![pattern.clone(), a.clone()]), &prod(vec![fact.clone(), y.clone()])).unwrap();
+        assert_eq!((bindings.get(&a), bindings.get(&b)), (Some(&y), Some(&x)));
+        assert!(unify(&prod(vec![pattern.clone(), a.clone()]), &prod(vec![fact.clone(), z.clone()])).is_none());
+    }
+
+    #[test]
+    fn test_term_constructors() {
+        use crate::nars::error::NarsError;
+        use crate::nars::parser::parse_term;
+
+        let atom = Term::atom_from_str;
+        let parsed = |text: &str| parse_term(text).unwrap().1;
+
+        assert_eq!(Term::inheritance(atom("cat"), atom("animal")), parsed("<cat --> animal>"));
+        assert_eq!(Term::similarity(atom("b"), atom("a")).to_string(), "<b <-> a>");
+        assert_eq!(Term::negation(Term::implication(atom("a"), atom("b"))), parsed("(--, <a ==> b>)"));
+        assert_eq!(Term::product(vec![atom("b"), atom("a")]).unwrap(), parsed("(*, b, a)"));
+
+        // Commutative compounds are sorted and deduplicated; single terms collapse, sets do not
+        assert_eq!(Term::ext_set(vec![atom("b"), atom("a"), atom("b")]).unwrap(), parsed("{a, b}"));
+        assert_eq!(Term::int_set(vec![atom("a")]).unwrap(), parsed("[a]"));
+        assert_eq!(Term::conjunction(vec![atom("a"), atom("a")]).unwrap(), atom("a"));
+        assert_eq!(Term::ext_intersection(vec![atom("y"), atom("x")]).unwrap(), parsed("(|, x, y)"));
+        assert_eq!(Term::compound(Operator::SequentialEvents, vec![atom("a"), Term::Interval(2), Term::Interval(3), atom("b")]).unwrap(),
+            parsed("(&/, a, +5, b)"));
+        // The parser builds the same normal form, whatever order the input is written in
+        let conj = Term::conjunction(vec![atom("b"), Term::ext_set(vec![atom("d"), atom("c")]).unwrap()]).unwrap();
+        assert_eq!(conj, parsed("(&&, {d, c}, b)"));
+        assert_eq!(conj, parsed("(&&, b, {c, d}, b)"));
+
+        // Arity and structure are checked
+        let invalid = |r: Result<Term, NarsError>| matches!(r, Err(NarsError::InvalidTerm(_)));
+        assert!(invalid(Term::conjunction(vec![])));
+        assert!(invalid(Term::compound(Operator::Inheritance, vec![atom("a")])));
+        assert!(invalid(Term::compound(Operator::DifferenceExt, vec![atom("a"), atom("b"), atom("c")])));
+        assert!(invalid(Term::compound(Operator::ExtImage, vec![atom("R"), atom("a")])));
+        assert_eq!(Term::compound(Operator::ExtImage, vec![atom("R"), Term::Placeholder, atom("a")]).unwrap(), parsed("(/, R, _, a)"));
+    }
+
+    #[test]
+    fn test_declared_operators() {
+        use crate::nars::error::NarsError;
+        use crate::nars::parser::parse_term;
+        use crate::nars::term::{declare_operator, OperatorSpec};
+
+        let parsed = |text: &str| parse_term(text).ok().filter(|(rest, _)| rest.is_empty()).map(|(_, term)| term);
+        let (a, b) = (Term::atom_from_str("a"), Term::atom_from_str("b"));
+        let custom = |symbol: &str| Operator::Other(symbol.to_string());
+
+        // Undeclared operators are rejected
+        assert!(parsed("<a <~> b>").is_none());
+        assert!(matches!(Term::compound(custom("pair"), vec![a.clone(), b.clone()]), Err(NarsError::InvalidTerm(_))));
+
+        declare_operator(OperatorSpec::copula("<~>", true)).unwrap();
+        declare_operator(OperatorSpec::compound("pair", (2, Some(2)), true)).unwrap();
+        declare_operator(OperatorSpec::copula("<~>", true)).unwrap(); // same spec again
+        assert!(declare_operator(OperatorSpec::copula("<~>", false)).is_err());
+        assert!(declare_operator(OperatorSpec::copula("-->", false)).is_err());
+        assert!(declare_operator(OperatorSpec::compound("^op", (1, None), false)).is_err());
+
+        // Parsed in infix and prefix form, printed infix, and ordered as a symmetric copula
+        let statement = parsed("<b <~> a>").unwrap();
+        assert_eq!(statement, Term::Compound(custom("<~>"), vec![b.clone(), a.clone()]));
+        assert_eq!(parsed("(<~>, b, a)").unwrap(), statement);
+        assert_eq!(statement.to_string(), "<b <~> a>");
+        assert_eq!(statement.with_symmetric_order().to_string(), "<a <~> b>");
+        assert_eq!(parsed("<<a --> b> <~> c>").unwrap().to_string(), "<<a --> b> <~> c>");
+        assert!(parsed("(<~>, a, b, c)").is_none());
+
+        // Commutative compounds are normalized, arity-checked and matched in any order
+        let pair = parsed("(pair, b, a)").unwrap();
+        assert_eq!(Term::compound(custom("pair"), vec![b.clone(), a.clone()]).unwrap(), pair.normalized());
+        assert!(matches!(Term::compound(custom("pair"), vec![a.clone()]), Err(NarsError::InvalidTerm(_))));
+        let bindings = unify(&parsed("(pair, $x, b)").unwrap(), &pair).unwrap();
+        assert_eq!(bindings.get(&Term::Var(VarType::Independent, "x".to_string())), Some(&a));
+        assert!(unify(&parsed("<a <~> $x>").unwrap(), &statement).is_some());
+    }
 }
@@ -7,6 +7,7 @@ mod tests {
     use crate::nars::sentence::Stamp;
 
     #[test]
+    #[allow(unused_variables, unused_mut)] // a stub: the setup is there, the steps are not written yet
     fn test_integration_deduction() {
         // 1. Initialize NarsSystem
         let mut system = NarsSystem::new(0.1, 0.5);
@@ -30,7 +31,7 @@ mod tests {
             tiger.clone(),
             vec_tiger,
             TruthValue::new(1.0, 0.9),
-            Stamp { creation_time: 0, evidence: vec![1] }
+            Stamp { creation_time: 0, evidence: vec![1], sources: vec![] }
         );
 
         // Feline
@@ -370,4 +371,2119 @@ mod tests {
         // Run cycle.
         // Check for <Tiger --> Animal>.
     }
+
+    #[test]
+    fn test_confidence_floor_suppresses_derivations() {
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.min_confidence = 0.85;
+
+        let bird = Term::atom_from_str("bird");
+        let animal = Term::atom_from_str("animal");
+        let robin = Term::atom_from_str("robin");
+        let bird_animal = Term::Compound(Operator::Inheritance, vec![bird.clone(), animal.clone()]);
+        let robin_bird = Term::Compound(Operator::Inheritance, vec![robin.clone(), bird]);
+
+        let v1 = system.resolve_vector(&bird_animal);
+        system.add_concept(Concept::new(bird_animal, v1, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![1])), true);
+        let v2 = system.resolve_vector(&robin_bird);
+        system.add_concept(Concept::new(robin_bird, v2, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![2])), true);
+
+        let mut outputs = Vec::new();
+        for _ in 0..20 {
+            system.cycle();
+            outputs.append(&mut system.output_buffer);
+        }
+
+        // Deduction yields c=0.81 and conversion c=0.45, both below the floor
+        let robin_animal = Term::Compound(Operator::Inheritance, vec![robin, animal]);
+        assert!(outputs.iter().all(|s| s.term != robin_animal));
+        assert!(system.memory.get(&robin_animal).is_none());
+        assert!(system.stats.suppressed_derivations > 0);
+        assert_eq!(system.stats.derivations, 0);
+    }
+
+    #[test]
+    fn test_run_for_respects_budget() {
+        use std::time::{Duration, Instant};
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        for i in 0..50 {
+            let term = Term::Compound(Operator::Inheritance, vec![
+                Term::atom_from_str(&format!("a{}", i)),
+                Term::atom_from_str(&format!("b{}", i % 7)),
+            ]);
+            let vector = system.resolve_vector(&term);
+            system.add_concept(Concept::new(term, vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![i])), true);
+        }
+
+        let budget = Duration::from_millis(50);
+        let start = Instant::now();
+        let cycles = system.run_for(budget);
+        // Allow one association step of overshoot
+        assert!(start.elapsed() < budget + Duration::from_millis(500));
+        assert!(cycles > 0);
+
+        // An idle system returns immediately
+        let mut idle = NarsSystem::new(0.1, 0.5);
+        assert_eq!(idle.run_for(Duration::from_secs(5)), 0);
+        assert_eq!(idle.run_cycles(3), 3);
+    }
+
+    #[test]
+    fn test_goal_activation_boosts_related_concepts() {
+        let mut system = NarsSystem::new(0.1, 0.6);
+        let charged = Term::Compound(Operator::IntSet, vec![Term::atom_from_str("charged")]);
+        let goal = Term::Compound(Operator::Inheritance, vec![Term::atom_from_str("robot"), charged.clone()]);
+        let related = Term::Compound(Operator::Inheritance, vec![Term::atom_from_str("charger"), charged]);
+        let unrelated = Term::atom_from_str("banana");
+
+        // However much else memory holds, the related concept is found through its atoms
+        let fillers = (0..90).map(|i| Term::atom_from_str(&format!("filler{}", i)));
+        for term in [related.clone(), unrelated.clone()].into_iter().chain(fillers) {
+            let vector = system.resolve_vector(&term);
+            let mut concept = Concept::new(term, vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![]));
+            concept.priority = 0.1;
+            system.add_concept(concept, false);
+        }
+
+        let boosted = system.spread_goal_activation(&goal, 0.9);
+        assert_eq!(boosted, 1);
+        let related_p = system.buffer.name_map[&related];
+        let unrelated_p = system.buffer.name_map[&unrelated];
+        assert!(related_p > unrelated_p, "goal-relevant concept should be prioritized ({} vs {})", related_p, unrelated_p);
+    }
+
+    #[test]
+    fn test_ground_blends_embedding() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let cat = Term::atom_from_str("cat");
+        let cat_vector = Hypervector::project(&[0.3, -0.2, 0.9, 0.1]);
+        system.add_embedding(Concept::new(cat.clone(), cat_vector, TruthValue::new(0.5, 0.1), Stamp::new(0, vec![])));
+
+        let symbol = Term::atom_from_str("obj_17");
+        let before = system.resolve_vector(&symbol).similarity(&cat_vector);
+        system.ground(&symbol, "cat").unwrap();
+        let after = system.memory.get(&symbol).unwrap().vector.similarity(&cat_vector);
+        assert!(after > before + 0.1, "grounding should pull the symbol towards the word ({} -> {})", before, after);
+
+        assert!(system.ground(&symbol, "unknown_word").is_err());
+    }
+
+    #[test]
+    fn test_consolidate_merges_equivalent_concepts() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let a = Term::atom_from_str("cat");
+        let b = Term::atom_from_str("feline");
+        let ab = Term::Compound(Operator::Similarity, vec![a.clone(), b.clone()]);
+        let ba = Term::Compound(Operator::Similarity, vec![b.clone(), a.clone()]);
+        let other = Term::Compound(Operator::Inheritance, vec![a, b]);
+
+        let shared = system.resolve_vector(&ab);
+        system.add_concept(Concept::new(ab.clone(), shared, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![1])), true);
+        system.add_concept(Concept::new(ba.clone(), shared, TruthValue::new(0.0, 0.9), Stamp::new(0, vec![2])), true);
+        let v = system.resolve_vector(&other);
+        system.add_concept(Concept::new(other.clone(), v, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![3])), true);
+
+        assert_eq!(ab.normalized(), ba.normalized());
+        let before = system.memory.len();
+        assert_eq!(system.consolidate(), 1);
+        assert_eq!(system.memory.len(), before - 1);
+
+        // <cat <-> feline> survives with the revised truth and both beliefs
+        let survivor = system.memory.get(&ab).expect("survivor kept");
+        assert!(system.memory.get(&ba).is_none());
+        assert!((survivor.truth.frequency - 0.5).abs() < 0.01);
+        assert!(survivor.truth.confidence > 0.9);
+        assert_eq!(survivor.beliefs.len(), 2);
+        assert!(system.memory.get(&other).is_some());
+    }
+
+    #[test]
+    fn test_question_input_creates_no_belief() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::Punctuation;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.input(parse_narsese("<a --> b>?").unwrap());
+        system.input(parse_narsese("<c --> d>!").unwrap());
+        system.run_cycles(10);
+
+        let question = parse_narsese("<a --> b>?").unwrap().term;
+        let concept = system.memory.get(&question).expect("question concept exists");
+        assert!(concept.beliefs.is_empty());
+        assert_eq!(concept.truth.confidence, 0.0);
+        assert_eq!(concept.questions.len(), 1);
+        assert_eq!(concept.questions[0].punctuation, Punctuation::Question);
+        assert!(system.answer_query(&question).is_none());
+
+        let goal = parse_narsese("<c --> d>!").unwrap().term;
+        let concept = system.memory.get(&goal).expect("goal concept exists");
+        assert!(concept.beliefs.is_empty());
+        assert_eq!(concept.goals.len(), 1);
+        assert_eq!(concept.goals[0].punctuation, Punctuation::Goal);
+
+        // Judgements still become beliefs
+        system.input(parse_narsese("<a --> b>.").unwrap());
+        system.run_cycles(10);
+        assert!(system.answer_query(&question).is_some());
+    }
+
+    #[test]
+    fn test_downward_revision_invalidates_dependents() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.input(parse_narsese("<a --> b>.").unwrap());
+        system.input(parse_narsese("<b --> c>.").unwrap());
+        system.run_cycles(20);
+
+        let derived = parse_narsese("<a --> c>.").unwrap().term;
+        let before = system.memory.get(&derived).expect("<a --> c> derived").truth.confidence;
+        assert!(before > 0.5);
+
+        // Strong counter-evidence for a premise weakens the stale conclusion
+        // (integrated directly so no re-derivation runs before the check)
+        let premise = parse_narsese("<a --> b>.").unwrap().term;
+        let counter = Concept::new(premise.clone(), Hypervector::from_term(&premise), TruthValue::new(0.0, 0.99), Stamp::new(0, vec![100]));
+        system.add_concept(counter, true);
+        assert!(system.stats.invalidations > 0);
+        let after = system.memory.get(&derived).unwrap().truth.confidence;
+        assert!(after < before * 0.5, "confidence {} not lowered from {}", after, before);
+    }
+
+    #[test]
+    fn test_retract_removes_concept_and_links() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::term::Term;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.input(parse_narsese("<robin --> bird>.").unwrap());
+        system.input(parse_narsese("<bird --> animal>.").unwrap());
+        system.run_cycles(20);
+
+        let robin = Term::atom_from_str("robin");
+        let removed = system.retract(&robin).unwrap();
+        assert!(removed >= 2);
+        assert!(system.memory.keys().all(|t| !t.contains(&robin)));
+        assert!(system.buffer.remove(&robin).is_none());
+        assert!(system.memory.get(&parse_narsese("<bird --> animal>.").unwrap().term).is_some());
+
+        assert!(system.retract(&robin).is_err());
+    }
+
+    #[test]
+    fn test_custom_rule_via_macro() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::rules::TruthFunction;
+        use crate::nars_rule;
+
+        fn certain(v: TruthValue) -> TruthValue {
+            TruthValue::new(v.frequency, 0.99)
+        }
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.add_rule(nars_rule!("(:S --> :P)" !- "(:S <-> :P)" TruthFunction::Single(certain)).unwrap());
+        assert!(nars_rule!("(:S --> :P)" !- "(:S <-> :P)" "no_such_fn").is_err());
+
+        system.input(parse_narsese("<cat --> pet>.").unwrap());
+        system.run_cycles(5);
+        let derived = system.memory.get(&parse_narsese("<cat <-> pet>.").unwrap().term).expect("custom rule fired");
+        assert!(derived.beliefs.iter().any(|b| (b.truth.confidence - 0.99).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_registered_truth_fn_in_rule_file() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::rules::TruthFunction;
+
+        fn halve(v: TruthValue) -> TruthValue {
+            TruthValue::new(v.frequency, v.confidence / 2.0)
+        }
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let rules = "; custom calculus\n(:S --> :P) !- (:S <-> :P) halve\n";
+        assert!(system.load_rules(rules).is_err(), "unknown truth function must be rejected");
+
+        let before = system.rules.len();
+        system.register_truth_fn("halve", TruthFunction::Single(halve));
+        assert_eq!(system.load_rules(rules).unwrap().loaded, 1);
+        assert_eq!(system.rules.len(), before + 1);
+
+        system.input(parse_narsese("<cat --> pet>.").unwrap());
+        system.run_cycles(5);
+        let derived = system.memory.get(&parse_narsese("<cat <-> pet>.").unwrap().term).expect("loaded rule fired");
+        assert!(derived.beliefs.iter().any(|b| (b.truth.confidence - 0.45).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_goal_derives_subgoals_through_implications() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::Punctuation;
+
+        // Beliefs are added directly so no derived shortcuts (e.g. <a ==> c>) exist
+        let mut system = NarsSystem::new(0.1, 0.5);
+        for (i, text) in ["<a ==> b>.", "<b ==> c>."].iter().enumerate() {
+            let term = parse_narsese(text).unwrap().term;
+            let belief = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![i as u64 + 1]));
+            system.add_concept(belief, true);
+        }
+
+        let goal = parse_narsese("c!").unwrap();
+        let subgoals = system.derive_subgoals(&goal, 0.8);
+        let desire = |name: &str| subgoals.iter().find(|g| g.term == Term::atom_from_str(name)).map(|g| g.truth);
+
+        // c! with <b ==> c> gives b! (strong), then a! through <a ==> b>
+        let b = desire("b").expect("b! derived");
+        assert!((b.frequency - 1.0).abs() < 1e-6 && (b.confidence - 0.81).abs() < 1e-3);
+        let a = desire("a").expect("a! derived");
+        assert!((a.confidence - 0.729).abs() < 1e-3);
+
+        let concept = system.memory.get(&Term::atom_from_str("a")).unwrap();
+        assert_eq!(concept.goals.len(), 1);
+        assert!(concept.beliefs.is_empty());
+
+        // Forward direction is weak: a! with <a ==> b> gives b! with lower desire
+        let forward = system.derive_subgoals(&parse_narsese("a!").unwrap(), 0.8);
+        let weak = forward.iter().find(|g| g.term == Term::atom_from_str("b")).unwrap();
+        assert!(weak.truth.confidence < b.confidence);
+
+        // Budget floor stops the chain
+        system.min_priority = 0.5;
+        assert!(system.derive_subgoals(&goal, 0.55).len() < subgoals.len());
+
+        // An input goal is expanded once, not again each time it is requeued
+        system.min_priority = 0.0;
+        system.output_buffer.clear();
+        system.input(parse_narsese("c!").unwrap());
+        system.run_cycles(30);
+        let b_goals = system.output_buffer.iter().filter(|s| s.punctuation == Punctuation::Goal && s.term == Term::atom_from_str("b")).count();
+        assert_eq!(b_goals, 1);
+    }
+
+    #[test]
+    fn test_plan_backward_chains_operations() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let beliefs = [
+            ("at_door.", 0.9),
+            ("at_window.", 0.9),
+            ("<(&/, at_door, ^open) =/> door_open>.", 0.9),
+            ("<(&/, door_open, ^walk) =/> inside>.", 0.9),
+            ("<(&/, at_window, ^climb) =/> inside>.", 0.5),
+        ];
+        for (i, (text, confidence)) in beliefs.iter().enumerate() {
+            let term = parse_narsese(text).unwrap().term;
+            let belief = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, *confidence), Stamp::new(0, vec![i as u64 + 1]));
+            system.add_concept(belief, true);
+        }
+
+        // The two-step route is better supported than climbing through the window
+        let plan = system.plan(&Term::atom_from_str("inside"));
+        let ops: Vec<String> = plan.iter().map(|s| s.operation.to_string()).collect();
+        assert_eq!(ops, vec!["^open", "^walk"]);
+        assert_eq!(plan[0].precondition, Some(Term::atom_from_str("at_door")));
+        assert_eq!(plan[1].outcome, Term::atom_from_str("inside"));
+        assert!((plan[0].truth.confidence - 0.729).abs() < 1e-3);
+
+        assert!(system.plan(&Term::atom_from_str("at_door")).is_empty());
+        assert!(system.plan(&Term::atom_from_str("unreachable")).is_empty());
+    }
+
+    #[test]
+    fn test_sensor_readings_become_judgements() {
+        use crate::nars::sensor::SensorChannel;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.add_sensor(SensorChannel::uniform("temp", 0.0, 40.0, &["cold", "warm", "hot"]));
+        assert!(system.sense("humidity", 0.3).is_err());
+
+        system.sense("temp", 35.0).unwrap();
+        system.run_cycles(10);
+
+        let channel = &system.sensors[0];
+        let hot = system.memory.get(&channel.term_for("hot")).expect("hot judgement integrated");
+        assert!((hot.truth.frequency - 0.75).abs() < 1e-3);
+        assert!(!hot.beliefs.is_empty());
+        let cold = system.memory.get(&channel.term_for("cold")).unwrap();
+        assert_eq!(cold.truth.frequency, 0.0);
+    }
+
+    #[test]
+    fn test_analogize_decodes_bound_relation() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let atom = Term::atom_from_str;
+        let vectors: Vec<(Term, Hypervector)> = ["man", "king", "woman", "apple", "river"].iter()
+            .map(|name| (atom(name), Hypervector::random()))
+            .collect();
+        for (term, vector) in &vectors {
+            system.add_concept(Concept::new(term.clone(), *vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), false);
+        }
+
+        // queen holds the same relation to woman as king to man, with some noise
+        let mut queen = vectors[1].1.bind(&vectors[0].1).bind(&vectors[2].1);
+        let noise = Hypervector::random();
+        queen.bits[..20].copy_from_slice(&noise.bits[..20]);
+        system.add_concept(Concept::new(atom("queen"), queen, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), false);
+
+        let results = system.analogize(&atom("man"), &atom("king"), &atom("woman"));
+        assert_eq!(results[0].0, atom("queen"));
+        assert!(results[0].1 > 0.8);
+        assert!(results.iter().all(|(t, _)| *t != atom("man") && *t != atom("king") && *t != atom("woman")));
+        assert!(results[1].1 < 0.6);
+    }
+
+    #[test]
+    fn test_inference_feedback_adjusts_priorities() {
+        use crate::nars::parser::parse_narsese;
+
+        let premise = |system: &mut NarsSystem, text: &str, id: u64| {
+            let term = parse_narsese(text).unwrap().term;
+            system.add_concept(Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![id])), true);
+            term
+        };
+
+        // A novel, confident conclusion rewards the rule that produced it
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.seed(1);
+        premise(&mut system, "<a --> b>.", 1);
+        premise(&mut system, "<b --> c>.", 2);
+        let conclusion = parse_narsese("<a --> c>.").unwrap().term;
+        for _ in 0..50 {
+            system.cycle();
+            if system.memory.get(&conclusion).is_some() {
+                break;
+            }
+        }
+        let deduction = system.rules.iter().position(|r| r.name == "deduction").unwrap();
+        assert!(system.rule_priority(deduction) > 0.5);
+
+        // Junk (every conclusion below the confidence floor) is penalized
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.min_confidence = 0.99;
+        let a = premise(&mut system, "<a --> b>.", 1);
+        premise(&mut system, "<b --> c>.", 2);
+        let before = system.memory.get(&a).unwrap().priority;
+        system.run_cycles(10);
+        assert!(system.stats.suppressed_derivations > 0);
+        assert!(!system.rule_priorities.is_empty());
+        assert!(system.rule_priorities.iter().all(|p| *p <= 0.5));
+        assert!(system.memory.get(&a).unwrap().priority < before);
+    }
+
+    #[test]
+    fn test_instances_share_resources() {
+        use crate::nars::control::SharedResources;
+        use crate::nars::memory::ProjectionMatrix;
+        use crate::nars_rule;
+        use std::sync::Arc;
+
+        let resources = SharedResources::new().with_projector(ProjectionMatrix::new(3));
+        let mut a = NarsSystem::with_resources(0.1, 0.5, resources.clone());
+        let b = NarsSystem::with_resources(0.1, 0.5, a.resources());
+        assert!(Arc::ptr_eq(&a.rules, &b.rules));
+
+        // Adding a rule copies the table for that instance only
+        a.add_rule(nars_rule!("(:S --> :P)" !- "(:S <-> :P)" "conversion").unwrap());
+        assert_eq!(a.rules.len(), b.rules.len() + 1);
+        assert!(Arc::ptr_eq(&b.rules, &resources.rules));
+
+        // Embedding loads reuse a shared projector of the right dimension
+        let path = std::env::temp_dir().join(format!("nars_shared_{}.txt", std::process::id()));
+        std::fs::write(&path, "cat 0.1 0.2 0.3\ndog 0.2 0.1 0.3\n").unwrap();
+        a.load_embeddings_from_file(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("bin"));
+        assert!(Arc::ptr_eq(a.projector.as_ref().unwrap(), resources.projector.as_ref().unwrap()));
+        assert!(a.memory.get(&Term::atom_from_str("cat")).is_some());
+    }
+
+    #[test]
+    fn test_mental_operations_create_tasks() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::Punctuation;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.input(parse_narsese("(^wonder, <?x --> animal>)!").unwrap());
+        system.input(parse_narsese("(^believe, <cat --> animal>)!").unwrap());
+        system.input(parse_narsese("(^want, <cat --> fed>)!").unwrap());
+        system.run_cycles(60);
+
+        let question = parse_narsese("<?x --> animal>?").unwrap().term;
+        let concept = system.memory.get(&question).expect("internal question created");
+        assert!(concept.questions.iter().any(|q| q.punctuation == Punctuation::Question));
+        assert!(concept.beliefs.is_empty());
+
+        let belief = system.memory.get(&parse_narsese("<cat --> animal>.").unwrap().term).expect("internal belief created");
+        assert!(!belief.beliefs.is_empty());
+
+        let goal = system.memory.get(&parse_narsese("<cat --> fed>!").unwrap().term).expect("internal goal created");
+        assert_eq!(goal.goals.len(), 1);
+
+        assert!(system.execute(&parse_narsese("(^fly, me)!").unwrap().term).is_err());
+    }
+
+    #[test]
+    fn test_complexity_limits_derivations() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let small = parse_narsese("<a --> b>.").unwrap().term;
+        let large = parse_narsese("<(&, a, b, c, d, e, f) --> (|, g, h, i, j, k)>.").unwrap().term;
+        assert_eq!(system.complexity_penalty(&small), 1.0);
+        assert!(system.complexity_penalty(&large) < 1.0);
+
+        // Nothing above the hard limit is derived
+        system.max_complexity = 4;
+        system.seed(1);
+        system.input(parse_narsese("<a --> b>.").unwrap());
+        system.input(parse_narsese("<c --> b>.").unwrap());
+        system.run_cycles(20);
+        assert!(system.stats.suppressed_derivations > 0);
+        assert!(system.memory.keys().all(|t| t.complexity() <= 4));
+    }
+
+    #[test]
+    fn test_temporal_induction_with_intervals() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::{Punctuation, Sentence};
+
+        let event = |name: &str, time: u64, id: u64| {
+            Sentence::new(Term::atom_from_str(name), Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(time, vec![id]))
+        };
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let first = system.induce_temporal(&event("a", 2, 1), &event("b", 12, 2)).unwrap();
+        assert_eq!(first.term.to_string(), "<(&/, a, +11) =/> b>");
+
+        // A similar delay maps to the same approximate interval and revises the implication
+        let second = system.induce_temporal(&event("a", 20, 3), &event("b", 32, 4)).unwrap();
+        assert_eq!(second.term, first.term);
+        let concept = system.memory.get(&first.term).unwrap();
+        assert_eq!(concept.beliefs.len(), 2);
+        assert!(concept.truth.confidence > first.truth.confidence);
+
+        // Intervals already in the antecedent are merged, simultaneous events are concurrent
+        let seq = Sentence::new(parse_narsese("(&/, x, +2).").unwrap().term, Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(5, vec![5]));
+        let merged = system.induce_temporal(&seq, &event("y", 7, 6)).unwrap();
+        assert_eq!(merged.term.to_string(), "<(&/, x, +4) =/> y>");
+        let concurrent = system.induce_temporal(&event("c", 9, 7), &event("d", 9, 8)).unwrap();
+        assert_eq!(concurrent.term.to_string(), "<c =|> d>");
+    }
+
+    #[test]
+    fn test_revision_history() {
+        use crate::nars::memory::HISTORY_CAPACITY;
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = parse_narsese("<swan --> white>.").unwrap().term;
+        let observe = |system: &mut NarsSystem, f: f32, id: u64| {
+            system.add_concept(Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(f, 0.9), Stamp::new(0, vec![id])), true);
+        };
+
+        observe(&mut system, 1.0, 1);
+        assert!(system.history(&term).unwrap().is_empty());
+        observe(&mut system, 0.0, 2);
+        let history = system.history(&term).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].previous, TruthValue::new(1.0, 0.9));
+        assert_eq!(history[0].stamp.evidence, vec![2]);
+        assert!(history[0].revised.frequency < 1.0);
+
+        for id in 3..40 {
+            observe(&mut system, 1.0, id);
+        }
+        let history = system.history(&term).unwrap();
+        assert_eq!(history.len(), HISTORY_CAPACITY);
+        assert_eq!(history.last().unwrap().stamp.evidence, vec![39]);
+        assert!(system.history(&Term::atom_from_str("unknown")).is_none());
+    }
+
+    #[test]
+    fn test_run_script_report() {
+        use crate::nars::script::run_script_str;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.seed(1);
+        let script = "' deduction\n<a --> b>.\n<b --> c>.\n''outputMustContain('<a --> c>. %1.00;0.81%')\n''outputMustContain('<c --> a>. %1.00;0.99%')\nnot narsese\n20\n// comment lines are not input\n";
+        let report = run_script_str(&mut system, script).unwrap();
+
+        assert_eq!(report.inputs, 2);
+        assert_eq!(report.cycles, 20);
+        assert_eq!(report.parse_errors.len(), 1);
+        assert_eq!(report.parse_errors[0].0, 6);
+        assert_eq!(report.expectations_met, vec!["<a --> c>. %1.00;0.81%".to_string()]);
+        assert_eq!(report.expectations_unmet, vec!["<c --> a>. %1.00;0.99%".to_string()]);
+        assert!(!report.passed());
+        assert!(report.closest_output("<d --> a>. %1.00;0.90%").is_none());
+    }
+
+    #[test]
+    fn test_expectation_tolerance() {
+        use crate::nars::script::run_script_str;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.seed(1);
+        let script = "<a --> b>.\n<b --> c>.\n\
+            ''outputMustContain('<a --> c>. %1.0;0.85%', 0.05)\n\
+            ''outputMustContain('<a --> c>. %1.0;0.70%')\n\
+            ''outputMustContain('<a --> c>. %1.0;0.81%', much)\n50\n";
+        let report = run_script_str(&mut system, script).unwrap();
+
+        // Within 0.05 of the derived %1.00;0.81%, but not within the default 0.01
+        assert_eq!(report.expectations_met, vec!["<a --> c>. %1.0;0.85%".to_string()]);
+        assert_eq!(report.expectations_unmet, vec!["<a --> c>. %1.0;0.70%".to_string()]);
+        // A malformed expectation is an error, not silently skipped
+        assert_eq!(report.parse_errors.len(), 1);
+        assert_eq!(report.parse_errors[0].0, 5);
+        let (closest, distance) = report.closest_output("<a --> c>. %1.0;0.70%").unwrap();
+        assert_eq!(closest.term.to_string(), "<a --> c>");
+        assert!(distance > 0.01 && distance <= 0.3, "{}", distance);
+    }
+
+    #[test]
+    fn test_reasoner_config_limits_inference() {
+        let input = |system: &mut NarsSystem| {
+            system.input(crate::nars::parser::parse_narsese("<a --> b>.").unwrap());
+            system.input(crate::nars::parser::parse_narsese("<b --> c>.").unwrap());
+            system.run_cycles(20);
+        };
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        input(&mut system);
+        assert!(system.stats.derivations > 0);
+
+        let mut limited = NarsSystem::new(0.1, -1.0);
+        limited.reasoner.max_rule_applications = Some(0);
+        input(&mut limited);
+        assert_eq!(limited.stats.derivations, 0);
+
+        // A limit keeps the rules with the highest priority, not the first ones in the table
+        let fired = |favored: Option<&str>| {
+            let mut system = NarsSystem::new(0.1, -1.0);
+            system.feedback_rate = 0.0;
+            system.reasoner.max_rule_applications = Some(1);
+            if let Some(name) = favored {
+                let favored = system.rules.iter().position(|r| r.name == name).unwrap();
+                system.rule_priorities = vec![0.1; system.rules.len()];
+                system.rule_priorities[favored] = 0.9;
+            }
+            system.input(crate::nars::parser::parse_narsese("<a ==> b>.").unwrap());
+            system.run_cycles(5);
+            (0..system.rules.len()).filter(|&i| system.rule_usage(i).fired > 0).map(|i| system.rules[i].name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(fired(None), ["conversion"]);
+        assert_eq!(fired(Some("contraposition")), ["contraposition"]);
+
+        let mut isolated = NarsSystem::new(0.1, -1.0);
+        isolated.reasoner.max_partners = 0;
+        input(&mut isolated);
+        assert!(isolated.memory.get(&crate::nars::parser::parse_term("<a --> c>").unwrap().1).is_none());
+    }
+
+    #[test]
+    fn test_input_batch() {
+        use crate::nars::parser::parse_narsese;
+
+        let sentences: Vec<_> = ["<a --> b>.", "<b --> c>.", "<a --> b>. %1.0;0.9%", "<a --> c>?"]
+            .iter()
+            .map(|s| parse_narsese(s).unwrap())
+            .collect();
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        assert_eq!(system.input_batch(&sentences, true), 2);
+        assert!(system.output_buffer.is_empty());
+        assert_eq!(system.lanes.len(), 1); // the question is queued as usual
+
+        let ab = system.memory.get(&sentences[0].term).unwrap();
+        assert!(ab.truth.confidence > 0.9);
+        assert_eq!(ab.stamp.evidence.len(), 2);
+        let bc = system.memory.get(&sentences[1].term).unwrap();
+        assert!(!bc.stamp.overlaps(&ab.stamp));
+
+        let mut sequential = NarsSystem::new(0.1, -1.0);
+        sequential.input_batch(&sentences, false);
+        assert_eq!(sequential.memory.get(&sentences[0].term).unwrap().vector, ab.vector);
+    }
+
+    #[test]
+    fn test_unanswered_question_event() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        // Only the two questions asked, none raised on their missing premises
+        system.reasoner.backward = false;
+        let fact = parse_narsese("<cat --> animal>.").unwrap().term;
+        system.add_concept(Concept::new(fact.clone(), Hypervector::from_term(&fact), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![1])), true);
+        system.input(parse_narsese("<dog --> animal>?").unwrap());
+        system.input(parse_narsese("<?x --> animal>?").unwrap());
+        system.run_cycles(100);
+
+        assert_eq!(system.unanswered.len(), 2);
+        let event = |question: &str| system.unanswered.iter()
+            .find(|e| e.question.term.to_string() == question)
+            .and_then(|e| e.best_match.clone())
+            .expect("reported with a closest belief");
+
+        assert!(!event("<dog --> animal>").unifies);
+        // Not answered directly, but a unifying belief exists
+        let open = event("<?x --> animal>");
+        assert!(open.unifies);
+        assert_eq!(open.term, fact);
+    }
+
+    #[test]
+    fn test_rule_annotations() {
+        use crate::nars::rules::{TruthRegistry, DesireSelector, Precondition};
+        use crate::nars::static_rules::parse_rules;
+
+        let text = "
+            (:M --> :P) (:S --> :M) !- (:S --> :P) :t/deduction :d/strong :allow-backward
+            (:P --> :M) (:S --> :M) !- (:S --> :P) abduction :pre ((:!= :S :P))
+            (:S --> :P) !- (:P --> :S) :t/belief-structural-deduction :question?
+            (:S --> :P) !- (:S --> :P) deduction :p/judgment
+            (:S --> :P) !- (:S --> :P) :t/belief-unknown
+        ";
+        let (rules, skipped) = parse_rules(text, &TruthRegistry::new()).unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped[0].reason.contains(":p/judgment"));
+
+        assert_eq!(rules[0].meta.desire, Some(DesireSelector::Strong));
+        assert!(rules[0].meta.allow_backward && !rules[0].meta.question_only);
+        assert!(matches!(rules[1].meta.preconditions.as_slice(), [Precondition::NotEqual(..)]));
+        assert!(rules[2].meta.question_only);
+        assert_eq!(rules[2].name, "belief-structural-deduction");
+    }
+
+    #[test]
+    fn test_question_only_rule_and_precondition() {
+        use crate::nars::control::SharedResources;
+        use crate::nars::parser::parse_narsese;
+        use std::sync::Arc;
+
+        let resources = SharedResources { rules: Arc::new(Vec::new()), projector: None };
+        let mut system = NarsSystem::with_resources(0.1, -1.0, resources);
+        let report = system.load_rules("
+            (:S --> :P) !- (:P --> :S) :t/belief-structural-deduction :question?
+            (:S --> :P) !- (:P <-> :S) structural_deduction :pre ((:!= :S :P))
+        ").unwrap();
+        assert_eq!(report.loaded, 2);
+
+        system.input(parse_narsese("<a --> b>.").unwrap());
+        system.input(parse_narsese("<c --> c>.").unwrap());
+        system.input(parse_narsese("<x --> y>?").unwrap()); // unanswered, so it stays on its concept
+        system.seed(0);
+        system.run_cycles(40);
+
+        let concept = |s: &str| system.memory.get(&system.canonical(&parse_narsese(s).unwrap().term));
+        let reversed = concept("<y --> x>?").expect("question derived");
+        assert!(!reversed.questions.is_empty());
+        assert!(reversed.beliefs.is_empty());
+        assert!(concept("<b <-> a>.").is_some());
+        assert!(concept("<c <-> c>.").is_none());
+    }
+
+    #[test]
+    fn test_builtin_rule_table() {
+        use crate::nars::rules::TruthFunction;
+        use crate::nars::static_rules::get_all_rules;
+
+        let rules = get_all_rules();
+        assert!(!rules.is_empty());
+        // Premise count always matches the truth function arity
+        for rule in &rules {
+            let arity = match rule.truth_fn {
+                TruthFunction::Single(_) => 1,
+                TruthFunction::Double(_) => 2,
+            };
+            assert_eq!(rule.premises.len(), arity, "rule {} -> {}", rule.name, rule.conclusion);
+        }
+    }
+
+    #[test]
+    fn test_answer_question_with_sources() {
+        use crate::nars::parser::{parse_narsese, parse_term};
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.input(parse_narsese("<a --> b>.").unwrap());
+        system.input(parse_narsese("<b --> c>.").unwrap());
+
+        let answers = system.answer_question(&parse_term("<a --> c>").unwrap().1, 200);
+        let best = answers.first().expect("answered within the cycle limit");
+        assert_eq!(best.sentence.term.to_string(), "<a --> c>");
+        assert!(best.cycles > 0);
+        let sources: Vec<String> = best.sources.iter().map(|s| s.term.to_string()).collect();
+        assert!(sources.contains(&"<a --> b>".to_string()) && sources.contains(&"<b --> c>".to_string()), "{:?}", sources);
+
+        // Query variables are answered by unification
+        let open = system.answer_question(&parse_term("<?x --> b>").unwrap().1, 10);
+        assert!(open.iter().any(|a| a.sentence.term.to_string() == "<a --> b>"));
+        assert!(system.answer_question(&parse_term("<z --> q>").unwrap().1, 5).is_empty());
+    }
+
+    #[test]
+    fn test_nearest_terms_uses_grounded_vectors() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let atom = Term::atom_from_str;
+        let cat = Hypervector::random();
+        system.vocabulary.insert("cat".to_string(), cat);
+        for (name, vector) in [("kitten", cat), ("car", Hypervector::random()), ("tree", Hypervector::random())] {
+            system.add_concept(Concept::new(atom(name), vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), false);
+        }
+
+        // "cat" has no concept, only a grounded embedding
+        let nearest = system.nearest_terms(&system.vector_for(&atom("cat")), 2);
+        assert_eq!(nearest.len(), 2);
+        assert_eq!(nearest[0].0, atom("kitten"));
+        assert!(nearest[0].1 > nearest[1].1);
+    }
+
+    #[test]
+    fn test_input_stamping_and_dedup() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::Punctuation;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.run_cycles(3);
+        assert!(system.input(parse_narsese("<a --> b>.").unwrap()));
+        assert!(!system.input(parse_narsese("<a --> b>.").unwrap()), "duplicate within the window");
+        assert!(system.input(parse_narsese("<a --> b>. %0.5;0.9%").unwrap()), "different truth is new input");
+        assert!(system.input(parse_narsese("<a --> b>!").unwrap()));
+        assert_eq!(system.lanes.len(), 3);
+
+        let stamps: Vec<_> = system.lanes.lane_mut(Punctuation::Judgement).tasks.values().map(|t| t.sentence.stamp.clone()).collect();
+        assert_eq!(stamps.len(), 2);
+        assert!(stamps.iter().all(|s| s.evidence.len() == 1 && s.creation_time == 3));
+        assert!(!stamps[0].overlaps(&stamps[1]));
+        let goal = system.lanes.lane_mut(Punctuation::Goal).tasks.values().next().unwrap().sentence.clone();
+        assert_eq!(goal.stamp.evidence.len(), 1);
+        // Questions may be asked again
+        assert!(system.input(parse_narsese("<a --> b>?").unwrap()));
+        assert!(system.input(parse_narsese("<a --> b>?").unwrap()));
+
+        system.run_cycles(20);
+        // Outside the window the same sentence counts as new evidence again
+        assert!(system.input(parse_narsese("<a --> b>.").unwrap()));
+    }
+
+    #[test]
+    fn test_symmetric_copula_canonical_order() {
+        use crate::nars::sentence::{Punctuation, Sentence};
+        use crate::nars::term::VarType;
+        use crate::nars::unify::unify;
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let a = Term::atom_from_str("a");
+        let b = Term::atom_from_str("b");
+        let ab = Term::Compound(Operator::Similarity, vec![a.clone(), b.clone()]);
+        let ba = Term::Compound(Operator::Similarity, vec![b.clone(), a.clone()]);
+        system.input(Sentence::new(ab.clone(), Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])));
+        system.input(Sentence::new(ba.clone(), Punctuation::Judgement, TruthValue::new(0.8, 0.9), Stamp::new(0, vec![])));
+        system.run_cycles(2);
+
+        // Both orientations revise a single concept
+        assert!(system.memory.get(&ba).is_none());
+        let concept = system.memory.get(&ab).unwrap();
+        assert_eq!(concept.beliefs.len(), 2);
+        assert!(concept.truth.confidence > 0.9);
+        assert!(system.answer_query(&ba).is_some());
+
+        // The unifier matches either orientation
+        let x = Term::var_from_str(VarType::Query, "x");
+        let pattern = Term::Compound(Operator::Similarity, vec![x.clone(), a.clone()]);
+        assert_eq!(unify(&pattern, &ab).unwrap().get(&x), Some(&b));
+
+        // Without canonical ordering the orientations stay apart
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.symmetric_copulas = false;
+        system.input(Sentence::new(ab.clone(), Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])));
+        system.input(Sentence::new(ba.clone(), Punctuation::Judgement, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])));
+        system.run_cycles(2);
+        assert!(system.memory.get(&ab).is_some() && system.memory.get(&ba).is_some());
+    }
+
+    #[test]
+    fn test_nal_level_profiles() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let level_of = |system: &NarsSystem, name: &str| {
+            system.rules.iter().filter(|r| r.name == name).map(|r| r.nal_level()).min().unwrap()
+        };
+        assert_eq!(level_of(&system, "deduction"), 1);
+        assert_eq!(level_of(&system, "resemblance"), 2);
+        assert_eq!(level_of(&system, "union"), 3);
+        assert!(system.rules.iter().any(|r| r.name == "induction" && r.nal_level() == 6)); // introduces $X
+
+        system.set_nal_level(1);
+        assert!(system.rules.iter().enumerate().all(|(i, r)| system.is_rule_enabled(i) == (r.nal_level() <= 1)));
+        assert_eq!(system.set_rule_enabled("conversion", false), 2);
+
+        system.input(parse_narsese("<a --> b>.").unwrap());
+        system.input(parse_narsese("<b --> a>.").unwrap());
+        system.run_cycles(50);
+        assert!(system.memory.keys().all(|t| t.nal_level() <= 1), "only NAL-1 conclusions expected");
+
+        // Rules loaded later respect the current level
+        let report = system.load_rules("(:M <-> :P) (:M --> :S) !- (:S --> :P) analogy").unwrap();
+        assert_eq!(report.loaded, 1);
+        assert!(!system.is_rule_enabled(system.rules.len() - 1));
+    }
+
+    #[test]
+    fn test_novelty_drops_rederivations() {
+        let run = |novelty_threshold: f32| {
+            let mut system = NarsSystem::new(0.1, -1.0);
+            system.seed(3);
+            system.novelty_threshold = novelty_threshold;
+            let a = Term::atom_from_str("a");
+            let b = Term::atom_from_str("b");
+            let c = Term::atom_from_str("c");
+            for (i, (s, p)) in [(a.clone(), b.clone()), (b, c.clone())].into_iter().enumerate() {
+                let term = Term::Compound(Operator::Inheritance, vec![s, p]);
+                let v = system.resolve_vector(&term);
+                system.add_concept(Concept::new(term, v, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![i as u64 + 1])), true);
+            }
+            system.run_cycles(100);
+            let ac = Term::Compound(Operator::Inheritance, vec![a, c]);
+            // Beliefs that repeat an earlier one (same truth from the same evidence)
+            let repeats = system.memory.get(&ac).map_or(0, |concept| {
+                let beliefs = &concept.beliefs;
+                (1..beliefs.len()).filter(|&i| beliefs[..i].iter().any(|b| b.truth == beliefs[i].truth && b.stamp.evidence == beliefs[i].stamp.evidence)).count()
+            });
+            (system.stats.redundant_derivations, repeats)
+        };
+
+        // Re-deriving known content from the same premises is dropped
+        let (redundant, repeats) = run(0.95);
+        assert!(redundant > 0);
+        assert_eq!(repeats, 0);
+
+        let (redundant, repeats) = run(1.1);
+        assert_eq!(redundant, 0);
+        assert!(repeats > 0);
+    }
+
+    #[test]
+    fn test_evidence_registry() {
+        use crate::nars::script::run_script_str;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        run_script_str(&mut system, "<a --> b>.\n<b --> c>.\n<a --> b>?\n").unwrap();
+        let record = system.evidence(1).expect("first input registered");
+        assert_eq!(record.sentence.term.to_string(), "<a --> b>");
+        assert_eq!((record.source.to_string(), record.line), ("script".to_string(), Some(1)));
+        assert_eq!(system.evidence(2).unwrap().line, Some(2));
+        assert_eq!(system.evidence(2).unwrap().source, record.source);
+        assert!(system.evidence(3).is_none()); // questions carry no evidence
+
+        let derived = Stamp::new(0, vec![1, 2, 7]);
+        assert_eq!(system.evidence_labels(&derived), vec!["script:1", "script:2", "#7"]);
+        let sources: Vec<String> = system.evidence_sources(&derived).iter().map(|s| s.term.to_string()).collect();
+        assert_eq!(sources, vec!["<a --> b>", "<b --> c>"]);
+
+        // Only the latest inputs are kept on record
+        system.evidence_capacity = 2;
+        run_script_str(&mut system, "<c --> d>.\n").unwrap();
+        assert!(system.evidence(1).is_none());
+        assert_eq!(system.evidence(2).unwrap().sentence.term.to_string(), "<b --> c>");
+        assert_eq!(system.evidence_labels(&derived)[0], "#1");
+    }
+
+    #[test]
+    fn test_source_reliability() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::{Sentence, Source};
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let rdf = Source::Import("dump.rdf".to_string());
+        system.set_source_reliability(rdf.clone(), 0.5);
+        let mut batch: Vec<Sentence> = ["<a --> b>.", "<b --> c>."].iter().map(|s| parse_narsese(s).unwrap()).collect();
+        system.input_batch_from(&batch, false, rdf.clone());
+        let ab = batch.remove(0).term;
+        let concept = system.memory.get(&ab).unwrap().clone();
+        assert!((concept.truth.confidence - 0.45).abs() < 1e-6);
+        assert_eq!(concept.stamp.sources, vec![rdf.clone()]);
+
+        // User input keeps its confidence; derived stamps carry both sources
+        system.input(parse_narsese("<c --> d>.").unwrap());
+        system.run_cycles(1);
+        let cd = system.memory.get(&parse_narsese("<c --> d>.").unwrap().term).unwrap();
+        assert!((cd.truth.confidence - 0.9).abs() < 1e-6);
+        let merged = concept.stamp.merge(&cd.stamp);
+        assert_eq!(merged.sources, vec![rdf, Source::User]);
+
+        // Embeddings from an import are discounted as well
+        let glove = Source::Import("glove.txt".to_string());
+        system.set_source_reliability(glove.clone(), 0.2);
+        let mut stamp = Stamp::new(0, vec![]);
+        stamp.add_source(glove);
+        let word = Term::atom_from_str("word");
+        system.add_embedding(Concept::new(word.clone(), Hypervector::random(), TruthValue::new(0.5, 0.5), stamp));
+        assert!((system.memory.get(&word).unwrap().truth.confidence - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pinned_concepts_are_not_forgotten() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let a = Term::atom_from_str("a");
+        let b = Term::atom_from_str("b");
+        let axiom = Term::Compound(Operator::Inheritance, vec![a.clone(), b.clone()]);
+        let other = Term::Compound(Operator::Similarity, vec![a.clone(), b.clone()]);
+        for term in [&axiom, &other] {
+            let v = system.resolve_vector(term);
+            system.add_concept(Concept::new(term.clone(), v, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), true);
+        }
+        system.pin(&axiom);
+        assert!(system.is_pinned(&axiom));
+
+        // Retracting `a` removes it and everything built on it except the pinned concept
+        assert_eq!(system.retract(&a).unwrap(), 2);
+        assert!(system.memory.get(&axiom).is_some());
+        assert!(system.memory.get(&other).is_none());
+
+        assert!(system.unpin(&axiom));
+        assert!(!system.unpin(&axiom));
+        assert_eq!(system.retract(&a).unwrap(), 1);
+        assert!(system.memory.get(&axiom).is_none());
+
+        // A full attention buffer keeps pinned terms
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.buffer = crate::nars::bag::Bag::new(2);
+        system.pin(&a);
+        system.pin(&b);
+        system.add_concept(Concept::new(a.clone(), Hypervector::from_term(&a), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![1])), true);
+        system.add_concept(Concept::new(b.clone(), Hypervector::from_term(&b), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![2])), true);
+        system.add_concept(Concept::new(axiom.clone(), Hypervector::from_term(&axiom), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![4])), true);
+        assert_eq!(system.buffer.count, 3);
+        assert!(system.buffer.name_map.contains_key(&a) && system.buffer.name_map.contains_key(&b));
+    }
+
+    #[test]
+    fn test_rule_coverage() {
+        use crate::nars::script::{run_script_str, RuleCoverage};
+
+        let mut coverage = RuleCoverage::default();
+        for _ in 0..2 {
+            let mut system = NarsSystem::new(0.1, -1.0);
+            run_script_str(&mut system, "<a --> b>. %1.00;0.90%\n5\n").unwrap();
+            coverage.record(&system);
+        }
+
+        // A single belief only enables immediate inference: conversion fires, syllogisms never do
+        let conversion = coverage.rules.iter().position(|r| r.ends_with(" conversion")).unwrap();
+        let deduction = coverage.rules.iter().position(|r| r.ends_with(" deduction")).unwrap();
+        assert!(coverage.usage[conversion].fired >= 2);
+        assert_eq!(coverage.usage[conversion].fired,
+            coverage.usage[conversion].accepted + coverage.usage[conversion].rejected);
+        assert!(coverage.never_fired().contains(&coverage.rules[deduction].as_str()));
+        assert!(!coverage.never_fired().contains(&coverage.rules[conversion].as_str()));
+    }
+
+    #[test]
+    fn test_quests_answered_from_desires() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::Punctuation;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = parse_narsese("<a ==> b>.").unwrap().term;
+        let belief = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![100]));
+        system.add_concept(belief, true);
+        assert!(system.answer_quest(&Term::atom_from_str("a")).is_none());
+
+        // b! makes a desired through <a ==> b>; the quest a@ is answered with that desire
+        system.input(parse_narsese("b!").unwrap());
+        system.input(parse_narsese("a@").unwrap());
+        system.run_cycles(20);
+        assert!(system.output_buffer.iter().any(|s| s.term == Term::atom_from_str("a") && s.punctuation == Punctuation::Goal));
+        let answer = system.answer_quest(&Term::atom_from_str("a")).expect("quest answered");
+        assert_eq!(answer.punctuation, Punctuation::Goal);
+        assert!((answer.truth.confidence - 0.81).abs() < 1e-3);
+
+        // A query variable matches every desired term; the input goal is the most desired
+        let any = system.answer_quest(&parse_narsese("?x@").unwrap().term).unwrap();
+        assert_eq!(any.term, Term::atom_from_str("b"));
+    }
+
+    #[test]
+    fn test_anytime_answer_refinement() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.refinement_confidence = 0.75;
+        let term = parse_narsese("<a --> c>.").unwrap().term;
+        let belief = |confidence: f32, id: u64| Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, confidence), Stamp::new(0, vec![id]));
+        system.add_concept(belief(0.3, 100), true);
+        system.input(parse_narsese("<a --> c>?").unwrap());
+        for _ in 0..20 {
+            if !system.answers.is_empty() {
+                break;
+            }
+            system.cycle();
+        }
+        assert_eq!(system.answers.len(), 1);
+        assert!(!system.answers[0].refinement);
+
+        // The weak answer left the question alive; independent evidence improves it
+        system.add_concept(belief(0.8, 101), true);
+        system.run_cycles(30);
+        let refinements: Vec<_> = system.answers.iter().filter(|e| e.refinement).collect();
+        assert_eq!(refinements.len(), 1);
+        assert_eq!(refinements[0].question, term);
+        assert!(refinements[0].answer.truth.confidence >= 0.8);
+
+        // A confident answer closes the question: nothing more is reported
+        system.answers.clear();
+        system.add_concept(belief(0.95, 102), true);
+        system.run_cycles(30);
+        assert!(system.answers.is_empty());
+    }
+
+    #[test]
+    fn test_knowledge_pack_layering() {
+        use crate::nars::parser::parse_narsese;
+
+        let term = |text: &str| parse_narsese(text).unwrap().term;
+        let mut source = NarsSystem::new(0.1, -1.0);
+        for (i, text) in ["<cat --> animal>.", "<dog --> animal>."].iter().enumerate() {
+            let t = term(text);
+            source.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, 0.6), Stamp::new(0, vec![i as u64 + 1])), true);
+        }
+        let path = std::env::temp_dir().join(format!("nars_layers_{}.pack", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let written = source.export_pack(&path).unwrap();
+        assert_eq!(written, source.memory.len());
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        assert_eq!(system.mount_pack(&path).unwrap(), written);
+        assert!(system.memory.is_empty());
+
+        // Lookups and similarity searches see the pack
+        let cat = term("<cat --> animal>.");
+        let answer = system.answer_query(&cat).expect("answered from the pack");
+        assert_eq!(answer.truth.confidence, 0.6);
+        assert_eq!(system.resolve_vector(&cat), source.memory.get(&cat).unwrap().vector);
+        let nearest = system.nearest_terms(&source.memory.get(&cat).unwrap().vector, 1);
+        assert_eq!(nearest[0].0, cat);
+
+        // Revision writes the combined concept to memory; the pack itself is unchanged
+        system.add_concept(Concept::new(cat.clone(), Hypervector::from_term(&cat), TruthValue::new(1.0, 0.6), Stamp::new(0, vec![100])), true);
+        let revised = system.memory.get(&cat).expect("copied into memory");
+        assert!(revised.truth.confidence > 0.6);
+        assert_eq!(revised.beliefs.len(), 2);
+        let pack = &system.packs[0];
+        assert_eq!(pack.truth(pack.find(&cat).unwrap()).confidence, 0.6);
+        assert!(system.answer_query(&cat).unwrap().truth.confidence >= 0.6);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_pipelined_matching_matches_sequential() {
+        use crate::nars::parser::parse_narsese;
+        use std::sync::Arc;
+        use crate::nars::pipeline::{match_pair, rank_partners, MatchArena, Pipeline, ShapeCache};
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.set_rule_enabled("induction", false);
+        let term = |text: &str| parse_narsese(text).unwrap().term;
+        let subject = term("<m --> p>.");
+        let partners = vec![term("<s --> m>."), term("<m --> s>."), term("<p --> m>."), term("<x ==> y>.")];
+
+        let mut pipeline = Pipeline::spawn();
+        let results = pipeline.submit(system.rules.clone(), &system.rule_enabled, subject.clone(), partners.clone(), true);
+        for partner in &partners {
+            let (forward, backward) = results.recv().unwrap();
+            assert_eq!(forward, match_pair(&system.rules, &system.rule_enabled, &subject, partner, &mut ShapeCache::new(), &mut MatchArena::new()));
+            assert_eq!(backward, match_pair(&system.rules, &system.rule_enabled, partner, &subject, &mut ShapeCache::new(), &mut MatchArena::new()));
+        }
+        // Retrieval ranks partners as the sequential cycle does
+        let query = Hypervector::from_term(&subject);
+        let candidates: Vec<(Term, Arc<Concept>)> = partners.iter()
+            .map(|t| (t.clone(), Arc::new(Concept::new(t.clone(), Hypervector::from_term(t), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])))))
+            .collect();
+        let retrieved = pipeline.retrieve(query, candidates.clone(), 0.0, 3).wait();
+        assert_eq!(retrieved.len(), 3);
+        assert_eq!(retrieved, rank_partners(&query, candidates, 0.0, 3));
+        drop(pipeline); // joins both stages
+
+        // Disabled rules are not matched
+        let induction: Vec<usize> = (0..system.rules.len()).filter(|&i| system.rules[i].name == "induction").collect();
+        let matched = match_pair(&system.rules, &system.rule_enabled, &subject, &term("<m --> s>."), &mut ShapeCache::new(), &mut MatchArena::new());
+        assert!(matched.iter().all(|(idx, _)| !induction.contains(idx)));
+
+        // A pipelined system reasons as before
+        system.reasoner.pipelined = true;
+        for (i, text) in ["<a --> b>.", "<b --> c>."].iter().enumerate() {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![i as u64 + 1])), true);
+        }
+        system.run_cycles(50);
+        assert!(system.memory.get(&term("<a --> c>.")).is_some_and(|c| !c.beliefs.is_empty()));
+    }
+
+    #[test]
+    fn test_metrics_export() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        // Only the two questions asked, none raised on their missing premises
+        system.reasoner.backward = false;
+        let fact = parse_narsese("<cat --> animal>.").unwrap().term;
+        system.add_concept(Concept::new(fact.clone(), Hypervector::from_term(&fact), TruthValue::new(1.0, 0.95), Stamp::new(0, vec![100])), true);
+        system.input(parse_narsese("<cat --> animal>?").unwrap());
+        system.run_cycles(10);
+
+        let metrics = system.metrics();
+        assert_eq!(metrics.cycles, 10);
+        assert_eq!(metrics.concepts, system.memory.len());
+        assert_eq!(metrics.answer_latency.count, 1);
+        assert!(metrics.answer_latency.sum <= 10);
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("# TYPE nars_cycles_total counter\nnars_cycles_total 10\n"));
+        assert!(text.contains("nars_answer_latency_cycles_bucket{le=\"+Inf\"} 1\n"));
+        assert!(text.contains("nars_answer_latency_cycles_count 1\n"));
+        // Buckets are cumulative
+        let buckets: Vec<u64> = text.lines()
+            .filter(|l| l.starts_with("nars_answer_latency_cycles_bucket"))
+            .map(|l| l.rsplit(' ').next().unwrap().parse().unwrap())
+            .collect();
+        assert!(buckets.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_input_truth_policy() {
+        use crate::nars::truth::TruthValue;
+
+        let mut system = NarsSystem::new(0.1, 0.4);
+        system.reasoner.truth_defaults.tensed_judgement = TruthValue::new(1.0, 0.6);
+        assert_eq!(system.parse("<a --> b>. :|:").unwrap().truth, TruthValue::new(1.0, 0.6));
+        assert_eq!(system.parse("<a --> b>.").unwrap().truth, TruthValue::new(1.0, 0.9));
+        assert!(system.parse("<a --> b").is_err());
+    }
+
+    #[test]
+    fn test_pipe_protocol() {
+        use crate::nars::script::run_pipe;
+
+        let mut system = NarsSystem::new(0.1, 0.4);
+        system.seed(6);
+        let input = "' comment\n<a --> b>.\n<b --> c>.\n<a --> c>?\n20\nnot narsese\n";
+        let mut out = Vec::new();
+        run_pipe(&mut system, input.as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(&lines[..3], ["IN: <a --> b>. %1.00;0.90%", "IN: <b --> c>. %1.00;0.90%", "IN: <a --> c>?"]);
+        assert!(lines.contains(&"Answer: <a --> c>. %1.00;0.81%"), "{}", out);
+        assert!(lines.iter().all(|l| ["IN: ", "OUT: ", "Answer: ", "ERR: "].iter().any(|p| l.starts_with(p))), "{}", out);
+        assert!(lines.last().unwrap().starts_with("ERR: "));
+    }
+
+    #[test]
+    fn test_similarity_bridged_inference_is_discounted() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::term::Term;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.load_rules("(:A --> :B) (:C --> :D) !- (& (:A --> :B) (:C --> :D)) intersection").unwrap();
+        system.seed(1);
+        for text in ["<cat --> animal>.", "<sun --> star>.", "<animal --> being>."] {
+            system.input(parse_narsese(text).unwrap());
+        }
+        for _ in 0..30 {
+            system.cycle();
+        }
+
+        let confidences = |text: &str| -> Vec<f32> {
+            let term = parse_narsese(text).unwrap().term;
+            system.output_buffer.iter().filter(|s| s.term == term).map(|s| s.truth.confidence).collect()
+        };
+        // Linked by a shared term: plain deduction
+        assert!(confidences("<cat --> being>.").iter().any(|c| (c - 0.81).abs() < 0.01));
+        // Unrelated premises are only bridged by their (random, ~0.5) vector similarity
+        let mut bridged = confidences("(&, <cat --> animal>, <sun --> star>).");
+        bridged.extend(confidences("(&, <sun --> star>, <cat --> animal>)."));
+        // (later revisions of the conjunction may gain confidence)
+        let first = bridged.iter().copied().reduce(f32::min).expect("bridged conjunction");
+        assert!(first < 0.81 * 0.75, "{:?}", bridged);
+        assert!(!Term::atom_from_str("cat").shares_atom(&Term::atom_from_str("sun")));
+    }
+
+    #[test]
+    fn test_export_beliefs() {
+        use crate::nars::control::{BeliefFilter, BeliefOrigin};
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::script::{run_script_file, write_nal};
+        use crate::nars::sentence::Source;
+
+        let sentence = |text: &str| parse_narsese(text).unwrap();
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.input_batch_from(&[sentence("<cat --> animal>."), sentence("<animal --> being>.")], false, Source::User);
+        system.input_batch_from(&[sentence("<sun --> bright>.")], false, Source::Sensor("eye".to_string()));
+        let premises = system.memory.get(&sentence("<cat --> animal>.").term).unwrap().stamp
+            .merge(&system.memory.get(&sentence("<animal --> being>.").term).unwrap().stamp);
+        let derived = sentence("<cat --> being>.").term;
+        system.add_concept(Concept::new(derived.clone(), Hypervector::from_term(&derived), TruthValue::new(1.0, 0.81), premises), true);
+
+        let terms = |filter: BeliefFilter| -> Vec<String> {
+            system.export_beliefs(&filter).iter().map(|s| s.term.to_string()).collect()
+        };
+        assert_eq!(terms(BeliefFilter::default()), ["<animal --> being>", "<cat --> animal>", "<cat --> being>", "<sun --> bright>"]);
+        assert_eq!(terms(BeliefFilter { origin: Some(BeliefOrigin::Derived), ..Default::default() }), ["<cat --> being>"]);
+        assert_eq!(terms(BeliefFilter { origin: Some(BeliefOrigin::Input), ..Default::default() }).len(), 3);
+        assert_eq!(terms(BeliefFilter { min_confidence: 0.85, ..Default::default() }).len(), 3);
+        assert_eq!(terms(BeliefFilter { eternal_only: true, ..Default::default() }).len(), 4);
+        assert_eq!(terms(BeliefFilter { source: Some("sensor:".to_string()), ..Default::default() }), ["<sun --> bright>"]);
+        // Age counts from the newest input behind a belief, so derived ones are as recent as their premises
+        assert_eq!(terms(BeliefFilter { max_age: Some(0), ..Default::default() }).len(), 4);
+
+        // The dump is a script that rebuilds the same beliefs
+        let path = std::env::temp_dir().join(format!("nars_dump_{}.nal", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let beliefs = system.export_beliefs(&BeliefFilter::default());
+        write_nal(&path, &beliefs).unwrap();
+        let mut restored = NarsSystem::new(0.1, -1.0);
+        let report = run_script_file(&mut restored, &path).unwrap();
+        assert_eq!(report.inputs, 4);
+        assert!(report.parse_errors.is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        // Events are left out of eternal exports, whatever their source
+        system.input_batch_from(&[sentence("<rain --> falling>. :|:")], false, Source::User);
+        let terms = |filter: BeliefFilter| -> Vec<String> {
+            system.export_beliefs(&filter).iter().map(|s| s.term.to_string()).collect()
+        };
+        assert!(terms(BeliefFilter::default()).contains(&"<rain --> falling>".to_string()));
+        assert!(!terms(BeliefFilter { eternal_only: true, ..Default::default() }).contains(&"<rain --> falling>".to_string()));
+    }
+
+    #[test]
+    fn test_curiosity_asks_about_unlinked_associations() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::Punctuation;
+
+        let mut system = NarsSystem::new(0.0, -1.0);
+        system.curiosity = 2;
+        system.seed(1);
+        let linked = parse_narsese("<cat --> bird>.").unwrap().term;
+        let terms = [Term::atom_from_str("cat"), Term::atom_from_str("dog"), Term::atom_from_str("bird"), linked];
+        for (i, term) in terms.into_iter().enumerate() {
+            system.add_concept(Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![100 + i as u64])), true);
+        }
+        for _ in 0..60 {
+            system.cycle();
+        }
+
+        let asked: Vec<String> = system.hypotheses.iter().map(|q| q.term.to_string()).collect();
+        assert!(system.hypotheses.iter().all(|q| q.punctuation == Punctuation::Question));
+        // Only the unrelated atom pairs are asked about: `cat` and `bird` are related by a
+        // statement, and atoms are not paired with statements
+        let unlinked = ["<cat --> dog>", "<dog --> cat>", "<bird --> dog>", "<dog --> bird>"];
+        assert!(!asked.is_empty());
+        assert!(asked.iter().all(|t| unlinked.contains(&t.as_str())), "{:?}", asked);
+        let mut unique = asked.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), asked.len());
+
+        // Disabled by default
+        let mut quiet = NarsSystem::new(0.0, -1.0);
+        for term in [Term::atom_from_str("cat"), Term::atom_from_str("dog")] {
+            quiet.add_concept(Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), true);
+        }
+        for _ in 0..20 {
+            quiet.cycle();
+        }
+        assert!(quiet.hypotheses.is_empty());
+    }
+
+    #[test]
+    fn test_deferred_input() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, 0.4);
+        let stimulus = parse_narsese("<light --> on>.").unwrap();
+        system.input_at(stimulus.clone(), 5);
+        assert_eq!(system.scheduled_inputs(), 1);
+        for _ in 0..5 {
+            system.cycle();
+        }
+        assert!(system.memory.get(&stimulus.term).is_none());
+
+        // Input at the start of the cycle run at t = 5, stamped with that time
+        system.cycle();
+        assert_eq!(system.scheduled_inputs(), 0);
+        assert_eq!(system.memory.get(&stimulus.term).expect("injected").stamp.creation_time, 5);
+
+        // Cycles already past input right away
+        system.input_at(parse_narsese("<light --> off>.").unwrap(), 2);
+        assert_eq!(system.scheduled_inputs(), 0);
+        assert_eq!(system.lanes.len(), 1);
+    }
+
+    #[test]
+    fn test_ranked_answers_prefer_independent_evidence() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::sentence::{Punctuation, Sentence, Stamp};
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let term = parse_narsese("<swan --> white>.").unwrap().term;
+        let belief = |frequency: f32, confidence: f32, evidence: Vec<u64>| {
+            Sentence::new(term.clone(), Punctuation::Judgement, TruthValue::new(frequency, confidence), Stamp::new(0, evidence))
+        };
+        let mut concept = Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![101]));
+        concept.add_belief(belief(1.0, 0.96, vec![101])); // the most confident, but dependent
+        concept.add_belief(belief(1.0, 0.9, vec![102]));
+        concept.add_belief(belief(1.0, 0.95, vec![101, 102]));
+        concept.add_belief(belief(0.1, 0.8, vec![103]));
+        system.add_concept(concept, false);
+
+        let ranked = system.ranked_answers(&term);
+        assert_eq!(ranked.len(), 4);
+        assert_eq!(ranked[0].sentence.stamp.evidence, vec![101, 102]);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        // The single-source beliefs are already part of the revised one and rank below the independent dissent
+        assert!(!ranked[1].dependent);
+        assert_eq!(ranked[1].sentence.stamp.evidence, vec![103]);
+        assert!(ranked[2..].iter().all(|r| r.dependent));
+
+        system.input(parse_narsese("<swan --> white>?").unwrap());
+        system.cycle();
+        let event = system.answers.iter().find(|e| e.question == term).expect("the question is answered");
+        assert_eq!(event.alternatives.len(), 4);
+        assert_eq!(event.alternatives[0].sentence.stamp.evidence, vec![101, 102]);
+        // The reported answer is the first alternative
+        assert_eq!((event.answer.truth, &event.answer.stamp.evidence), (event.alternatives[0].sentence.truth, &vec![101, 102]));
+    }
+
+    #[test]
+    fn test_input_budget() {
+        use crate::nars::parser::parse_narsese;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.seed(1);
+        system.reasoner.backward = false;
+        system.input(parse_narsese("$0.90;0.80;0.95$ <cat --> animal>.").unwrap());
+        system.input(parse_narsese("$0.20;0.10$ <dog --> animal>.").unwrap());
+        system.input(parse_narsese("$0.90;0.10$ <unicorn --> animal>?").unwrap());
+        for _ in 0..10 {
+            system.cycle();
+        }
+
+        let cat = system.memory.get(&parse_narsese("<cat --> animal>.").unwrap().term).unwrap();
+        // (priority moves as the concept is reasoned with)
+        assert_eq!((cat.durability, cat.quality), (0.8, 0.95));
+        assert!(cat.utility() >= 0.95);
+        let dog = system.memory.get(&parse_narsese("<dog --> animal>.").unwrap().term).unwrap();
+        assert!(dog.utility() < 0.05);
+        // A low durability gives up on the question after two attempts instead of dozens
+        assert!(system.unanswered.iter().any(|u| u.question.term.to_string() == "<unicorn --> animal>"));
+
+        // Memory keeps the valuable concept when it fills up
+        system.memory.capacity = system.memory.len();
+        for i in 0..20 {
+            let term = parse_narsese(&format!("<w{} --> word>.", i)).unwrap().term;
+            system.add_concept(Concept::new(term.clone(), Hypervector::from_term(&term), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![200 + i])), true);
+        }
+        assert!(system.memory.get(&parse_narsese("<cat --> animal>.").unwrap().term).is_some());
+
+        // Full durability still decays: the question is given up rather than retried forever
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.input(parse_narsese("$0.90;1.00;0.50$ <griffin --> animal>?").unwrap());
+        for _ in 0..100 {
+            system.cycle();
+        }
+        assert!(system.lanes.is_empty());
+        assert!(system.unanswered.iter().any(|u| u.question.term.to_string() == "<griffin --> animal>"));
+    }
+
+    #[test]
+    fn test_events_stay_out_of_concept_memory() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::term::Operator;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.input(parse_narsese("<door --> open>. :|:").unwrap());
+        system.run_cycles(3);
+        system.input(parse_narsese("<light --> on>. :|:").unwrap());
+        system.input(parse_narsese("<cat --> animal>.").unwrap());
+        system.run_cycles(5);
+
+        assert_eq!(system.events.len(), 2);
+        assert_eq!(system.events.between(0, 1).count(), 1);
+        // Concepts only hold the eternalized event; eternal input is untouched
+        let belief = |text: &str| system.memory.get(&parse_narsese(text).unwrap().term).unwrap().beliefs[0].truth;
+        assert!((belief("<door --> open>.").confidence - 0.9 / 1.9).abs() < 1e-3);
+        assert_eq!(belief("<cat --> animal>.").confidence, 0.9);
+        // The two events were related by temporal induction
+        assert!(system.memory.values().any(|c| matches!(c.term.term(), Term::Compound(Operator::PredictiveImplication, args)
+            if args[1].to_string() == "<light --> on>")));
+
+        // A new event replaces the previous one instead of being revised in
+        system.input(parse_narsese("<door --> open>. :|: %0.0;0.9%").unwrap());
+        system.run_cycles(3);
+        assert_eq!(system.events.len(), 3);
+        let door = system.memory.get(&parse_narsese("<door --> open>.").unwrap().term).unwrap();
+        let events: Vec<_> = door.beliefs.iter().filter(|b| b.is_event()).collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].truth.frequency, 0.0);
+        assert!((events[0].truth.confidence - 0.9 / 1.9).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_strongest_path() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::truth::deduction;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |text: &str| parse_narsese(&format!("{}.", text)).unwrap().term;
+        for (i, (text, confidence)) in [("<cat --> mammal>", 0.9), ("<mammal --> animal>", 0.9), ("<cat --> pet>", 0.9),
+            ("<pet --> animal>", 0.5), ("<animal --> being>", 0.8), ("<<cat --> pet> ==> <cat --> loved>>", 0.9)].into_iter().enumerate() {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, confidence), Stamp::new(0, vec![300 + i as u64])), true);
+        }
+        let (cat, being) = (Term::atom_from_str("cat"), Term::atom_from_str("being"));
+
+        let path = system.path(&cat, &being).expect("cat and being are connected");
+        let steps: Vec<String> = path.steps.iter().map(|s| s.term.to_string()).collect();
+        assert_eq!(steps, ["<cat --> mammal>", "<mammal --> animal>", "<animal --> being>"]);
+        let expected = deduction(deduction(TruthValue::new(1.0, 0.9), TruthValue::new(1.0, 0.9)), TruthValue::new(1.0, 0.8));
+        assert!((path.truth.confidence - expected.confidence).abs() < 1e-6);
+        assert!(system.path(&being, &cat).is_none());
+        // Implication chains are separate from inheritance chains
+        assert_eq!(system.path(&term("<cat --> pet>"), &term("<cat --> loved>")).unwrap().steps.len(), 1);
+        assert!(system.path(&cat, &term("<cat --> loved>")).is_none());
+
+        // Forgetting a link reroutes through the weaker one
+        system.memory.remove(&term("<mammal --> animal>"));
+        assert_eq!(system.path(&cat, &being).unwrap().steps[1].term, term("<pet --> animal>"));
+
+        // Negative statements are not links, and reaching a node through one copula does not
+        // close it to chains of the other
+        let mut add = |text: &str, frequency: f32, confidence: f32| {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(frequency, confidence), Stamp::new(0, vec![])), true);
+        };
+        add("<cat --> rock>", 0.0, 0.95);
+        add("<<cat --> pet> --> <cat --> loved>>", 1.0, 0.95);
+        add("<<cat --> loved> ==> <cat --> happy>>", 1.0, 0.9);
+        assert!(system.path(&cat, &Term::atom_from_str("rock")).is_none());
+        let path = system.path(&term("<cat --> pet>"), &term("<cat --> happy>")).expect("an implication chain");
+        assert_eq!(path.steps.len(), 2);
+    }
+
+    #[test]
+    fn test_reground_vectors() {
+        use crate::nars::parser::parse_narsese;
+        use crate::nars::term::Operator;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        assert_eq!(system.drift_control.reground_interval, 0);
+        let atom = Term::atom_from_str;
+        for name in ["cat", "dog", "pet"] {
+            system.add_concept(Concept::new(atom(name), Hypervector::from_term(&atom(name)), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), false);
+        }
+        let product = parse_narsese("(*, cat, dog).").unwrap().term;
+        let statement = parse_narsese("<(*, cat, dog) --> pet>.").unwrap().term;
+        for term in [&product, &statement] {
+            let vector = system.resolve_vector(term);
+            system.add_concept(Concept::new(term.clone(), vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), false);
+        }
+        assert_eq!(system.reground_vectors(), 0);
+
+        // Learning moved the atom: both compounds above it follow, the outer one from the new inner one
+        system.memory.get_mut(&atom("cat")).unwrap().set_vector(Hypervector::random());
+        assert_eq!(system.reground_vectors(), 2);
+        let cat = system.memory.get(&atom("cat")).unwrap().vector;
+        let dog = system.memory.get(&atom("dog")).unwrap().vector;
+        let pet = system.memory.get(&atom("pet")).unwrap().vector;
+        let inner = Hypervector::compound(&Operator::Product, &[cat, dog]);
+        assert_eq!(system.memory.get(&product).unwrap().vector, inner);
+        assert_eq!(system.memory.get(&statement).unwrap().vector, Hypervector::compound(&Operator::Inheritance, &[inner, pet]));
+        assert_eq!(system.reground_vectors(), 0);
+    }
+
+    #[test]
+    fn test_contrastive_update_keeps_vectors_apart() {
+        // Concepts that start out much alike (similarity ~0.75) and are reasoned with at random
+        let run = |contrastive_rate: f32| {
+            let mut system = NarsSystem::new(0.1, 0.5);
+            system.drift_control.contrastive_rate = contrastive_rate;
+            system.drift_control.decay = 0.1; // learning fades within the run
+            let base = Hypervector::random();
+            for i in 0..30 {
+                let term = Term::atom_from_str(&format!("c{}", i));
+                let mut vector = base;
+                vector.update(&Hypervector::random(), 0.5);
+                system.add_concept(Concept::new(term, vector, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![])), false);
+            }
+            let before = system.memory.similarity_stats(500);
+            for _ in 0..300 {
+                system.cycle();
+            }
+            (before, system.memory.similarity_stats(500))
+        };
+
+        let (before, plain) = run(0.0);
+        let (_, contrastive) = run(0.2);
+        assert!(before.mean > 0.7 && before.pairs == 500);
+        // Hebbian learning alone collapses them together; negative sampling spreads them out
+        assert!(plain.mean > before.mean, "{:?}", plain);
+        assert!(contrastive.mean < 0.65, "{:?}", contrastive);
+        assert!(contrastive.min >= 0.45);
+    }
+
+    #[test]
+    fn test_contexts_commit_or_drop() {
+        use crate::nars::parser::parse_term;
+        use std::sync::Arc;
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        system.input(system.parse("<cat --> animal>.").unwrap());
+        system.run_cycles(5);
+        assert!(system.pop_context().is_err());
+
+        // Dropped: neither the input nor anything derived from it survives, nor what reasoning
+        // with it changed elsewhere
+        let cat = system.memory.get_shared(&term("<cat --> animal>")).unwrap();
+        let (cycles, output, priorities) = (system.cycle_count, system.output_buffer.len(), system.rule_priorities.clone());
+        let (derivations, activity) = (system.stats.derivations, system.activity.to_json());
+        system.push_context("hypothetical");
+        system.input(system.parse("<animal --> mortal>.").unwrap());
+        system.run_cycles(20);
+        assert!(system.memory.get(&term("<animal --> mortal>")).is_some());
+        assert_eq!(system.contexts(), ["hypothetical"]);
+        assert_eq!(system.pop_context().unwrap(), "hypothetical");
+        assert!(system.memory.get(&term("<animal --> mortal>")).is_none());
+        assert!(system.memory.get(&term("<cat --> mortal>")).is_none());
+        assert!(Arc::ptr_eq(&system.memory.get_shared(&term("<cat --> animal>")).unwrap(), &cat));
+        assert_eq!((system.cycle_count, system.output_buffer.len()), (cycles, output));
+        assert_eq!(system.rule_priorities, priorities);
+        assert_eq!(system.stats.derivations, derivations);
+        assert_eq!(system.activity.to_json(), activity);
+
+        // Nested: the inner context commits into the outer one, which is then dropped
+        system.push_context("outer");
+        system.push_context("inner");
+        system.input(system.parse("<cat --> pet>.").unwrap());
+        system.run_cycles(5);
+        assert_eq!(system.contexts(), ["outer", "inner"]);
+        assert_eq!(system.commit_context().unwrap(), "inner");
+        assert!(system.memory.get(&term("<cat --> pet>")).is_some());
+        system.pop_context().unwrap();
+        assert!(system.memory.get(&term("<cat --> pet>")).is_none());
+
+        // Committed into the base
+        system.push_context("kept");
+        system.input(system.parse("<cat --> pet>.").unwrap());
+        system.run_cycles(5);
+        system.commit_context().unwrap();
+        assert!(system.contexts().is_empty());
+        assert!(system.memory.get(&term("<cat --> pet>")).is_some());
+    }
+
+    #[test]
+    fn test_suppose_leaves_memory_untouched() {
+        use crate::nars::parser::parse_term;
+        use crate::nars::sensor::{FuzzySet, SensorChannel};
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        system.input(system.parse("<cat --> animal>.").unwrap());
+        system.run_cycles(5);
+        system.add_sensor(SensorChannel::new("temperature").with_set(FuzzySet::triangle("hot", 20.0, 30.0, f32::INFINITY)));
+        system.sense("temperature", 35.0).unwrap();
+        let concepts = system.memory.len();
+        let before = |system: &NarsSystem| (system.cycle_count, system.rule_priorities.clone(), system.rule_usage.clone(),
+            system.stats.derivations, system.activity.to_json(), system.answer_latency.clone(), system.output_buffer.len());
+        let state = before(&system);
+
+        let premise = system.parse("<animal --> mortal>.").unwrap();
+        let answer = system.suppose(&[premise], &term("<cat --> mortal>"), 50).expect("hypothetical answer");
+        assert!(answer.truth.frequency > 0.5);
+
+        assert_eq!(system.memory.len(), concepts);
+        assert_eq!(before(&system), state);
+        // The reading taken before is still pending
+        let events = system.events.len();
+        system.cycle();
+        assert!(system.events.len() > events);
+        assert!(system.memory.get(&term("<animal --> mortal>")).is_none());
+        assert!(system.answer_query(&term("<cat --> mortal>")).is_none());
+        assert!(system.answers.is_empty());
+        assert!(system.contexts().is_empty());
+    }
+
+    #[test]
+    fn test_difference_decomposition() {
+        use crate::nars::parser::parse_term;
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        // m is an s, but not an s that is no p: so it is a p
+        for (text, frequency, id) in [("<m --> s>", 1.0, 101), ("<m --> (-, s, p)>", 0.0, 102)] {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(frequency, 0.9), Stamp::new(0, vec![id])), true);
+        }
+        system.run_cycles(50);
+        let derived = system.answer_query(&term("<m --> p>")).expect("decomposed difference");
+        assert!(derived.truth.frequency > 0.9, "{:?}", derived.truth);
+
+        // Differences are binary in rule files too
+        assert!(system.load_rules("(:M --> (- :S :P :Q)) !- (:M --> :S) structural_deduction").is_err());
+    }
+
+    #[test]
+    fn test_backward_inference_inverts_marked_rules() {
+        use crate::nars::control::SharedResources;
+        use crate::nars::parser::parse_narsese;
+        use std::sync::Arc;
+
+        let resources = SharedResources { rules: Arc::new(Vec::new()), projector: None };
+        let mut system = NarsSystem::with_resources(0.1, -1.0, resources);
+        system.load_rules("
+            (:M --> :P) (:S --> :M) !- (:S --> :P) deduction :allow-backward
+            (:P --> :M) (:S --> :M) !- (:S --> :P) abduction
+            (:A --> :B) (:C --> :D) !- (:A ==> :D) deduction :allow-backward
+        ").unwrap();
+        let term = |s: &str| parse_narsese(s).unwrap().term;
+        let belief = term("<a --> b>.");
+        system.add_concept(Concept::new(belief.clone(), Hypervector::from_term(&belief), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![101])), true);
+        system.input(parse_narsese("<a --> c>?").unwrap());
+        system.input(parse_narsese("<x ==> b>?").unwrap());
+        system.run_cycles(300); // selection is random; both questions must meet the belief
+
+        let asked = |s: &str| system.memory.get(&term(s)).is_some_and(|c| !c.questions.is_empty());
+        // Deduction is inverted: <b --> c> would answer <a --> c> with <a --> b>; abduction is not
+        assert!(asked("<b --> c>?"));
+        assert!(!asked("<c --> b>?"));
+        // Rule variables the match leaves open become query variables
+        assert!(asked("<x --> ?B>?"));
+    }
+
+    #[test]
+    fn test_query_handle_reads_while_cycling() {
+        use crate::nars::parser::parse_term;
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        for (text, id) in [("<a --> b>", 101), ("<b --> c>", 102)] {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![id])), true);
+        }
+        let handle = system.query_handle();
+        assert!(handle.belief(&term("<a --> b>")).is_some());
+        assert_eq!(handle.stats().cycles, 0);
+
+        let reasoner = std::thread::spawn(move || {
+            system.run_cycles(50);
+            system
+        });
+        // Readers poll while the reasoner runs; every snapshot is complete
+        while !reasoner.is_finished() {
+            let snapshot = handle.snapshot();
+            assert!(snapshot.belief(&term("<b --> c>")).is_some());
+            assert_eq!(snapshot.len(), snapshot.metrics.concepts);
+        }
+        let system = reasoner.join().unwrap();
+
+        assert_eq!(handle.stats().cycles, 50);
+        assert_eq!(handle.belief(&term("<a --> c>")).map(|b| b.truth), system.answer_query(&term("<a --> c>")).map(|b| b.truth));
+        let similar = handle.find_similar(&term("<a --> b>"), 3);
+        assert!(!similar.is_empty() && similar.iter().all(|(t, _)| *t != term("<a --> b>")));
+
+        // Single cycles publish every `snapshot_interval` (10) cycles
+        let mut system = system;
+        for _ in 0..9 {
+            system.cycle();
+        }
+        assert_eq!(handle.stats().cycles, 50);
+        system.cycle();
+        assert_eq!(handle.stats().cycles, 60);
+        // Once the handles are dropped, the next one starts from the current state
+        drop(handle);
+        system.cycle();
+        assert_eq!(system.query_handle().stats().cycles, 61);
+    }
+
+    #[test]
+    fn test_duplicate_and_conflicting_rules() {
+        use crate::nars::BUILTIN_RULES;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let count = system.rules.len();
+        // Loading the built-ins again adds nothing
+        let report = system.load_rules(BUILTIN_RULES).unwrap();
+        assert_eq!((report.loaded, report.duplicates.len(), report.conflicts.len()), (0, count, 0));
+        // and the built-ins hold no duplicates among themselves
+        let report = NarsSystem::new(0.1, -1.0).replace_rules(BUILTIN_RULES).unwrap();
+        assert_eq!((report.loaded, report.duplicates.len(), report.conflicts.len()), (count, 0, 0));
+
+        // Variable names and the order of commutative arguments do not matter
+        let report = system.load_rules("
+            (:X --> :Y) (:Z --> :X) !- (:Z --> :Y) deduction
+            (:B --> :C) (:A --> :C) !- ((& :B :A) --> :C) intersection
+            (:X --> :Y) (:Z --> :X) !- (:Z --> :Y) abduction
+            (:X --> :Y) (:Z --> :X) !- (:Z --> :Y) deduction :allow-backward
+            (:X --> :Y) (:Y --> :Z) !- (:X --> :Z) exemplification
+            (:A --> :B) (:B --> :C) !- (:A --> :C) exemplification
+        ").unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.duplicates.len(), 3, "{:?}", report.duplicates);
+        assert_eq!(report.conflicts.len(), 2, "{:?}", report.conflicts);
+        assert!(report.conflicts[0].reason.contains("deduction"));
+        assert_eq!(system.rules.len(), count + 1);
+    }
+
+    #[test]
+    fn test_add_or_replace_rules_from_file() {
+        use crate::nars::parser::parse_term;
+        let path = std::env::temp_dir().join(format!("nars_rules_{}.lisp", std::process::id()));
+        std::fs::write(&path, "
+            ; a custom rule and a copy of a built-in one
+            (:S --> (| :A :B)) (:A --> :C) !- (:S --> (| :C :B)) deduction
+            (:M --> :P) (:S --> :M) !- (:S --> :P) deduction :allow-backward
+        ").unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let count = system.rules.len();
+        let report = system.add_rules_from_file(path).unwrap();
+        assert_eq!((report.loaded, report.duplicates.len()), (1, 1));
+        assert_eq!(system.rules.len(), count + 1);
+
+        system.set_rule_enabled("deduction", false);
+        let report = system.replace_rules_from_file(path).unwrap();
+        assert_eq!((report.loaded, report.duplicates.len()), (2, 0));
+        assert_eq!(system.rules.len(), 2);
+        assert!(system.is_rule_enabled(1));
+
+        let term = |t: &str| parse_term(t).unwrap().1;
+        for (text, id) in [("<b --> c>", 101), ("<a --> b>", 102)] {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![id])), true);
+        }
+        system.run_cycles(50);
+        assert!(system.answer_query(&term("<a --> c>")).is_some());
+
+        // The deprecated loader keeps replacing the rules
+        let mut system = NarsSystem::new(0.1, -1.0);
+        #[allow(deprecated)]
+        let report = system.load_rules_from_file(path).unwrap();
+        assert_eq!((report.loaded, system.rules.len()), (2, 2));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_evaluate_without_cycling() {
+        use crate::nars::parser::parse_term;
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        for (text, frequency, id) in [("<a --> b>", 1.0, 101), ("<b --> c>", 1.0, 102), ("<a --> c>", 0.0, 103)] {
+            let t = term(text);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(frequency, 0.5), Stamp::new(0, vec![id])), true);
+        }
+        let concepts = system.memory.len();
+
+        // The direct belief is revised with the deduction from the other two
+        let estimate = system.evaluate(&term("<a --> c>"));
+        assert_eq!(estimate.sources.len(), 2, "{:?}", estimate.sources);
+        assert_eq!(estimate.sources[0].stamp.evidence, vec![103]);
+        assert!(estimate.truth.confidence > 0.5 && estimate.truth.frequency > 0.0 && estimate.truth.frequency < 0.5, "{:?}", estimate.truth);
+        assert!(estimate.bounds.lower <= estimate.value && estimate.value <= estimate.bounds.upper);
+        assert_eq!(system.memory.len(), concepts);
+
+        // Not in memory, but derivable
+        let estimate = system.evaluate(&term("<c --> a>"));
+        assert!(!estimate.sources.is_empty() && estimate.truth.confidence > 0.0);
+        assert!(system.memory.get(&term("<c --> a>")).is_none());
+
+        let unknown = system.evaluate(&term("<x --> y>"));
+        assert!(unknown.sources.is_empty());
+        assert_eq!((unknown.value, unknown.bounds.lower, unknown.bounds.upper), (0.5, 0.0, 1.0));
+
+        // Premises are looked up by the atoms they share with the term, whatever else is known
+        for i in 0..200 {
+            let t = term(&format!("<x{} --> y{}>", i, i % 7));
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, 0.5), Stamp::new(0, vec![1000 + i])), true);
+        }
+        assert_eq!(system.evaluate(&term("<a --> c>")).sources.len(), 2);
+    }
+
+    #[test]
+    fn test_confidence_erosion() {
+        use crate::nars::activity::ActivityKind;
+        use crate::nars::memory::ErosionPolicy;
+        use crate::nars::parser::parse_term;
+        use crate::nars::sentence::Source;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        for (text, time, source) in [("<a --> b>", 0, Source::User), ("<t --> hot>", 0, Source::Sensor("temp".to_string())), ("<c --> d>", 90, Source::User)] {
+            let t = term(text);
+            let mut stamp = Stamp::new(time, vec![time + 1]);
+            stamp.add_source(source);
+            system.add_concept(Concept::new(t.clone(), Hypervector::from_term(&t), TruthValue::new(1.0, 0.9), stamp), true);
+        }
+        let confidence = |system: &NarsSystem, t: &str| system.memory.get(&term(t)).unwrap().truth.confidence;
+
+        system.cycle_count = 100;
+        system.erosion.idle_cycles = 50;
+        system.erosion.policy = ErosionPolicy { rate: 0.1, floor: 0.2 };
+        system.erosion.namespaces.push(("sensor:".to_string(), ErosionPolicy { rate: 0.5, floor: 0.0 }));
+        assert_eq!(system.erode_beliefs(), 2);
+        assert!((confidence(&system, "<a --> b>") - 0.83).abs() < 1e-4);
+        assert!((confidence(&system, "<t --> hot>") - 0.45).abs() < 1e-4);
+        assert_eq!(confidence(&system, "<c --> d>"), 0.9); // recently reinforced
+        assert!(system.memory.get(&term("<a --> b>")).unwrap().beliefs.iter().all(|b| b.truth.confidence < 0.9));
+
+        // Used concepts keep their confidence; the others approach the floor
+        system.activity.record(100, &term("<a --> b>"), ActivityKind::Selection);
+        for _ in 0..100 {
+            system.erode_beliefs();
+        }
+        assert!((confidence(&system, "<a --> b>") - 0.83).abs() < 1e-4);
+        assert!(confidence(&system, "<t --> hot>") < 0.01);
+    }
+
+    #[test]
+    fn test_question_information_value() {
+        use crate::nars::parser::parse_term;
+        use crate::nars::sentence::{Punctuation, Sentence};
+        use crate::nars::task::{QuestionContext, Task};
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        let implication = term("<q ==> g>");
+        system.add_concept(Concept::new(implication.clone(), Hypervector::from_term(&implication), TruthValue::new(1.0, 0.9), Stamp::new(0, vec![1])), true);
+        let mut goal = Concept::new(term("g"), Hypervector::from_term(&term("g")), TruthValue::new(0.5, 0.0), Stamp::new(0, vec![]));
+        goal.add_task(Sentence::new(term("g"), Punctuation::Goal, TruthValue::new(1.0, 0.9), Stamp::new(0, vec![2])));
+        system.memory.put(goal);
+
+        // An answer to `q?` would detach the implication towards the goal on `g`
+        let question = |t: &str| Task::new(Sentence::new(term(t), Punctuation::Question, TruthValue::new(0.0, 0.0), Stamp::new(0, vec![])), 0.5);
+        let context = system.question_context(&question("q"));
+        assert_eq!((context.blocked_derivations, context.answer, context.asked_by_user), (1, None, false));
+        assert!((context.blocked_goals - TruthValue::new(1.0, 0.9).expectation()).abs() < 1e-6);
+        assert_eq!(system.question_context(&question("r")).blocked_goals, 0.0);
+
+        // Asked questions are weighed by the scorer in the question lane
+        system.question_scorer = |c: &QuestionContext| if c.blocked_goals > 0.0 { 0.9 } else { 0.1 };
+        for text in ["q?", "r?"] {
+            system.input(system.parse(text).unwrap());
+        }
+        let lane = &system.lanes.questions;
+        let weight = |t: &str| lane.tasks.iter().find(|(_, task)| task.sentence.term == term(t)).map(|(id, _)| lane.bag.name_map[id]);
+        assert_eq!((weight("q"), weight("r")), (Some(0.9), Some(0.1)));
+    }
+
+    #[test]
+    fn test_insert_concept_with_vector() {
+        use crate::nars::parser::parse_term;
+        use crate::nars::sentence::Source;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        system.input(system.parse("<cat --> animal>. %0.2;0.5%").unwrap());
+        system.run_cycles(1);
+
+        // Replaces the concept without revision and keeps the vector as given
+        let vector = Hypervector::random();
+        system.insert_concept_with_vector(term("<cat --> animal>"), TruthValue::new(1.0, 0.9), vector);
+        let concept = system.memory.get(&term("<cat --> animal>")).unwrap();
+        assert_eq!((concept.truth, concept.vector, concept.beliefs.len()), (TruthValue::new(1.0, 0.9), vector, 1));
+        assert_eq!(concept.stamp.sources, [Source::User]);
+
+        // Atoms without evidence get no belief
+        system.insert_concept_with_vector(term("cat"), TruthValue::new(0.5, 0.0), Hypervector::empty());
+        assert!(system.memory.get(&term("cat")).unwrap().beliefs.is_empty());
+        assert_eq!(system.resolve_vector(&term("cat")), Hypervector::empty());
+    }
+
+    #[test]
+    fn test_attach_perceptual_features() {
+        use crate::nars::grounding::RandomProjection;
+        use crate::nars::parser::parse_term;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        let term = |t: &str| parse_term(t).unwrap().1;
+        let camera = RandomProjection { seed: 1 };
+        let cat_photo = [0.9, -0.3, 0.4, 0.1, -0.7, 0.2];
+        let other_cat_photo = [0.85, -0.25, 0.45, 0.1, -0.65, 0.2];
+        let img_1 = system.attach_features(&term("{img_1}"), &cat_photo, &camera);
+        let img_2 = system.attach_features(&term("{img_2}"), &other_cat_photo, &camera);
+        assert!(img_1.similarity(&img_2) > 0.9);
+        assert_eq!(system.memory.get(&term("{img_1}")).unwrap().vector, img_1);
+
+        // Regrounding and eviction keep the perceptual vector
+        system.input(system.parse("<{img_1} --> cat>.").unwrap());
+        system.run_cycles(1);
+        system.reground_vectors();
+        assert_eq!(system.memory.get(&term("{img_1}")).unwrap().vector, img_1);
+        system.memory.remove(&term("{img_2}"));
+        assert_eq!(system.resolve_vector(&term("{img_2}")), img_2);
+        let nearest = system.nearest_terms(&img_2, 1);
+        assert_eq!(nearest[0].0, term("{img_1}"));
+
+        // So do saving and loading memory
+        let path = std::env::temp_dir().join(format!("nars_percepts_{}.bin", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        system.save_memory(&path).unwrap();
+        let mut reloaded = NarsSystem::new(0.1, 0.5);
+        reloaded.load_memory(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(reloaded.memory.get(&term("{img_1}")).unwrap().grounded);
+        reloaded.reground_vectors();
+        assert_eq!(reloaded.memory.get(&term("{img_1}")).unwrap().vector, img_1);
+        reloaded.memory.remove(&term("{img_1}"));
+        assert_eq!(reloaded.resolve_vector(&term("{img_1}")), img_1);
+    }
+
+    #[test]
+    fn test_declared_copula_in_rules_and_input() {
+        use crate::nars::term::{declare_operator, OperatorSpec};
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.seed(1);
+        assert!(system.parse("<alice likes bob>.").is_err());
+        declare_operator(OperatorSpec::copula("likes", false)).unwrap();
+        let report = system.load_rules("(:A likes :B) (:B likes :C) !- (:A likes :C) deduction").unwrap();
+        assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+        for text in ["<alice likes bob>.", "<bob likes carol>."] {
+            system.input(system.parse(text).unwrap());
+        }
+        system.run_cycles(30);
+        let derived = system.parse("<alice likes carol>.").unwrap().term;
+        assert!(system.output_buffer.iter().any(|s| s.term == derived && (s.truth.confidence - 0.81).abs() < 0.01),
+            "{:?}", system.output_buffer.iter().map(|s| s.to_narsese()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_input_admission_control() {
+        use crate::nars::task::{AdmissionControl, BackpressureStats, Overflow};
+
+        let mut system = NarsSystem::new(0.1, -1.0);
+        system.dedup_window = 0;
+        system.admission = AdmissionControl { rate: 2, ..AdmissionControl::default() };
+        let mut input = |text: &str| { let sentence = system.parse(text).unwrap(); system.input(sentence) };
+        assert!(input("<a --> b>."));
+        assert!(input("<b --> c>."));
+        // Over the rate: merged into the pending task on the same term, or dropped without one
+        assert!(input("<a --> b>. %0.0;0.9%"));
+        assert!(!input("<c --> d>."));
+        // Urgent inputs are still admitted
+        assert!(input("$0.90;0.50;0.50$ <c --> d>."));
+        assert_eq!(system.backpressure, BackpressureStats { admitted: 3, merged: 1, dropped: 1, peak_pending: 2 });
+        assert_eq!(system.lanes.len(), 3);
+        let merged = system.lanes.judgements.tasks.values().find(|t| t.sentence.term.to_string() == "<a --> b>").unwrap();
+        assert!((merged.sentence.truth.frequency - 0.5).abs() < 0.01 && merged.sentence.stamp.evidence.len() == 2);
+
+        // The rate applies per cycle; a full queue refuses inputs as well
+        system.cycle();
+        system.admission = AdmissionControl { max_pending: 3, overflow: Overflow::Drop, ..system.admission };
+        assert!(system.input(system.parse("<d --> e>.").unwrap()));
+        assert!(!system.input(system.parse("<e --> f>.").unwrap()));
+        assert_eq!(system.metrics().inputs.dropped, 2);
+        assert!(system.metrics().to_prometheus().contains("nars_inputs_dropped_total 2"));
+        assert!((system.backpressure.pressure() - 3.0 / 7.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_goal_conflict_arbitration() {
+        use crate::nars::parser::parse_term;
+        use crate::nars::sentence::Punctuation;
+
+        let mut system = NarsSystem::new(0.1, 0.5);
+        system.seed(1);
+        let goal = |system: &mut NarsSystem, text: &str| {
+            let sentence = system.parse(text).unwrap();
+            system.input(sentence);
+            system.cycle();
+        };
+        // Not believing it is wanted more than believing it: the operation is not executed
+        goal(&mut system, "(--, (^believe, <sky --> green>))! %1.0;0.9%");
+        goal(&mut system, "(^believe, <sky --> green>)! %1.0;0.6%");
+        let believed = parse_term("<sky --> green>").unwrap().1;
+        assert!(system.memory.get(&believed).is_none_or(|c| c.beliefs.is_empty()));
+        assert_eq!(system.conflicts.len(), 1);
+        let conflict = &system.conflicts[0];
+        assert_eq!(conflict.winner.term.to_string(), "(--, (^believe, <sky --> green>))");
+        assert_eq!(conflict.loser.term.to_string(), "(^believe, <sky --> green>)");
+
+        // Arbitrating the same conflict again is not reported; a change of winner is
+        goal(&mut system, "(^believe, <sky --> green>)! %1.0;0.6%");
+        assert_eq!(system.conflicts.len(), 1);
+        goal(&mut system, "(^believe, <sky --> green>)! %1.0;0.99%");
+        assert_eq!(system.conflicts.len(), 2);
+        assert_eq!(system.conflicts[1].winner.term.to_string(), "(^believe, <sky --> green>)");
+        system.run_cycles(5);
+        assert!(system.memory.get(&believed).is_some_and(|c| !c.beliefs.is_empty()));
+
+        // Weak opposing goals do not conflict
+        goal(&mut system, "<door --> open>! %1.0;0.9%");
+        goal(&mut system, "(--, <door --> open>)! %1.0;0.1%");
+        assert_eq!(system.conflicts.len(), 2);
+
+        // A tie goes to the positive goal, whichever came first; the loser stays queued
+        for (first, second) in [("<lamp --> on>!", "(--, <lamp --> on>)!"), ("(--, <lamp --> on>)!", "<lamp --> on>!")] {
+            let mut system = NarsSystem::new(0.1, 0.5);
+            goal(&mut system, first);
+            goal(&mut system, second);
+            assert_eq!(system.conflicts.len(), 1);
+            assert_eq!(system.conflicts[0].winner.term.to_string(), "<lamp --> on>");
+            assert_eq!(system.lanes.lane(Punctuation::Goal).len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_ancestors_and_descendants() {
+        let mut system = NarsSystem::new(0.1, 0.5);
+        for text in ["<tiger --> cat>.", "<lion --> cat>.", "<cat --> mammal>.", "<mammal --> animal>.", "<rock --> mineral>.", "<fish --> mammal>. %0.0;0.9%"] {
+            let sentence = system.parse(text).unwrap();
+            system.input(sentence);
+        }
+        system.run_cycles(10);
+        let atom = Term::atom_from_str;
+
+        // Reached through inheritance statements that are believed, input or derived
+        let ancestors = system.ancestors(&atom("tiger"));
+        assert!(["cat", "mammal", "animal"].iter().all(|n| ancestors.contains(&atom(n))));
+        assert!(!ancestors.contains(&atom("mineral")) && !ancestors.contains(&atom("tiger")));
+        let descendants = system.descendants(&atom("mammal"));
+        assert!(["cat", "lion", "tiger"].iter().all(|n| descendants.contains(&atom(n))));
+        assert!(!descendants.contains(&atom("fish")) && !descendants.contains(&atom("rock")));
+        assert_eq!(system.descendants(&atom("mineral")), [atom("rock")]);
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let run = |seed: u64| {
+            let mut system = NarsSystem::new(0.1, -1.0);
+            system.seed(seed);
+            for text in ["<a --> b>.", "<b --> c>.", "<c --> d>.", "<a --> d>?"] {
+                let sentence = system.parse(text).unwrap();
+                system.input(sentence);
+            }
+            system.run_cycles(30);
+            system.output_buffer.iter().map(|s| s.to_narsese()).collect::<Vec<_>>()
+        };
+        let outputs = run(7);
+        assert!(!outputs.is_empty());
+        assert_eq!(run(7), outputs);
+    }
+
+    #[test]
+    fn test_built_terms_find_input_concepts() {
+        let mut system = NarsSystem::new(0.1, -1.0);
+        let sentence = system.parse("<(|, y, x) --> z>.").unwrap();
+        system.input(sentence);
+        system.cycle();
+        let atom = Term::atom_from_str;
+        let built = Term::inheritance(Term::ext_intersection(vec![atom("y"), atom("x")]).unwrap(), atom("z"));
+        assert!(system.memory.get(&built).is_some());
+        // Derived terms are normalized the same way before they reach memory
+        let written = Term::Compound(Operator::Inheritance, vec![Term::Compound(Operator::ExtIntersection, vec![atom("y"), atom("x")]), atom("z")]);
+        assert_eq!(system.canonical(&written), built);
+    }
 }
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod tests {
+    use crate::nars::truth::{self, TruthValue, TruthInterval, TruthRepresentation};
+    use proptest::prelude::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    type Double = (&'static str, fn(TruthValue, TruthValue) -> TruthValue);
+    type Single = (&'static str, fn(TruthValue) -> TruthValue);
+
+    const DOUBLE_FNS: &[Double] = &[
+        ("revision", truth::revision),
+        ("union", truth::union),
+        ("difference", truth::difference),
+        ("intersection", truth::intersection),
+        ("deduction", truth::deduction),
+        ("abduction", truth::abduction),
+        ("exemplification", truth::exemplification),
+        ("induction", truth::induction),
+        ("comparison", truth::comparison),
+        ("desire_weak", truth::desire_weak),
+        ("temporal_induction", truth::temporal_induction),
+        ("resemblance", truth::resemblance),
+        ("analogy", truth::analogy),
+        ("decompose_nnn", truth::decompose_nnn),
+        ("decompose_npp", truth::decompose_npp),
+        ("decompose_ppp", truth::decompose_ppp),
+        ("decompose_pnn", truth::decompose_pnn),
+        ("decompose_pnp", truth::decompose_pnp),
+        ("desire_strong", truth::desire_strong),
+        ("combine", truth::combine),
+        ("reduce_disjunction", truth::reduce_disjunction),
+    ];
+
+    const SINGLE_FNS: &[Single] = &[
+        ("structural_deduction", truth::structural_deduction),
+        ("contraposition", truth::contraposition),
+        ("identity", truth::identity),
+        ("negation", truth::negation),
+        ("desire_structural_strong", truth::desire_structural_strong),
+        ("conversion", truth::conversion),
+    ];
+
+    fn tv(f: f32, c: f32) -> TruthValue {
+        TruthValue::new(f, c)
+    }
+
+    fn assert_truth(name: &str, actual: TruthValue, expected: TruthValue) {
+        assert!(
+            (actual.frequency - expected.frequency).abs() < EPSILON && (actual.confidence - expected.confidence).abs() < EPSILON,
+            "{}: expected %{:.4};{:.4}%, got %{:.4};{:.4}%",
+            name, expected.frequency, expected.confidence, actual.frequency, actual.confidence
+        );
+    }
+
+    fn in_unit(x: f32) -> bool {
+        (0.0..=1.0 + EPSILON).contains(&x)
+    }
+
+    // Reference values from the NAL truth tables (k = 1)
+    #[test]
+    fn test_reference_table() {
+        let strong = tv(1.0, 0.9);
+        let w = 0.81 / 1.81; // w2c(f1 * c1 * c2) for two strong premises
+
+        assert_truth("deduction", truth::deduction(strong, strong), tv(1.0, 0.81));
+        assert_truth("abduction", truth::abduction(strong, strong), tv(1.0, w));
+        assert_truth("induction", truth::induction(strong, strong), tv(1.0, w));
+        assert_truth("exemplification", truth::exemplification(strong, strong), tv(1.0, w));
+        assert_truth("comparison", truth::comparison(strong, strong), tv(1.0, w));
+        assert_truth("analogy", truth::analogy(strong, strong), tv(1.0, 0.81));
+        assert_truth("resemblance", truth::resemblance(strong, strong), tv(1.0, 0.81));
+        assert_truth("intersection", truth::intersection(strong, strong), tv(1.0, 0.81));
+        assert_truth("union", truth::union(tv(1.0, 0.9), tv(0.0, 0.9)), tv(1.0, 0.81));
+        assert_truth("difference", truth::difference(tv(1.0, 0.9), tv(0.0, 0.9)), tv(1.0, 0.81));
+        assert_truth("conversion", truth::conversion(strong), tv(1.0, 0.9 / 1.9));
+        assert_truth("negation", truth::negation(tv(0.2, 0.9)), tv(0.8, 0.9));
+        assert_truth("structural_deduction", truth::structural_deduction(strong), tv(1.0, 0.81));
+        assert_truth("contraposition", truth::contraposition(tv(0.0, 0.9)), tv(0.0, 0.9 / 1.9));
+
+        // w1 = 9, w2 = 1.5 -> f = 9.15 / 10.5, c = 10.5 / 11.5
+        assert_truth("revision", truth::revision(tv(1.0, 0.9), tv(0.1, 0.6)), tv(9.15 / 10.5, 10.5 / 11.5));
+    }
+
+    fn truth_value() -> impl Strategy<Value = TruthValue> {
+        (0.0f32..=1.0, 0.0f32..0.99).prop_map(|(f, c)| tv(f, c))
+    }
+
+    #[test]
+    fn test_truth_interval() {
+        let interval = TruthInterval::from(tv(0.8, 0.9));
+        assert!((interval.lower - 0.72).abs() < EPSILON && (interval.upper - 0.82).abs() < EPSILON);
+        assert_eq!(TruthValue::from(TruthInterval::new(0.0, 1.0)), tv(0.5, 0.0));
+        assert_eq!(TruthRepresentation::Interval.format(tv(0.8, 0.9)), "[0.72, 0.82]");
+        assert_eq!(TruthRepresentation::FrequencyConfidence.format(tv(0.8, 0.9)), "%0.80;0.90%");
+    }
+
+    #[test]
+    fn test_calibration_constructors() {
+        assert_truth("observations", TruthValue::from_observations(3.0, 4.0, 1.0), tv(0.75, 0.8));
+        assert_eq!(TruthValue::from_observations(0.0, 0.0, 1.0), tv(0.5, 0.0));
+        assert_truth("probability", TruthValue::from_probability(0.7, 9.0), tv(0.7, 0.9));
+        assert!((tv(1.0, 0.9).to_probability() - 0.95).abs() < 1e-6);
+        assert!((tv(0.2, 0.0).to_probability() - 0.5).abs() < 1e-6);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_probability_round_trip(p in 0.0f64..=1.0, weight in 0.0f64..1000.0) {
+            let t = TruthValue::from_probability(p, weight);
+            prop_assert!(in_unit(t.frequency) && in_unit(t.confidence));
+            // Exported probability lies between the input and total ignorance
+            let q = t.to_probability();
+            prop_assert!(q >= p.min(0.5) - 1e-3 && q <= p.max(0.5) + 1e-3);
+        }
+
+        #[test]
+        fn prop_interval_round_trip_and_functions(v1 in truth_value(), v2 in truth_value()) {
+            let i1 = TruthInterval::from(v1);
+            let i2 = TruthInterval::from(v2);
+            prop_assert!(i1.lower <= i1.upper + EPSILON && in_unit(i1.lower) && in_unit(i1.upper));
+            // A zero-confidence interval is [0, 1] and carries no frequency
+            prop_assume!(v1.confidence > 0.01 && v2.confidence > 0.01);
+            assert_truth("round trip", TruthValue::from(i1), v1);
+            assert_truth("interval revision", i1.revision(i2).into(), truth::revision(v1, v2));
+            assert_truth("interval deduction", i1.deduction(i2).into(), truth::deduction(v1, v2));
+            // Revision narrows the interval (more evidence)
+            prop_assert!(i1.revision(i2).width() <= i1.width().min(i2.width()) + EPSILON);
+        }
+
+        #[test]
+        fn prop_double_functions_stay_in_bounds(v1 in truth_value(), v2 in truth_value()) {
+            for (name, f) in DOUBLE_FNS {
+                let r = f(v1, v2);
+                prop_assert!(in_unit(r.frequency) && in_unit(r.confidence), "{} out of bounds: {:?}", name, r);
+            }
+        }
+
+        #[test]
+        fn prop_single_functions_stay_in_bounds(v in truth_value()) {
+            for (name, f) in SINGLE_FNS {
+                let r = f(v);
+                prop_assert!(in_unit(r.frequency) && in_unit(r.confidence), "{} out of bounds: {:?}", name, r);
+            }
+        }
+
+        #[test]
+        fn prop_abduction_induction_symmetry(v1 in truth_value(), v2 in truth_value()) {
+            let a = truth::abduction(v1, v2);
+            let i = truth::induction(v2, v1);
+            prop_assert!((a.frequency - i.frequency).abs() < EPSILON);
+            prop_assert!((a.confidence - i.confidence).abs() < EPSILON);
+        }
+
+        #[test]
+        fn prop_deduction_is_commutative_and_weakening(v1 in truth_value(), v2 in truth_value()) {
+            let d1 = truth::deduction(v1, v2);
+            let d2 = truth::deduction(v2, v1);
+            prop_assert!((d1.frequency - d2.frequency).abs() < EPSILON);
+            prop_assert!((d1.confidence - d2.confidence).abs() < EPSILON);
+            prop_assert!(d1.confidence <= v1.confidence.min(v2.confidence) + EPSILON);
+        }
+
+        #[test]
+        fn prop_revision_is_monotonic(v1 in truth_value(), v2 in truth_value()) {
+            prop_assume!(v1.confidence > 0.0 && v2.confidence > 0.0);
+            let r = truth::revision(v1, v2);
+            // More evidence never lowers confidence
+            prop_assert!(r.confidence + EPSILON >= v1.confidence.max(v2.confidence));
+            // Revised frequency is a weighted average of the inputs
+            prop_assert!(r.frequency + EPSILON >= v1.frequency.min(v2.frequency));
+            prop_assert!(r.frequency <= v1.frequency.max(v2.frequency) + EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_calc() {
+        use crate::nars::calc::{self, Observation};
+
+        assert_eq!(calc::revise(&[]), TruthValue::new(0.5, 0.0));
+        let counts = Observation::Counts { positive: 3.0, total: 4.0 };
+        assert_eq!(calc::revise(&[counts]), TruthValue::from_observations(3.0, 4.0, 1.0));
+
+        // Revising counts separately equals counting them together
+        let split = calc::revise(&[counts, Observation::Counts { positive: 1.0, total: 4.0 }]);
+        let pooled = TruthValue::from_observations(4.0, 8.0, 1.0);
+        assert!((split.frequency - pooled.frequency).abs() < EPSILON);
+        assert!((split.confidence - pooled.confidence).abs() < EPSILON);
+
+        let t = TruthValue::new(0.9, 0.9);
+        assert_eq!(calc::project(t, 5, 5, 0.8), t);
+        let two_steps = calc::project(t, 5, 3, 0.8);
+        assert!((two_steps.confidence - 0.9 * 0.64).abs() < EPSILON);
+        assert_eq!(calc::project(t, 3, 5, 0.8), two_steps);
+        assert!((calc::eternalize(t).confidence - 0.9 / 1.9).abs() < EPSILON);
+        assert!((calc::expectation(t) - 0.86).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_discount() {
+        let t = TruthValue::new(0.8, 0.9);
+        assert_eq!(truth::discount(t, 1.0), t);
+        assert!((truth::discount(t, 0.5).confidence - 0.45).abs() < EPSILON);
+        assert_eq!(truth::discount(t, 0.5).frequency, 0.8);
+        assert_eq!(truth::discount(t, -1.0).confidence, 0.0);
+    }
+}
@@ -10,6 +10,102 @@ impl TruthValue {
     pub fn new(frequency: f32, confidence: f32) -> Self {
         Self { frequency, confidence }
     }
+
+    /// Expected frequency: `c * (f - 0.5) + 0.5`.
+    pub fn expectation(&self) -> f32 {
+        self.confidence * (self.frequency - 0.5) + 0.5
+    }
+
+    /// Truth from counted evidence: `f = positive / total`, `c = total / (total + k)`.
+    /// No evidence gives total ignorance, `%0.5;0.0%`.
+    pub fn from_observations(positive: f64, total: f64, k: f64) -> Self {
+        if total <= 0.0 {
+            return Self::new(0.5, 0.0);
+        }
+        let frequency = (positive / total).clamp(0.0, 1.0);
+        let confidence = total / (total + k);
+        Self::new(frequency as f32, confidence as f32)
+    }
+
+    /// Truth from an external probability estimate (a classifier score, an embedding
+    /// similarity, ...). `weight` is how many observations the estimate is worth; it sets the
+    /// confidence with the default evidential horizon `k = 1`, so a weight of 9 gives `c = 0.9`.
+    pub fn from_probability(p: f64, weight: f64) -> Self {
+        Self::from_observations(p.clamp(0.0, 1.0) * weight, weight, EVIDENTIAL_HORIZON)
+    }
+
+    /// Probability estimate for exporting truth: the expectation, which moves the frequency
+    /// towards 0.5 as confidence drops.
+    pub fn to_probability(&self) -> f64 {
+        self.expectation() as f64
+    }
+}
+
+/// Default evidential horizon `k`: the amount of future evidence confidence is measured against.
+pub const EVIDENTIAL_HORIZON: f64 = 1.0;
+
+/// Frequency interval `[lower, upper]`: the range the frequency can still reach when the
+/// current evidence is extended by `k` more observations (k = 1). It carries the same
+/// information as (f, c): `lower = f * c`, `upper = lower + (1 - c)`, so the width is the
+/// ignorance `1 - c`. Truth functions work on either representation through the conversions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TruthInterval {
+    pub lower: f32,
+    pub upper: f32,
+}
+
+impl TruthInterval {
+    pub fn new(lower: f32, upper: f32) -> Self {
+        Self { lower, upper }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.upper - self.lower
+    }
+
+    pub fn revision(self, other: TruthInterval) -> TruthInterval {
+        revision(self.into(), other.into()).into()
+    }
+
+    pub fn deduction(self, other: TruthInterval) -> TruthInterval {
+        deduction(self.into(), other.into()).into()
+    }
+}
+
+impl From<TruthValue> for TruthInterval {
+    fn from(t: TruthValue) -> Self {
+        let lower = t.frequency * t.confidence;
+        TruthInterval::new(lower, lower + (1.0 - t.confidence))
+    }
+}
+
+impl From<TruthInterval> for TruthValue {
+    fn from(i: TruthInterval) -> Self {
+        let confidence = (1.0 - i.width()).clamp(0.0, 1.0);
+        // Without evidence every frequency is possible; report the midpoint
+        let frequency = if confidence > 0.0 { (i.lower / confidence).clamp(0.0, 1.0) } else { 0.5 };
+        TruthValue::new(frequency, confidence)
+    }
+}
+
+/// How a system reports truth values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruthRepresentation {
+    #[default]
+    FrequencyConfidence, // %f;c%
+    Interval,            // [lower, upper]
+}
+
+impl TruthRepresentation {
+    pub fn format(&self, truth: TruthValue) -> String {
+        match self {
+            TruthRepresentation::FrequencyConfidence => format!("%{:.2};{:.2}%", truth.frequency, truth.confidence),
+            TruthRepresentation::Interval => {
+                let interval = TruthInterval::from(truth);
+                format!("[{:.2}, {:.2}]", interval.lower, interval.upper)
+            }
+        }
+    }
 }
 
 // Helper functions
@@ -292,6 +388,11 @@ pub fn combine(v1: TruthValue, v2: TruthValue) -> TruthValue {
     )
 }
 
+/// Confidence scaled by `factor` (0..1), e.g. the similarity a premise was retrieved with.
+pub fn discount(v: TruthValue, factor: f32) -> TruthValue {
+    TruthValue::new(v.frequency, v.confidence * factor.clamp(0.0, 1.0))
+}
+
 pub fn assumption_of_failure() -> TruthValue {
     TruthValue::new(0.0, 0.15)
 }
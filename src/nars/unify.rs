@@ -1,86 +1,395 @@
 use std::collections::HashMap;
-use super::term::Term;
+use bumpalo::Bump;
+use bumpalo::collections::Vec as BumpVec;
+use super::term::{Term, Operator};
 
 pub type Bindings = HashMap<Term, Term>;
 
+#[cfg_attr(not(feature = "unstable-internals"), allow(dead_code))] // used by tests
 pub fn unify(x: &Term, y: &Term) -> Option<Bindings> {
     unify_with_bindings(x, y, HashMap::new())
 }
 
+/// Unifies `x` with `y` under `bindings`, keeping the first solution. For one-off matches:
+/// the bindings are kept in the map itself, without the scratch arena of rule matching.
 pub fn unify_with_bindings(x: &Term, y: &Term, bindings: Bindings) -> Option<Bindings> {
-    unify_internal(x, y, bindings)
+    let mut map = MapBindings { bindings, trail: Vec::new(), attempts: MAX_AC_ATTEMPTS };
+    map.unify_then(x, y, &mut |_| true).then_some(map.bindings)
 }
 
-fn unify_internal(x: &Term, y: &Term, bindings: Bindings) -> Option<Bindings> {
-    // Check if x or y are variables
-    if let Term::Var(_, _) = x {
-        return unify_var(x, y, bindings);
+/// What a variable is bound to: a term, or the remainder of an AC compound that a variable of
+/// the shorter side stands for. The remainder is kept as references into the longer side
+/// rather than built as a new compound, so matching never copies terms.
+#[derive(Clone, Copy)]
+enum Value<'a> {
+    Term(&'a Term),
+    Rest(&'a Operator, &'a [&'a Term]),
+}
+
+impl Value<'_> {
+    fn to_term(self) -> Term {
+        match self {
+            Value::Term(term) => term.clone(),
+            Value::Rest(op, args) => Term::Compound(op.clone(), args.iter().map(|&arg| arg.clone()).collect()),
+        }
     }
-    if let Term::Var(_, _) = y {
-        return unify_var(y, x, bindings);
+}
+
+/// AC pairings tried by one match before it gives up, so that long conjunctions cannot make
+/// matching factorial.
+const MAX_AC_ATTEMPTS: usize = 1000;
+
+/// Continuation of a match: called with the bindings of each solution found so far, it accepts
+/// the solution (`true`) or asks for the next one (`false`).
+type Then<'t, 'a> = &'t mut dyn FnMut(&mut ScratchBindings<'a>) -> bool;
+
+/// Bindings of a match in progress, borrowing the terms being matched and allocated in `arena`.
+/// Failed attempts cost no heap allocations once the arena has grown; successful matches are
+/// copied out with [`ScratchBindings::to_bindings`].
+pub struct ScratchBindings<'a> {
+    arena: &'a Bump,
+    trail: BumpVec<'a, (&'a Term, Value<'a>)>, // variable -> value, each variable bound once
+    attempts: usize, // AC pairings left, see `MAX_AC_ATTEMPTS`
+}
+
+impl<'a> ScratchBindings<'a> {
+    pub fn new(arena: &'a Bump) -> Self {
+        Self { arena, trail: BumpVec::new_in(arena), attempts: MAX_AC_ATTEMPTS }
     }
 
-    match (x, y) {
-        (Term::Compound(op1, args1), Term::Compound(op2, args2)) => {
-            if op1 != op2 || args1.len() != args2.len() {
-                return None;
-            }
-            let mut current_bindings = bindings;
-            for (arg1, arg2) in args1.iter().zip(args2.iter()) {
-                if let Some(new_bindings) = unify_internal(arg1, arg2, current_bindings) {
-                    current_bindings = new_bindings;
-                } else {
-                    return None;
+    /// Unifies `x` with `y` under the bindings so far, extending them. On failure the bindings
+    /// are left as they were.
+    #[cfg_attr(not(feature = "unstable-internals"), allow(dead_code))] // used by tests
+    pub fn unify(&mut self, x: &'a Term, y: &'a Term) -> bool {
+        self.unify_then(x, y, &mut |_| true)
+    }
+
+    /// Unifies `x` with `y` like `unify`, handing each solution to `then` until it accepts one.
+    /// AC compounds can match in several ways, e.g. `(&&, $a, $b)` against `(&&, x, y)`; a
+    /// later match that needs `$a = y` rejects the first solution and gets the second.
+    pub fn unify_then(&mut self, x: &'a Term, y: &'a Term, then: Then<'_, 'a>) -> bool {
+        let mark = self.trail.len();
+        let unified = self.unify_values(Value::Term(x), Value::Term(y), then);
+        if !unified {
+            self.trail.truncate(mark);
+        }
+        unified
+    }
+
+    pub fn to_bindings(&self) -> Bindings {
+        self.trail.iter().map(|(var, val)| ((*var).clone(), val.to_term())).collect()
+    }
+
+    fn lookup(&self, var: &Term) -> Option<Value<'a>> {
+        self.trail.iter().find(|(bound, _)| *bound == var).map(|(_, val)| *val)
+    }
+
+    fn unify_values(&mut self, x: Value<'a>, y: Value<'a>, then: Then<'_, 'a>) -> bool {
+        // Check if x or y are variables
+        if let Value::Term(var @ Term::Var(_, _)) = x {
+            return self.unify_var(var, y, then);
+        }
+        if let Value::Term(var @ Term::Var(_, _)) = y {
+            return self.unify_var(var, x, then);
+        }
+
+        match (x, y) {
+            (Value::Term(Term::Compound(op1, args1)), Value::Term(Term::Compound(op2, args2))) => {
+                if op1 != op2 {
+                    return false;
                 }
+                if is_associative_commutative(op1) {
+                    let args1 = self.refs(args1);
+                    let args2 = self.refs(args2);
+                    return self.unify_ac(op1, args1, args2, then);
+                }
+                if op1.is_symmetric_copula() && args1.len() == 2 && args2.len() == 2 {
+                    let mark = self.trail.len();
+                    if self.unify_all(&args1[..], &args2[..], then) {
+                        return true;
+                    }
+                    self.trail.truncate(mark);
+                    let swapped = self.refs_of([&args2[1], &args2[0]]);
+                    return self.unify_all_refs(self.refs(args1), swapped, then);
+                }
+                if args1.len() != args2.len() {
+                    return false;
+                }
+                self.unify_all(args1, args2, then)
             }
-            Some(current_bindings)
-        }
-        (Term::Atom(h1), Term::Atom(h2)) => {
-            if h1 == h2 {
-                Some(bindings)
-            } else {
-                None
+            // A remainder is an AC compound, matched as one against compounds of its operator
+            (Value::Rest(op1, args1), Value::Term(Term::Compound(op2, args2))) => {
+                let args2 = self.refs(args2);
+                op1 == op2 && self.unify_ac(op1, args1, args2, then)
+            }
+            (Value::Term(Term::Compound(op1, args1)), Value::Rest(op2, args2)) => {
+                let args1 = self.refs(args1);
+                op1 == op2 && self.unify_ac(op1, args1, args2, then)
             }
+            (Value::Rest(op1, args1), Value::Rest(op2, args2)) => op1 == op2 && self.unify_ac(op1, args1, args2, then),
+            (Value::Term(Term::Atom(h1)), Value::Term(Term::Atom(h2))) => h1 == h2 && then(self),
+            (Value::Term(Term::Placeholder), Value::Term(Term::Placeholder)) => then(self),
+            (Value::Term(Term::Interval(a)), Value::Term(Term::Interval(b))) => a == b && then(self),
+            _ => false,
         }
-        _ => None,
     }
-}
 
-fn unify_var(var: &Term, x: &Term, mut bindings: Bindings) -> Option<Bindings> {
-    if let Some(val) = bindings.get(var) {
-        // Need to clone val because bindings is moved into unify_internal
-        let val_clone = val.clone(); 
-        return unify_internal(&val_clone, x, bindings);
+    fn unify_terms(&mut self, x: &'a Term, y: &'a Term, then: Then<'_, 'a>) -> bool {
+        self.unify_values(Value::Term(x), Value::Term(y), then)
+    }
+
+    /// Unifies the arguments pairwise, in order.
+    fn unify_all(&mut self, xs: &'a [Term], ys: &'a [Term], then: Then<'_, 'a>) -> bool {
+        let (xs, ys) = (self.refs(xs), self.refs(ys));
+        self.unify_all_refs(xs, ys, then)
+    }
+
+    fn unify_all_refs(&mut self, xs: &'a [&'a Term], ys: &'a [&'a Term], then: Then<'_, 'a>) -> bool {
+        match (xs.split_first(), ys.split_first()) {
+            (Some((x, xs)), Some((y, ys))) => self.unify_terms(x, y, &mut |s| s.unify_all_refs(xs, ys, then)),
+            _ => then(self),
+        }
+    }
+
+    fn refs(&self, args: &'a [Term]) -> &'a [&'a Term] {
+        self.arena.alloc_slice_fill_iter(args.iter())
+    }
+
+    fn refs_of<const N: usize>(&self, args: [&'a Term; N]) -> &'a [&'a Term] {
+        self.arena.alloc_slice_fill_iter(args)
+    }
+
+    /// Matches the arguments of two AC compounds. With equal counts every argument is paired
+    /// with one on the other side in some order; otherwise a variable of the shorter side binds
+    /// the remainder as a compound of the same operator, e.g. `(&&, $a, $b)` against
+    /// `(&&, x, y, z)` gives `$a = x, $b = (&&, y, z)`. Assignments are tried in turn until
+    /// `then` accepts one, or `MAX_AC_ATTEMPTS` pairings have been tried.
+    fn unify_ac(&mut self, op: &'a Operator, args1: &'a [&'a Term], args2: &'a [&'a Term], then: Then<'_, 'a>) -> bool {
+        let (short, long) = if args1.len() <= args2.len() { (args1, args2) } else { (args2, args1) };
+        let available: &[usize] = self.arena.alloc_slice_fill_iter(0..long.len());
+        if short.len() == long.len() {
+            let pending: &[usize] = self.arena.alloc_slice_fill_iter(0..short.len());
+            return self.assign(op, short, long, pending, available, None, then);
+        }
+        (0..short.len())
+            .filter(|&r| matches!(short[r], Term::Var(..)))
+            .any(|r| {
+                let pending = BumpVec::from_iter_in((0..short.len()).filter(|&i| i != r), self.arena).into_bump_slice();
+                let mark = self.trail.len();
+                let assigned = self.assign(op, short, long, pending, available, Some(r), then);
+                if !assigned {
+                    self.trail.truncate(mark);
+                }
+                assigned
+            })
+    }
+
+    /// Pairs each `pending` argument of `short` with a distinct `available` argument of `long`,
+    /// then binds the remainder variable `rest` (if any) to what is left.
+    #[allow(clippy::too_many_arguments)]
+    fn assign(&mut self, op: &'a Operator, short: &'a [&'a Term], long: &'a [&'a Term], pending: &'a [usize], available: &[usize], rest: Option<usize>, then: Then<'_, 'a>) -> bool {
+        let Some((&i, pending)) = pending.split_first() else {
+            return match rest {
+                Some(r) => {
+                    let remainder: &[&Term] = self.arena.alloc_slice_fill_iter(available.iter().map(|&j| long[j]));
+                    self.unify_values(Value::Term(short[r]), Value::Rest(op, remainder), then)
+                }
+                None => then(self),
+            };
+        };
+        available.iter().enumerate().any(|(pos, &j)| {
+            if self.attempts == 0 {
+                return false;
+            }
+            self.attempts -= 1;
+            let mark = self.trail.len();
+            let left = BumpVec::from_iter_in(available.iter().enumerate().filter(|&(p, _)| p != pos).map(|(_, &j)| j), self.arena);
+            if self.unify_terms(short[i], long[j], &mut |s| s.assign(op, short, long, pending, &left, rest, then)) {
+                return true;
+            }
+            self.trail.truncate(mark);
+            false
+        })
     }
-    if let Term::Var(_, _) = x {
-        if let Some(val) = bindings.get(x) {
-            let val_clone = val.clone();
-            return unify_internal(var, &val_clone, bindings);
+
+    fn unify_var(&mut self, var: &'a Term, x: Value<'a>, then: Then<'_, 'a>) -> bool {
+        if let Some(val) = self.lookup(var) {
+            return self.unify_values(val, x, then);
+        }
+        if let Value::Term(x_var @ Term::Var(_, _)) = x
+            && let Some(val) = self.lookup(x_var)
+        {
+            return self.unify_values(Value::Term(var), val, then);
+        }
+        if self.occurs_in(var, x) {
+            return false;
         }
+
+        let mark = self.trail.len();
+        self.trail.push((var, x));
+        if then(self) {
+            return true;
+        }
+        self.trail.truncate(mark);
+        false
     }
-    if occurs_in(var, x, &bindings) {
-        return None;
+
+    fn occurs_in(&self, var: &Term, x: Value<'a>) -> bool {
+        match x {
+            Value::Term(term) if term == var => true,
+            Value::Term(term @ Term::Var(_, _)) => self.lookup(term).is_some_and(|val| self.occurs_in(var, val)),
+            Value::Term(Term::Compound(_, args)) => args.iter().any(|arg| self.occurs_in(var, Value::Term(arg))),
+            Value::Rest(_, args) => args.iter().any(|&arg| self.occurs_in(var, Value::Term(arg))),
+            _ => false,
+        }
     }
-    
-    bindings.insert(var.clone(), x.clone());
-    Some(bindings)
 }
 
-fn occurs_in(var: &Term, x: &Term, bindings: &Bindings) -> bool {
-    if var == x {
-        return true;
+/// Continuation of a one-off match, see [`Then`].
+type MapThen<'t> = &'t mut dyn FnMut(&mut MapBindings) -> bool;
+
+/// Bindings of a one-off match, kept in a plain map. Matches like [`ScratchBindings`], but
+/// clones what it binds, so it needs no arena.
+struct MapBindings {
+    bindings: Bindings,
+    trail: Vec<Term>, // variables bound by this match, in order, to undo on backtracking
+    attempts: usize, // AC pairings left, see `MAX_AC_ATTEMPTS`
+}
+
+impl MapBindings {
+    fn undo(&mut self, mark: usize) {
+        for var in self.trail.drain(mark..) {
+            self.bindings.remove(&var);
+        }
+    }
+
+    fn unify_then(&mut self, x: &Term, y: &Term, then: MapThen<'_>) -> bool {
+        if let Term::Var(_, _) = x {
+            return self.unify_var(x, y, then);
+        }
+        if let Term::Var(_, _) = y {
+            return self.unify_var(y, x, then);
+        }
+
+        match (x, y) {
+            (Term::Compound(op1, args1), Term::Compound(op2, args2)) => {
+                if op1 != op2 {
+                    return false;
+                }
+                let (refs1, refs2): (Vec<&Term>, Vec<&Term>) = (args1.iter().collect(), args2.iter().collect());
+                if is_associative_commutative(op1) {
+                    return self.unify_ac(op1, &refs1, &refs2, then);
+                }
+                if op1.is_symmetric_copula() && args1.len() == 2 && args2.len() == 2 {
+                    let mark = self.trail.len();
+                    if self.unify_all(&refs1, &refs2, then) {
+                        return true;
+                    }
+                    self.undo(mark);
+                    return self.unify_all(&refs1, &[refs2[1], refs2[0]], then);
+                }
+                args1.len() == args2.len() && self.unify_all(&refs1, &refs2, then)
+            }
+            (Term::Atom(h1), Term::Atom(h2)) => h1 == h2 && then(self),
+            (Term::Placeholder, Term::Placeholder) => then(self),
+            (Term::Interval(a), Term::Interval(b)) => a == b && then(self),
+            _ => false,
+        }
+    }
+
+    fn unify_all(&mut self, xs: &[&Term], ys: &[&Term], then: MapThen<'_>) -> bool {
+        match (xs.split_first(), ys.split_first()) {
+            (Some((x, xs)), Some((y, ys))) => self.unify_then(x, y, &mut |s| s.unify_all(xs, ys, then)),
+            _ => then(self),
+        }
     }
-    if let Term::Var(_, _) = x {
-        if let Some(val) = bindings.get(x) {
-            return occurs_in(var, val, bindings);
+
+    /// Like [`ScratchBindings::unify_ac`], binding the remainder to a new compound.
+    fn unify_ac(&mut self, op: &Operator, args1: &[&Term], args2: &[&Term], then: MapThen<'_>) -> bool {
+        let (short, long) = if args1.len() <= args2.len() { (args1, args2) } else { (args2, args1) };
+        let available: Vec<usize> = (0..long.len()).collect();
+        if short.len() == long.len() {
+            let pending: Vec<usize> = (0..short.len()).collect();
+            return self.assign(op, short, long, &pending, &available, None, then);
         }
+        (0..short.len())
+            .filter(|&r| matches!(short[r], Term::Var(..)))
+            .any(|r| {
+                let pending: Vec<usize> = (0..short.len()).filter(|&i| i != r).collect();
+                let mark = self.trail.len();
+                let assigned = self.assign(op, short, long, &pending, &available, Some(r), then);
+                if !assigned {
+                    self.undo(mark);
+                }
+                assigned
+            })
     }
-    if let Term::Compound(_, args) = x {
-        for arg in args {
-            if occurs_in(var, arg, bindings) {
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign(&mut self, op: &Operator, short: &[&Term], long: &[&Term], pending: &[usize], available: &[usize], rest: Option<usize>, then: MapThen<'_>) -> bool {
+        let Some((&i, pending)) = pending.split_first() else {
+            return match rest {
+                Some(r) => {
+                    let remainder = Term::Compound(op.clone(), available.iter().map(|&j| long[j].clone()).collect());
+                    self.unify_then(short[r], &remainder, then)
+                }
+                None => then(self),
+            };
+        };
+        available.iter().enumerate().any(|(pos, &j)| {
+            if self.attempts == 0 {
+                return false;
+            }
+            self.attempts -= 1;
+            let mark = self.trail.len();
+            let left: Vec<usize> = available.iter().enumerate().filter(|&(p, _)| p != pos).map(|(_, &j)| j).collect();
+            if self.unify_then(short[i], long[j], &mut |s| s.assign(op, short, long, pending, &left, rest, then)) {
                 return true;
             }
+            self.undo(mark);
+            false
+        })
+    }
+
+    fn unify_var(&mut self, var: &Term, x: &Term, then: MapThen<'_>) -> bool {
+        if let Some(val) = self.bindings.get(var).cloned() {
+            return self.unify_then(&val, x, then);
+        }
+        if let Term::Var(_, _) = x
+            && let Some(val) = self.bindings.get(x).cloned()
+        {
+            return self.unify_then(var, &val, then);
+        }
+        if self.occurs_in(var, x) {
+            return false;
         }
+
+        let mark = self.trail.len();
+        self.bindings.insert(var.clone(), x.clone());
+        self.trail.push(var.clone());
+        if then(self) {
+            return true;
+        }
+        self.undo(mark);
+        false
+    }
+
+    fn occurs_in(&self, var: &Term, x: &Term) -> bool {
+        match x {
+            term if term == var => true,
+            Term::Var(_, _) => self.bindings.get(x).is_some_and(|val| self.occurs_in(var, val)),
+            Term::Compound(_, args) => args.iter().any(|arg| self.occurs_in(var, arg)),
+            _ => false,
+        }
+    }
+}
+
+/// Operators matched as multisets: argument order is ignored and a variable may stand for
+/// several arguments.
+pub(crate) fn is_associative_commutative(op: &Operator) -> bool {
+    match op {
+        // Declared commutative operators match like the built-in ones
+        Operator::Other(_) => op.is_commutative() && !op.is_symmetric_copula(),
+        _ => matches!(op, Operator::Conjunction | Operator::Disjunction | Operator::ExtIntersection | Operator::IntIntersection),
     }
-    false
 }
@@ -0,0 +1,23 @@
+//! The stable entry point of the library: the reasoner, its configuration and output events,
+//! and the types needed to build inputs and read results.
+//!
+//! ```
+//! use hybrid_nars_rust::prelude::*;
+//!
+//! let mut system = NarsSystem::new(0.1, 0.5);
+//! system.input(parse_narsese("<cat --> animal>.").unwrap());
+//! system.cycle();
+//! let question = Term::inheritance(Term::atom_from_str("cat"), Term::atom_from_str("animal"));
+//! let answer: Option<Sentence> = system.answer_query(&question);
+//! assert!(answer.is_some_and(|a| a.truth.confidence > 0.8));
+//! ```
+pub use crate::nars::control::{
+    Answer, AnswerEvent, BeliefFilter, BeliefOrigin, Expectation, InferencePath, NarsSystem, PartialMatch, RankedAnswer, ReasonerConfig, Unanswered,
+};
+pub use crate::nars::error::NarsError;
+pub use crate::nars::parser::{parse_narsese, parse_narsese_all, parse_narsese_with, parse_term, ParserOptions};
+pub use crate::nars::sentence::{Budget, Punctuation, Sentence, Source, Stamp, Tense, TruthDefaults};
+pub use crate::nars::term::{Operator, Term, VarType};
+pub use crate::nars::truth::{TruthRepresentation, TruthValue};
+pub use crate::nars::snapshot::QueryHandle;
+pub use crate::nars::RuleLoadReport;
@@ -0,0 +1,21 @@
+//! End-to-end inference: every `.nal` file under `tests/nal<level>/` is a test that passes when
+//! all of its `outputMustContain` expectations are met. Tests are generated by `build.rs`.
+
+use hybrid_nars_rust::nars::control::NarsSystem;
+use hybrid_nars_rust::nars::script::run_script_file;
+
+fn run_nal_file(path: &str) {
+    // A negative similarity threshold lets every pair of concepts meet, as in `test_runner`
+    let mut system = NarsSystem::new(0.1, -1.0);
+    let report = run_script_file(&mut system, path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    assert!(report.parse_errors.is_empty(), "{}: unparsable lines {:?}", path, report.parse_errors);
+    let unmet: Vec<String> = report.expectations_unmet.iter()
+        .map(|expected| match report.closest_output(expected) {
+            Some((closest, distance)) => format!("{} (closest: {}, distance {:.3})", expected, closest.to_narsese(), distance),
+            None => format!("{} (nothing output on this term)", expected),
+        })
+        .collect();
+    assert!(unmet.is_empty(), "{}: unmet expectations:\n  {}", path, unmet.join("\n  "));
+}
+
+include!(concat!(env!("OUT_DIR"), "/nal_tests.rs"));